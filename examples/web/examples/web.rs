@@ -64,7 +64,7 @@ fn add_load_more_button() {
     );
     btn.add_event_listener_with_callback(
         "click",
-        &on_click
+        on_click
             .as_ref()
             .dyn_ref()
             .expect_throw("on click is not a Function"),
@@ -104,22 +104,20 @@ fn render_response(response: graphql_client_web::Response<puppy_smiles::Response
         .map(|puppy| puppy.fullname_id.clone());
     LAST_ENTRY.lock().unwrap_throw().replace(new_cursor);
 
-    for puppy in &listings {
-        if let Some(puppy) = puppy {
-            write!(
-                inner_html,
-                r#"
-                    <div class="card" style="width: 26rem;">
-                        <img class="img-thumbnail card-img-top" alt="{}" src="{}" />
-                        <div class="card-body">
-                            <h5 class="card-title">{}</h5>
-                        </div>
+    for puppy in listings.iter().flatten() {
+        write!(
+            inner_html,
+            r#"
+                <div class="card" style="width: 26rem;">
+                    <img class="img-thumbnail card-img-top" alt="{}" src="{}" />
+                    <div class="card-body">
+                        <h5 class="card-title">{}</h5>
                     </div>
-                    "#,
-                puppy.title, puppy.url, puppy.title
-            )
-            .expect_throw("write to string");
-        }
+                </div>
+                "#,
+            puppy.title, puppy.url, puppy.title
+        )
+        .expect_throw("write to string");
     }
     response.set_inner_html(&format!(
         "<h2>response:</h2><div class=\"container\"><div class=\"row\">{}</div></div>",
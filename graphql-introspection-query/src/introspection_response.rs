@@ -141,7 +141,7 @@ pub struct FullType {
 #[serde(rename_all = "camelCase")]
 pub struct FullTypeFieldsArgs {
     #[serde(flatten)]
-    input_value: InputValue,
+    pub input_value: InputValue,
 }
 
 #[derive(Clone, Debug, Deserialize)]
@@ -247,7 +247,7 @@ pub struct SchemaTypes {
 #[serde(rename_all = "camelCase")]
 pub struct SchemaDirectivesArgs {
     #[serde(flatten)]
-    input_value: InputValue,
+    pub input_value: InputValue,
 }
 
 #[derive(Clone, Debug, Deserialize)]
@@ -266,7 +266,7 @@ pub struct Schema {
     pub mutation_type: Option<SchemaMutationType>,
     pub subscription_type: Option<SchemaSubscriptionType>,
     pub types: Option<Vec<Option<SchemaTypes>>>,
-    directives: Option<Vec<Option<SchemaDirectives>>>,
+    pub directives: Option<Vec<Option<SchemaDirectives>>>,
 }
 
 #[derive(Clone, Debug, Deserialize)]
@@ -291,7 +291,7 @@ impl IntrospectionResponse {
     pub fn as_schema(&self) -> &SchemaContainer {
         match self {
             IntrospectionResponse::FullResponse(full_response) => &full_response.data,
-            IntrospectionResponse::Schema(schema) => &schema,
+            IntrospectionResponse::Schema(schema) => schema,
         }
     }
 
@@ -1 +1,11 @@
 pub mod introspection_response;
+
+/// The introspection query, selecting the fields that
+/// [`IntrospectionResponse`](introspection_response::IntrospectionResponse) deserializes.
+pub const INTROSPECTION_QUERY: &str = include_str!("introspection_query.graphql");
+
+/// The introspection query, selecting the fields that
+/// [`IntrospectionResponse`](introspection_response::IntrospectionResponse) deserializes.
+pub fn introspection_query() -> &'static str {
+    INTROSPECTION_QUERY
+}
@@ -0,0 +1,37 @@
+//! Store responses in document databases such as MongoDB, as [BSON](http://bsonspec.org/)
+//! documents.
+//!
+//! The generated types only rely on serde features that `bson` supports (field renames, flattened
+//! fragments, unions tagged by `__typename`, enums serialized as strings), so responses and
+//! response data round-trip through BSON documents, stored with the GraphQL field names.
+
+use serde::{de::DeserializeOwned, Serialize};
+
+pub use ::bson::Document;
+
+/// Serialize a response, or the response data of an operation, to a BSON document.
+///
+/// ```
+/// # use graphql_client::bson::{from_document, to_document};
+/// # use serde::{Deserialize, Serialize};
+/// #[derive(Debug, Serialize, Deserialize, PartialEq)]
+/// struct ResponseData {
+///     #[serde(rename = "displayName")]
+///     display_name: String,
+/// }
+///
+/// let data = ResponseData { display_name: "Ferris".to_owned() };
+/// let document = to_document(&data)?;
+///
+/// assert_eq!(document.get_str("displayName")?, "Ferris");
+/// assert_eq!(from_document::<ResponseData>(document)?, data);
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub fn to_document<T: Serialize>(value: &T) -> Result<Document, ::bson::ser::Error> {
+    ::bson::to_document(value)
+}
+
+/// Deserialize a response, or the response data of an operation, from a BSON document.
+pub fn from_document<T: DeserializeOwned>(document: Document) -> Result<T, ::bson::de::Error> {
+    ::bson::from_document(document)
+}
@@ -0,0 +1,84 @@
+//! Support for the tests generated with the `codegen-tests` feature: each generated enum gets a
+//! test comparing its values with the ones of the enum in the schema file, so that the tests fail
+//! when values are added to or removed from the schema after the code was generated.
+
+/// Panic if the values of the enum named `enum_name` in the schema file are not the values of the
+/// generated enum. The schema file is read when the test runs: either a GraphQL schema
+/// (`.graphql`) or a JSON introspection response (`.json`).
+///
+/// This is called by the generated tests, with the `VARIANTS` of the enum.
+pub fn assert_enum_values(schema_path: &str, enum_name: &str, generated_values: &[&str]) {
+    let schema = std::fs::read_to_string(schema_path)
+        .unwrap_or_else(|err| panic!("Failed to read the schema at {}: {}", schema_path, err));
+
+    let schema_values = if schema_path.ends_with(".json") {
+        json_enum_values(&schema, enum_name)
+    } else {
+        sdl_enum_values(&schema, enum_name)
+    }
+    .unwrap_or_else(|| {
+        panic!(
+            "The enum `{}` is not in the schema at {}.",
+            enum_name, schema_path
+        )
+    });
+
+    let added: Vec<&str> = schema_values
+        .iter()
+        .map(String::as_str)
+        .filter(|value| !generated_values.contains(value))
+        .collect();
+    let removed: Vec<&str> = generated_values
+        .iter()
+        .copied()
+        .filter(|value| {
+            !schema_values
+                .iter()
+                .any(|schema_value| schema_value == value)
+        })
+        .collect();
+
+    if !added.is_empty() || !removed.is_empty() {
+        panic!(
+            "The enum `{}` changed in the schema at {} since the code was generated (added: {:?}, removed: {:?}). Generate the code again.",
+            enum_name, schema_path, added, removed
+        );
+    }
+}
+
+fn sdl_enum_values(schema: &str, enum_name: &str) -> Option<Vec<String>> {
+    use graphql_parser::schema::{Definition, TypeDefinition};
+
+    let document = graphql_parser::parse_schema(schema)
+        .unwrap_or_else(|err| panic!("Failed to parse the schema: {}", err));
+
+    document
+        .definitions
+        .into_iter()
+        .find_map(|definition| match definition {
+            Definition::TypeDefinition(TypeDefinition::Enum(enm)) if enm.name == enum_name => {
+                Some(enm.values.into_iter().map(|value| value.name).collect())
+            }
+            _ => None,
+        })
+}
+
+fn json_enum_values(schema: &str, enum_name: &str) -> Option<Vec<String>> {
+    let schema: serde_json::Value = serde_json::from_str(schema)
+        .unwrap_or_else(|err| panic!("Failed to parse the schema: {}", err));
+    // The introspection response, or its `data`.
+    let types = schema
+        .pointer("/data/__schema/types")
+        .or_else(|| schema.pointer("/__schema/types"))?
+        .as_array()?;
+
+    let enm = types
+        .iter()
+        .find(|ty| ty["kind"] == "ENUM" && ty["name"] == enum_name)?;
+
+    enm["enumValues"]
+        .as_array()?
+        .iter()
+        .map(|value| value["name"].as_str().map(ToOwned::to_owned))
+        .collect()
+}
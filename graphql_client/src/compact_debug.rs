@@ -0,0 +1,68 @@
+//! Truncated `Debug` output, used by the code generated with the `compact_debug` option.
+
+use std::fmt::{self, Debug};
+
+/// Strings longer than this number of characters are truncated.
+pub const MAX_STRING_CHARS: usize = 100;
+
+/// Values that can be formatted in a truncated form.
+pub trait CompactDebug {
+    /// Format the value, showing at most `max_items` items of lists.
+    fn fmt_compact(&self, f: &mut fmt::Formatter<'_>, max_items: usize) -> fmt::Result;
+}
+
+impl<T: Debug> CompactDebug for Vec<T> {
+    fn fmt_compact(&self, f: &mut fmt::Formatter<'_>, max_items: usize) -> fmt::Result {
+        let mut list = f.debug_list();
+        list.entries(self.iter().take(max_items));
+
+        if self.len() > max_items {
+            list.entry(&format_args!("... {} more", self.len() - max_items));
+        }
+
+        list.finish()
+    }
+}
+
+impl CompactDebug for String {
+    fn fmt_compact(&self, f: &mut fmt::Formatter<'_>, _max_items: usize) -> fmt::Result {
+        match self.char_indices().nth(MAX_STRING_CHARS) {
+            Some((end, _)) => write!(
+                f,
+                "{:?}... ({} more characters)",
+                &self[..end],
+                self[end..].chars().count()
+            ),
+            None => Debug::fmt(self, f),
+        }
+    }
+}
+
+impl<T: CompactDebug> CompactDebug for Option<T> {
+    fn fmt_compact(&self, f: &mut fmt::Formatter<'_>, max_items: usize) -> fmt::Result {
+        match self {
+            Some(value) => f
+                .debug_tuple("Some")
+                .field(&Compact(value, max_items))
+                .finish(),
+            None => f.write_str("None"),
+        }
+    }
+}
+
+/// Formats the wrapped value with [`CompactDebug`], showing at most the given number of list items.
+///
+/// ```
+/// use graphql_client::compact_debug::Compact;
+///
+/// let numbers: Vec<u32> = (0..1000).collect();
+///
+/// assert_eq!(format!("{:?}", Compact(&numbers, 3)), "[0, 1, 2, ... 997 more]");
+/// ```
+pub struct Compact<'a, T>(pub &'a T, pub usize);
+
+impl<'a, T: CompactDebug> Debug for Compact<'a, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt_compact(f, self.1)
+    }
+}
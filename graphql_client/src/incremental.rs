@@ -0,0 +1,190 @@
+//! Incremental delivery of responses, for operations using `@defer`.
+//!
+//! The deferred fragments arrive as separate payloads after the initial response. Each of them carries the
+//! `path` of the object it completes and, if the fragment was labeled with `@defer(label: "...")`, its
+//! `label`. The payloads can be merged into the untyped initial response data with [`apply_patch`], before
+//! deserializing it into the generated `ResponseData`.
+
+use crate::*;
+
+/// A subsequent payload of an incrementally delivered response.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct IncrementalPayload {
+    /// The data of the deferred fragment.
+    pub data: Option<serde_json::Value>,
+    /// The path of the object the data belongs to, from the root of the response data.
+    #[serde(default)]
+    pub path: Vec<PathFragment>,
+    /// The label of the deferred fragment, from `@defer(label: "...")`.
+    pub label: Option<String>,
+    /// The errors raised while executing the deferred fragment.
+    pub errors: Option<Vec<Error>>,
+    /// Whether more payloads will follow.
+    pub has_next: Option<bool>,
+}
+
+impl IncrementalPayload {
+    /// Merge the data of the payload into the response data. Payloads without data leave it unchanged.
+    pub fn apply_to(&self, response_data: &mut serde_json::Value) -> Result<(), PatchError> {
+        match &self.data {
+            Some(data) => apply_patch(response_data, &self.path, data.clone()),
+            None => Ok(()),
+        }
+    }
+}
+
+/// The response data has no object at the path of a patch.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PatchError {
+    /// The path of the patch.
+    pub path: Vec<PathFragment>,
+}
+
+impl Display for PatchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let path = self
+            .path
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join("/");
+
+        write!(f, "No object at path `{}` in the response data", path)
+    }
+}
+
+impl std::error::Error for PatchError {}
+
+/// Merge `data` into the object found at `path` in `response_data`. The fields of nested objects are
+/// merged too, and the other fields are replaced.
+///
+/// ```
+/// # use graphql_client::{incremental::apply_patch, PathFragment};
+/// # use serde_json::json;
+/// let mut response_data = json!({ "user": { "friends": [{ "id": "1" }] } });
+///
+/// apply_patch(
+///     &mut response_data,
+///     &[
+///         PathFragment::Key("user".to_owned()),
+///         PathFragment::Key("friends".to_owned()),
+///         PathFragment::Index(0),
+///     ],
+///     json!({ "name": "Ada" }),
+/// )?;
+///
+/// assert_eq!(
+///     response_data,
+///     json!({ "user": { "friends": [{ "id": "1", "name": "Ada" }] } })
+/// );
+/// # Ok::<(), graphql_client::incremental::PatchError>(())
+/// ```
+pub fn apply_patch(
+    response_data: &mut serde_json::Value,
+    path: &[PathFragment],
+    data: serde_json::Value,
+) -> Result<(), PatchError> {
+    let error = || PatchError {
+        path: path.to_vec(),
+    };
+
+    let mut target = response_data;
+
+    for fragment in path {
+        target = match fragment {
+            PathFragment::Key(key) => target.get_mut(key.as_str()),
+            PathFragment::Index(index) => target.get_mut(*index as usize),
+        }
+        .ok_or_else(error)?;
+    }
+
+    if !target.is_object() {
+        return Err(error());
+    }
+
+    merge(target, data);
+
+    Ok(())
+}
+
+fn merge(target: &mut serde_json::Value, data: serde_json::Value) {
+    match (target, data) {
+        (serde_json::Value::Object(target), serde_json::Value::Object(data)) => {
+            for (key, value) in data {
+                match target.get_mut(&key) {
+                    Some(existing) => merge(existing, value),
+                    None => {
+                        target.insert(key, value);
+                    }
+                }
+            }
+        }
+        (target, data) => *target = data,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn labeled_patch_is_applied_at_its_path() {
+        let mut response_data = json!({
+            "user": {
+                "name": "Ada",
+                "repositories": [
+                    { "name": "engine", "stats": { "stars": 3 } },
+                    { "name": "notes", "stats": { "stars": 1 } }
+                ]
+            }
+        });
+
+        let payload: IncrementalPayload = serde_json::from_value(json!({
+            "label": "RepositoryStats",
+            "path": ["user", "repositories", 1],
+            "data": { "stats": { "forks": 2 }, "description": "Notes" },
+            "hasNext": false
+        }))
+        .unwrap();
+
+        assert_eq!(payload.label.as_deref(), Some("RepositoryStats"));
+        assert_eq!(payload.has_next, Some(false));
+
+        payload.apply_to(&mut response_data).unwrap();
+
+        assert_eq!(
+            response_data,
+            json!({
+                "user": {
+                    "name": "Ada",
+                    "repositories": [
+                        { "name": "engine", "stats": { "stars": 3 } },
+                        {
+                            "name": "notes",
+                            "stats": { "stars": 1, "forks": 2 },
+                            "description": "Notes"
+                        }
+                    ]
+                }
+            })
+        );
+    }
+
+    #[test]
+    fn patch_at_missing_path_is_an_error() {
+        let mut response_data = json!({ "user": null });
+        let path = vec![
+            PathFragment::Key("user".to_owned()),
+            PathFragment::Key("repositories".to_owned()),
+        ];
+
+        let error = apply_patch(&mut response_data, &path, json!({})).unwrap_err();
+
+        assert_eq!(
+            error.to_string(),
+            "No object at path `user/repositories` in the response data"
+        );
+    }
+}
@@ -14,9 +14,21 @@ pub use graphql_query_derive::*;
 
 use serde::*;
 
+#[cfg(feature = "bson")]
+pub mod bson;
+#[cfg(feature = "codegen-tests")]
+pub mod codegen_tests;
+pub mod compact_debug;
+pub mod incremental;
+pub mod serde_helpers;
+#[cfg(feature = "sse")]
+pub mod sse;
 #[cfg(feature = "web")]
 pub mod web;
+#[cfg(feature = "ws")]
+pub mod ws;
 
+use std::borrow::Cow;
 use std::collections::HashMap;
 use std::fmt::{self, Display};
 
@@ -69,8 +81,115 @@ pub trait GraphQLQuery {
     /// The top-level shape of the response data (the `data` field in the GraphQL response). In practice this should be generated, since it is hard to write by hand without error.
     type ResponseData: for<'de> serde::Deserialize<'de>;
 
+    /// The name of the operation, as written in the query document. It is sent as `operationName`.
+    const OPERATION_NAME: &'static str;
+    /// The query document sent to the server. The generated code uses the whole query file, with all its operations and fragments: the server executes the operation named [`GraphQLQuery::OPERATION_NAME`].
+    const QUERY: &'static str;
+    /// The type of the operation.
+    const OPERATION_TYPE: OperationType;
+    /// The SHA-256 hash of [`GraphQLQuery::QUERY`], as lowercase hexadecimal.
+    const QUERY_SHA256: &'static str;
+
     /// Produce a GraphQL query struct that can be JSON serialized and sent to a GraphQL API.
     fn build_query(variables: Self::Variables) -> QueryBody<Self::Variables>;
+
+    /// Produce a GraphQL query struct like [`GraphQLQuery::build_query`], with another query text. This is useful to add directives at runtime, or for automatic persisted queries, where the full query is only sent when the server does not know its hash.
+    ///
+    /// ```
+    /// # use graphql_client::GraphQLQuery;
+    /// #[derive(GraphQLQuery)]
+    /// #[graphql(
+    ///   query_path = "../graphql_client_codegen/src/tests/star_wars_query.graphql",
+    ///   schema_path = "../graphql_client_codegen/src/tests/star_wars_schema.graphql"
+    /// )]
+    /// struct StarWarsQuery;
+    ///
+    /// let query = format!("{} # sent by the retry", star_wars_query::QUERY);
+    /// let body = StarWarsQuery::build_query_with_query(
+    ///     star_wars_query::Variables { episode_for_hero: star_wars_query::Episode::NEWHOPE },
+    ///     query.clone(),
+    /// );
+    ///
+    /// assert_eq!(body.query, query);
+    /// assert_eq!(body.operation_name, "StarWarsQuery");
+    /// ```
+    fn build_query_with_query(
+        variables: Self::Variables,
+        query: impl Into<Cow<'static, str>>,
+    ) -> QueryBody<Self::Variables> {
+        QueryBody {
+            query: query.into(),
+            ..Self::build_query(variables)
+        }
+    }
+
+    /// Whether the operation can safely be retried. The generated code returns `true` for queries and subscriptions, and for mutations marked with the `@idempotent` directive.
+    fn is_idempotent() -> bool {
+        false
+    }
+}
+
+/// The type of a GraphQL operation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OperationType {
+    /// A `query`.
+    Query,
+    /// A `mutation`.
+    Mutation,
+    /// A `subscription`.
+    Subscription,
+}
+
+/// The metadata of an operation, usable as a trait object. It is implemented for all the [`GraphQLQuery`]
+/// types, so the operations of an application can be listed in a static registry without allocating.
+///
+/// ```
+/// use graphql_client::*;
+///
+/// #[derive(GraphQLQuery)]
+/// #[graphql(
+///   query_path = "../graphql_client_codegen/src/tests/star_wars_query.graphql",
+///   schema_path = "../graphql_client_codegen/src/tests/star_wars_schema.graphql"
+/// )]
+/// struct StarWarsQuery;
+///
+/// static OPERATIONS: &[&dyn OperationMeta] = &[&StarWarsQuery];
+///
+/// assert_eq!(OPERATIONS[0].operation_name(), "StarWarsQuery");
+/// assert_eq!(OPERATIONS[0].operation_type(), OperationType::Query);
+/// ```
+///
+/// To let the operations register themselves wherever they are defined, the registry can be a distributed
+/// slice from the `linkme` crate: `#[distributed_slice] static OPERATIONS: [&'static dyn OperationMeta] = [..];`,
+/// with `#[distributed_slice(OPERATIONS)] static STAR_WARS_QUERY: &dyn OperationMeta = &StarWarsQuery;` next to
+/// each operation.
+pub trait OperationMeta: Sync {
+    /// The name of the operation.
+    fn operation_name(&self) -> &'static str;
+    /// The query document sent to the server.
+    fn query(&self) -> &'static str;
+    /// The type of the operation.
+    fn operation_type(&self) -> OperationType;
+    /// The SHA-256 hash of the query document, as lowercase hexadecimal.
+    fn query_sha256(&self) -> &'static str;
+}
+
+impl<T: GraphQLQuery + Sync> OperationMeta for T {
+    fn operation_name(&self) -> &'static str {
+        T::OPERATION_NAME
+    }
+
+    fn query(&self) -> &'static str {
+        T::QUERY
+    }
+
+    fn operation_type(&self) -> OperationType {
+        T::OPERATION_TYPE
+    }
+
+    fn query_sha256(&self) -> &'static str {
+        T::QUERY_SHA256
+    }
 }
 
 /// The form in which queries are sent over HTTP in most implementations. This will be built using the [`GraphQLQuery`] trait normally.
@@ -78,13 +197,94 @@ pub trait GraphQLQuery {
 pub struct QueryBody<Variables> {
     /// The values for the variables. They must match those declared in the queries. This should be the `Variables` struct from the generated module corresponding to the query.
     pub variables: Variables,
-    /// The GraphQL query, as a string.
-    pub query: &'static str,
+    /// The GraphQL query, as a string. This is the query document of the operation, unless it was built with [`GraphQLQuery::build_query_with_query`].
+    pub query: Cow<'static, str>,
     /// The GraphQL operation name, as a string.
     #[serde(rename = "operationName")]
     pub operation_name: &'static str,
 }
 
+/// A directive on a GraphQL operation, as written in the query document. The generated modules expose the directives of their operation as a `DIRECTIVES` constant.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Directive {
+    /// The name of the directive, without the `@`.
+    pub name: &'static str,
+    /// The arguments of the directive, in order. String arguments are given without their quotes, the other scalars as they are written in the query.
+    pub arguments: &'static [(&'static str, &'static str)],
+}
+
+/// Implement `From` between two copies of a schema enum generated for different operations, going through
+/// their GraphQL values. Unknown values (`Other`) are kept as they are.
+///
+/// The enums must have the same values in the schema: this is checked at compile time.
+///
+/// ```
+/// use graphql_client::*;
+///
+/// #[derive(GraphQLQuery)]
+/// #[graphql(
+///   query_path = "tests/convert_enum/queries.graphql",
+///   schema_path = "tests/convert_enum/schema.graphql"
+/// )]
+/// struct IssueStates;
+///
+/// #[derive(GraphQLQuery)]
+/// #[graphql(
+///   query_path = "tests/convert_enum/queries.graphql",
+///   schema_path = "tests/convert_enum/schema.graphql"
+/// )]
+/// struct IssueTitles;
+///
+/// convert_enum!(issue_states::IssueState => issue_titles::IssueState);
+///
+/// let state: issue_titles::IssueState = issue_states::IssueState::CLOSED.into();
+/// assert_eq!(state.as_str(), "CLOSED");
+/// ```
+#[macro_export]
+macro_rules! convert_enum {
+    ($from:ty => $to:ty) => {
+        const _: () = assert!(
+            $crate::__enum_values_match(<$from>::VARIANTS, <$to>::VARIANTS),
+            "convert_enum!: the enums do not have the same values"
+        );
+
+        impl ::std::convert::From<$from> for $to {
+            fn from(value: $from) -> Self {
+                <$to>::from_value(value.as_str().to_owned())
+            }
+        }
+    };
+}
+
+/// Whether two lists of enum values are the same, for `convert_enum!`.
+#[doc(hidden)]
+pub const fn __enum_values_match(left: &[&str], right: &[&str]) -> bool {
+    if left.len() != right.len() {
+        return false;
+    }
+
+    let mut i = 0;
+    while i < left.len() {
+        let (a, b) = (left[i].as_bytes(), right[i].as_bytes());
+
+        if a.len() != b.len() {
+            return false;
+        }
+
+        let mut j = 0;
+        while j < a.len() {
+            if a[j] != b[j] {
+                return false;
+            }
+            j += 1;
+        }
+
+        i += 1;
+    }
+
+    true
+}
+
 /// Represents a location inside a query string. Used in errors. See [`Error`].
 #[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
 pub struct Location {
@@ -115,7 +315,9 @@ impl Display for PathFragment {
 
 /// An element in the top-level `errors` array of a response body.
 ///
-/// This tries to be as close to the spec as possible.
+/// This tries to be as close to the spec as possible. The `extensions` are a JSON object by default. A type
+/// for the extensions returned by a server can be plugged in with the `error_extensions` attribute of the
+/// derive, see [`Response`].
 ///
 /// [Spec](https://github.com/facebook/graphql/blob/master/spec/Section%207%20--%20Response.md)
 ///
@@ -179,7 +381,7 @@ impl Display for PathFragment {
 /// # }
 /// ```
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
-pub struct Error {
+pub struct Error<Extensions = HashMap<String, serde_json::Value>> {
     /// The human-readable error message. This is the only required field.
     pub message: String,
     /// Which locations in the query the error applies to.
@@ -187,10 +389,10 @@ pub struct Error {
     /// Which path in the query the error applies to, e.g. `["users", 0, "email"]`.
     pub path: Option<Vec<PathFragment>>,
     /// Additional errors. Their exact format is defined by the server.
-    pub extensions: Option<HashMap<String, serde_json::Value>>,
+    pub extensions: Option<Extensions>,
 }
 
-impl Display for Error {
+impl<Extensions> Display for Error<Extensions> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         // Use `/` as a separator like JSON Pointer.
         let path = self
@@ -222,7 +424,10 @@ impl Display for Error {
 
 /// The generic shape taken by the responses of GraphQL APIs.
 ///
-/// This will generally be used with the `ResponseData` struct from a derived module.
+/// This will generally be used with the `ResponseData` struct from a derived module. With the
+/// `error_extensions = "MyExtensions"` attribute, the derived module also has an `Extensions` alias for
+/// `MyExtensions` and a `Response` alias for `Response<ResponseData, Extensions>`, whose errors have typed
+/// extensions.
 ///
 /// [Spec](https://github.com/facebook/graphql/blob/master/spec/Section%207%20--%20Response.md)
 ///
@@ -272,11 +477,186 @@ impl Display for Error {
 /// # }
 /// ```
 #[derive(Debug, Serialize, Deserialize, PartialEq)]
-pub struct Response<Data> {
+pub struct Response<Data, Extensions = HashMap<String, serde_json::Value>> {
     /// The absent, partial or complete response data.
     pub data: Option<Data>,
     /// The top-level errors returned by the server.
-    pub errors: Option<Vec<Error>>,
+    pub errors: Option<Vec<Error<Extensions>>>,
+}
+
+impl<Data, Extensions> Response<Data, Extensions> {
+    /// The response data, when the server did not return any error.
+    ///
+    /// Responses with neither data nor errors (`{"data": null}`) are not allowed by the spec, but some servers return them, for example when nothing was found. They are reported as [`ResponseError::NoData`].
+    pub fn into_result(self) -> Result<Data, ResponseError<Extensions>> {
+        match (self.data, self.errors) {
+            (_, Some(errors)) if !errors.is_empty() => Err(ResponseError::Errors(errors)),
+            (Some(data), _) => Ok(data),
+            (None, _) => Err(ResponseError::NoData),
+        }
+    }
+
+    /// Whether the response has neither data nor errors, which the spec does not allow.
+    pub fn is_empty(&self) -> bool {
+        self.data.is_none() && self.errors.as_ref().map(Vec::is_empty).unwrap_or(true)
+    }
+}
+
+/// A [`Response`] along with its other top-level fields: the `extensions` returned by the server,
+/// and the fields added by gateways and proxies, like a `traceId`. [`Response`] ignores them, so that
+/// they are not buffered when they are not needed.
+///
+/// ```
+/// # use serde_json::json;
+/// use graphql_client::ResponseWithExtra;
+///
+/// let body: ResponseWithExtra<serde_json::Value> = serde_json::from_value(json!({
+///     "data": { "dogs": [] },
+///     "traceId": "f00d",
+/// }))?;
+///
+/// assert_eq!(body.response.data, Some(json!({ "dogs": [] })));
+/// assert_eq!(body.extra["traceId"], json!("f00d"));
+/// # Ok::<(), serde_json::Error>(())
+/// ```
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct ResponseWithExtra<Data, Extensions = HashMap<String, serde_json::Value>> {
+    /// The data and the errors of the response.
+    #[serde(flatten)]
+    pub response: Response<Data, Extensions>,
+    /// The other top-level fields of the response.
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
+}
+
+/// Why a [`Response`] could not be turned into its data. See [`Response::into_result`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum ResponseError<Extensions = HashMap<String, serde_json::Value>> {
+    /// The server returned errors, with or without partial data.
+    Errors(Vec<Error<Extensions>>),
+    /// The server returned neither data nor errors.
+    NoData,
+}
+
+impl<Extensions> Display for ResponseError<Extensions> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ResponseError::Errors(errors) => {
+                write!(f, "The server returned {} error(s)", errors.len())?;
+
+                for error in errors {
+                    write!(f, "\n{}", error)?;
+                }
+
+                Ok(())
+            }
+            ResponseError::NoData => f.write_str("The server returned neither data nor errors"),
+        }
+    }
+}
+
+impl<Extensions: fmt::Debug> std::error::Error for ResponseError<Extensions> {}
+
+/// Why a response body could not be decoded. See [`parse_response`].
+#[derive(Debug)]
+pub enum DecodeError {
+    /// The response body is empty.
+    EmptyBody,
+    /// The response body is a JSON object without `data` and `errors` keys.
+    MissingDataAndErrors,
+    /// The response body is not JSON, or does not match the generated code.
+    Json(serde_json::Error),
+}
+
+impl Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DecodeError::EmptyBody => f.write_str("The response body is empty"),
+            DecodeError::MissingDataAndErrors => {
+                f.write_str("The response has neither a `data` nor an `errors` key")
+            }
+            DecodeError::Json(err) => write!(f, "Invalid response: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for DecodeError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            DecodeError::Json(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+/// Decode a response body, with errors naming what is missing for empty bodies and bodies without `data` or `errors`.
+///
+/// ```
+/// # use graphql_client::{parse_response, DecodeError, Response, ResponseError};
+/// let response: Response<serde_json::Value> = parse_response(r#"{"data": null}"#)?;
+/// assert_eq!(response.into_result(), Err(ResponseError::NoData));
+///
+/// let error = parse_response::<serde_json::Value>("{}").unwrap_err();
+/// assert_eq!(error.to_string(), "The response has neither a `data` nor an `errors` key");
+/// # Ok::<(), DecodeError>(())
+/// ```
+pub fn parse_response<Data>(body: &str) -> Result<Response<Data>, DecodeError>
+where
+    Data: for<'de> Deserialize<'de>,
+{
+    if body.trim().is_empty() {
+        return Err(DecodeError::EmptyBody);
+    }
+
+    let value: serde_json::Value = serde_json::from_str(body).map_err(DecodeError::Json)?;
+
+    if let serde_json::Value::Object(object) = &value {
+        if !object.contains_key("data") && !object.contains_key("errors") {
+            return Err(DecodeError::MissingDataAndErrors);
+        }
+    }
+
+    serde_json::from_value(value).map_err(DecodeError::Json)
+}
+
+/// Serialize the variables of a query to JSON with the keys of every object sorted, including in nested input objects, for a serialization that does not depend on the order of the fields in the `Variables` struct. This is useful for signing requests or as a cache key.
+///
+/// ```
+/// # use graphql_client::canonical_variables_json;
+/// #[derive(serde::Serialize)]
+/// struct Variables {
+///     name: String,
+///     age: i64,
+/// }
+///
+/// let variables = Variables { name: "Ferris".to_owned(), age: 5 };
+/// assert_eq!(canonical_variables_json(&variables)?, r#"{"age":5,"name":"Ferris"}"#);
+/// # Ok::<(), serde_json::Error>(())
+/// ```
+pub fn canonical_variables_json<Variables>(
+    variables: &Variables,
+) -> Result<String, serde_json::Error>
+where
+    Variables: Serialize,
+{
+    serde_json::to_string(&sort_keys(serde_json::to_value(variables)?))
+}
+
+/// Rebuild the objects in a JSON value with their keys in sorted order. `serde_json` maps are not sorted when its `preserve_order` feature is enabled, so this does not rely on the default `BTreeMap` representation.
+fn sort_keys(value: serde_json::Value) -> serde_json::Value {
+    match value {
+        serde_json::Value::Object(object) => {
+            let sorted: std::collections::BTreeMap<String, serde_json::Value> = object
+                .into_iter()
+                .map(|(key, value)| (key, sort_keys(value)))
+                .collect();
+            serde_json::Value::Object(sorted.into_iter().collect())
+        }
+        serde_json::Value::Array(items) => {
+            serde_json::Value::Array(items.into_iter().map(sort_keys).collect())
+        }
+        other => other,
+    }
 }
 
 #[cfg(test)]
@@ -284,6 +664,67 @@ mod tests {
     use super::*;
     use serde_json::json;
 
+    #[test]
+    fn null_data_without_errors_is_no_data() {
+        let response: Response<serde_json::Value> = parse_response(r#"{"data": null}"#).unwrap();
+
+        assert!(response.is_empty());
+        assert_eq!(response.into_result(), Err(ResponseError::NoData));
+
+        let response: Response<serde_json::Value> =
+            parse_response(r#"{"data": null, "errors": []}"#).unwrap();
+
+        assert_eq!(response.into_result(), Err(ResponseError::NoData));
+    }
+
+    #[test]
+    fn errors_take_precedence_over_data() {
+        let response: Response<serde_json::Value> = parse_response(
+            r#"{"data": {"dogs": []}, "errors": [{"message": "Dogs are sleeping"}]}"#,
+        )
+        .unwrap();
+
+        assert!(!response.is_empty());
+        match response.into_result() {
+            Err(ResponseError::Errors(errors)) => {
+                assert_eq!(errors[0].message, "Dogs are sleeping")
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn extra_top_level_fields_are_kept() {
+        let response: ResponseWithExtra<serde_json::Value> = serde_json::from_str(
+            r#"{"data": {"dogs": []}, "extensions": {"cost": 3}, "traceId": "f00d"}"#,
+        )
+        .unwrap();
+
+        assert_eq!(response.response.data, Some(json!({"dogs": []})));
+        assert_eq!(response.response.errors, None);
+        assert_eq!(response.extra["traceId"], json!("f00d"));
+        assert_eq!(response.extra["extensions"], json!({"cost": 3}));
+        assert_eq!(
+            serde_json::to_value(&response).unwrap(),
+            json!({"data": {"dogs": []}, "errors": null, "extensions": {"cost": 3}, "traceId": "f00d"})
+        );
+    }
+
+    #[test]
+    fn empty_bodies_are_decode_errors() {
+        let error = parse_response::<serde_json::Value>("").unwrap_err();
+        assert_eq!(error.to_string(), "The response body is empty");
+
+        let error = parse_response::<serde_json::Value>("{}").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "The response has neither a `data` nor an `errors` key"
+        );
+
+        let error = parse_response::<serde_json::Value>("{").unwrap_err();
+        assert!(matches!(error, DecodeError::Json(_)));
+    }
+
     #[test]
     fn graphql_error_works_with_just_message() {
         let err = json!({
@@ -381,4 +822,58 @@ mod tests {
             }
         )
     }
+
+    #[test]
+    fn canonical_variables_json_does_not_depend_on_field_order() {
+        #[derive(Serialize)]
+        struct Filter {
+            state: &'static str,
+            author: &'static str,
+        }
+
+        #[derive(Serialize)]
+        struct Variables {
+            query: &'static str,
+            filters: Vec<Filter>,
+            first: i64,
+        }
+
+        #[derive(Serialize)]
+        struct ReorderedFilter {
+            author: &'static str,
+            state: &'static str,
+        }
+
+        #[derive(Serialize)]
+        struct ReorderedVariables {
+            first: i64,
+            filters: Vec<ReorderedFilter>,
+            query: &'static str,
+        }
+
+        let variables = Variables {
+            query: "graphql",
+            filters: vec![Filter {
+                state: "OPEN",
+                author: "tomhoule",
+            }],
+            first: 10,
+        };
+        let reordered_variables = ReorderedVariables {
+            first: 10,
+            filters: vec![ReorderedFilter {
+                author: "tomhoule",
+                state: "OPEN",
+            }],
+            query: "graphql",
+        };
+        let expected =
+            r#"{"filters":[{"author":"tomhoule","state":"OPEN"}],"first":10,"query":"graphql"}"#;
+
+        assert_eq!(canonical_variables_json(&variables).unwrap(), expected);
+        assert_eq!(
+            canonical_variables_json(&reordered_variables).unwrap(),
+            expected
+        );
+    }
 }
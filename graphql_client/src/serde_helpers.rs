@@ -0,0 +1,455 @@
+//! Helpers referenced by generated code to customize serialization and deserialization.
+
+use serde::de::{self, DeserializeSeed, MapAccess, SeqAccess, Visitor};
+use serde::ser::{SerializeMap, Serializer};
+use serde::{Deserialize, Deserializer, Serialize};
+use std::collections::HashSet;
+use std::fmt;
+
+/// Deserialize a nullable value, falling back to the type's default value when it is `null`.
+///
+/// This is used on response fields when the `null_coercion` option is enabled, so nullable lists and strings can be used without unwrapping an `Option`.
+pub fn null_as_default<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de> + Default,
+{
+    Ok(Option::<T>::deserialize(deserializer)?.unwrap_or_default())
+}
+
+/// A serializer that leaves out the entries of a map whose key was already serialized.
+///
+/// The response structs with flattened fragments or `on` enums serialize through it: the fragments and the enums can select
+/// the same fields, like `__typename`, and the serialized response would otherwise have duplicate keys, which it cannot be
+/// deserialized from. The duplicated fields have the same value, since they come from the same response.
+pub struct DistinctKeys<S>(pub S);
+
+/// The map of a [`DistinctKeys`] serializer.
+pub struct DistinctKeysMap<M> {
+    map: M,
+    keys: HashSet<String>,
+    skips_value: bool,
+}
+
+macro_rules! forward_serialize {
+    ($($method:ident($($arg:ident: $ty:ty),*) -> $ok:ty;)*) => {
+        $(
+            fn $method(self, $($arg: $ty),*) -> Result<$ok, Self::Error> {
+                self.0.$method($($arg),*)
+            }
+        )*
+    };
+}
+
+impl<S: Serializer> Serializer for DistinctKeys<S> {
+    type Ok = S::Ok;
+    type Error = S::Error;
+    type SerializeSeq = S::SerializeSeq;
+    type SerializeTuple = S::SerializeTuple;
+    type SerializeTupleStruct = S::SerializeTupleStruct;
+    type SerializeTupleVariant = S::SerializeTupleVariant;
+    type SerializeMap = DistinctKeysMap<S::SerializeMap>;
+    type SerializeStruct = S::SerializeStruct;
+    type SerializeStructVariant = S::SerializeStructVariant;
+
+    forward_serialize! {
+        serialize_bool(v: bool) -> S::Ok;
+        serialize_i8(v: i8) -> S::Ok;
+        serialize_i16(v: i16) -> S::Ok;
+        serialize_i32(v: i32) -> S::Ok;
+        serialize_i64(v: i64) -> S::Ok;
+        serialize_i128(v: i128) -> S::Ok;
+        serialize_u8(v: u8) -> S::Ok;
+        serialize_u16(v: u16) -> S::Ok;
+        serialize_u32(v: u32) -> S::Ok;
+        serialize_u64(v: u64) -> S::Ok;
+        serialize_u128(v: u128) -> S::Ok;
+        serialize_f32(v: f32) -> S::Ok;
+        serialize_f64(v: f64) -> S::Ok;
+        serialize_char(v: char) -> S::Ok;
+        serialize_str(v: &str) -> S::Ok;
+        serialize_bytes(v: &[u8]) -> S::Ok;
+        serialize_none() -> S::Ok;
+        serialize_unit() -> S::Ok;
+        serialize_unit_struct(name: &'static str) -> S::Ok;
+        serialize_unit_variant(name: &'static str, index: u32, variant: &'static str) -> S::Ok;
+        serialize_seq(len: Option<usize>) -> S::SerializeSeq;
+        serialize_tuple(len: usize) -> S::SerializeTuple;
+        serialize_tuple_struct(name: &'static str, len: usize) -> S::SerializeTupleStruct;
+        serialize_tuple_variant(name: &'static str, index: u32, variant: &'static str, len: usize) -> S::SerializeTupleVariant;
+        serialize_struct(name: &'static str, len: usize) -> S::SerializeStruct;
+        serialize_struct_variant(name: &'static str, index: u32, variant: &'static str, len: usize) -> S::SerializeStructVariant;
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<S::Ok, S::Error> {
+        self.0.serialize_some(value)
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        name: &'static str,
+        value: &T,
+    ) -> Result<S::Ok, S::Error> {
+        self.0.serialize_newtype_struct(name, value)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        name: &'static str,
+        index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<S::Ok, S::Error> {
+        self.0
+            .serialize_newtype_variant(name, index, variant, value)
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<Self::SerializeMap, S::Error> {
+        Ok(DistinctKeysMap {
+            map: self.0.serialize_map(len)?,
+            keys: HashSet::new(),
+            skips_value: false,
+        })
+    }
+
+    fn is_human_readable(&self) -> bool {
+        self.0.is_human_readable()
+    }
+}
+
+impl<M: SerializeMap> SerializeMap for DistinctKeysMap<M> {
+    type Ok = M::Ok;
+    type Error = M::Error;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<(), M::Error> {
+        // The keys of the response structs are field names.
+        if let Ok(serde_json::Value::String(key)) = serde_json::to_value(key) {
+            if !self.keys.insert(key) {
+                self.skips_value = true;
+                return Ok(());
+            }
+        }
+
+        self.map.serialize_key(key)
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), M::Error> {
+        if self.skips_value {
+            self.skips_value = false;
+            return Ok(());
+        }
+
+        self.map.serialize_value(value)
+    }
+
+    fn end(self) -> Result<M::Ok, M::Error> {
+        self.map.end()
+    }
+}
+
+/// A deserializer that reports the missing fields of a struct with their GraphQL name and the GraphQL type of the struct,
+/// e.g. "missing non-null field `createdAt` on `Issue`", instead of "missing field `createdAt`".
+///
+/// The response structs deserialize through it with the `graphql_error_names` option. The other errors are left as they are.
+pub struct GraphQLErrorNames<D>(pub D, pub &'static str);
+
+macro_rules! forward_deserialize {
+    ($($method:ident($($arg:ident: $ty:ty),*);)*) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, $($arg: $ty,)* visitor: V) -> Result<V::Value, D::Error> {
+                self.0.$method($($arg,)* visitor)
+            }
+        )*
+    };
+}
+
+impl<'de, D: Deserializer<'de>> Deserializer<'de> for GraphQLErrorNames<D> {
+    type Error = D::Error;
+
+    forward_deserialize! {
+        deserialize_any();
+        deserialize_bool();
+        deserialize_i8();
+        deserialize_i16();
+        deserialize_i32();
+        deserialize_i64();
+        deserialize_i128();
+        deserialize_u8();
+        deserialize_u16();
+        deserialize_u32();
+        deserialize_u64();
+        deserialize_u128();
+        deserialize_f32();
+        deserialize_f64();
+        deserialize_char();
+        deserialize_str();
+        deserialize_string();
+        deserialize_bytes();
+        deserialize_byte_buf();
+        deserialize_option();
+        deserialize_unit();
+        deserialize_unit_struct(name: &'static str);
+        deserialize_newtype_struct(name: &'static str);
+        deserialize_seq();
+        deserialize_tuple(len: usize);
+        deserialize_tuple_struct(name: &'static str, len: usize);
+        deserialize_enum(name: &'static str, variants: &'static [&'static str]);
+        deserialize_identifier();
+        deserialize_ignored_any();
+    }
+
+    fn deserialize_map<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, D::Error> {
+        self.0.deserialize_map(NamedVisitor(visitor, self.1))
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, D::Error> {
+        self.0
+            .deserialize_struct(name, fields, NamedVisitor(visitor, self.1))
+    }
+
+    fn is_human_readable(&self) -> bool {
+        self.0.is_human_readable()
+    }
+}
+
+/// The visitor of a struct, with the GraphQL type of the struct.
+struct NamedVisitor<V>(V, &'static str);
+
+impl<'de, V: Visitor<'de>> Visitor<'de> for NamedVisitor<V> {
+    type Value = V::Value;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.expecting(formatter)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, seq: A) -> Result<V::Value, A::Error> {
+        self.0.visit_seq(seq)
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, map: A) -> Result<V::Value, A::Error> {
+        let type_name = self.1;
+
+        self.0
+            .visit_map(NamedMap(map))
+            .map_err(|err| err.into_error(type_name))
+    }
+}
+
+/// The map of a struct, whose errors tell the missing fields apart.
+struct NamedMap<A>(A);
+
+impl<'de, A: MapAccess<'de>> MapAccess<'de> for NamedMap<A> {
+    type Error = NamedError<A::Error>;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, Self::Error> {
+        self.0.next_key_seed(seed).map_err(NamedError::Other)
+    }
+
+    fn next_value_seed<T: DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<T::Value, Self::Error> {
+        self.0.next_value_seed(seed).map_err(NamedError::Other)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        self.0.size_hint()
+    }
+}
+
+/// An error of a [`NamedMap`].
+#[derive(Debug)]
+enum NamedError<E> {
+    MissingField(&'static str),
+    Other(E),
+}
+
+impl<E: de::Error> NamedError<E> {
+    fn into_error(self, type_name: &str) -> E {
+        match self {
+            NamedError::MissingField(field) => E::custom(format_args!(
+                "missing non-null field `{}` on `{}`",
+                field, type_name
+            )),
+            NamedError::Other(err) => err,
+        }
+    }
+}
+
+impl<E: de::Error> de::Error for NamedError<E> {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        NamedError::Other(E::custom(msg))
+    }
+
+    fn invalid_type(unexp: de::Unexpected<'_>, exp: &dyn de::Expected) -> Self {
+        NamedError::Other(E::invalid_type(unexp, exp))
+    }
+
+    fn invalid_value(unexp: de::Unexpected<'_>, exp: &dyn de::Expected) -> Self {
+        NamedError::Other(E::invalid_value(unexp, exp))
+    }
+
+    fn invalid_length(len: usize, exp: &dyn de::Expected) -> Self {
+        NamedError::Other(E::invalid_length(len, exp))
+    }
+
+    fn unknown_variant(variant: &str, expected: &'static [&'static str]) -> Self {
+        NamedError::Other(E::unknown_variant(variant, expected))
+    }
+
+    fn unknown_field(field: &str, expected: &'static [&'static str]) -> Self {
+        NamedError::Other(E::unknown_field(field, expected))
+    }
+
+    fn missing_field(field: &'static str) -> Self {
+        NamedError::MissingField(field)
+    }
+
+    fn duplicate_field(field: &'static str) -> Self {
+        NamedError::Other(E::duplicate_field(field))
+    }
+}
+
+impl<E: fmt::Display> fmt::Display for NamedError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NamedError::MissingField(field) => write!(f, "missing field `{}`", field),
+            NamedError::Other(err) => err.fmt(f),
+        }
+    }
+}
+
+impl<E: std::error::Error> std::error::Error for NamedError<E> {}
+
+/// (De)serialization of the date and time types of the `time` crate as ISO 8601 strings, for the
+/// `date_time_crate = "time"` option: date-times as RFC 3339 strings, dates as `2020-03-13` and
+/// times as `18:30:00`. The fields can be optional or lists of these types.
+#[cfg(feature = "time")]
+pub mod iso8601 {
+    use serde::{de, ser, Deserialize, Deserializer, Serialize, Serializer};
+    use time::format_description::well_known::Rfc3339;
+    use time::macros::format_description;
+
+    /// A type (de)serialized as ISO 8601 strings.
+    pub trait Iso8601: Sized {
+        /// Serialize the value as ISO 8601 strings.
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error>;
+
+        /// Deserialize the value from ISO 8601 strings.
+        fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error>;
+    }
+
+    /// Serialize a value as ISO 8601 strings.
+    pub fn serialize<T: Iso8601, S: Serializer>(
+        value: &T,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        value.serialize(serializer)
+    }
+
+    /// Deserialize a value from ISO 8601 strings.
+    pub fn deserialize<'de, T: Iso8601, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<T, D::Error> {
+        T::deserialize(deserializer)
+    }
+
+    impl Iso8601 for time::OffsetDateTime {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            let formatted = self.format(&Rfc3339).map_err(ser::Error::custom)?;
+            serializer.serialize_str(&formatted)
+        }
+
+        fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            let value = String::deserialize(deserializer)?;
+            time::OffsetDateTime::parse(&value, &Rfc3339).map_err(de::Error::custom)
+        }
+    }
+
+    impl Iso8601 for time::Date {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            let formatted = self
+                .format(format_description!("[year]-[month]-[day]"))
+                .map_err(ser::Error::custom)?;
+            serializer.serialize_str(&formatted)
+        }
+
+        fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            let value = String::deserialize(deserializer)?;
+            time::Date::parse(&value, format_description!("[year]-[month]-[day]"))
+                .map_err(de::Error::custom)
+        }
+    }
+
+    impl Iso8601 for time::Time {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            // The fractional seconds are only written when there are some.
+            let formatted = if self.nanosecond() == 0 {
+                self.format(format_description!("[hour]:[minute]:[second]"))
+            } else {
+                self.format(format_description!("[hour]:[minute]:[second].[subsecond]"))
+            };
+            serializer.serialize_str(&formatted.map_err(ser::Error::custom)?)
+        }
+
+        fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            let value = String::deserialize(deserializer)?;
+            time::Time::parse(
+                &value,
+                format_description!("[hour]:[minute]:[second][optional [.[subsecond]]]"),
+            )
+            .map_err(de::Error::custom)
+        }
+    }
+
+    impl<T: Iso8601> Iso8601 for Option<T> {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            match self {
+                Some(value) => serializer.serialize_some(&Ser(value)),
+                None => serializer.serialize_none(),
+            }
+        }
+
+        fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            let value = Option::<De<T>>::deserialize(deserializer)?;
+            Ok(value.map(|De(value)| value))
+        }
+    }
+
+    impl<T: Iso8601> Iso8601 for Vec<T> {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.collect_seq(self.iter().map(Ser))
+        }
+
+        fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            let values = Vec::<De<T>>::deserialize(deserializer)?;
+            Ok(values.into_iter().map(|De(value)| value).collect())
+        }
+    }
+
+    /// The items of the optional values and the lists, serialized as ISO 8601 strings.
+    struct Ser<'a, T>(&'a T);
+
+    impl<T: Iso8601> Serialize for Ser<'_, T> {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            Iso8601::serialize(self.0, serializer)
+        }
+    }
+
+    /// The items of the optional values and the lists, deserialized from ISO 8601 strings.
+    struct De<T>(T);
+
+    impl<'de, T: Iso8601> Deserialize<'de> for De<T> {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            Iso8601::deserialize(deserializer).map(De)
+        }
+    }
+}
@@ -0,0 +1,62 @@
+//! Parse subscription events delivered over
+//! [Server-Sent Events](https://github.com/enisdenjo/graphql-sse/blob/master/PROTOCOL.md)
+//! (the `graphql-sse` protocol) into typed responses.
+
+use crate::*;
+use thiserror::*;
+
+/// A subscription event received over SSE.
+#[derive(Debug, PartialEq)]
+pub enum SubscriptionEvent<Data> {
+    /// A `next` event, carrying an execution result.
+    Next(Response<Data>),
+    /// A `complete` event: the server will not send more results for this subscription.
+    Complete,
+}
+
+/// All the ways an SSE event can fail to be parsed.
+#[derive(Debug, Error)]
+pub enum SseError {
+    /// The event type is neither `next` nor `complete`
+    #[error("Unknown SSE event type: {0}")]
+    UnknownEvent(String),
+    /// The data of a `next` event does not match the generated code
+    #[error("Response shape error")]
+    ResponseShape(#[from] serde_json::Error),
+}
+
+/// Parse a single SSE event, from the value of its `event:` field and the contents of its `data:` field(s).
+///
+/// The `data` of `complete` events is ignored.
+///
+/// ```
+/// # use graphql_client::sse::{parse_sse_event, SubscriptionEvent};
+/// # use serde::Deserialize;
+/// #[derive(Debug, Deserialize, PartialEq)]
+/// struct ResponseData {
+///     greeting: String,
+/// }
+///
+/// let event = parse_sse_event::<ResponseData>("next", r#"{"data":{"greeting":"hi"}}"#)?;
+///
+/// match event {
+///     SubscriptionEvent::Next(response) => {
+///         assert_eq!(response.data.unwrap().greeting, "hi");
+///     }
+///     SubscriptionEvent::Complete => unreachable!(),
+/// }
+/// # Ok::<(), graphql_client::sse::SseError>(())
+/// ```
+pub fn parse_sse_event<Data>(
+    event_type: &str,
+    data: &str,
+) -> Result<SubscriptionEvent<Data>, SseError>
+where
+    Data: for<'de> Deserialize<'de>,
+{
+    match event_type {
+        "next" => Ok(SubscriptionEvent::Next(serde_json::from_str(data)?)),
+        "complete" => Ok(SubscriptionEvent::Complete),
+        other => Err(SseError::UnknownEvent(other.to_owned())),
+    }
+}
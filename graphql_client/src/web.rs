@@ -24,7 +24,7 @@ pub struct Client {
 /// All the ways a request can go wrong.
 ///
 /// not exhaustive
-#[derive(Debug, Error, PartialEq)]
+#[derive(Debug, Error)]
 pub enum ClientError {
     /// The body couldn't be built
     #[error("Request body is not a valid string")]
@@ -42,7 +42,7 @@ pub enum ClientError {
     NoWindow,
     /// Response shape does not match the generated code
     #[error("Response shape error")]
-    ResponseShape,
+    ResponseShape(#[source] DecodeError),
     /// Response could not be converted to text
     #[error("Response conversion to text failed (Response.text threw)")]
     ResponseText,
@@ -85,7 +85,7 @@ impl Client {
         let custom_headers = self.headers.clone();
 
         web_sys::window()
-            .ok_or_else(|| ClientError::NoWindow)
+            .ok_or(ClientError::NoWindow)
             .into_future()
             .and_then(move |window| {
                 serde_json::to_string(&Q::build_query(variables))
@@ -93,10 +93,9 @@ impl Client {
                     .map(move |body| (window, body))
             })
             .and_then(move |(window, body)| {
-                let mut request_init = web_sys::RequestInit::new();
-                request_init
-                    .method("POST")
-                    .body(Some(&JsValue::from_str(&body)));
+                let request_init = web_sys::RequestInit::new();
+                request_init.set_method("POST");
+                request_init.set_body(&JsValue::from_str(&body));
 
                 web_sys::Request::new_with_str_and_init(&endpoint, &request_init)
                     .map_err(|_| ClientError::JsException)
@@ -138,7 +137,14 @@ impl Client {
             .and_then(|text| {
                 let response_text = text.as_string().unwrap_or_default();
                 debug!("response text as string: {:?}", response_text);
-                serde_json::from_str(&response_text).map_err(|_| ClientError::ResponseShape)
+                let response: crate::Response<Q::ResponseData> =
+                    parse_response(&response_text).map_err(ClientError::ResponseShape)?;
+
+                if response.is_empty() {
+                    warn!("The server returned neither data nor errors, which the GraphQL spec does not allow.");
+                }
+
+                Ok(response)
             })
     }
 }
@@ -0,0 +1,262 @@
+//! Decode the messages of subscriptions delivered over WebSockets, in the
+//! [graphql-transport-ws](https://github.com/enisdenjo/graphql-ws/blob/master/PROTOCOL.md)
+//! protocol or in the legacy
+//! [subscriptions-transport-ws](https://github.com/apollographql/subscriptions-transport-ws/blob/master/PROTOCOL.md)
+//! protocol, into typed responses. There is no I/O: the text frames are fed by the caller.
+
+use crate::*;
+use std::marker::PhantomData;
+use thiserror::*;
+
+/// A message of the server, in either protocol.
+#[derive(Debug, PartialEq)]
+pub enum ServerMessage<Data> {
+    /// `connection_ack`: the server accepted the connection.
+    ConnectionAck,
+    /// `ka`: a keep-alive of the legacy protocol.
+    KeepAlive,
+    /// `ping`: the server expects a `pong` message in reply.
+    Ping,
+    /// `pong`: the reply to a `ping` message of the client.
+    Pong,
+    /// `next`, or `data` in the legacy protocol: an execution result of the subscription.
+    Next {
+        /// The id of the subscription.
+        id: String,
+        /// The execution result.
+        payload: Response<Data>,
+    },
+    /// `error`: the subscription failed, for example because the operation is invalid. The
+    /// server sends no other message for the subscription.
+    Error {
+        /// The id of the subscription.
+        id: String,
+        /// The errors.
+        payload: Vec<Error>,
+    },
+    /// `complete`: the server will not send more results for the subscription.
+    Complete {
+        /// The id of the subscription.
+        id: String,
+    },
+}
+
+/// All the ways a message can fail to be decoded.
+#[derive(Debug, Error)]
+pub enum WsError {
+    /// The frame is not a JSON object with a `type`, or a field of the message is missing
+    #[error("Invalid message: {0}")]
+    InvalidMessage(serde_json::Error),
+    /// The message type is not one the server sends
+    #[error("Unknown message type: {0}")]
+    UnknownMessage(String),
+    /// The payload of a `next` message does not match the generated code
+    #[error("Response shape error")]
+    ResponseShape(serde_json::Error),
+    /// The message is for another subscription
+    #[error("Message for the subscription {found}, expected {expected}")]
+    UnexpectedId {
+        /// The id of the subscription of the decoder.
+        expected: String,
+        /// The id of the message.
+        found: String,
+    },
+    /// The message was received after the subscription completed
+    #[error("Message for the subscription {0} after it completed")]
+    AfterCompletion(String),
+}
+
+/// The fields of the messages of both protocols.
+#[derive(Deserialize)]
+struct RawMessage {
+    #[serde(rename = "type")]
+    message_type: String,
+    id: Option<String>,
+    #[serde(default)]
+    payload: serde_json::Value,
+}
+
+/// The payload of `error` messages: a list of errors, or a single error in the legacy protocol.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum ErrorPayload {
+    Many(Vec<Error>),
+    One(Error),
+}
+
+/// Parse a single text frame sent by the server.
+///
+/// ```
+/// # use graphql_client::ws::{parse_server_message, ServerMessage};
+/// # use serde::Deserialize;
+/// #[derive(Debug, Deserialize, PartialEq)]
+/// struct ResponseData {
+///     greeting: String,
+/// }
+///
+/// let frame = r#"{"type":"next","id":"1","payload":{"data":{"greeting":"hi"}}}"#;
+///
+/// match parse_server_message::<ResponseData>(frame)? {
+///     ServerMessage::Next { id, payload } => {
+///         assert_eq!(id, "1");
+///         assert_eq!(payload.data.unwrap().greeting, "hi");
+///     }
+///     _ => unreachable!(),
+/// }
+/// # Ok::<(), graphql_client::ws::WsError>(())
+/// ```
+pub fn parse_server_message<Data>(frame: &str) -> Result<ServerMessage<Data>, WsError>
+where
+    Data: for<'de> Deserialize<'de>,
+{
+    let RawMessage {
+        message_type,
+        id,
+        payload,
+    } = serde_json::from_str(frame).map_err(WsError::InvalidMessage)?;
+    let id =
+        move || id.ok_or_else(|| WsError::InvalidMessage(serde::de::Error::missing_field("id")));
+
+    match message_type.as_str() {
+        "connection_ack" => Ok(ServerMessage::ConnectionAck),
+        "ka" => Ok(ServerMessage::KeepAlive),
+        "ping" => Ok(ServerMessage::Ping),
+        "pong" => Ok(ServerMessage::Pong),
+        "next" | "data" => Ok(ServerMessage::Next {
+            id: id()?,
+            payload: serde_json::from_value(payload).map_err(WsError::ResponseShape)?,
+        }),
+        "error" => {
+            let payload = match serde_json::from_value(payload).map_err(WsError::InvalidMessage)? {
+                ErrorPayload::Many(errors) => errors,
+                ErrorPayload::One(error) => vec![error],
+            };
+            Ok(ServerMessage::Error { id: id()?, payload })
+        }
+        "complete" => Ok(ServerMessage::Complete { id: id()? }),
+        other => Err(WsError::UnknownMessage(other.to_owned())),
+    }
+}
+
+/// What a frame means for a subscription, as decoded by a [`SubscriptionDecoder`].
+#[derive(Debug)]
+pub enum Decoded<Data> {
+    /// A keep-alive, a `pong`, or a `connection_ack`: there is nothing to do.
+    Ignore,
+    /// A `ping`: the server expects a `pong` message in reply.
+    Ping,
+    /// An execution result. The errors of an `error` message are a response without data, and
+    /// the last one of the subscription: only a `complete` message may follow it.
+    Data(Response<Data>),
+    /// The server will not send more results.
+    Completed,
+    /// The frame could not be decoded, or breaks the protocol.
+    ProtocolError(WsError),
+}
+
+/// Decodes the frames of a subscription of the `Q` operation, tracking its id and whether it
+/// completed.
+///
+/// The decoder does no I/O and only changes once a frame is decoded, so dropping a future that
+/// awaits the next frame loses no message: it is cancellation-safe.
+///
+/// ```
+/// # use graphql_client::ws::{Decoded, SubscriptionDecoder};
+/// # use graphql_client::GraphQLQuery;
+/// #[derive(GraphQLQuery)]
+/// #[graphql(
+///   query_path = "tests/subscription/subscription_query.graphql",
+///   schema_path = "tests/subscription/subscription_schema.graphql"
+/// )]
+/// struct SubscriptionQuery;
+///
+/// let mut decoder = SubscriptionDecoder::<SubscriptionQuery>::new("1");
+///
+/// assert!(matches!(decoder.decode(r#"{"type":"ka"}"#), Decoded::Ignore));
+/// match decoder.decode(r#"{"type":"next","id":"1","payload":{"data":{"dogBirthdays":[]}}}"#) {
+///     Decoded::Data(response) => assert_eq!(response.data.unwrap().dog_birthdays.unwrap().len(), 0),
+///     _ => panic!("expected a response"),
+/// }
+/// assert!(matches!(decoder.decode(r#"{"type":"complete","id":"1"}"#), Decoded::Completed));
+/// assert!(decoder.is_completed());
+/// ```
+pub struct SubscriptionDecoder<Q: GraphQLQuery> {
+    id: String,
+    state: State,
+    query: PhantomData<fn() -> Q>,
+}
+
+/// Where a subscription is in its lifecycle.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum State {
+    Active,
+    /// After an `error` message. Some servers still send a `complete` message.
+    Failed,
+    Completed,
+}
+
+impl<Q: GraphQLQuery> SubscriptionDecoder<Q> {
+    /// A decoder for the subscription started with this id.
+    pub fn new(id: impl Into<String>) -> Self {
+        SubscriptionDecoder {
+            id: id.into(),
+            state: State::Active,
+            query: PhantomData,
+        }
+    }
+
+    /// The id of the subscription.
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// Whether the server completed the subscription, with a `complete` or an `error` message.
+    pub fn is_completed(&self) -> bool {
+        self.state != State::Active
+    }
+
+    /// Decode a text frame sent by the server.
+    pub fn decode(&mut self, frame: &str) -> Decoded<Q::ResponseData> {
+        let message = match parse_server_message::<Q::ResponseData>(frame) {
+            Ok(message) => message,
+            Err(err) => return Decoded::ProtocolError(err),
+        };
+
+        let id = match &message {
+            ServerMessage::ConnectionAck | ServerMessage::KeepAlive | ServerMessage::Pong => {
+                return Decoded::Ignore
+            }
+            ServerMessage::Ping => return Decoded::Ping,
+            ServerMessage::Next { id, .. }
+            | ServerMessage::Error { id, .. }
+            | ServerMessage::Complete { id } => id,
+        };
+
+        if *id != self.id {
+            return Decoded::ProtocolError(WsError::UnexpectedId {
+                expected: self.id.clone(),
+                found: id.clone(),
+            });
+        }
+
+        match (self.state, message) {
+            (State::Active, ServerMessage::Next { payload, .. }) => Decoded::Data(payload),
+            (State::Active, ServerMessage::Error { payload, .. }) => {
+                self.state = State::Failed;
+                Decoded::Data(Response {
+                    data: None,
+                    errors: Some(payload),
+                })
+            }
+            (State::Active, ServerMessage::Complete { .. })
+            | (State::Failed, ServerMessage::Complete { .. }) => {
+                self.state = State::Completed;
+                Decoded::Completed
+            }
+            (State::Failed, _) | (State::Completed, _) => {
+                Decoded::ProtocolError(WsError::AfterCompletion(self.id.clone()))
+            }
+            (State::Active, _) => unreachable!("The message has no id."),
+        }
+    }
+}
@@ -0,0 +1,62 @@
+use graphql_client::*;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "tests/unions/union_schema.graphql",
+    query_path = "tests/anonymous_operations/query.graphql",
+    response_derives = "Debug"
+)]
+pub struct DogNames;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "tests/unions/union_schema.graphql",
+    query_path = "tests/anonymous_operations/shorthand_query.graphql",
+    response_derives = "Debug"
+)]
+pub struct SearchTotal;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "tests/unions/union_schema.graphql",
+    query_path = "tests/anonymous_operations/shorthand_query.graphql",
+    variables_derives = "Deserialize, Debug, PartialEq"
+)]
+pub struct DeserializedSearchTotal;
+
+#[test]
+fn anonymous_operations_are_named_after_the_struct() {
+    assert_eq!(dog_names::OPERATION_NAME, "DogNames");
+    assert!(dog_names::QUERY.starts_with("query DogNames {"));
+
+    let body = DogNames::build_query(dog_names::Variables);
+    assert_eq!(body.operation_name, "DogNames");
+}
+
+#[test]
+fn shorthand_queries_are_named_after_the_struct() {
+    assert_eq!(search_total::OPERATION_NAME, "SearchTotal");
+    assert!(search_total::QUERY.starts_with("query SearchTotal {"));
+
+    let response: search_total::ResponseData =
+        serde_json::from_value(serde_json::json!({ "search": { "total": 1 } })).unwrap();
+    assert_eq!(response.search.unwrap().total, 1);
+}
+
+#[test]
+fn operations_without_variables_send_an_empty_object() {
+    let body = serde_json::to_value(DogNames::build_query(dog_names::Variables)).unwrap();
+
+    assert_eq!(body["variables"], serde_json::json!({}));
+}
+
+#[test]
+fn variables_without_fields_are_deserialized_from_an_empty_object_or_null() {
+    use deserialized_search_total::Variables;
+
+    assert_eq!(serde_json::from_str::<Variables>("{}").unwrap(), Variables);
+    assert_eq!(
+        serde_json::from_str::<Variables>("null").unwrap(),
+        Variables
+    );
+}
@@ -0,0 +1,21 @@
+use graphql_client::*;
+use serde_json::json;
+
+// Both files start with a UTF-8 byte order mark.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "tests/bom/schema.graphql",
+    query_path = "tests/bom/query.graphql",
+    response_derives = "Debug, PartialEq"
+)]
+pub struct GreetingQuery;
+
+#[test]
+fn files_with_byte_order_mark_are_supported() {
+    assert!(!greeting_query::QUERY.starts_with('\u{feff}'));
+
+    let response: greeting_query::ResponseData =
+        serde_json::from_value(json!({ "greeting": "hello" })).unwrap();
+
+    assert_eq!(response.greeting, "hello");
+}
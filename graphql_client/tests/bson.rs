@@ -0,0 +1,96 @@
+#![cfg(feature = "bson")]
+
+use graphql_client::bson::{from_document, to_document};
+use graphql_client::*;
+use serde_json::json;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "tests/bson/query.graphql",
+    schema_path = "tests/bson/schema.graphql",
+    response_derives = "Debug, PartialEq, Serialize"
+)]
+pub struct CachedQuery;
+
+fn response() -> serde_json::Value {
+    json!({
+        "viewer": {
+            "__typename": "User",
+            "id": "1",
+            "displayName": "Ferris",
+            "karma": 9_000_000_000_i64,
+            "score": 0.5,
+            "status": "ACTIVE",
+            "tags": ["rust", null],
+        },
+        "node": {
+            "__typename": "Organization",
+            "id": "2",
+            "memberCount": 12,
+        },
+        "search": [
+            {
+                "__typename": "User",
+                "id": "3",
+                "displayName": "Corro",
+                "karma": -1,
+                "score": null,
+                "status": "ARCHIVED",
+            },
+            {
+                "__typename": "Organization",
+                "memberCount": 0,
+            },
+        ],
+    })
+}
+
+#[test]
+fn responses_round_trip_through_bson() {
+    let response_data: cached_query::ResponseData = serde_json::from_value(response()).unwrap();
+
+    let document = to_document(&response_data).unwrap();
+    let from_bson: cached_query::ResponseData = from_document(document).unwrap();
+
+    assert_eq!(from_bson, response_data);
+    assert_eq!(serde_json::to_value(&from_bson).unwrap(), response());
+}
+
+#[test]
+fn responses_are_stored_with_the_graphql_field_names() {
+    let response_data: cached_query::ResponseData = serde_json::from_value(response()).unwrap();
+
+    let document = to_document(&response_data).unwrap();
+    let viewer = document.get_document("viewer").unwrap();
+
+    assert_eq!(viewer.get_str("displayName").unwrap(), "Ferris");
+    assert_eq!(viewer.get_i64("karma").unwrap(), 9_000_000_000);
+    assert_eq!(viewer.get_str("status").unwrap(), "ACTIVE");
+    assert_eq!(
+        document.get_array("search").unwrap()[1]
+            .as_document()
+            .unwrap()
+            .get_str("__typename")
+            .unwrap(),
+        "Organization"
+    );
+}
+
+#[test]
+fn responses_with_errors_round_trip_through_bson() {
+    let response = json!({
+        "data": response(),
+        "errors": [{
+            "message": "The search is partial.",
+            "locations": [{ "line": 20, "column": 3 }],
+            "path": ["search", 1],
+            "extensions": { "code": "PARTIAL", "retryAfter": 30 },
+        }],
+    });
+    let response: Response<cached_query::ResponseData> = serde_json::from_value(response).unwrap();
+
+    let document = to_document(&response).unwrap();
+    let from_bson: Response<cached_query::ResponseData> = from_document(document).unwrap();
+
+    assert_eq!(from_bson, response);
+}
@@ -0,0 +1,22 @@
+//! The generated code must not trigger the clippy lints, including the pedantic ones, of the crates
+//! enabling them. The gates run clippy on the tests with `-D warnings`.
+#![deny(clippy::all, clippy::pedantic)]
+
+use graphql_client::*;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "tests/clippy_pedantic/schema.graphql",
+    query_path = "tests/clippy_pedantic/query.graphql",
+    response_derives = "Debug, PartialEq"
+)]
+pub struct PedanticQuery;
+
+#[test]
+fn the_query_is_built() {
+    let body = PedanticQuery::build_query(
+        pedantic_query::Variables::builder().build("graphql".to_owned()),
+    );
+
+    assert_eq!(body.operation_name, "PedanticQuery");
+}
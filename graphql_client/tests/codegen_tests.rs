@@ -0,0 +1,53 @@
+#![cfg(feature = "codegen-tests")]
+
+use graphql_client::*;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "tests/codegen_tests/schema.graphql",
+    query_path = "tests/codegen_tests/query.graphql"
+)]
+pub struct TasksQuery;
+
+#[test]
+fn generated_enum_tests_pass_for_a_matching_schema() {
+    tasks_query::task_status_matches_the_schema();
+}
+
+#[test]
+fn introspection_schemas_are_compared_too() {
+    codegen_tests::assert_enum_values(
+        concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/codegen_tests/schema.json"
+        ),
+        "TaskStatus",
+        tasks_query::TaskStatus::VARIANTS,
+    );
+}
+
+#[test]
+#[should_panic(expected = "(added: [\"ARCHIVED\"], removed: [\"IN_PROGRESS\"])")]
+fn enums_that_changed_in_the_schema_fail() {
+    codegen_tests::assert_enum_values(
+        concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/codegen_tests/drifted_schema.graphql"
+        ),
+        "TaskStatus",
+        tasks_query::TaskStatus::VARIANTS,
+    );
+}
+
+#[test]
+#[should_panic(expected = "The enum `Missing` is not in the schema")]
+fn enums_missing_from_the_schema_fail() {
+    codegen_tests::assert_enum_values(
+        concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/codegen_tests/schema.graphql"
+        ),
+        "Missing",
+        &[],
+    );
+}
@@ -0,0 +1,36 @@
+use graphql_client::*;
+use serde_json::json;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "tests/compact_debug/schema.graphql",
+    query_path = "tests/compact_debug/query.graphql",
+    response_derives = "Debug",
+    compact_debug = "3"
+)]
+pub struct CatalogQuery;
+
+#[test]
+fn long_lists_and_strings_are_truncated() {
+    let product_ids: Vec<i64> = (0..1000).collect();
+    let response: catalog_query::ResponseData = serde_json::from_value(json!({
+        "catalog": {
+            "name": "Spring",
+            "description": "x".repeat(150),
+            "productIds": product_ids,
+            "owner": { "id": "1", "age": 42 },
+        }
+    }))
+    .unwrap();
+
+    let debug = format!("{:?}", response.catalog);
+
+    assert!(debug.contains("product_ids: [0, 1, 2, ... 997 more]"));
+    assert!(debug.contains(&format!(
+        "description: Some({:?}... (50 more characters))",
+        "x".repeat(100)
+    )));
+    assert!(debug.contains("name: \"Spring\""));
+    // Structs without lists or strings keep the derived Debug impl.
+    assert!(debug.contains("owner: Some(CatalogQueryCatalogOwner { id: \"1\", age: Some(42) })"));
+}
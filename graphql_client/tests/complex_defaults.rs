@@ -0,0 +1,62 @@
+use graphql_client::*;
+use serde_json::json;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "tests/complex_defaults/query.graphql",
+    schema_path = "tests/complex_defaults/schema.graphql"
+)]
+pub struct ComplexDefaultsQuery;
+
+#[test]
+fn default_values_with_enum_lists_and_nested_inputs() {
+    use complex_defaults_query::*;
+
+    let x: Option<ComplexInput> = Variables::default_x();
+
+    assert!(matches!(
+        x,
+        Some(ComplexInput {
+            tags: Some(ref tags),
+            more_tags: Some(ref more_tags),
+            single_tag: Some(ref single_tag),
+            nested: Some(NestedInput {
+                count: 3,
+                ratio: Some(ratio),
+                id: Some(ref id),
+            }),
+            label: None,
+        }) if matches!(tags[..], [Color::RED, Color::GREEN])
+            && matches!(more_tags[..], [Some(Color::BLUE), None])
+            && matches!(single_tag[..], [Color::GREEN])
+            && ratio == 1.0
+            && id == "42"
+    ));
+
+    assert_eq!(
+        serde_json::to_value(&x).unwrap(),
+        json!({
+            "tags": ["RED", "GREEN"],
+            "moreTags": ["BLUE", null],
+            "singleTag": ["GREEN"],
+            "nested": { "count": 3, "ratio": 1.0, "id": "42" },
+            "label": null,
+        })
+    );
+}
+
+#[test]
+fn default_values_of_recursive_inputs_are_boxed() {
+    use complex_defaults_query::*;
+
+    let path = Variables::default_path().unwrap();
+
+    assert_eq!(path.head, "root");
+    assert_eq!(
+        serde_json::to_value(&path).unwrap(),
+        json!({
+            "head": "root",
+            "tail": { "head": "leaf", "tail": null },
+        })
+    );
+}
@@ -0,0 +1,48 @@
+use graphql_client::*;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "tests/convert_enum/queries.graphql",
+    schema_path = "tests/convert_enum/schema.graphql",
+    response_derives = "Debug, PartialEq"
+)]
+pub struct IssueTitles;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "tests/convert_enum/queries.graphql",
+    schema_path = "tests/convert_enum/schema.graphql",
+    response_derives = "Debug, PartialEq"
+)]
+pub struct IssueStates;
+
+convert_enum!(issue_states::IssueState => issue_titles::IssueState);
+convert_enum!(issue_titles::IssueState => issue_states::IssueState);
+
+#[test]
+fn string_values_bridge() {
+    use issue_states::IssueState;
+
+    assert_eq!(IssueState::VARIANTS, &["OPEN", "CLOSED"]);
+    assert_eq!(IssueState::from_str_value("OPEN"), Some(IssueState::OPEN));
+    assert_eq!(IssueState::from_str_value("DRAFT"), None);
+    assert_eq!(IssueState::CLOSED.as_str_value(), Some("CLOSED"));
+    assert_eq!(IssueState::Other("DRAFT".to_owned()).as_str_value(), None);
+}
+
+#[test]
+fn enums_convert_between_operations() {
+    let state: issue_titles::IssueState = issue_states::IssueState::OPEN.into();
+    assert_eq!(state, issue_titles::IssueState::OPEN);
+
+    let state: issue_states::IssueState = issue_titles::IssueState::CLOSED.into();
+    assert_eq!(state, issue_states::IssueState::CLOSED);
+}
+
+#[test]
+fn unknown_values_are_kept() {
+    let state: issue_titles::IssueState =
+        issue_states::IssueState::Other("DRAFT".to_owned()).into();
+
+    assert_eq!(state, issue_titles::IssueState::Other("DRAFT".to_owned()));
+}
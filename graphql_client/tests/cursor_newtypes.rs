@@ -0,0 +1,60 @@
+use graphql_client::*;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "tests/cursor_newtypes/schema.graphql",
+    query_path = "tests/cursor_newtypes/query.graphql",
+    response_derives = "Debug",
+    cursor_newtypes = "true"
+)]
+pub struct RepoView;
+
+#[test]
+fn cursors_round_trip_through_their_newtype() {
+    use repo_view::{RepoViewRepositoryIssuesCursor, RepoViewRepositoryPullRequestsCursor};
+
+    let response: repo_view::ResponseData = serde_json::from_value(serde_json::json!({
+        "repository": {
+            "issues": {
+                "edges": [{ "cursor": "issue-1", "node": { "title": "Bug" } }],
+                "pageInfo": { "endCursor": "issue-1", "hasNextPage": true },
+            },
+            "pullRequests": {
+                "nodes": [],
+                "pageInfo": { "endCursor": null },
+            },
+        }
+    }))
+    .unwrap();
+    let repository = response.repository.unwrap();
+
+    assert_eq!(
+        repository.issues.edges[0].cursor,
+        RepoViewRepositoryIssuesCursor("issue-1".to_owned())
+    );
+    assert_eq!(repository.pull_requests.page_info.end_cursor, None);
+
+    let end_cursor: Option<RepoViewRepositoryIssuesCursor> = repository.issues.page_info.end_cursor;
+    assert_eq!(
+        end_cursor
+            .as_ref()
+            .map(RepoViewRepositoryIssuesCursor::as_str),
+        Some("issue-1")
+    );
+
+    let variables = repo_view::Variables {
+        name: "graphql-client".to_owned(),
+        issues_after: end_cursor,
+        pull_requests_after: Some(RepoViewRepositoryPullRequestsCursor("pr-5".to_owned())),
+    };
+
+    // The newtypes are serialized as the cursor strings.
+    assert_eq!(
+        serde_json::to_value(&variables).unwrap(),
+        serde_json::json!({
+            "name": "graphql-client",
+            "issuesAfter": "issue-1",
+            "pullRequestsAfter": "pr-5",
+        })
+    );
+}
@@ -0,0 +1,62 @@
+#![cfg(feature = "chrono-tests")]
+
+use chrono::{NaiveDate, NaiveTime, TimeZone, Utc};
+use graphql_client::*;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "tests/date_times/schema.graphql",
+    query_path = "tests/date_times/query.graphql",
+    date_time_crate = "chrono"
+)]
+pub struct EventsQuery;
+
+#[test]
+fn date_times_are_deserialized_from_iso_8601_strings() {
+    let response = serde_json::json!({
+        "events": [
+            {
+                "name": "Release party",
+                "startsAt": "2020-03-13T18:30:00+01:00",
+                "endsAt": null,
+                "day": "2020-03-13",
+                "opensAt": "18:00:00",
+                "previousStarts": ["2020-03-06T18:30:00Z"],
+            },
+        ],
+    });
+
+    let data: events_query::ResponseData = serde_json::from_value(response).unwrap();
+    let event = &data.events[0];
+
+    assert_eq!(
+        event.starts_at,
+        Utc.with_ymd_and_hms(2020, 3, 13, 17, 30, 0).unwrap()
+    );
+    assert_eq!(event.ends_at, None);
+    assert_eq!(event.day, NaiveDate::from_ymd_opt(2020, 3, 13).unwrap());
+    assert_eq!(event.opens_at, NaiveTime::from_hms_opt(18, 0, 0));
+    assert_eq!(
+        event.previous_starts,
+        Some(vec![Utc.with_ymd_and_hms(2020, 3, 6, 18, 30, 0).unwrap()])
+    );
+}
+
+#[test]
+fn date_time_variables_are_serialized_as_iso_8601_strings() {
+    let variables = events_query::Variables {
+        filter: Some(events_query::EventFilter {
+            starts_after: None,
+            day: NaiveDate::from_ymd_opt(2020, 3, 13),
+        }),
+        before: Utc.with_ymd_and_hms(2020, 12, 31, 23, 59, 59).unwrap(),
+    };
+
+    assert_eq!(
+        serde_json::to_value(&variables).unwrap(),
+        serde_json::json!({
+            "filter": { "startsAfter": null, "day": "2020-03-13" },
+            "before": "2020-12-31T23:59:59Z",
+        })
+    );
+}
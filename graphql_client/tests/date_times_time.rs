@@ -0,0 +1,73 @@
+#![cfg(feature = "time")]
+
+use graphql_client::*;
+use time::macros::{date, datetime, time};
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "tests/date_times/schema.graphql",
+    query_path = "tests/date_times/query.graphql",
+    response_derives = "Serialize",
+    date_time_crate = "time"
+)]
+pub struct EventsQuery;
+
+#[test]
+fn date_times_are_deserialized_from_iso_8601_strings() {
+    let response = serde_json::json!({
+        "events": [
+            {
+                "name": "Release party",
+                "startsAt": "2020-03-13T18:30:00+01:00",
+                "endsAt": null,
+                "day": "2020-03-13",
+                "opensAt": "18:00:00",
+                "previousStarts": ["2020-03-06T18:30:00Z", "2020-02-28T18:30:00.5Z"],
+            },
+        ],
+    });
+
+    let data: events_query::ResponseData = serde_json::from_value(response).unwrap();
+    let event = &data.events[0];
+
+    assert_eq!(event.starts_at, datetime!(2020-03-13 18:30 +01:00));
+    assert_eq!(event.ends_at, None);
+    assert_eq!(event.day, date!(2020 - 03 - 13));
+    assert_eq!(event.opens_at, Some(time!(18:00)));
+    assert_eq!(
+        event.previous_starts,
+        Some(vec![
+            datetime!(2020-03-06 18:30 UTC),
+            datetime!(2020-02-28 18:30:00.5 UTC),
+        ])
+    );
+
+    // The response is serialized back to the same strings.
+    assert_eq!(
+        serde_json::to_value(&data).unwrap()["events"][0]["previousStarts"],
+        serde_json::json!(["2020-03-06T18:30:00Z", "2020-02-28T18:30:00.5Z"])
+    );
+    assert_eq!(
+        serde_json::to_value(&data).unwrap()["events"][0]["opensAt"],
+        "18:00:00"
+    );
+}
+
+#[test]
+fn date_time_variables_are_serialized_as_rfc_3339_strings() {
+    let variables = events_query::Variables {
+        filter: Some(events_query::EventFilter {
+            starts_after: Some(datetime!(2020-03-13 18:30 UTC)),
+            day: None,
+        }),
+        before: datetime!(2020-12-31 23:59:59 UTC),
+    };
+
+    assert_eq!(
+        serde_json::to_value(&variables).unwrap(),
+        serde_json::json!({
+            "filter": { "startsAfter": "2020-03-13T18:30:00Z", "day": null },
+            "before": "2020-12-31T23:59:59Z",
+        })
+    );
+}
@@ -0,0 +1,47 @@
+use graphql_client::*;
+use serde_json::json;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "tests/dedupe_types/schema.graphql",
+    query_path = "tests/dedupe_types/query.graphql",
+    response_derives = "Debug, PartialEq",
+    dedupe_types = "true"
+)]
+pub struct RepositoryQuery;
+
+#[test]
+fn deduped_types_deserialize() {
+    use repository_query::*;
+
+    let response: ResponseData = serde_json::from_value(json!({
+        "repository": {
+            "owner": { "login": "tomhoule", "avatarUrl": "https://example.com/tom.png" },
+            "issues": [{
+                "title": "Dedupe",
+                "author": { "login": "ada", "avatarUrl": "https://example.com/ada.png" },
+                "comments": [
+                    { "body": "+1", "author": { "login": "tomhoule", "avatarUrl": "https://example.com/tom.png" } }
+                ]
+            }],
+            "pullRequests": [{
+                "title": "Dedupe types",
+                "author": { "login": "ada", "avatarUrl": "https://example.com/ada.png" },
+                "comments": []
+            }]
+        }
+    }))
+    .unwrap();
+
+    let repository = response.repository.unwrap();
+    let owner: RepositoryQueryRepositoryOwner = repository.owner;
+
+    // The authors of issues, pull requests and comments all use the owner's type.
+    assert_eq!(repository.issues[0].comments[0].author, owner);
+    let pull_request_author: &RepositoryQueryRepositoryOwner = &repository.pull_requests[0].author;
+    assert_eq!(pull_request_author.login, "ada");
+
+    // Pull requests have the same shape as issues.
+    let pull_request: &RepositoryQueryRepositoryIssues = &repository.pull_requests[0];
+    assert_eq!(pull_request.title, "Dedupe types");
+}
@@ -0,0 +1,25 @@
+use graphql_client::*;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "tests/mutation_only/schema.graphql",
+    query_path = "tests/directives/query.graphql"
+)]
+pub struct CreatePost;
+
+#[test]
+fn operation_directives_are_exposed() {
+    assert_eq!(
+        create_post::DIRECTIVES,
+        &[
+            Directive {
+                name: "owner",
+                arguments: &[("team", "payments"), ("oncall", "true")],
+            },
+            Directive {
+                name: "budget",
+                arguments: &[("maxLatencyMs", "250"), ("tier", "GOLD")],
+            },
+        ]
+    );
+}
@@ -0,0 +1,28 @@
+use graphql_client::*;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "tests/empty_object/query.graphql",
+    schema_path = "tests/empty_object/schema.json",
+    response_derives = "Debug, PartialEq"
+)]
+pub struct AccountTypename;
+
+#[test]
+fn typename_only_selection_on_empty_object() {
+    let response_data: account_typename::ResponseData = serde_json::from_value(serde_json::json!({
+        "version": "1.2.0",
+        "account": { "__typename": "Account" }
+    }))
+    .unwrap();
+
+    assert_eq!(
+        response_data,
+        account_typename::ResponseData {
+            version: "1.2.0".to_owned(),
+            account: Some(account_typename::AccountTypenameAccount {
+                typename: Some("Account".to_owned()),
+            }),
+        }
+    );
+}
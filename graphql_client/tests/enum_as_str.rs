@@ -0,0 +1,21 @@
+use graphql_client::*;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "tests/enum_as_str/schema.graphql",
+    query_path = "tests/enum_as_str/query.graphql",
+    response_derives = "Debug"
+)]
+pub struct AccountQuery;
+
+#[test]
+fn enum_variants_as_str_are_graphql_values() {
+    use account_query::AccountStatus;
+
+    assert_eq!(AccountStatus::ACTIVE.as_str(), "ACTIVE");
+    assert_eq!(AccountStatus::SUSPENDED.as_ref(), "SUSPENDED");
+    // Keyword-escaped variants keep their GraphQL name.
+    assert_eq!(AccountStatus::self_.as_str(), "self");
+    assert_eq!(AccountStatus::type_.as_str(), "type");
+    assert_eq!(AccountStatus::Other("CLOSED".to_owned()).as_str(), "CLOSED");
+}
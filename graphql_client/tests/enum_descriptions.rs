@@ -0,0 +1,31 @@
+use graphql_client::*;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "tests/enum_descriptions/schema.graphql",
+    query_path = "tests/enum_descriptions/query.graphql",
+    enum_descriptions = "true"
+)]
+pub struct TasksQuery;
+
+#[test]
+fn enum_variants_have_their_schema_description() {
+    use tasks_query::TaskStatus;
+
+    assert_eq!(
+        TaskStatus::TODO.description(),
+        Some("Nobody has started working on the task.")
+    );
+    assert_eq!(
+        TaskStatus::IN_PROGRESS.description(),
+        Some("Work has started.")
+    );
+}
+
+#[test]
+fn variants_without_description_have_none() {
+    use tasks_query::TaskStatus;
+
+    assert_eq!(TaskStatus::DONE.description(), None);
+    assert_eq!(TaskStatus::Other("ARCHIVED".to_owned()).description(), None);
+}
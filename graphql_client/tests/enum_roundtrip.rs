@@ -0,0 +1,24 @@
+/// The code generated for `enum_roundtrip/query.graphql`, where the `gen-tests` command of the
+/// CLI expects it.
+#[allow(dead_code)]
+mod query {
+    use graphql_client::GraphQLQuery;
+
+    #[derive(GraphQLQuery)]
+    #[graphql(
+        query_path = "tests/enum_roundtrip/query.graphql",
+        schema_path = "tests/enum_roundtrip/schema.graphql"
+    )]
+    pub struct Tasks;
+
+    #[derive(GraphQLQuery)]
+    #[graphql(
+        query_path = "tests/enum_roundtrip/query.graphql",
+        schema_path = "tests/enum_roundtrip/schema.graphql"
+    )]
+    pub struct Viewer;
+}
+
+// Generated with `graphql-client gen-tests --schema-path tests/enum_roundtrip/schema.graphql
+// --output tests/enum_roundtrip/roundtrip_tests.rs tests/enum_roundtrip/query.graphql`.
+include!("enum_roundtrip/roundtrip_tests.rs");
@@ -0,0 +1,75 @@
+// Generated by `graphql-client gen-tests`: serialization and deserialization round trips
+// for the enums used by the queries.
+
+#[test]
+fn priority_round_trips() {
+    use crate::query::tasks::Priority;
+
+    assert_eq!(serde_json::to_string(&Priority::LOW).unwrap(), "\"LOW\"");
+    assert!(matches!(
+        serde_json::from_str::<Priority>("\"LOW\"").unwrap(),
+        Priority::LOW
+    ));
+    assert_eq!(serde_json::to_string(&Priority::HIGH).unwrap(), "\"HIGH\"");
+    assert!(matches!(
+        serde_json::from_str::<Priority>("\"HIGH\"").unwrap(),
+        Priority::HIGH
+    ));
+    assert_eq!(serde_json::to_string(&Priority::self_).unwrap(), "\"self\"");
+    assert!(matches!(
+        serde_json::from_str::<Priority>("\"self\"").unwrap(),
+        Priority::self_
+    ));
+    // Values that are not in the schema are deserialized as `Other`.
+    assert!(matches!(
+        serde_json::from_str::<Priority>("\"UNKNOWN_VALUE\"").unwrap(),
+        Priority::Other(ref value) if value == "UNKNOWN_VALUE"
+    ));
+}
+
+#[test]
+fn status_round_trips() {
+    use crate::query::tasks::Status;
+
+    assert_eq!(serde_json::to_string(&Status::OPEN).unwrap(), "\"OPEN\"");
+    assert!(matches!(
+        serde_json::from_str::<Status>("\"OPEN\"").unwrap(),
+        Status::OPEN
+    ));
+    assert_eq!(serde_json::to_string(&Status::IN_PROGRESS).unwrap(), "\"IN_PROGRESS\"");
+    assert!(matches!(
+        serde_json::from_str::<Status>("\"IN_PROGRESS\"").unwrap(),
+        Status::IN_PROGRESS
+    ));
+    assert_eq!(serde_json::to_string(&Status::DONE).unwrap(), "\"DONE\"");
+    assert!(matches!(
+        serde_json::from_str::<Status>("\"DONE\"").unwrap(),
+        Status::DONE
+    ));
+    // Values that are not in the schema are deserialized as `Other`.
+    assert!(matches!(
+        serde_json::from_str::<Status>("\"UNKNOWN_VALUE\"").unwrap(),
+        Status::Other(ref value) if value == "UNKNOWN_VALUE"
+    ));
+}
+
+#[test]
+fn role_round_trips() {
+    use crate::query::viewer::Role;
+
+    assert_eq!(serde_json::to_string(&Role::ADMIN).unwrap(), "\"ADMIN\"");
+    assert!(matches!(
+        serde_json::from_str::<Role>("\"ADMIN\"").unwrap(),
+        Role::ADMIN
+    ));
+    assert_eq!(serde_json::to_string(&Role::MEMBER).unwrap(), "\"MEMBER\"");
+    assert!(matches!(
+        serde_json::from_str::<Role>("\"MEMBER\"").unwrap(),
+        Role::MEMBER
+    ));
+    // Values that are not in the schema are deserialized as `Other`.
+    assert!(matches!(
+        serde_json::from_str::<Role>("\"UNKNOWN_VALUE\"").unwrap(),
+        Role::Other(ref value) if value == "UNKNOWN_VALUE"
+    ));
+}
@@ -0,0 +1,34 @@
+use graphql_client::*;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "tests/enum_descriptions/schema.graphql",
+    query_path = "tests/enum_descriptions/query.graphql",
+    response_derives = "Debug, PartialEq",
+    normalization = "rust"
+)]
+pub struct TasksQuery;
+
+#[test]
+fn all_lists_the_variants_of_the_schema() {
+    use tasks_query::TaskStatus;
+
+    assert_eq!(
+        TaskStatus::all(),
+        &[TaskStatus::Todo, TaskStatus::InProgress, TaskStatus::Done]
+    );
+}
+
+#[test]
+fn variants_are_named_as_in_graphql() {
+    use tasks_query::TaskStatus;
+
+    let values: Vec<&str> = TaskStatus::all().iter().map(TaskStatus::as_str).collect();
+
+    assert_eq!(values, TaskStatus::VARIANTS);
+    assert_eq!(values, &["TODO", "IN_PROGRESS", "DONE"]);
+    assert_eq!(
+        TaskStatus::Other("ARCHIVED".to_owned()).as_str(),
+        "ARCHIVED"
+    );
+}
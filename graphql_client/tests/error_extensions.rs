@@ -0,0 +1,61 @@
+use graphql_client::*;
+use serde::Deserialize;
+use serde_json::json;
+
+/// The extensions our server adds to its errors.
+#[derive(Debug, Clone, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct MyExtensions {
+    code: String,
+    retry_after: Option<u32>,
+}
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "tests/operation_selection/queries.graphql",
+    schema_path = "tests/operation_selection/schema.graphql",
+    response_derives = "Debug, PartialEq",
+    error_extensions = "MyExtensions"
+)]
+pub struct Heights;
+
+#[test]
+fn error_extensions_are_deserialized_to_the_given_type() {
+    let response: heights::Response = serde_json::from_value(json!({
+        "data": null,
+        "errors": [{
+            "message": "Too many requests",
+            "extensions": { "code": "RATE_LIMITED", "retryAfter": 30 },
+        }],
+    }))
+    .unwrap();
+
+    let errors = match response.into_result() {
+        Err(ResponseError::Errors(errors)) => errors,
+        other => panic!("unexpected result: {:?}", other),
+    };
+
+    assert_eq!(
+        errors[0].extensions,
+        Some(MyExtensions {
+            code: "RATE_LIMITED".to_owned(),
+            retry_after: Some(30),
+        })
+    );
+}
+
+#[test]
+fn error_extensions_are_a_json_object_by_default() {
+    let response: Response<heights::ResponseData> = serde_json::from_value(json!({
+        "data": null,
+        "errors": [{
+            "message": "Too many requests",
+            "extensions": { "code": "RATE_LIMITED" },
+        }],
+    }))
+    .unwrap();
+
+    let extensions = response.errors.unwrap()[0].extensions.clone().unwrap();
+
+    assert_eq!(extensions["code"], json!("RATE_LIMITED"));
+}
@@ -0,0 +1,57 @@
+use graphql_client::*;
+use serde_json::json;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "tests/fragments/query.graphql",
+    schema_path = "tests/fragments/schema.graphql",
+    expand_recursive_fragments = "2",
+    response_derives = "Debug, PartialEq"
+)]
+pub struct RecursiveFragmentQuery;
+
+#[test]
+fn recursive_fragments_are_expanded_two_levels_deep() {
+    use recursive_fragment_query::*;
+
+    // The struct literals list all the fields: the second level has no `tail`.
+    let _ = RecursiveFragment {
+        head: Some("ABCD".to_string()),
+        tail: Some(RecursiveFragmentTail {
+            recursive_fragment_level2: RecursiveFragmentLevel2 {
+                head: Some("EFGH".to_string()),
+            },
+        }),
+    };
+
+    assert!(QUERY.contains("fragment RecursiveFragmentLevel2 on RecursiveNode"));
+    assert!(!QUERY.contains("RecursiveFragmentLevel3"));
+}
+
+#[test]
+fn two_level_responses_are_deserialized() {
+    use recursive_fragment_query::*;
+
+    let response = json!({
+        "recursive": {
+            "head": "ABCD",
+            "tail": {
+                "head": "EFGH",
+            },
+        },
+    });
+
+    let data: ResponseData = serde_json::from_value(response).unwrap();
+
+    assert_eq!(
+        data.recursive.recursive_fragment,
+        RecursiveFragment {
+            head: Some("ABCD".to_string()),
+            tail: Some(RecursiveFragmentTail {
+                recursive_fragment_level2: RecursiveFragmentLevel2 {
+                    head: Some("EFGH".to_string()),
+                },
+            }),
+        }
+    );
+}
@@ -0,0 +1,37 @@
+use graphql_client::*;
+use serde_json::json;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "tests/facade/schema.graphql",
+    query_path = "tests/facade/query.graphql",
+    response_derives = "Debug, PartialEq",
+    facade = "true"
+)]
+pub struct PostsQuery;
+
+use posts_query::api;
+
+#[test]
+fn facade_reexports_the_public_types() {
+    let body = api::PostsQuery::build_query(api::Variables {
+        filter: api::PostFilter {
+            status: Some(api::PostStatus::PUBLISHED),
+        },
+    });
+
+    assert_eq!(
+        serde_json::to_value(&body.variables).unwrap(),
+        json!({ "filter": { "status": "PUBLISHED" } })
+    );
+
+    let response: api::ResponseData = serde_json::from_value(json!({
+        "posts": [{ "id": "1", "status": "DRAFT", "author": { "name": "Jane" } }]
+    }))
+    .unwrap();
+
+    let post_fields: &api::PostFields = &response.posts[0].post_fields;
+    assert_eq!(post_fields.id, "1");
+    assert_eq!(post_fields.status, api::PostStatus::DRAFT);
+    assert_eq!(response.posts[0].author.name, "Jane");
+}
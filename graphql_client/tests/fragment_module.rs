@@ -0,0 +1,62 @@
+use graphql_client::*;
+use serde_json::json;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "tests/fragment_module/schema.graphql",
+    query_path = "tests/fragment_module/query.graphql",
+    response_derives = "Debug, PartialEq",
+    fragment = "UserCard"
+)]
+pub struct UserCardFragment;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "tests/fragment_module/schema.graphql",
+    query_path = "tests/fragment_module/query.graphql",
+    response_derives = "Debug, PartialEq"
+)]
+pub struct MeQuery;
+
+const USER: &str = r#"{
+    "name": "Ada",
+    "role": "ADMIN",
+    "avatar": { "url": "https://example.com/ada.png" }
+}"#;
+
+#[test]
+fn fragment_module_has_the_fragment_types() {
+    let user: user_card::UserCard = serde_json::from_str(USER).unwrap();
+
+    assert_eq!(
+        user,
+        user_card::UserCard {
+            name: "Ada".to_owned(),
+            role: user_card::Role::ADMIN,
+            avatar: Some(user_card::UserCardAvatar {
+                thumbnail: user_card::Thumbnail {
+                    url: "https://example.com/ada.png".to_owned(),
+                },
+            }),
+        }
+    );
+}
+
+#[test]
+fn fragment_source_contains_the_spread_fragments() {
+    assert_eq!(user_card::FRAGMENT_NAME, "UserCard");
+    assert!(user_card::FRAGMENT_SOURCE.starts_with("fragment UserCard on User {"));
+    assert!(user_card::FRAGMENT_SOURCE.contains("fragment Thumbnail on Image {"));
+    assert!(!user_card::FRAGMENT_SOURCE.contains("query"));
+}
+
+#[test]
+fn operations_spreading_the_fragment_have_their_own_types() {
+    let response: me_query::ResponseData = serde_json::from_value(
+        json!({ "me": serde_json::from_str::<serde_json::Value>(USER).unwrap() }),
+    )
+    .unwrap();
+    let user: user_card::UserCard = serde_json::from_str(USER).unwrap();
+
+    assert_eq!(response.me.user_card.name, user.name);
+}
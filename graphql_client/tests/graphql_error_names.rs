@@ -0,0 +1,108 @@
+use graphql_client::*;
+use serde_json::json;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "tests/graphql_error_names/schema.graphql",
+    query_path = "tests/graphql_error_names/query.graphql",
+    response_derives = "Debug, Serialize",
+    graphql_error_names = "true"
+)]
+pub struct IssueQuery;
+
+pub mod without_names {
+    use graphql_client::*;
+
+    #[derive(GraphQLQuery)]
+    #[graphql(
+        schema_path = "tests/graphql_error_names/schema.graphql",
+        query_path = "tests/graphql_error_names/query.graphql",
+        response_derives = "Debug"
+    )]
+    pub struct IssueQuery;
+}
+
+fn response() -> serde_json::Value {
+    json!({
+        "viewer": { "login": "ferris" },
+        "issue": {
+            "opened": "2020-03-13",
+            "title": null,
+            "author": { "__typename": "User", "id": "1", "login": "ferris" },
+        },
+    })
+}
+
+fn missing_field_error(pointer: &str, field: &str) -> String {
+    let mut response = response();
+    response
+        .pointer_mut(pointer)
+        .and_then(serde_json::Value::as_object_mut)
+        .unwrap()
+        .remove(field);
+
+    serde_json::from_value::<issue_query::ResponseData>(response)
+        .unwrap_err()
+        .to_string()
+}
+
+#[test]
+fn missing_fields_are_named_with_their_graphql_type() {
+    assert_eq!(
+        missing_field_error("", "viewer"),
+        "missing non-null field `viewer` on `Query`"
+    );
+    // The aliased fields are named with their alias, the key of the response.
+    assert_eq!(
+        missing_field_error("/issue", "opened"),
+        "missing non-null field `opened` on `Issue`"
+    );
+    // The fields of fragments are named with the type of the fragment.
+    assert_eq!(
+        missing_field_error("/issue/author", "id"),
+        "missing non-null field `id` on `Node`"
+    );
+    assert_eq!(
+        missing_field_error("/issue/author", "login"),
+        "missing non-null field `login` on `User`"
+    );
+}
+
+#[test]
+fn errors_from_strings_keep_their_position() {
+    let err = serde_json::from_str::<issue_query::ResponseData>(r#"{"viewer": {}, "issue": null}"#)
+        .unwrap_err();
+
+    assert_eq!(
+        err.to_string(),
+        "missing non-null field `login` on `User` at line 1 column 13"
+    );
+}
+
+#[test]
+fn other_errors_are_left_as_they_are() {
+    let err = serde_json::from_value::<issue_query::ResponseData>(json!({
+        "viewer": { "login": 1 },
+        "issue": null,
+    }))
+    .unwrap_err();
+
+    assert_eq!(
+        err.to_string(),
+        "invalid type: integer `1`, expected a string"
+    );
+}
+
+#[test]
+fn responses_deserialize_and_serialize_as_without_the_option() {
+    let response_data: issue_query::ResponseData = serde_json::from_value(response()).unwrap();
+
+    assert_eq!(serde_json::to_value(&response_data).unwrap(), response());
+
+    let err = serde_json::from_value::<without_names::issue_query::ResponseData>(json!({
+        "issue": null,
+    }))
+    .unwrap_err();
+
+    assert_eq!(err.to_string(), "missing field `viewer`");
+}
@@ -0,0 +1,59 @@
+use graphql_client::*;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "tests/idempotent/schema.graphql",
+    query_path = "tests/idempotent/queries.graphql"
+)]
+pub struct UpsertPost;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "tests/idempotent/schema.graphql",
+    query_path = "tests/idempotent/queries.graphql"
+)]
+pub struct CreatePost;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "tests/idempotent/schema.graphql",
+    query_path = "tests/idempotent/queries.graphql"
+)]
+pub struct PostQuery;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "tests/idempotent/schema.graphql",
+    query_path = "tests/idempotent/keep_directive.graphql",
+    keep_idempotent_directive = "true"
+)]
+pub struct UpsertPostKeepingDirective;
+
+fn is_idempotent<Q: GraphQLQuery>() -> bool {
+    Q::is_idempotent()
+}
+
+#[test]
+fn annotated_mutation_is_idempotent() {
+    assert!(is_idempotent::<UpsertPost>());
+    assert_eq!(upsert_post::IDEMPOTENT, UpsertPost::is_idempotent());
+    assert!(!upsert_post::QUERY.contains("@idempotent"));
+}
+
+#[test]
+fn unannotated_mutation_is_not_idempotent() {
+    assert!(!is_idempotent::<CreatePost>());
+    assert_eq!(create_post::IDEMPOTENT, CreatePost::is_idempotent());
+}
+
+#[test]
+fn queries_are_idempotent() {
+    assert!(is_idempotent::<PostQuery>());
+    assert_eq!(post_query::IDEMPOTENT, PostQuery::is_idempotent());
+}
+
+#[test]
+fn idempotent_directive_can_be_kept() {
+    assert!(is_idempotent::<UpsertPostKeepingDirective>());
+    assert!(upsert_post_keeping_directive::QUERY.contains("@idempotent"));
+}
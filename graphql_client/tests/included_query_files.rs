@@ -0,0 +1,59 @@
+use graphql_client::*;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "tests/included_query_files/schema.graphql",
+    query_path = "tests/included_query_files/fragments.graphql, tests/included_query_files/query.graphql"
+)]
+pub struct BookQuery;
+
+mod with_included_files {
+    use graphql_client::*;
+
+    #[derive(GraphQLQuery)]
+    #[graphql(
+        schema_path = "tests/included_query_files/schema.graphql",
+        query_path = "tests/included_query_files/fragments.graphql, tests/included_query_files/query.graphql",
+        query_with_included_files = "true"
+    )]
+    pub struct BookQuery;
+}
+
+const FRAGMENTS: &str = include_str!("included_query_files/fragments.graphql");
+const QUERY: &str = include_str!("included_query_files/query.graphql");
+
+#[test]
+fn fragments_of_included_query_files_can_be_spread() {
+    let response: book_query::ResponseData = serde_json::from_value(serde_json::json!({
+        "book": {
+            "title": "Dune",
+            "author": { "name": "Frank Herbert" },
+        }
+    }))
+    .unwrap();
+
+    let book = response.book.unwrap();
+    assert_eq!(book.title, "Dune");
+    assert_eq!(book.author.author_fields.author_name.name, "Frank Herbert");
+}
+
+#[test]
+fn the_spread_fragments_are_sent_after_the_query_file_by_default() {
+    let sent = book_query::QUERY;
+
+    assert!(sent.starts_with(QUERY));
+    let fragments = &sent[QUERY.len()..];
+    assert!(fragments.contains("fragment AuthorFields on Author"));
+    assert!(fragments.contains("fragment AuthorName on Author"));
+    assert!(!fragments.contains("UnusedBookFields"));
+}
+
+#[test]
+fn included_query_files_can_be_sent_with_the_query() {
+    let body =
+        with_included_files::BookQuery::build_query(with_included_files::book_query::Variables {
+            id: "1".to_owned(),
+        });
+
+    assert_eq!(body.query, format!("{}\n{}", FRAGMENTS, QUERY));
+}
@@ -0,0 +1,41 @@
+use graphql_client::*;
+use serde_json::json;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "tests/input_defaults/schema.graphql",
+    query_path = "tests/input_defaults/query.graphql"
+)]
+pub struct IssuesQuery;
+
+#[test]
+fn input_objects_default_to_the_schema_default_values() {
+    assert_eq!(
+        serde_json::to_value(issues_query::IssueFilter::default()).unwrap(),
+        json!({
+            "states": ["OPEN"],
+            "first": 10,
+            "labels": null,
+            "orderBy": { "field": "CREATED_AT", "direction": "DESC" },
+        })
+    );
+    assert!(matches!(
+        issues_query::IssueOrder::default_direction(),
+        issues_query::OrderDirection::ASC
+    ));
+}
+
+#[test]
+fn fields_left_out_of_variable_defaults_get_the_schema_default_values() {
+    let filter = issues_query::Variables::default_filter();
+
+    assert_eq!(
+        serde_json::to_value(filter).unwrap(),
+        json!({
+            "states": ["OPEN"],
+            "first": 10,
+            "labels": ["bug"],
+            "orderBy": { "field": "UPDATED_AT", "direction": "ASC" },
+        })
+    );
+}
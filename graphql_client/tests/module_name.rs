@@ -0,0 +1,85 @@
+use graphql_client::*;
+use serde_json::json;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "tests/fragments/query.graphql",
+    schema_path = "tests/fragments/schema.graphql"
+)]
+pub struct FragmentReference;
+
+mod renamed {
+    use graphql_client::GraphQLQuery;
+
+    // The same operation, generated in a module that does not collide with the one above.
+    #[derive(GraphQLQuery)]
+    #[graphql(
+        query_path = "tests/fragments/query.graphql",
+        schema_path = "tests/fragments/schema.graphql",
+        module_name = "fragment_reference_v2"
+    )]
+    pub struct FragmentReference;
+}
+
+// Derives in different Rust modules of the same file can give their modules the same name.
+mod first {
+    use graphql_client::GraphQLQuery;
+
+    #[derive(GraphQLQuery)]
+    #[graphql(
+        query_path = "tests/fragments/query.graphql",
+        schema_path = "tests/fragments/schema.graphql",
+        module_name = "shared_module"
+    )]
+    pub struct FragmentReference;
+}
+
+mod second {
+    use graphql_client::GraphQLQuery;
+
+    #[derive(GraphQLQuery)]
+    #[graphql(
+        query_path = "tests/fragments/query.graphql",
+        schema_path = "tests/fragments/schema.graphql",
+        module_name = "shared_module"
+    )]
+    pub struct SnakeCaseFragment;
+}
+
+#[test]
+fn module_name_overrides_the_generated_module() {
+    let response = json!({ "inFragment": "value" });
+
+    let data: renamed::fragment_reference_v2::ResponseData =
+        serde_json::from_value(response.clone()).unwrap();
+    assert_eq!(
+        data.fragment_reference.in_fragment.as_deref(),
+        Some("value")
+    );
+
+    let data: fragment_reference::ResponseData = serde_json::from_value(response).unwrap();
+    assert_eq!(
+        data.fragment_reference.in_fragment.as_deref(),
+        Some("value")
+    );
+}
+
+#[test]
+fn graphql_query_impl_uses_the_renamed_module() {
+    let body = renamed::FragmentReference::build_query(renamed::fragment_reference_v2::Variables);
+
+    assert_eq!(body.operation_name, "FragmentReference");
+    assert_eq!(
+        <renamed::FragmentReference as GraphQLQuery>::QUERY,
+        renamed::fragment_reference_v2::QUERY
+    );
+}
+
+#[test]
+fn modules_with_the_same_name_in_different_rust_modules_do_not_collide() {
+    let first = first::FragmentReference::build_query(first::shared_module::Variables);
+    let second = second::SnakeCaseFragment::build_query(second::shared_module::Variables);
+
+    assert_eq!(first.operation_name, "FragmentReference");
+    assert_eq!(second.operation_name, "SnakeCaseFragment");
+}
@@ -27,3 +27,22 @@ fn response_derives_can_be_added() {
     assert_ne!(response_data, response_data_2);
     assert!(response_data < response_data_2);
 }
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "tests/more_derives/schema.graphql",
+    query_path = "tests/more_derives/query.graphql",
+    derives_for = "ResponseData: Clone, PartialEq; DerivesForCurrentUser: Clone, PartialEq"
+)]
+pub struct DerivesFor;
+
+#[test]
+fn derives_can_be_added_to_specific_types() {
+    let response_data = derives_for::ResponseData {
+        current_user: Some(derives_for::DerivesForCurrentUser {
+            name: Some("Angela Merkel".to_owned()),
+        }),
+    };
+
+    assert!(response_data.clone() == response_data);
+}
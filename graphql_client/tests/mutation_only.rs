@@ -0,0 +1,39 @@
+use graphql_client::*;
+use serde_json::json;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "tests/mutation_only/schema.graphql",
+    query_path = "tests/mutation_only/query.graphql",
+    response_derives = "Debug, PartialEq"
+)]
+pub struct CreatePost;
+
+#[test]
+fn mutation_on_schema_with_empty_query_type_works() {
+    let body = CreatePost::build_query(create_post::Variables {
+        title: "Hello".to_owned(),
+        body: None,
+    });
+
+    assert_eq!(body.operation_name, "CreatePost");
+    assert_eq!(
+        serde_json::to_value(&body.variables).unwrap(),
+        json!({ "title": "Hello", "body": null })
+    );
+
+    let response: create_post::ResponseData = serde_json::from_value(json!({
+        "createPost": { "id": "1", "title": "Hello" }
+    }))
+    .unwrap();
+
+    assert_eq!(
+        response,
+        create_post::ResponseData {
+            create_post: create_post::CreatePostCreatePost {
+                id: "1".to_owned(),
+                title: "Hello".to_owned(),
+            },
+        }
+    );
+}
@@ -0,0 +1,48 @@
+use graphql_client::*;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "tests/non_exhaustive_enums/schema.graphql",
+    query_path = "tests/non_exhaustive_enums/query.graphql",
+    exhaustive_enums = "false",
+    enum_other_variant = "Unknown"
+)]
+pub struct ItemsQuery;
+
+#[test]
+fn unknown_values_are_deserialized_as_the_renamed_variant() {
+    use items_query::Category;
+
+    let response: items_query::ResponseData = serde_json::from_value(serde_json::json!({
+        "items": [
+            { "name": "Dune", "category": "BOOK" },
+            { "name": "Apple", "category": "FOOD" },
+            { "name": "Lamp", "category": "Other" },
+        ]
+    }))
+    .unwrap();
+
+    assert!(matches!(response.items[0].category, Category::BOOK));
+    assert!(matches!(response.items[1].category, Category::Unknown(ref value) if value == "FOOD"));
+    assert!(matches!(response.items[2].category, Category::Other));
+
+    assert_eq!(
+        serde_json::to_value(&response.items[1].category).unwrap(),
+        serde_json::json!("FOOD")
+    );
+    assert_eq!(Category::Unknown("FOOD".to_owned()).as_str_value(), None);
+}
+
+#[test]
+fn schema_values_named_like_the_default_variant_are_regular_variants() {
+    let variables = items_query::Variables::default();
+
+    assert!(matches!(
+        variables.category,
+        Some(items_query::Category::Other)
+    ));
+    assert_eq!(
+        serde_json::to_value(&variables).unwrap(),
+        serde_json::json!({ "category": "Other" })
+    );
+}
@@ -0,0 +1,52 @@
+use graphql_client::*;
+use serde_json::json;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "tests/normalization/schema.graphql",
+    query_path = "tests/normalization/query.graphql",
+    response_derives = "Debug",
+    normalization = "rust"
+)]
+pub struct MovieQuery;
+
+#[test]
+fn enum_wire_values_are_not_normalized() {
+    // Only the type and variant names are normalized, e.g. `release_kind` becomes `ReleaseKind`.
+    let variables = movie_query::Variables {
+        release_kind: movie_query::ReleaseKind::DirectToVideo,
+    };
+
+    assert_eq!(
+        serde_json::to_value(&variables).unwrap(),
+        json!({ "release_kind": "DIRECT_TO_VIDEO" })
+    );
+    assert_eq!(
+        movie_query::ReleaseKind::DirectToVideo.as_str(),
+        "DIRECT_TO_VIDEO"
+    );
+    assert_eq!(
+        serde_json::to_value(movie_query::ReleaseKind::StreamingOnly).unwrap(),
+        json!("streaming_only")
+    );
+
+    let response: movie_query::ResponseData = serde_json::from_value(json!({
+        "movie": { "title": "Heat", "release_kind": "streaming_only" }
+    }))
+    .unwrap();
+
+    assert_eq!(
+        response.movie.unwrap().release_kind,
+        movie_query::ReleaseKind::StreamingOnly
+    );
+
+    let response: movie_query::ResponseData = serde_json::from_value(json!({
+        "movie": { "title": "Heat", "release_kind": "THEATRICAL" }
+    }))
+    .unwrap();
+
+    assert_eq!(
+        response.movie.unwrap().release_kind,
+        movie_query::ReleaseKind::Theatrical
+    );
+}
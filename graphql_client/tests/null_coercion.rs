@@ -0,0 +1,54 @@
+use graphql_client::*;
+use serde_json::json;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "tests/null_coercion/schema.graphql",
+    query_path = "tests/null_coercion/query.graphql",
+    response_derives = "Debug, PartialEq",
+    null_coercion = "true"
+)]
+pub struct UserQuery;
+
+#[test]
+fn null_lists_and_strings_are_coerced_to_default() {
+    let response: user_query::ResponseData = serde_json::from_value(json!({
+        "user": { "id": "1", "name": null, "nicknames": null, "age": null }
+    }))
+    .unwrap();
+
+    assert_eq!(
+        response.user,
+        Some(user_query::UserQueryUser {
+            id: "1".to_owned(),
+            name: String::new(),
+            nicknames: Vec::new(),
+            age: None,
+        })
+    );
+}
+
+#[test]
+fn missing_coerced_fields_are_defaulted() {
+    let response: user_query::ResponseData = serde_json::from_value(json!({
+        "user": { "id": "1", "age": 33 }
+    }))
+    .unwrap();
+
+    let user = response.user.unwrap();
+    assert_eq!(user.name, "");
+    assert!(user.nicknames.is_empty());
+    assert_eq!(user.age, Some(33));
+}
+
+#[test]
+fn present_values_are_kept() {
+    let response: user_query::ResponseData = serde_json::from_value(json!({
+        "user": { "id": "1", "name": "Jane", "nicknames": ["J", null], "age": null }
+    }))
+    .unwrap();
+
+    let user = response.user.unwrap();
+    assert_eq!(user.name, "Jane");
+    assert_eq!(user.nicknames, vec![Some("J".to_owned()), None]);
+}
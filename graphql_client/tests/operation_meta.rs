@@ -0,0 +1,64 @@
+use graphql_client::*;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "tests/convert_enum/queries.graphql",
+    schema_path = "tests/convert_enum/schema.graphql"
+)]
+pub struct IssueTitles;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "tests/mutation_only/schema.graphql",
+    query_path = "tests/mutation_only/query.graphql"
+)]
+pub struct CreatePost;
+
+static OPERATIONS: &[&dyn OperationMeta] = &[&IssueTitles, &CreatePost];
+
+#[test]
+fn static_registry_of_operations() {
+    let operations: Vec<_> = OPERATIONS
+        .iter()
+        .map(|operation| (operation.operation_name(), operation.operation_type()))
+        .collect();
+
+    assert_eq!(
+        operations,
+        vec![
+            ("IssueTitles", OperationType::Query),
+            ("CreatePost", OperationType::Mutation),
+        ]
+    );
+    assert_eq!(OPERATIONS[1].query(), create_post::QUERY);
+}
+
+#[test]
+fn query_sha256_is_the_hash_of_the_query() {
+    const QUERY_SHA256: &str = <IssueTitles as GraphQLQuery>::QUERY_SHA256;
+
+    assert_eq!(OPERATIONS[0].query_sha256(), QUERY_SHA256);
+    assert_eq!(QUERY_SHA256, issue_titles::QUERY_SHA256);
+    assert_eq!(QUERY_SHA256.len(), 64);
+    assert!(QUERY_SHA256
+        .chars()
+        .all(|c| c.is_ascii_digit() || ('a'..='f').contains(&c)));
+}
+
+/// Generic code only needs the type of the operation, never an instance of it.
+fn operation_document<Q: GraphQLQuery>() -> (&'static str, &'static str) {
+    (Q::OPERATION_NAME, Q::QUERY)
+}
+
+#[test]
+fn operation_consts_are_read_through_the_trait() {
+    assert_eq!(
+        operation_document::<IssueTitles>(),
+        ("IssueTitles", issue_titles::QUERY)
+    );
+    assert_eq!(
+        operation_document::<CreatePost>(),
+        (create_post::OPERATION_NAME, create_post::QUERY)
+    );
+    assert_eq!(std::mem::size_of::<CreatePost>(), 0);
+}
@@ -65,3 +65,57 @@ fn operation_name_is_correct() {
         "Heights"
     );
 }
+
+/// The names of the operations defined in a query document.
+fn operation_names(query: &str) -> Vec<String> {
+    use graphql_parser::query::{Definition, OperationDefinition};
+
+    graphql_parser::parse_query(query)
+        .unwrap()
+        .definitions
+        .into_iter()
+        .filter_map(|definition| match definition {
+            Definition::Operation(OperationDefinition::Query(query)) => query.name,
+            Definition::Operation(OperationDefinition::Mutation(mutation)) => mutation.name,
+            Definition::Operation(OperationDefinition::Subscription(subscription)) => {
+                subscription.name
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn operation_name_is_defined_in_the_query_document() {
+    // The whole document is sent, the server picks the operation by name.
+    assert_eq!(operation_names(Heights::QUERY), vec!["Heights", "Echo"]);
+    assert_eq!(Echo::QUERY, Heights::QUERY);
+
+    for operation_name in &[Heights::OPERATION_NAME, Echo::OPERATION_NAME] {
+        assert_eq!(
+            operation_names(Heights::QUERY)
+                .iter()
+                .filter(|name| name == operation_name)
+                .count(),
+            1,
+        );
+    }
+}
+
+#[test]
+fn the_query_can_be_overridden() {
+    let echo_variables = echo::Variables {
+        msg: Some("hi".to_string()),
+    };
+    let query = "query Echo($msg: String) { echo(msg: $msg) }";
+
+    let body = Echo::build_query_with_query(echo_variables, query);
+
+    assert_eq!(body.query, query);
+    assert_eq!(body.operation_name, "Echo");
+    assert_eq!(body.variables.msg.as_deref(), Some("hi"));
+    assert_eq!(
+        Echo::build_query(echo::Variables { msg: None }).query,
+        echo::QUERY
+    );
+}
@@ -0,0 +1,43 @@
+use graphql_client::*;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "tests/preserve_normalization/schema.graphql",
+    query_path = "tests/preserve_normalization/query.graphql",
+    response_derives = "Debug",
+    normalization = "preserve"
+)]
+pub struct UsersQuery;
+
+#[test]
+fn variables_keep_their_graphql_names() {
+    let variables = users_query::Variables {
+        filter: users_query::UserFilter {
+            firstName: Some("Ada".to_owned()),
+            isActive: None,
+        },
+        maxCount: Some(10),
+    };
+
+    assert_eq!(
+        serde_json::to_value(&variables).unwrap(),
+        serde_json::json!({
+            "filter": { "firstName": "Ada", "isActive": null },
+            "maxCount": 10,
+        })
+    );
+}
+
+#[test]
+fn response_fields_keep_their_graphql_names() {
+    let response: users_query::ResponseData = serde_json::from_value(serde_json::json!({
+        "users": [{ "firstName": "Ada", "lastName": null, "type": "admin" }]
+    }))
+    .unwrap();
+
+    let user = &response.users[0];
+    assert_eq!(user.firstName, "Ada");
+    assert_eq!(user.lastName, None);
+    // Keywords are still escaped.
+    assert_eq!(user.type_, "admin");
+}
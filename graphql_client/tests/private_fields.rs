@@ -0,0 +1,30 @@
+use graphql_client::*;
+use serde_json::json;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "tests/facade/schema.graphql",
+    query_path = "tests/facade/query.graphql",
+    response_derives = "Debug, PartialEq",
+    private_fields_with_getters = "true"
+)]
+pub struct PostsQuery;
+
+#[test]
+fn getters_return_the_private_fields() {
+    let response: posts_query::ResponseData = serde_json::from_value(json!({
+        "posts": [
+            { "id": "1", "status": "PUBLISHED", "author": { "name": "Ada" } }
+        ]
+    }))
+    .unwrap();
+
+    let post = &response.posts()[0];
+
+    assert_eq!(post.author().name(), "Ada");
+    assert_eq!(post.post_fields().id(), "1");
+    assert_eq!(
+        post.post_fields().status(),
+        &posts_query::PostStatus::PUBLISHED
+    );
+}
@@ -0,0 +1,33 @@
+use graphql_client::GraphQLQuery;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "tests/operation_selection/queries.graphql",
+    schema_path = "tests/operation_selection/schema.graphql",
+    query_as_bytes = "true"
+)]
+pub struct Heights;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "tests/operation_selection/queries.graphql",
+    schema_path = "tests/operation_selection/schema.graphql",
+    query_as_bytes = "true"
+)]
+pub struct Echo;
+
+const QUERY_SOURCE: &str = include_str!("operation_selection/queries.graphql");
+
+#[test]
+fn queries_embedded_as_bytes_are_the_query_source() {
+    assert_eq!(heights::QUERY, QUERY_SOURCE);
+    assert_eq!(echo::QUERY, QUERY_SOURCE);
+    assert_eq!(<Heights as GraphQLQuery>::QUERY, QUERY_SOURCE);
+    assert_eq!(
+        Echo::build_query(echo::Variables {
+            msg: Some("hi".to_owned())
+        })
+        .query,
+        QUERY_SOURCE
+    );
+}
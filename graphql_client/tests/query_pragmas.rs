@@ -0,0 +1,27 @@
+use graphql_client::*;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "tests/preserve_normalization/schema.graphql",
+    query_path = "tests/query_pragmas/query.graphql",
+    response_derives = "Debug",
+    normalization = "none"
+)]
+pub struct UsersQuery;
+
+#[test]
+fn pragmas_override_the_attributes() {
+    let response: users_query::ResponseData = serde_json::from_value(serde_json::json!({
+        "users": [{ "firstName": "Ada", "lastName": null }]
+    }))
+    .unwrap();
+
+    // `preserve` keeps the names of the fields, and `PartialEq` is derived.
+    assert_eq!(
+        response.users[0],
+        users_query::UsersQueryUsers {
+            firstName: "Ada".to_owned(),
+            lastName: None,
+        }
+    );
+}
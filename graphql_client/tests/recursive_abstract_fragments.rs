@@ -0,0 +1,141 @@
+use graphql_client::*;
+use serde_json::json;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "tests/recursive_abstract_fragments/query.graphql",
+    schema_path = "tests/recursive_abstract_fragments/schema.graphql",
+    response_derives = "Debug"
+)]
+pub struct InterfaceRecursion;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "tests/recursive_abstract_fragments/query.graphql",
+    schema_path = "tests/recursive_abstract_fragments/schema.graphql",
+    response_derives = "Debug"
+)]
+pub struct InterfaceVariantRecursion;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "tests/recursive_abstract_fragments/query.graphql",
+    schema_path = "tests/recursive_abstract_fragments/schema.graphql",
+    response_derives = "Debug"
+)]
+pub struct UnionVariantRecursion;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "tests/recursive_abstract_fragments/query.graphql",
+    schema_path = "tests/recursive_abstract_fragments/schema.graphql",
+    response_derives = "Debug"
+)]
+pub struct MutualRecursion;
+
+#[test]
+fn fragment_recursive_through_an_interface_field() {
+    use interface_recursion::*;
+
+    let response = json!({
+        "comment": {
+            "__typename": "Comment",
+            "id": "3",
+            "parent": {
+                "__typename": "Comment",
+                "id": "2",
+                "parent": { "__typename": "Post", "id": "1", "parent": null },
+            },
+        },
+    });
+
+    let data: ResponseData = serde_json::from_value(response).unwrap();
+    let parent = data.comment.unwrap().parent_chain.parent.unwrap();
+    let grandparent = parent.parent_chain.parent.unwrap();
+
+    assert_eq!(parent.parent_chain.id, "2");
+    assert_eq!(grandparent.parent_chain.id, "1");
+    assert!(matches!(grandparent.on, ParentChainParentOn::Post));
+}
+
+#[test]
+fn fragment_recursive_through_an_interface_member() {
+    use interface_variant_recursion::*;
+
+    let response = json!({
+        "comment": {
+            "body": "Agreed.",
+            "parent": {
+                "__typename": "Comment",
+                "body": "Ship it.",
+                "parent": { "__typename": "Post" },
+            },
+        },
+    });
+
+    let data: ResponseData = serde_json::from_value(response).unwrap();
+    let parent = data.comment.unwrap().reply_chain.parent.unwrap();
+
+    match parent.on {
+        ReplyChainParentOn::Comment(comment) => {
+            assert_eq!(comment.reply_chain.body, "Ship it.");
+            assert!(matches!(
+                comment.reply_chain.parent.unwrap().on,
+                ReplyChainParentOn::Post
+            ));
+        }
+        other => panic!("Unexpected parent: {:?}", other),
+    }
+}
+
+#[test]
+fn fragment_recursive_through_a_union_member() {
+    use union_variant_recursion::*;
+
+    let response = json!({
+        "comment": {
+            "body": "Agreed.",
+            "inReplyTo": {
+                "__typename": "Comment",
+                "body": "Ship it.",
+                "inReplyTo": { "__typename": "Post", "title": "Release 1.0" },
+            },
+        },
+    });
+
+    let data: ResponseData = serde_json::from_value(response).unwrap();
+
+    match data.comment.unwrap().reply_thread.in_reply_to.unwrap() {
+        ReplyThreadInReplyTo::Comment(comment) => {
+            assert_eq!(comment.reply_thread.body, "Ship it.");
+            assert!(matches!(
+                comment.reply_thread.in_reply_to,
+                Some(ReplyThreadInReplyTo::Post(ReplyThreadInReplyToOnPost { ref title }))
+                    if title == "Release 1.0"
+            ));
+        }
+        other => panic!("Unexpected reply: {:?}", other),
+    }
+}
+
+#[test]
+fn fragments_recursive_through_each_other() {
+    use mutual_recursion::*;
+
+    let response = json!({
+        "comment": {
+            "__typename": "Comment",
+            "parent": {
+                "__typename": "Post",
+                "id": "1",
+                "parent": null,
+            },
+        },
+    });
+
+    let data: ResponseData = serde_json::from_value(response).unwrap();
+    let parent = data.comment.unwrap().parent_link.parent.unwrap();
+
+    assert_eq!(parent.linked_parent.id, "1");
+    assert!(parent.linked_parent.parent_link.parent.is_none());
+}
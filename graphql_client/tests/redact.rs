@@ -0,0 +1,40 @@
+use graphql_client::*;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "tests/redact/schema.graphql",
+    query_path = "tests/redact/mutation.graphql",
+    variables_derives = "Debug",
+    response_derives = "Debug",
+    redact = "Variables: password; LoginLogin: token"
+)]
+pub struct Login;
+
+#[test]
+fn redacted_variables_are_not_printed() {
+    let variables = login::Variables {
+        username: "alice".to_owned(),
+        password: "hunter2".to_owned(),
+    };
+
+    assert_eq!(
+        format!("{:?}", variables),
+        r#"Variables { username: "alice", password: *** }"#
+    );
+}
+
+#[test]
+fn redacted_response_fields_are_not_printed() {
+    let response: login::ResponseData = serde_json::from_value(serde_json::json!({
+        "login": {
+            "token": "secret-token",
+            "user": { "name": "alice" },
+        },
+    }))
+    .unwrap();
+
+    assert_eq!(
+        format!("{:?}", response),
+        r#"ResponseData { login: LoginLogin { token: ***, user: LoginLoginUser { name: "alice" } } }"#
+    );
+}
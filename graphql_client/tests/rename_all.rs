@@ -0,0 +1,79 @@
+use graphql_client::*;
+use serde_json::json;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "tests/rename_all/schema.graphql",
+    query_path = "tests/rename_all/query.graphql",
+    response_derives = "Debug, PartialEq",
+    rename_all = "none"
+)]
+pub struct ReleaseQuery;
+
+pub mod camel_case {
+    use graphql_client::*;
+
+    #[derive(GraphQLQuery)]
+    #[graphql(
+        schema_path = "tests/rename_all/schema.graphql",
+        query_path = "tests/rename_all/query.graphql",
+        response_derives = "Debug, PartialEq, Serialize",
+        rename_all = "camelCase"
+    )]
+    pub struct ReleaseQuery;
+}
+
+#[test]
+fn none_keeps_the_names_from_the_schema() {
+    let response: release_query::ResponseData = serde_json::from_value(json!({
+        "release": {
+            "id": "1",
+            "display_name": "1.0",
+            "releasedAt": null,
+            "channel": "LONG_TERM_SUPPORT",
+        }
+    }))
+    .unwrap();
+    let release = response.release.unwrap();
+
+    assert_eq!(release.display_name, "1.0");
+    assert_eq!(release.releasedAt, None);
+    assert_eq!(
+        release.channel,
+        release_query::ReleaseChannel::LONG_TERM_SUPPORT
+    );
+    assert_eq!(
+        serde_json::to_value(release_query::ReleaseChannel::NIGHTLY).unwrap(),
+        json!("NIGHTLY")
+    );
+}
+
+#[test]
+fn camel_case_renames_the_fields_and_variants() {
+    let response: camel_case::release_query::ResponseData = serde_json::from_value(json!({
+        "release": {
+            "id": "1",
+            "display_name": "1.0",
+            "releasedAt": "2020-03-13",
+            "channel": "LONG_TERM_SUPPORT",
+        }
+    }))
+    .unwrap();
+    let release = response.release.unwrap();
+
+    assert_eq!(release.displayName, "1.0");
+    assert_eq!(release.releasedAt.as_deref(), Some("2020-03-13"));
+    assert_eq!(
+        release.channel,
+        camel_case::release_query::ReleaseChannel::longTermSupport
+    );
+    assert_eq!(
+        serde_json::to_value(release).unwrap(),
+        json!({
+            "id": "1",
+            "display_name": "1.0",
+            "releasedAt": "2020-03-13",
+            "channel": "LONG_TERM_SUPPORT",
+        })
+    );
+}
@@ -0,0 +1,249 @@
+use graphql_client::*;
+use serde_json::json;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "tests/unions/union_query.graphql",
+    schema_path = "tests/unions/union_schema.graphql",
+    response_derives = "Serialize"
+)]
+pub struct UnionQuery;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "tests/unions/union_query.graphql",
+    schema_path = "tests/unions/union_schema.graphql",
+    response_derives = "Serialize"
+)]
+pub struct FragmentOnUnion;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "tests/interfaces/interface_query.graphql",
+    schema_path = "tests/interfaces/interface_schema.graphql",
+    response_derives = "Serialize"
+)]
+pub struct InterfaceQuery;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "tests/interfaces/interface_with_fragment_query.graphql",
+    schema_path = "tests/interfaces/interface_schema.graphql",
+    response_derives = "Serialize"
+)]
+pub struct InterfaceWithFragmentQuery;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "tests/fragments/query.graphql",
+    schema_path = "tests/fragments/schema.graphql",
+    response_derives = "Serialize"
+)]
+pub struct RecursiveFragmentQuery;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "tests/skip_include/query.graphql",
+    schema_path = "tests/skip_include/schema.graphql",
+    response_derives = "Serialize"
+)]
+pub struct SkipIncludeQuery;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "tests/deprecation/query.graphql",
+    schema_path = "tests/deprecation/schema.graphql",
+    response_derives = "Serialize",
+    deprecated = "warn"
+)]
+pub struct WarnDeprecation;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "tests/deprecation/query.graphql",
+    schema_path = "tests/deprecation/schema.graphql",
+    response_derives = "Serialize",
+    deprecated = "deny"
+)]
+pub struct DenyDeprecation;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "tests/untagged_unions/query.graphql",
+    schema_path = "tests/untagged_unions/schema.graphql",
+    response_derives = "Serialize",
+    untagged_unions = "true"
+)]
+pub struct SearchQuery;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "tests/recursive_abstract_fragments/query.graphql",
+    schema_path = "tests/recursive_abstract_fragments/schema.graphql",
+    response_derives = "Serialize"
+)]
+pub struct InterfaceRecursion;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "tests/recursive_abstract_fragments/query.graphql",
+    schema_path = "tests/recursive_abstract_fragments/schema.graphql",
+    response_derives = "Serialize"
+)]
+pub struct UnionVariantRecursion;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "tests/recursive_abstract_fragments/query.graphql",
+    schema_path = "tests/recursive_abstract_fragments/schema.graphql",
+    response_derives = "Serialize"
+)]
+pub struct MutualRecursion;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "tests/response_roundtrip/union_fragment_query.graphql",
+    schema_path = "tests/unions/union_schema.graphql",
+    response_derives = "Serialize"
+)]
+pub struct UnionFragmentTypename;
+
+/// Deserialize the response, serialize it back and compare the JSON.
+fn assert_round_trip<ResponseData>(response: serde_json::Value)
+where
+    ResponseData: serde::Serialize + for<'de> serde::Deserialize<'de>,
+{
+    let response_data: ResponseData = serde_json::from_value(response.clone()).unwrap();
+    // The fields selected by several fragments are serialized once, so that the serialized
+    // response can be deserialized again.
+    let serialized = serde_json::to_string(&response_data).unwrap();
+    let response_data: ResponseData = serde_json::from_str(&serialized).unwrap();
+
+    assert_eq!(serde_json::to_value(&response_data).unwrap(), response);
+}
+
+fn fixture(json: &str) -> serde_json::Value {
+    serde_json::from_str(json).unwrap()
+}
+
+#[test]
+fn unions_round_trip_with_their_typename() {
+    assert_round_trip::<union_query::ResponseData>(fixture(include_str!(
+        "unions/union_query_response.json"
+    )));
+}
+
+#[test]
+fn fragments_on_unions_round_trip() {
+    assert_round_trip::<fragment_on_union::ResponseData>(json!({
+        "names": [
+            { "__typename": "Dog", "name": "Laïka" },
+            { "__typename": "Person", "firstName": "Audrey" },
+        ],
+    }));
+}
+
+#[test]
+fn interfaces_round_trip() {
+    assert_round_trip::<interface_query::ResponseData>(fixture(include_str!(
+        "interfaces/interface_response.json"
+    )));
+    assert_round_trip::<interface_with_fragment_query::ResponseData>(fixture(include_str!(
+        "interfaces/interface_with_fragment_response.json"
+    )));
+}
+
+#[test]
+fn flattened_fragments_round_trip() {
+    assert_round_trip::<recursive_fragment_query::ResponseData>(json!({
+        "recursive": {
+            "head": "a",
+            "tail": { "head": "b", "tail": null },
+        },
+    }));
+}
+
+#[test]
+fn skipped_fields_stay_out_of_the_serialized_response() {
+    // `email` is not included by `@include(if: $withEmail)`.
+    assert_round_trip::<skip_include_query::ResponseData>(fixture(include_str!(
+        "response_roundtrip/skip_include_response.json"
+    )));
+}
+
+#[test]
+fn deprecated_fields_round_trip() {
+    assert_round_trip::<warn_deprecation::ResponseData>(json!({
+        "currentUser": {
+            "name": "Ferris",
+            "id": "1",
+            "deprecatedWithReason": "old",
+            "deprecatedNoReason": "older",
+        },
+    }));
+    // The deprecated fields are not generated under the `deny` strategy.
+    assert_round_trip::<deny_deprecation::ResponseData>(json!({
+        "currentUser": { "name": "Ferris", "id": "1" },
+    }));
+}
+
+#[test]
+fn untagged_unions_round_trip_without_typename() {
+    assert_round_trip::<search_query::ResponseData>(json!({
+        "search": [
+            { "title": "Dune", "isbn": "978-0441013593", "pages": null },
+            { "title": "Wired", "issue": 12 },
+            { "title": "Hard Fork" },
+        ],
+    }));
+}
+
+#[test]
+fn boxed_recursive_fragments_round_trip() {
+    assert_round_trip::<interface_recursion::ResponseData>(json!({
+        "comment": {
+            "__typename": "Comment",
+            "id": "2",
+            "parent": { "__typename": "Post", "id": "1", "parent": null },
+        },
+    }));
+    assert_round_trip::<union_variant_recursion::ResponseData>(json!({
+        "comment": {
+            "body": "Agreed.",
+            "inReplyTo": {
+                "__typename": "Comment",
+                "body": "Ship it.",
+                "inReplyTo": { "__typename": "Post", "title": "Release 1.0" },
+            },
+        },
+    }));
+    // `__typename` is selected by both fragments, and serialized once.
+    assert_round_trip::<mutual_recursion::ResponseData>(json!({
+        "comment": {
+            "__typename": "Comment",
+            "parent": { "__typename": "Post", "id": "1", "parent": null },
+        },
+    }));
+}
+
+#[test]
+fn typename_selected_by_fragments_is_serialized_once() {
+    // `__typename` is selected by the union, and by the fragment on its member.
+    assert_round_trip::<union_fragment_typename::ResponseData>(json!({
+        "names": [
+            { "__typename": "Dog", "name": "Laïka" },
+            { "__typename": "Person" },
+        ],
+    }));
+    // `__typename` is selected by the interface, and by the fragment on it. `birthday` is
+    // selected by the fragment and an inline fragment.
+    let response = fixture(include_str!(
+        "interfaces/interface_with_fragment_response.json"
+    ));
+    let response_data: interface_with_fragment_query::ResponseData =
+        serde_json::from_value(response).unwrap();
+    let serialized = serde_json::to_string(&response_data).unwrap();
+
+    assert_eq!(serialized.matches("\"__typename\"").count(), 4);
+    assert_eq!(serialized.matches("\"birthday\"").count(), 1);
+}
@@ -0,0 +1,29 @@
+use graphql_client::*;
+use serde_json::json;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "tests/mutation_only/schema.graphql",
+    query_path = "tests/mutation_only/query.graphql",
+    response_derives = "Debug, PartialEq",
+    response_struct_name = "CreatedPost"
+)]
+pub struct CreatePost;
+
+#[test]
+fn response_struct_can_be_renamed() {
+    let response: <CreatePost as GraphQLQuery>::ResponseData = serde_json::from_value(json!({
+        "createPost": { "id": "1", "title": "Hello" }
+    }))
+    .unwrap();
+
+    assert_eq!(
+        response,
+        create_post::CreatedPost {
+            create_post: create_post::CreatePostCreatePost {
+                id: "1".to_owned(),
+                title: "Hello".to_owned(),
+            },
+        }
+    );
+}
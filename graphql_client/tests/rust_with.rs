@@ -0,0 +1,74 @@
+use graphql_client::*;
+use serde_json::json;
+
+mod parsers {
+    // Only `deserialize`, since the response does not derive `Serialize`.
+    pub mod semicolon_list {
+        use serde::{Deserialize, Deserializer};
+
+        pub fn deserialize<'de, D: Deserializer<'de>>(
+            deserializer: D,
+        ) -> Result<Vec<String>, D::Error> {
+            let list = String::deserialize(deserializer)?;
+            Ok(list.split(';').map(ToOwned::to_owned).collect())
+        }
+    }
+
+    pub mod round_trip {
+        pub use super::semicolon_list::deserialize;
+        use serde::Serializer;
+
+        pub fn serialize<S: Serializer>(list: &[String], serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.serialize_str(&list.join(";"))
+        }
+    }
+}
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "tests/rust_with/schema.graphql",
+    query_path = "tests/rust_with/query.graphql",
+    response_derives = "Debug, PartialEq"
+)]
+pub struct ArticleQuery;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "tests/rust_with/schema.graphql",
+    query_path = "tests/rust_with/round_trip_query.graphql",
+    response_derives = "Debug, PartialEq, Serialize"
+)]
+pub struct RoundTripQuery;
+
+#[test]
+fn rust_with_directive_deserializes_with_the_given_module() {
+    let response: article_query::ResponseData = serde_json::from_value(json!({
+        "article": { "id": "1", "title": "Typed queries", "tags": "rust;graphql" }
+    }))
+    .unwrap();
+
+    assert_eq!(
+        response.article,
+        Some(article_query::ArticleQueryArticle {
+            id: "1".to_owned(),
+            title: "Typed queries".to_owned(),
+            tags: vec!["rust".to_owned(), "graphql".to_owned()],
+        })
+    );
+}
+
+#[test]
+fn rust_with_directive_is_stripped_from_the_query() {
+    assert!(!article_query::QUERY.contains("rust_with"));
+    assert!(article_query::QUERY.contains("tags"));
+}
+
+#[test]
+fn rust_with_directive_serializes_with_the_given_module() {
+    let response = json!({
+        "article": { "id": "1", "title": "Typed queries", "tags": "rust;graphql" }
+    });
+    let data: round_trip_query::ResponseData = serde_json::from_value(response.clone()).unwrap();
+
+    assert_eq!(serde_json::to_value(&data).unwrap(), response);
+}
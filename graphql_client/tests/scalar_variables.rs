@@ -33,3 +33,45 @@ fn scalar_variables_default() {
 
     assert_eq!(out, r#"{"msg":"o, hai","reps":3}"#);
 }
+
+#[test]
+fn scalar_variables_with_defaults() {
+    let variables = default_scalar_variables_query::Variables {
+        msg: Some("hello".to_string()),
+        reps: None,
+    }
+    .with_defaults();
+
+    let out = serde_json::to_string(&variables).unwrap();
+
+    assert_eq!(out, r#"{"msg":"hello","reps":3}"#);
+}
+
+#[test]
+fn scalar_variables_implement_default_with_the_default_values() {
+    let out = serde_json::to_string(&default_scalar_variables_query::Variables::default()).unwrap();
+
+    assert_eq!(out, r#"{"msg":"o, hai","reps":3}"#);
+
+    let variables = default_scalar_variables_query::Variables {
+        msg: Some("hello".to_string()),
+        ..Default::default()
+    };
+
+    assert_eq!(variables.reps, Some(3));
+}
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "tests/scalar_variables/scalar_variables_query_required_default.graphql",
+    schema_path = "tests/scalar_variables/scalar_variables_schema.graphql"
+)]
+pub struct RequiredDefaultScalarVariablesQuery;
+
+#[test]
+fn optional_variables_without_a_default_value_are_none_by_default() {
+    let variables = required_default_scalar_variables_query::Variables::default();
+
+    assert_eq!(variables.msg, "o, hai");
+    assert_eq!(variables.reps, None);
+}
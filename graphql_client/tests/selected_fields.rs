@@ -0,0 +1,22 @@
+use graphql_client::*;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "tests/selected_fields/query.graphql",
+    schema_path = "tests/selected_fields/schema.graphql",
+    emit_selected_fields = "true"
+)]
+pub struct ViewerRepositories;
+
+#[test]
+fn selected_fields_lists_the_field_paths() {
+    assert_eq!(
+        viewer_repositories::SELECTED_FIELDS,
+        &[
+            "viewer.login",
+            "viewer.repositories.totalCount",
+            "viewer.repositories.nodes.name",
+            "viewer.repositories.nodes.stargazerCount",
+        ]
+    );
+}
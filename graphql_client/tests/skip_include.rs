@@ -0,0 +1,47 @@
+use graphql_client::*;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "tests/skip_include/schema.graphql",
+    query_path = "tests/skip_include/query.graphql",
+    response_derives = "Debug, PartialEq"
+)]
+pub struct SkipIncludeQuery;
+
+#[test]
+fn conditional_fields_are_optional() {
+    let response: skip_include_query::ResponseData =
+        serde_json::from_value(serde_json::json!({ "user": { "name": "Ada" } })).unwrap();
+
+    assert_eq!(
+        response.user,
+        skip_include_query::SkipIncludeQueryUser {
+            name: "Ada".to_owned(),
+            email: None,
+            nickname: None,
+            friends: None,
+        }
+    );
+}
+
+#[test]
+fn conditional_fields_are_deserialized_when_present() {
+    let response: skip_include_query::ResponseData = serde_json::from_value(serde_json::json!({
+        "user": {
+            "name": "Ada",
+            "email": "ada@example.com",
+            "nickname": null,
+            "friends": [{ "id": "2" }],
+        }
+    }))
+    .unwrap();
+
+    assert_eq!(response.user.email.as_deref(), Some("ada@example.com"));
+    assert_eq!(response.user.nickname, None);
+    assert_eq!(
+        response.user.friends,
+        Some(vec![skip_include_query::SkipIncludeQueryUserFriends {
+            id: "2".to_owned()
+        }])
+    );
+}
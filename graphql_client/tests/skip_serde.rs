@@ -0,0 +1,41 @@
+use graphql_client::*;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "tests/skip_serde/schema.graphql",
+    query_path = "tests/skip_serde/query.graphql",
+    response_derives = "Debug, Serialize",
+    skip_serde = "ItemsQueryItems: local_notes"
+)]
+pub struct ItemsQuery;
+
+#[test]
+fn skipped_fields_are_defaulted_on_deserialization() {
+    let response: items_query::ResponseData = serde_json::from_value(serde_json::json!({
+        "items": [
+            { "id": "1", "name": "Towel", "localNotes": ["Don't panic"] },
+            { "id": "2", "name": "Guide" },
+        ]
+    }))
+    .unwrap();
+
+    assert_eq!(response.items.len(), 2);
+    assert_eq!(response.items[0].name, "Towel");
+    assert_eq!(response.items[0].local_notes, None);
+    assert_eq!(response.items[1].local_notes, None);
+}
+
+#[test]
+fn skipped_fields_are_not_serialized() {
+    let mut response: items_query::ResponseData = serde_json::from_value(serde_json::json!({
+        "items": [{ "id": "1", "name": "Towel" }]
+    }))
+    .unwrap();
+
+    response.items[0].local_notes = Some(vec!["Don't panic".to_owned()]);
+
+    assert_eq!(
+        serde_json::to_value(&response).unwrap(),
+        serde_json::json!({ "items": [{ "id": "1", "name": "Towel" }] })
+    );
+}
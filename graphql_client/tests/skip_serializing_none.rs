@@ -0,0 +1,35 @@
+use graphql_client::*;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "tests/scalar_variables/scalar_variables_query.graphql",
+    schema_path = "tests/scalar_variables/scalar_variables_schema.graphql",
+    skip_serializing_none = "true"
+)]
+pub struct ScalarVariablesQuery;
+
+#[test]
+fn none_variables_are_left_out() {
+    let variables = scalar_variables_query::Variables {
+        msg: "hello".to_string(),
+        reps: None,
+    };
+
+    assert_eq!(
+        serde_json::to_string(&variables).unwrap(),
+        r#"{"msg":"hello"}"#
+    );
+}
+
+#[test]
+fn some_variables_are_serialized() {
+    let variables = scalar_variables_query::Variables {
+        msg: "hello".to_string(),
+        reps: Some(32),
+    };
+
+    assert_eq!(
+        serde_json::to_string(&variables).unwrap(),
+        r#"{"msg":"hello","reps":32}"#
+    );
+}
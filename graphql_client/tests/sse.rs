@@ -0,0 +1,60 @@
+#![cfg(feature = "sse")]
+
+use graphql_client::sse::{parse_sse_event, SseError, SubscriptionEvent};
+use graphql_client::*;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "tests/subscription/subscription_schema.graphql",
+    query_path = "tests/subscription/subscription_query.graphql",
+    response_derives = "Debug, PartialEq"
+)]
+pub struct SubscriptionQuery;
+
+#[test]
+fn next_event_is_parsed() {
+    let frame = r#"{"data":{"dogBirthdays":[{"name":"Maya"}]}}"#;
+
+    let event = parse_sse_event::<subscription_query::ResponseData>("next", frame).unwrap();
+
+    match event {
+        SubscriptionEvent::Next(response) => {
+            let data = response.data.unwrap();
+            assert_eq!(data.dog_birthdays.unwrap()[0].name.as_deref(), Some("Maya"));
+            assert!(response.errors.is_none());
+        }
+        SubscriptionEvent::Complete => panic!("expected a next event"),
+    }
+}
+
+#[test]
+fn next_event_with_errors_is_parsed() {
+    let frame = r#"{"data":null,"errors":[{"message":"Forbidden"}]}"#;
+
+    let event = parse_sse_event::<subscription_query::ResponseData>("next", frame).unwrap();
+
+    match event {
+        SubscriptionEvent::Next(response) => {
+            assert!(response.data.is_none());
+            assert_eq!(response.errors.unwrap()[0].message, "Forbidden");
+        }
+        SubscriptionEvent::Complete => panic!("expected a next event"),
+    }
+}
+
+#[test]
+fn complete_event_is_parsed() {
+    let event = parse_sse_event::<subscription_query::ResponseData>("complete", "").unwrap();
+
+    assert_eq!(event, SubscriptionEvent::Complete);
+}
+
+#[test]
+fn unknown_event_is_rejected() {
+    let result = parse_sse_event::<subscription_query::ResponseData>("ping", "");
+
+    match result {
+        Err(SseError::UnknownEvent(event)) => assert_eq!(event, "ping"),
+        other => panic!("unexpected result: {:?}", other),
+    }
+}
@@ -11,6 +11,16 @@ const RESPONSE: &str = include_str!("subscription/subscription_query_response.js
 // )]
 // struct SubscriptionInvalidQuery;
 
+// The same query, for the servers that accept several root fields.
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "tests/subscription/subscription_schema.graphql",
+    query_path = "tests/subscription/subscription_invalid_query.graphql",
+    response_derives = "Debug, PartialEq",
+    allow_multiple_subscription_fields = "true"
+)]
+pub struct InvalidSubscription;
+
 #[derive(GraphQLQuery)]
 #[graphql(
     schema_path = "tests/subscription/subscription_schema.graphql",
@@ -47,3 +57,32 @@ fn subscriptions_work() {
         Some(4)
     );
 }
+
+#[test]
+fn subscription_variables_work() {
+    let query = SubscriptionQuery::build_query(subscription_query::Variables {
+        filter: Some("Maya".to_string()),
+    });
+
+    assert_eq!(query.operation_name, "SubscriptionQuery");
+    assert_eq!(
+        serde_json::to_value(&query.variables).unwrap(),
+        serde_json::json!({ "filter": "Maya" })
+    );
+}
+
+#[test]
+fn multiple_root_fields_are_optional() {
+    let response_data: invalid_subscription::ResponseData =
+        serde_json::from_str(r#"{"dogBirthdays": [{"name": "Maya"}]}"#).unwrap();
+
+    assert_eq!(response_data.new_dogs, None);
+    assert_eq!(
+        response_data.dog_birthdays,
+        Some(vec![
+            invalid_subscription::InvalidSubscriptionDogBirthdays {
+                name: Some("Maya".to_string()),
+            }
+        ])
+    );
+}
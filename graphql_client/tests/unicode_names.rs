@@ -0,0 +1,38 @@
+use graphql_client::*;
+use serde_json::json;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "tests/unicode_names/query.graphql",
+    schema_path = "tests/unicode_names/schema.json",
+    response_derives = "Debug, PartialEq"
+)]
+pub struct WeatherQuery;
+
+#[test]
+fn enum_values_that_are_not_rust_identifiers() {
+    use weather_query::Weather;
+
+    let response_data: weather_query::ResponseData =
+        serde_json::from_value(json!({ "weather": "ENSOLEILLÉ" })).unwrap();
+
+    assert_eq!(response_data.weather, Weather::ENSOLEILL_);
+    assert_eq!(Weather::_1ST_FROST.as_str(), "1ST_FROST");
+    assert_eq!(
+        serde_json::to_value(Weather::ENSOLEILL_).unwrap(),
+        json!("ENSOLEILLÉ")
+    );
+}
+
+#[test]
+fn input_fields_that_are_not_rust_identifiers() {
+    let filter = weather_query::Filter {
+        r_gion: Some("Bretagne".to_owned()),
+        _2nd_city: None,
+    };
+
+    assert_eq!(
+        serde_json::to_value(filter).unwrap(),
+        json!({ "région": "Bretagne", "2ndCity": null })
+    );
+}
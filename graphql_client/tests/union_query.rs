@@ -78,3 +78,139 @@ fn fragment_on_union() {
 
     assert_eq!(response_data, expected);
 }
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "tests/unions/list_of_unions_query.graphql",
+    schema_path = "tests/unions/union_schema.graphql",
+    response_derives = "PartialEq, Debug"
+)]
+pub struct ListOfUnionsQuery;
+
+#[test]
+fn list_of_unions_in_nested_field() {
+    use list_of_unions_query::*;
+
+    let response_data: ResponseData = serde_json::from_value(serde_json::json!({
+        "search": {
+            "total": 3,
+            "items": [
+                { "__typename": "Dog", "name": "Laïka" },
+                { "__typename": "Organization", "title": "Mozilla" },
+                { "__typename": "Person" }
+            ],
+            "pages": [
+                [{ "__typename": "Person", "firstName": "Audrey" }, null],
+                null
+            ]
+        }
+    }))
+    .unwrap();
+
+    let search = response_data.search.unwrap();
+    let items: Vec<ListOfUnionsQuerySearchItems> = search.items;
+
+    assert_eq!(search.total, 3);
+    assert_eq!(
+        items,
+        vec![
+            ListOfUnionsQuerySearchItems::Dog(ListOfUnionsQuerySearchItemsOnDog {
+                name: "Laïka".to_string(),
+            }),
+            ListOfUnionsQuerySearchItems::Organization(
+                ListOfUnionsQuerySearchItemsOnOrganization {
+                    title: "Mozilla".to_string(),
+                }
+            ),
+            ListOfUnionsQuerySearchItems::Person,
+        ]
+    );
+    assert_eq!(
+        search.pages,
+        Some(vec![
+            Some(vec![
+                Some(ListOfUnionsQuerySearchPages::Person(
+                    ListOfUnionsQuerySearchPagesOnPerson {
+                        first_name: "Audrey".to_string(),
+                    }
+                )),
+                None,
+            ]),
+            None,
+        ])
+    );
+}
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "tests/unions/union_query.graphql",
+    schema_path = "tests/unions/union_schema.graphql",
+    response_derives = "PartialEq, Debug",
+    union_member_traits = "true"
+)]
+pub struct UnionMemberQuery;
+
+#[test]
+fn union_member_traits() {
+    use union_member_query::*;
+
+    fn describe<M: UnionMemberQueryNamesMember + std::fmt::Debug>(member: M) -> String {
+        format!("{:?}", member)
+    }
+
+    let response_data: ResponseData = serde_json::from_str(RESPONSE).unwrap();
+    let descriptions: Vec<String> = response_data
+        .names
+        .unwrap()
+        .into_iter()
+        .map(|name| match name {
+            UnionMemberQueryNames::Person(person) => describe(person),
+            UnionMemberQueryNames::Dog(dog) => describe(dog),
+            UnionMemberQueryNames::Organization(organization) => describe(organization),
+        })
+        .collect();
+
+    assert_eq!(
+        descriptions[..2],
+        [
+            "UnionMemberQueryNamesOnPerson { first_name: \"Audrey\", last_name: Some(\"Lorde\") }",
+            "UnionMemberQueryNamesOnDog { name: \"Laïka\" }",
+        ]
+    );
+}
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "tests/unions/aliased_union_query.graphql",
+    schema_path = "tests/unions/union_schema.graphql",
+    response_derives = "PartialEq, Debug",
+    normalization = "preserve"
+)]
+pub struct AliasedUnionQuery;
+
+#[test]
+fn sibling_aliases_with_the_same_rust_name_get_their_own_types() {
+    use aliased_union_query::*;
+
+    // The fields keep their names, but the types are named in camel case.
+    let response_data: ResponseData = serde_json::from_value(serde_json::json!({
+        "userA": [{ "__typename": "Person", "firstName": "Audrey" }],
+        "user_a": [{ "__typename": "Dog", "name": "Laïka" }],
+    }))
+    .unwrap();
+
+    assert_eq!(
+        response_data.userA.unwrap(),
+        vec![AliasedUnionQueryUserA::Person(
+            AliasedUnionQueryUserAOnPerson {
+                firstName: "Audrey".to_string(),
+            }
+        )]
+    );
+    assert_eq!(
+        response_data.user_a.unwrap(),
+        vec![AliasedUnionQueryUserA2::Dog(AliasedUnionQueryUserA2OnDog {
+            name: "Laïka".to_string(),
+        })]
+    );
+}
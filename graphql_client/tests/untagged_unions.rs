@@ -0,0 +1,73 @@
+use graphql_client::*;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "tests/untagged_unions/schema.graphql",
+    query_path = "tests/untagged_unions/query.graphql",
+    untagged_unions = "true",
+    response_derives = "Debug"
+)]
+pub struct SearchQuery;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "tests/untagged_unions/schema.graphql",
+    query_path = "tests/untagged_unions/query.graphql",
+    untagged_unions = "true"
+)]
+pub struct NodesQuery;
+
+#[test]
+fn union_responses_without_typename_are_deserialized_as_the_most_specific_variant() {
+    use search_query::SearchQuerySearch;
+
+    let response: search_query::ResponseData = serde_json::from_value(serde_json::json!({
+        "search": [
+            { "title": "Dune", "isbn": "978-0441013593", "pages": null },
+            { "title": "Wired", "issue": 12 },
+            { "title": "Hard Fork" },
+            {},
+        ]
+    }))
+    .unwrap();
+
+    match &response.search[0] {
+        SearchQuerySearch::Book(book) => {
+            assert_eq!(book.isbn, "978-0441013593");
+            assert_eq!(book.pages, None);
+        }
+        other => panic!("Unexpected variant: {:?}", other),
+    }
+    assert!(
+        matches!(&response.search[1], SearchQuerySearch::Magazine(magazine) if magazine.issue == 12)
+    );
+    assert!(
+        matches!(&response.search[2], SearchQuerySearch::Podcast(podcast) if podcast.title == "Hard Fork")
+    );
+    // The authors have no selected fields.
+    assert!(matches!(&response.search[3], SearchQuerySearch::Other {}));
+}
+
+#[test]
+fn interface_responses_without_typename_are_deserialized_as_the_matching_variant() {
+    use nodes_query::NodesQueryNodesOn;
+
+    let response: nodes_query::ResponseData = serde_json::from_value(serde_json::json!({
+        "nodes": [
+            { "id": "1", "email": "ada@example.com" },
+            { "id": "2", "members": ["1"] },
+            { "id": "3" },
+        ]
+    }))
+    .unwrap();
+
+    assert_eq!(response.nodes[0].id, "1");
+    assert!(
+        matches!(&response.nodes[0].on, NodesQueryNodesOn::User(user) if user.email == "ada@example.com")
+    );
+    assert!(
+        matches!(&response.nodes[1].on, NodesQueryNodesOn::Group(group) if group.members == ["1"])
+    );
+    assert_eq!(response.nodes[2].id, "3");
+    assert!(matches!(&response.nodes[2].on, NodesQueryNodesOn::Other {}));
+}
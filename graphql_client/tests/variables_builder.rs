@@ -0,0 +1,72 @@
+use graphql_client::*;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "tests/variables_builder/schema.graphql",
+    query_path = "tests/variables_builder/query.graphql"
+)]
+pub struct CreateIssue;
+
+#[test]
+fn unset_variables_are_null_or_defaulted() {
+    let variables = create_issue::Variables::builder().build("Crash on start".to_owned());
+
+    assert_eq!(
+        serde_json::to_value(&variables).unwrap(),
+        serde_json::json!({
+            "title": "Crash on start",
+            "body": null,
+            "labels": ["triage"],
+            "priority": 3,
+            "type": null,
+        })
+    );
+}
+
+#[test]
+fn variables_are_set_with_the_builder_methods() {
+    let variables = create_issue::Variables::builder()
+        .with_body("It crashes.".to_owned())
+        .with_labels(None)
+        .with_priority(1)
+        .with_type(Some("bug".to_owned()))
+        .build("Crash on start".to_owned());
+
+    assert_eq!(
+        serde_json::to_value(&variables).unwrap(),
+        serde_json::json!({
+            "title": "Crash on start",
+            "body": "It crashes.",
+            // A nullable variable explicitly set to `None` is sent as `null`.
+            "labels": null,
+            "priority": 1,
+            "type": "bug",
+        })
+    );
+}
+
+mod build_variable {
+    use graphql_client::*;
+
+    // The `build` variable does not clash with the `build` method.
+    #[derive(GraphQLQuery)]
+    #[graphql(
+        schema_path = "tests/variables_builder/schema.graphql",
+        query_path = "tests/variables_builder/build_query.graphql"
+    )]
+    pub struct IssueBuild;
+}
+
+#[test]
+fn variables_named_like_the_builder_methods_can_be_set() {
+    let variables = build_variable::issue_build::Variables::builder()
+        .with_build(Some("nightly".to_owned()))
+        .build("1".to_owned());
+
+    let body = build_variable::IssueBuild::build_query(variables);
+
+    assert_eq!(
+        serde_json::to_value(&body.variables).unwrap(),
+        serde_json::json!({ "id": "1", "build": "nightly" })
+    );
+}
@@ -0,0 +1,122 @@
+use graphql_client::*;
+use std::borrow::Cow;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "tests/variables_lifetime/query.graphql",
+    schema_path = "tests/variables_lifetime/schema.graphql",
+    variables_derives = "Debug, Deserialize, PartialEq",
+    response_derives = "Debug",
+    redact = "Variables: source",
+    variables_lifetime = "true"
+)]
+pub struct ImportRows;
+
+// The operation has no string or list variable to borrow.
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "tests/variables_lifetime/query.graphql",
+    schema_path = "tests/variables_lifetime/schema.graphql",
+    variables_lifetime = "true"
+)]
+pub struct ImportCount;
+
+mod owned {
+    use graphql_client::GraphQLQuery;
+
+    // The same operation, with owned variables.
+    #[derive(GraphQLQuery)]
+    #[graphql(
+        query_path = "tests/variables_lifetime/query.graphql",
+        schema_path = "tests/variables_lifetime/schema.graphql",
+        module_name = "owned_import_rows"
+    )]
+    pub struct ImportRows;
+}
+
+#[test]
+fn borrowed_variables_serialize_like_owned_ones() {
+    let rows: Vec<String> = (0..3).map(|i| format!("row {}", i)).collect();
+    let row_refs: Vec<&str> = rows.iter().map(String::as_str).collect();
+    let source = String::from("upload-1");
+
+    let borrowed = import_rows::Variables {
+        source: &source,
+        rows: Cow::Borrowed(&row_refs),
+        tags: Some(Cow::Owned(vec![Some("bulk"), None])),
+        label: None,
+        limit: Some(100),
+        extra: Some(Cow::Owned(vec![import_rows::Row {
+            key: "origin".to_string(),
+            value: Some("cli".to_string()),
+            kind: Some(import_rows::RowKind::TEXT),
+        }])),
+    };
+    let owned = owned::owned_import_rows::Variables {
+        source: source.clone(),
+        rows: rows.clone(),
+        tags: Some(vec![Some("bulk".to_string()), None]),
+        label: None,
+        limit: Some(100),
+        extra: Some(vec![owned::owned_import_rows::Row {
+            key: "origin".to_string(),
+            value: Some("cli".to_string()),
+            kind: Some(owned::owned_import_rows::RowKind::TEXT),
+        }]),
+    };
+
+    assert_eq!(
+        serde_json::to_value(ImportRows::build_query(borrowed)).unwrap(),
+        serde_json::to_value(owned::ImportRows::build_query(owned)).unwrap()
+    );
+}
+
+#[test]
+fn defaults_are_borrowed_from_the_query() {
+    let variables = import_rows::Variables::builder().build("upload-1", Cow::Borrowed(&[]));
+
+    assert_eq!(variables.tags, Some(Cow::Borrowed(&[Some("bulk")][..])));
+    assert_eq!(variables.label, Some("import"));
+    assert_eq!(
+        serde_json::to_string(&variables).unwrap(),
+        r#"{"source":"upload-1","rows":[],"tags":["bulk"],"label":"import","limit":null,"extra":null}"#
+    );
+}
+
+#[test]
+fn strings_are_deserialized_borrowed() {
+    let json = r#"{"source":"upload-1","rows":["a","b"],"tags":null,"label":"import","limit":null,"extra":null}"#;
+
+    let variables: import_rows::Variables<'_> = serde_json::from_str(json).unwrap();
+
+    assert_eq!(variables.source, "upload-1");
+    assert_eq!(*variables.rows, ["a", "b"]);
+    assert_eq!(variables.label, Some("import"));
+    assert!(format!("{:?}", variables).contains(r#"source: ***, rows: ["a", "b"]"#));
+}
+
+#[test]
+fn graphql_query_impl_takes_static_variables() {
+    let body = <ImportRows as GraphQLQuery>::build_query(import_rows::Variables {
+        source: "upload-1",
+        rows: Cow::Borrowed(&["a"]),
+        tags: None,
+        label: None,
+        limit: None,
+        extra: None,
+    });
+
+    assert_eq!(body.operation_name, "ImportRows");
+    assert_eq!(*body.variables.rows, ["a"]);
+}
+
+#[test]
+fn variables_without_strings_or_lists_are_owned() {
+    let body =
+        <ImportCount as GraphQLQuery>::build_query(import_count::Variables { limit: Some(10) });
+
+    assert_eq!(
+        serde_json::to_string(&body.variables).unwrap(),
+        r#"{"limit":10}"#
+    );
+}
@@ -88,13 +88,11 @@ fn test_bad_url() -> impl Future<Item = (), Error = JsValue> {
             },
         )
         .map(|_response| panic!("The API endpoint does not exist, this should not be called."))
-        .map_err(|err| {
-            assert_eq!(
-                err,
-                graphql_client::web::ClientError::Network(
-                    "NetworkError when attempting to fetch resource.".into()
-                )
-            );
+        .map_err(|err| match err {
+            graphql_client::web::ClientError::Network(message) => {
+                assert_eq!(message, "NetworkError when attempting to fetch resource.")
+            }
+            other => panic!("unexpected error: {:?}", other),
         })
         .then(|_| Ok(()))
 }
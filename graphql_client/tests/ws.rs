@@ -0,0 +1,136 @@
+#![cfg(feature = "ws")]
+
+use graphql_client::ws::{
+    parse_server_message, Decoded, ServerMessage, SubscriptionDecoder, WsError,
+};
+use graphql_client::*;
+
+const FRAMES: &str = include_str!("subscription/ws_frames.jsonl");
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "tests/subscription/subscription_schema.graphql",
+    query_path = "tests/subscription/subscription_query.graphql",
+    response_derives = "Debug, PartialEq"
+)]
+pub struct SubscriptionQuery;
+
+/// A decoded frame, without the response types.
+#[derive(Debug, PartialEq)]
+enum Event {
+    Ignore,
+    Ping,
+    Data(Vec<String>),
+    Errors(Vec<String>),
+    Completed,
+    UnexpectedId(String),
+    AfterCompletion,
+}
+
+fn event(decoded: Decoded<subscription_query::ResponseData>) -> Event {
+    match decoded {
+        Decoded::Ignore => Event::Ignore,
+        Decoded::Ping => Event::Ping,
+        Decoded::Data(response) => match response.data {
+            Some(data) => Event::Data(
+                data.dog_birthdays
+                    .unwrap()
+                    .into_iter()
+                    .map(|dog| dog.name.unwrap())
+                    .collect(),
+            ),
+            None => Event::Errors(
+                response
+                    .errors
+                    .unwrap()
+                    .into_iter()
+                    .map(|error| error.message)
+                    .collect(),
+            ),
+        },
+        Decoded::Completed => Event::Completed,
+        Decoded::ProtocolError(WsError::UnexpectedId { found, .. }) => Event::UnexpectedId(found),
+        Decoded::ProtocolError(WsError::AfterCompletion(_)) => Event::AfterCompletion,
+        Decoded::ProtocolError(err) => panic!("unexpected protocol error: {}", err),
+    }
+}
+
+#[test]
+fn a_captured_frame_sequence_is_decoded() {
+    let mut decoder = SubscriptionDecoder::<SubscriptionQuery>::new("1");
+
+    let events: Vec<Event> = FRAMES
+        .lines()
+        .map(|frame| event(decoder.decode(frame)))
+        .collect();
+
+    assert_eq!(
+        events,
+        vec![
+            Event::Ignore,
+            Event::Ignore,
+            Event::Data(vec!["Maya".to_owned()]),
+            Event::Ignore,
+            Event::Ping,
+            Event::UnexpectedId("2".to_owned()),
+            Event::Ignore,
+            Event::Errors(vec!["Too many dogs".to_owned()]),
+            Event::Ignore,
+            Event::Completed,
+            Event::AfterCompletion,
+        ]
+    );
+    assert!(decoder.is_completed());
+}
+
+#[test]
+fn legacy_data_and_single_error_messages_are_parsed() {
+    let data = parse_server_message::<subscription_query::ResponseData>(
+        r#"{"type":"data","id":"7","payload":{"data":{"dogBirthdays":null}}}"#,
+    )
+    .unwrap();
+    let error = parse_server_message::<subscription_query::ResponseData>(
+        r#"{"type":"error","id":"7","payload":{"message":"Invalid operation"}}"#,
+    )
+    .unwrap();
+
+    match data {
+        ServerMessage::Next { id, payload } => {
+            assert_eq!(id, "7");
+            assert_eq!(payload.data.unwrap().dog_birthdays, None);
+        }
+        other => panic!("expected a next message: {:?}", other),
+    }
+    match error {
+        ServerMessage::Error { id, payload } => {
+            assert_eq!(id, "7");
+            assert_eq!(payload[0].message, "Invalid operation");
+        }
+        other => panic!("expected an error message: {:?}", other),
+    }
+}
+
+#[test]
+fn invalid_frames_are_protocol_errors() {
+    let mut decoder = SubscriptionDecoder::<SubscriptionQuery>::new("1");
+
+    let errors: Vec<String> = [
+        "not json",
+        r#"{"type":"connection_error"}"#,
+        r#"{"type":"next","payload":{"data":null}}"#,
+        r#"{"type":"next","id":"1","payload":{"data":{"dogBirthdays":3}}}"#,
+    ]
+    .iter()
+    .map(|frame| match decoder.decode(frame) {
+        Decoded::ProtocolError(err) => err.to_string(),
+        other => panic!("expected a protocol error: {:?}", other),
+    })
+    .collect();
+
+    assert!(errors[0].starts_with("Invalid message: expected ident"));
+    assert_eq!(errors[1], "Unknown message type: connection_error");
+    assert_eq!(errors[2], "Invalid message: missing field `id`");
+    assert_eq!(errors[3], "Response shape error");
+    // The subscription is still going.
+    assert!(!decoder.is_completed());
+}
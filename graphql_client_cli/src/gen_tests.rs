@@ -0,0 +1,165 @@
+use anyhow::*;
+use graphql_client_codegen::{CodegenMode, GraphQLClientCodegenOptions, UsedEnum};
+use heck::SnakeCase;
+use std::path::{Path, PathBuf};
+
+pub(crate) struct CliGenTestsParams {
+    pub schema_path: PathBuf,
+    pub query_paths: Vec<PathBuf>,
+    pub output: PathBuf,
+    pub module_path: syn::Path,
+}
+
+pub(crate) fn gen_tests(params: CliGenTestsParams) -> Result<()> {
+    let CliGenTestsParams {
+        schema_path,
+        query_paths,
+        output,
+        module_path,
+    } = params;
+
+    let tests = roundtrip_tests(&schema_path, &query_paths, &module_path)?;
+
+    std::fs::write(&output, tests).with_context(|| format!("Writing {}", output.display()))?;
+
+    Ok(())
+}
+
+/// A test file with one round trip test for each enum used by the query files.
+fn roundtrip_tests(
+    schema_path: &Path,
+    query_paths: &[PathBuf],
+    module_path: &syn::Path,
+) -> Result<String> {
+    let mut tests = String::from(
+        "// Generated by `graphql-client gen-tests`: serialization and deserialization round trips\n\
+         // for the enums used by the queries.\n",
+    );
+    let mut tested_enums: Vec<String> = Vec::new();
+
+    for query_path in query_paths {
+        let gen = graphql_client_codegen::generate_code(
+            query_path.to_owned(),
+            schema_path,
+            GraphQLClientCodegenOptions::new(CodegenMode::Cli),
+        )
+        .map_err(|fail| fail.compat())
+        .with_context(|| format!("Generating the code for {}", query_path.display()))?;
+
+        // The `generate` command writes the code for `queries/user.graphql` to `user.rs`.
+        let file_module = query_path
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .ok_or_else(|| {
+                format_err!("Failed to find a file name in {}.", query_path.display())
+            })?;
+
+        for used_enum in gen.used_enums {
+            if tested_enums.contains(&used_enum.name) {
+                continue;
+            }
+
+            let path = format!(
+                "{}::{}::{}::{}",
+                path_to_string(module_path),
+                file_module,
+                used_enum.module,
+                used_enum.name
+            );
+            tests.push_str(&roundtrip_test(&path, &used_enum));
+            tested_enums.push(used_enum.name);
+        }
+    }
+
+    Ok(tests)
+}
+
+/// A test serializing and deserializing each variant of the enum, and deserializing a value that
+/// is not in the schema.
+fn roundtrip_test(path: &str, used_enum: &UsedEnum) -> String {
+    let name = &used_enum.name;
+    let mut test = format!(
+        "\n#[test]\nfn {}_round_trips() {{\n    use {};\n\n",
+        name.to_snake_case(),
+        path
+    );
+
+    for (variant, value) in &used_enum.variants {
+        let json = json_string(value);
+        test.push_str(&format!(
+            "    assert_eq!(serde_json::to_string(&{}::{}).unwrap(), {:?});\n",
+            name, variant, json
+        ));
+        test.push_str(&format!(
+            "    assert!(matches!(\n        serde_json::from_str::<{}>({:?}).unwrap(),\n        {}::{}\n    ));\n",
+            name, json, name, variant
+        ));
+    }
+
+    let mut unknown_value = String::from("UNKNOWN_VALUE");
+    while used_enum
+        .variants
+        .iter()
+        .any(|(_, value)| *value == unknown_value)
+    {
+        unknown_value.push('_');
+    }
+
+    test.push_str(&format!(
+        "    // Values that are not in the schema are deserialized as `{}`.\n    \
+         assert!(matches!(\n        serde_json::from_str::<{}>({:?}).unwrap(),\n        \
+         {}::{}(ref value) if value == {:?}\n    ));\n}}\n",
+        used_enum.other_variant,
+        name,
+        json_string(&unknown_value),
+        name,
+        used_enum.other_variant,
+        unknown_value
+    ));
+
+    test
+}
+
+fn json_string(value: &str) -> String {
+    serde_json::Value::String(value.to_owned()).to_string()
+}
+
+/// The path as written in Rust code, `my_crate::queries`.
+pub(crate) fn path_to_string(path: &syn::Path) -> String {
+    let segments: Vec<String> = path
+        .segments
+        .iter()
+        .map(|segment| segment.ident.to_string())
+        .collect();
+    let leading_colon = if path.leading_colon.is_some() {
+        "::"
+    } else {
+        ""
+    };
+
+    format!("{}{}", leading_colon, segments.join("::"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn roundtrip_tests_match_the_compiled_fixture() {
+        let tests_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("../graphql_client/tests/enum_roundtrip");
+
+        let tests = roundtrip_tests(
+            &tests_dir.join("schema.graphql"),
+            &[tests_dir.join("query.graphql")],
+            &syn::parse_str("crate").unwrap(),
+        )
+        .unwrap();
+
+        // The fixture is included and run by the `enum_roundtrip` tests of graphql_client.
+        assert_eq!(
+            tests,
+            std::fs::read_to_string(tests_dir.join("roundtrip_tests.rs")).unwrap()
+        );
+    }
+}
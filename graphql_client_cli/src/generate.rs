@@ -1,38 +1,103 @@
+use crate::introspect_schema::Header;
+use crate::schema_cache::SchemaCache;
 use anyhow::*;
+use graphql_client_codegen::schema::ParsedSchema;
+use graphql_client_codegen::warnings::Warning;
 use graphql_client_codegen::{
-    generate_module_token_stream, CodegenMode, GraphQLClientCodegenOptions,
+    CodegenMode, GeneratedCode, GraphQLClientCodegenOptions, OperationOptions, UnappliedTypeOption,
 };
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
 use std::fs::File;
 use std::io::Write as _;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 use syn::Token;
 
+/// The default for `--schema-cache-max-age`, in seconds.
+const DEFAULT_SCHEMA_CACHE_MAX_AGE: u64 = 600;
+
 pub(crate) struct CliCodegenParams {
-    pub query_path: PathBuf,
-    pub schema_path: PathBuf,
+    pub query_paths: Vec<PathBuf>,
+    pub schema_path: Option<PathBuf>,
+    pub schema_url: Option<String>,
+    pub schema_out: Option<PathBuf>,
+    pub headers: Vec<Header>,
+    pub authorization: Option<String>,
+    pub schema_cache: Option<PathBuf>,
+    pub schema_cache_max_age: Option<u64>,
     pub selected_operation: Option<String>,
+    pub fragment: Option<String>,
     pub variables_derives: Option<String>,
     pub response_derives: Option<String>,
+    pub derives_for: Option<String>,
     pub deprecation_strategy: Option<String>,
-    pub no_formatting: bool,
+    /// The boolean parameters are `None` when neither their flag nor its `--no-` negation is
+    /// given, so that the config file applies.
+    pub no_formatting: Option<bool>,
     pub module_visibility: Option<String>,
     pub output_directory: Option<PathBuf>,
+    pub serde_crate: Option<syn::Path>,
+    pub emit_scalar_stubs: Option<bool>,
+    pub config: Option<PathBuf>,
+    pub print_config: bool,
+    pub deny_warnings: Option<bool>,
+    pub forbidden_fields: Option<String>,
+    pub allowed_types: Option<String>,
+    pub file_header: Option<String>,
 }
 
 pub(crate) fn generate_code(params: CliCodegenParams) -> Result<()> {
+    let (params, operations) = match params.config.clone() {
+        Some(config) => {
+            let mut config = read_config(&config)?;
+            let operations = std::mem::take(&mut config.operations);
+            (params.with_config(config)?, operations)
+        }
+        None => (params, BTreeMap::new()),
+    };
+
+    if params.print_config {
+        print!("{}", params.to_config_file(operations)?);
+        return Ok(());
+    }
+
     let CliCodegenParams {
         variables_derives,
         response_derives,
+        derives_for,
         deprecation_strategy,
         no_formatting,
         output_directory,
         module_visibility: _module_visibility,
-        query_path,
+        query_paths,
         schema_path,
+        schema_url,
+        schema_out,
+        headers,
+        authorization,
+        schema_cache,
+        schema_cache_max_age,
         selected_operation,
+        fragment,
         serde_crate,
+        emit_scalar_stubs,
+        config,
+        print_config: _,
+        deny_warnings,
+        forbidden_fields,
+        allowed_types,
+        file_header,
     } = params;
 
+    if query_paths.is_empty() {
+        return Err(format_err!("A query path is required."));
+    }
+
+    let no_formatting = no_formatting.unwrap_or(false);
+    let emit_scalar_stubs = emit_scalar_stubs.unwrap_or(false);
+    let deny_warnings = deny_warnings.unwrap_or(false);
+
     let deprecation_strategy = deprecation_strategy.as_ref().and_then(|s| s.parse().ok());
 
     let mut options = GraphQLClientCodegenOptions::new(CodegenMode::Cli);
@@ -48,6 +113,10 @@ pub(crate) fn generate_code(params: CliCodegenParams) -> Result<()> {
         options.set_operation_name(selected_operation);
     }
 
+    if let Some(fragment) = fragment {
+        options.set_fragment(fragment);
+    }
+
     if let Some(variables_derives) = variables_derives {
         options.set_variables_derives(variables_derives);
     }
@@ -56,6 +125,10 @@ pub(crate) fn generate_code(params: CliCodegenParams) -> Result<()> {
         options.set_response_derives(response_derives);
     }
 
+    if let Some(derives_for) = derives_for {
+        options.set_derives_for(derives_for);
+    }
+
     if let Some(deprecation_strategy) = deprecation_strategy {
         options.set_deprecation_strategy(deprecation_strategy);
     }
@@ -64,31 +137,563 @@ pub(crate) fn generate_code(params: CliCodegenParams) -> Result<()> {
         options.set_serde_crate(serde_crate);
     }
 
-    let gen = generate_module_token_stream(query_path.clone(), &schema_path, options)
-        .map_err(|fail| fail.compat())?;
+    if let Some(forbidden_fields) = forbidden_fields {
+        options.set_forbidden_fields(forbidden_fields);
+    }
+
+    if let Some(allowed_types) = allowed_types {
+        options.set_allowed_types(allowed_types);
+    }
+
+    if let Some(file_header) = file_header {
+        options.set_file_header(file_header);
+    }
+
+    for (operation_name, operation_options) in operations {
+        options.set_operation_options(operation_name, operation_options);
+    }
+
+    // The `derives_for` types and the operations of the config file are checked against all the
+    // query files.
+    options.set_defer_option_checks(true);
+
+    let schema_url = match (&schema_path, schema_url) {
+        (Some(_), Some(_)) => {
+            return Err(format_err!(
+                "The schema path and the schema URL cannot be used together."
+            ))
+        }
+        (Some(schema_path), None) => schema_path
+            .to_str()
+            .filter(|path| path.starts_with("http://") || path.starts_with("https://"))
+            .map(ToOwned::to_owned),
+        (None, schema_url) => schema_url,
+    };
+
+    let introspected_schema;
+    let schema = match (schema_url, schema_path.as_deref()) {
+        (Some(schema_url), _) => {
+            let cache = schema_cache.map(|directory| SchemaCache {
+                directory,
+                max_age: Duration::from_secs(
+                    schema_cache_max_age.unwrap_or(DEFAULT_SCHEMA_CACHE_MAX_AGE),
+                ),
+            });
+            introspected_schema = introspect_schema(
+                &schema_url,
+                authorization,
+                headers,
+                cache.as_ref(),
+                schema_out.as_deref(),
+            )?;
+            Schema::Introspected(&introspected_schema)
+        }
+        (None, Some(_))
+            if schema_out.is_some()
+                || !headers.is_empty()
+                || authorization.is_some()
+                || schema_cache.is_some() =>
+        {
+            return Err(format_err!(
+                "The --schema-out, --header, --authorization and --schema-cache flags only apply to schemas introspected from a URL."
+            ))
+        }
+        (None, Some(schema_path)) => Schema::Path(schema_path),
+        (None, None) => return Err(format_err!("A schema path or a schema URL is required.")),
+    };
+
+    // A query file matched by several query paths is generated once.
+    let mut query_files = Vec::new();
+    let mut seen_query_files = BTreeSet::new();
+    for query_path in &query_paths {
+        for query_file in expand_query_path(query_path)? {
+            let canonical =
+                std::fs::canonicalize(&query_file).unwrap_or_else(|_| query_file.clone());
+            if seen_query_files.insert(canonical) {
+                query_files.push(query_file);
+            }
+        }
+    }
+    check_output_files(&query_files, output_directory.as_deref())?;
+
+    let mut failures = Vec::new();
+    // The type options that no query file generated so far applies.
+    let mut unapplied_type_options: Option<BTreeSet<UnappliedTypeOption>> = None;
+    let mut operation_names = BTreeSet::new();
+
+    for query_path in query_files {
+        let result = generate_file(
+            &query_path,
+            &schema,
+            options.clone(),
+            output_directory.as_deref(),
+            no_formatting,
+            emit_scalar_stubs,
+            deny_warnings,
+        );
+
+        match result {
+            Result::Ok(gen) => {
+                operation_names.extend(gen.operation_names);
+                let unapplied: BTreeSet<_> = gen.unapplied_type_options.into_iter().collect();
+                unapplied_type_options = Some(match unapplied_type_options {
+                    Some(previous) => previous.intersection(&unapplied).cloned().collect(),
+                    None => unapplied,
+                });
+            }
+            Err(err) => failures.push(format!("{}: {}", query_path.display(), err)),
+        }
+    }
+
+    if !failures.is_empty() {
+        return Err(format_err!(
+            "Code generation failed for {} query file(s):\n{}",
+            failures.len(),
+            failures.join("\n")
+        ));
+    }
+
+    let unapplied_type_options: Vec<String> = unapplied_type_options
+        .into_iter()
+        .flatten()
+        .map(|unapplied| unapplied.to_string())
+        .collect();
+    if !unapplied_type_options.is_empty() {
+        return Err(format_err!("{}", unapplied_type_options.join("\n")));
+    }
+
+    let operation_names: Vec<String> = operation_names.into_iter().collect();
+    let warnings = graphql_client_codegen::warnings::unknown_operations(&operation_names, &options);
+    // The operation options come from the config file.
+    if let Some(config) = &config {
+        print_warnings(config, &warnings);
+    }
+
+    if deny_warnings && !warnings.is_empty() {
+        return Err(format_err!(
+            "{} warning(s) reported, and warnings are denied.",
+            warnings.len()
+        ));
+    }
+
+    Ok(())
+}
+
+/// The `--config` file: the parameters of the command, named like the flags in snake case, and
+/// the options for specific operations.
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+struct CliConfig {
+    query_path: Option<PathBuf>,
+    /// The query paths, when there are several of them.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    query_paths: Vec<PathBuf>,
+    schema_path: Option<PathBuf>,
+    schema_url: Option<String>,
+    schema_out: Option<PathBuf>,
+    /// The headers, as `'X-Name: Value'`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    headers: Vec<String>,
+    authorization: Option<String>,
+    schema_cache: Option<PathBuf>,
+    schema_cache_max_age: Option<u64>,
+    selected_operation: Option<String>,
+    fragment: Option<String>,
+    variables_derives: Option<String>,
+    response_derives: Option<String>,
+    derives_for: Option<String>,
+    deprecation_strategy: Option<String>,
+    #[serde(default)]
+    no_formatting: bool,
+    module_visibility: Option<String>,
+    output_directory: Option<PathBuf>,
+    serde_crate: Option<String>,
+    #[serde(default)]
+    emit_scalar_stubs: bool,
+    #[serde(default)]
+    deny_warnings: bool,
+    forbidden_fields: Option<String>,
+    allowed_types: Option<String>,
+    file_header: Option<String>,
+    /// The options for specific operations, by operation name (`[operations.RepoView]`).
+    #[serde(default)]
+    operations: BTreeMap<String, OperationOptions>,
+}
+
+impl CliCodegenParams {
+    /// The parameters, with the values of the config file for the ones that are not set on the
+    /// command line. A schema path or URL on
+    /// the command line replaces both the schema path and the schema URL of the file, since they
+    /// can't be used together, and the query paths of the command line replace all the ones of
+    /// the file.
+    fn with_config(self, config: CliConfig) -> Result<Self> {
+        let schema_on_command_line = self.schema_path.is_some() || self.schema_url.is_some();
+        let (schema_path, schema_url) = if schema_on_command_line {
+            (self.schema_path, self.schema_url)
+        } else {
+            (config.schema_path, config.schema_url)
+        };
 
-    let generated_code = gen.to_string();
-    let generated_code = if cfg!(feature = "rustfmt") && !no_formatting {
+        let headers = if self.headers.is_empty() {
+            config
+                .headers
+                .iter()
+                .map(|header| header.parse())
+                .collect::<Result<_>>()?
+        } else {
+            self.headers
+        };
+
+        let serde_crate = match (self.serde_crate, config.serde_crate) {
+            (Some(serde_crate), _) => Some(serde_crate),
+            (None, Some(serde_crate)) => Some(syn::parse_str(&serde_crate).map_err(|_| {
+                format_err!(
+                    "The serde_crate of the config file, `{}`, is not a valid path.",
+                    serde_crate
+                )
+            })?),
+            (None, None) => None,
+        };
+
+        let query_paths = if self.query_paths.is_empty() {
+            config
+                .query_path
+                .into_iter()
+                .chain(config.query_paths)
+                .collect()
+        } else {
+            self.query_paths
+        };
+
+        Ok(CliCodegenParams {
+            query_paths,
+            schema_path,
+            schema_url,
+            schema_out: self.schema_out.or(config.schema_out),
+            headers,
+            authorization: self.authorization.or(config.authorization),
+            schema_cache: self.schema_cache.or(config.schema_cache),
+            schema_cache_max_age: self.schema_cache_max_age.or(config.schema_cache_max_age),
+            selected_operation: self.selected_operation.or(config.selected_operation),
+            fragment: self.fragment.or(config.fragment),
+            variables_derives: self.variables_derives.or(config.variables_derives),
+            response_derives: self.response_derives.or(config.response_derives),
+            derives_for: self.derives_for.or(config.derives_for),
+            deprecation_strategy: self.deprecation_strategy.or(config.deprecation_strategy),
+            no_formatting: self.no_formatting.or(Some(config.no_formatting)),
+            module_visibility: self.module_visibility.or(config.module_visibility),
+            output_directory: self.output_directory.or(config.output_directory),
+            serde_crate,
+            emit_scalar_stubs: self.emit_scalar_stubs.or(Some(config.emit_scalar_stubs)),
+            config: self.config,
+            print_config: self.print_config,
+            deny_warnings: self.deny_warnings.or(Some(config.deny_warnings)),
+            forbidden_fields: self.forbidden_fields.or(config.forbidden_fields),
+            allowed_types: self.allowed_types.or(config.allowed_types),
+            file_header: self.file_header.or(config.file_header),
+        })
+    }
+
+    /// The parameters as a config file, for `--print-config`. The values of the headers and the
+    /// authorization are replaced by `***`, and the paths are relative to the current directory.
+    fn to_config_file(&self, operations: BTreeMap<String, OperationOptions>) -> Result<String> {
+        let (query_path, query_paths) = match self.query_paths.as_slice() {
+            [query_path] => (Some(query_path.clone()), Vec::new()),
+            query_paths => (None, query_paths.to_vec()),
+        };
+        let config = CliConfig {
+            query_path,
+            query_paths,
+            schema_path: self.schema_path.clone(),
+            schema_url: self.schema_url.clone(),
+            schema_out: self.schema_out.clone(),
+            headers: self.headers.iter().map(Header::redacted).collect(),
+            authorization: self.authorization.as_ref().map(|_| "***".to_owned()),
+            schema_cache: self.schema_cache.clone(),
+            schema_cache_max_age: self.schema_cache_max_age,
+            selected_operation: self.selected_operation.clone(),
+            fragment: self.fragment.clone(),
+            variables_derives: self.variables_derives.clone(),
+            response_derives: self.response_derives.clone(),
+            derives_for: self.derives_for.clone(),
+            deprecation_strategy: self.deprecation_strategy.clone(),
+            no_formatting: self.no_formatting.unwrap_or(false),
+            module_visibility: self.module_visibility.clone(),
+            output_directory: self.output_directory.clone(),
+            serde_crate: self
+                .serde_crate
+                .as_ref()
+                .map(crate::gen_tests::path_to_string),
+            emit_scalar_stubs: self.emit_scalar_stubs.unwrap_or(false),
+            deny_warnings: self.deny_warnings.unwrap_or(false),
+            forbidden_fields: self.forbidden_fields.clone(),
+            allowed_types: self.allowed_types.clone(),
+            file_header: self.file_header.clone(),
+            operations,
+        };
+
+        Ok(toml::to_string(&config)?)
+    }
+}
+
+/// The config file, with its relative paths resolved against its directory.
+fn read_config(path: &Path) -> Result<CliConfig> {
+    let config = std::fs::read_to_string(path)
+        .with_context(|| format!("Could not read the config file {}", path.display()))?;
+
+    let mut config: CliConfig = toml::from_str(&config)
+        .with_context(|| format!("Invalid config file {}", path.display()))?;
+    if let Some(directory) = path.parent() {
+        config.resolve_paths(directory);
+    }
+
+    Ok(config)
+}
+
+impl CliConfig {
+    /// Join the relative paths to the directory. The schema path can be a URL, which is left as
+    /// is.
+    fn resolve_paths(&mut self, directory: &Path) {
+        let resolve = |path: &mut PathBuf| *path = directory.join(&*path);
+        let is_url = |path: &PathBuf| {
+            path.to_str()
+                .is_some_and(|path| path.starts_with("http://") || path.starts_with("https://"))
+        };
+
+        self.query_path.iter_mut().for_each(resolve);
+        self.query_paths.iter_mut().for_each(resolve);
+        self.schema_path
+            .iter_mut()
+            .filter(|path| !is_url(path))
+            .for_each(resolve);
+        self.schema_out.iter_mut().for_each(resolve);
+        self.schema_cache.iter_mut().for_each(resolve);
+        self.output_directory.iter_mut().for_each(resolve);
+    }
+}
+
+/// The query files to generate code for: the files matching the query path if it is a glob
+/// pattern such as `queries/**/*.graphql`, otherwise the query path itself.
+pub(crate) fn expand_query_path(query_path: &Path) -> Result<Vec<PathBuf>> {
+    let pattern = query_path
+        .to_str()
+        .filter(|path| path.contains(['*', '?', '[']));
+
+    let pattern = match pattern {
+        Some(pattern) => pattern,
+        None => return Ok(vec![query_path.to_owned()]),
+    };
+
+    let mut paths = glob::glob(pattern)?
+        .filter(|entry| entry.as_ref().map(|path| path.is_file()).unwrap_or(true))
+        .collect::<std::result::Result<Vec<PathBuf>, _>>()?;
+
+    if paths.is_empty() {
+        return Err(format_err!("No query file matches `{}`.", pattern));
+    }
+
+    paths.sort();
+
+    Ok(paths)
+}
+
+/// The schema to generate code for.
+enum Schema<'a> {
+    /// A schema file (`--schema-path`).
+    Path(&'a Path),
+    /// The schema introspected from an endpoint (`--schema-url`).
+    Introspected(&'a ParsedSchema),
+}
+
+/// Run the introspection query against the endpoint, unless the schema is in the cache, and write
+/// the JSON response to `schema_out` if it is set.
+fn introspect_schema(
+    schema_url: &str,
+    authorization: Option<String>,
+    headers: Vec<Header>,
+    cache: Option<&SchemaCache>,
+    schema_out: Option<&Path>,
+) -> Result<ParsedSchema> {
+    let schema = match cache.and_then(|cache| cache.get(schema_url)) {
+        Some(schema) => schema,
+        None => {
+            let schema =
+                crate::introspect_schema::fetch_schema(schema_url, authorization, headers)?;
+
+            if let Some(cache) = cache {
+                cache.put(schema_url, &schema)?;
+            }
+
+            schema
+        }
+    };
+
+    if let Some(schema_out) = schema_out {
+        std::fs::write(schema_out, &schema)?;
+    }
+
+    let response = serde_json::from_str(&schema).map_err(|err| {
+        format_err!(
+            "The response to the introspection query to {} is not an introspection response: {}",
+            schema_url,
+            err
+        )
+    })?;
+
+    Ok(ParsedSchema::Json(response))
+}
+
+fn generate_file(
+    query_path: &Path,
+    schema: &Schema<'_>,
+    options: GraphQLClientCodegenOptions,
+    output_directory: Option<&Path>,
+    no_formatting: bool,
+    emit_scalar_stubs: bool,
+    deny_warnings: bool,
+) -> Result<GeneratedCode> {
+    let header = options.file_header_lines().map(|lines| file_header(&lines));
+
+    let gen = match schema {
+        Schema::Path(schema_path) => {
+            graphql_client_codegen::generate_code(query_path.to_owned(), schema_path, options)
+        }
+        Schema::Introspected(parsed_schema) => graphql_client_codegen::generate_code_with_schema(
+            query_path.to_owned(),
+            parsed_schema,
+            options,
+        ),
+    }
+    .map_err(|fail| fail.compat())?;
+
+    print_warnings(query_path, &gen.warnings);
+
+    if deny_warnings && !gen.warnings.is_empty() {
+        return Err(format_err!(
+            "{} warning(s) reported, and warnings are denied.",
+            gen.warnings.len()
+        ));
+    }
+
+    let generated_code = gen.tokens.to_string();
+    let mut generated_code = if cfg!(feature = "rustfmt") && !no_formatting {
         format(&generated_code)
     } else {
         generated_code
     };
 
+    if emit_scalar_stubs {
+        generated_code.push_str(&scalar_stubs(&gen.referenced_scalars));
+    }
+
+    if let Some(header) = header {
+        generated_code.insert_str(0, &header);
+    }
+
+    let mut file = File::create(output_file_path(query_path, output_directory)?)?;
+    write!(file, "{}", generated_code)?;
+
+    Ok(gen)
+}
+
+/// The file the code for the query file is written to: next to the query file, or in the output
+/// directory.
+fn output_file_path(query_path: &Path, output_directory: Option<&Path>) -> Result<PathBuf> {
     let query_file_name: ::std::ffi::OsString = query_path
         .file_name()
         .map(ToOwned::to_owned)
         .ok_or_else(|| format_err!("Failed to find a file name in the provided query path."))?;
 
-    let dest_file_path: PathBuf = output_directory
+    Ok(output_directory
         .map(|output_dir| output_dir.join(query_file_name).with_extension("rs"))
-        .unwrap_or_else(move || query_path.with_extension("rs"));
+        .unwrap_or_else(move || query_path.with_extension("rs")))
+}
 
-    let mut file = File::create(dest_file_path)?;
-    write!(file, "{}", generated_code)?;
+/// Fail when two query files would be generated in the same file, like `a/query.graphql` and
+/// `b/query.graphql` with an output directory, before generating any of them.
+fn check_output_files(query_files: &[PathBuf], output_directory: Option<&Path>) -> Result<()> {
+    let mut output_files: BTreeMap<PathBuf, &Path> = BTreeMap::new();
+
+    for query_file in query_files {
+        let output_file = output_file_path(query_file, output_directory)?;
+
+        if let Some(previous) = output_files.insert(output_file.clone(), query_file) {
+            return Err(format_err!(
+                "The query files {} and {} would both be generated in {}. Rename one of them, or generate them in different output directories.",
+                previous.display(),
+                query_file.display(),
+                output_file.display()
+            ));
+        }
+    }
 
     Ok(())
 }
 
+/// Print the warnings to stderr, in color when it is a terminal.
+fn print_warnings(query_path: &Path, warnings: &[Warning]) {
+    use std::io::IsTerminal as _;
+
+    let label = if std::io::stderr().is_terminal() {
+        "\x1b[1;33mwarning\x1b[0m"
+    } else {
+        "warning"
+    };
+
+    for warning in warnings {
+        eprintln!("{}", format_warning(label, query_path, warning));
+    }
+}
+
+fn format_warning(label: &str, query_path: &Path, warning: &Warning) -> String {
+    let location = warning
+        .location
+        .map(|location| format!(":{}:{}", location.line, location.column))
+        .unwrap_or_default();
+
+    format!(
+        "{}[{}]: {}\n  --> {}{}",
+        label,
+        warning.code.as_str(),
+        warning.message,
+        query_path.display(),
+        location
+    )
+}
+
+/// The header of the generated file, as a comment block.
+fn file_header(lines: &[&str]) -> String {
+    let mut header = String::new();
+
+    for line in lines {
+        if line.is_empty() {
+            header.push_str("//\n");
+        } else {
+            header.push_str(&format!("// {}\n", line));
+        }
+    }
+
+    header.push('\n');
+    header
+}
+
+/// A commented block listing the custom scalars the generated code refers to as `super::ScalarName`.
+fn scalar_stubs(scalars: &[String]) -> String {
+    if scalars.is_empty() {
+        return String::new();
+    }
+
+    let mut stubs = String::from(
+        "\n// Custom scalars used by the generated code. Define them in the parent module:\n",
+    );
+
+    for scalar in scalars {
+        stubs.push_str(&format!("// type {} = ...; // TODO\n", scalar));
+    }
+
+    stubs
+}
+
 #[allow(unused_variables)]
 fn format(codes: &str) -> String {
     #[cfg(feature = "rustfmt")]
@@ -111,3 +716,708 @@ fn format(codes: &str) -> String {
     #[cfg(not(feature = "rustfmt"))]
     unreachable!()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::test_directory;
+
+    #[test]
+    fn scalar_stubs_list_the_referenced_scalars() {
+        let mut options = GraphQLClientCodegenOptions::new(CodegenMode::Cli);
+        options.set_operation_name("EventsQuery".to_owned());
+        let tests_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("src/tests");
+
+        let gen = graphql_client_codegen::generate_code(
+            tests_dir.join("scalars_query.graphql"),
+            &tests_dir.join("scalars_schema.graphql"),
+            options,
+        )
+        .unwrap();
+
+        assert_eq!(
+            scalar_stubs(&gen.referenced_scalars),
+            "\n// Custom scalars used by the generated code. Define them in the parent module:\n\
+             // type DateTime = ...; // TODO\n\
+             // type Url = ...; // TODO\n"
+        );
+    }
+
+    #[test]
+    fn scalar_stubs_are_empty_without_custom_scalars() {
+        assert_eq!(scalar_stubs(&[]), "");
+    }
+
+    #[test]
+    fn query_path_globs_are_expanded() {
+        let tests_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("src/tests");
+
+        assert_eq!(
+            expand_query_path(&tests_dir.join("scalars_*.graphql")).unwrap(),
+            vec![
+                tests_dir.join("scalars_query.graphql"),
+                tests_dir.join("scalars_schema.graphql"),
+            ]
+        );
+        assert_eq!(
+            expand_query_path(&tests_dir.join("scalars_query.graphql")).unwrap(),
+            vec![tests_dir.join("scalars_query.graphql")]
+        );
+        assert!(expand_query_path(&tests_dir.join("*.txt")).is_err());
+    }
+
+    #[test]
+    fn failures_are_reported_after_all_the_query_files() {
+        let tests_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("src/tests");
+        let output_directory = test_directory("glob_output");
+
+        let error = generate_code(CliCodegenParams {
+            // The schema is not a valid query document.
+            query_paths: vec![tests_dir.join("scalars_*.graphql")],
+            schema_path: Some(tests_dir.join("scalars_schema.graphql")),
+            no_formatting: Some(true),
+            output_directory: Some(output_directory.clone()),
+            ..unset_params()
+        })
+        .unwrap_err()
+        .to_string();
+
+        assert!(error.starts_with("Code generation failed for 1 query file(s):"));
+        assert!(error.contains("scalars_schema.graphql: "));
+        assert!(output_directory.join("scalars_query.rs").is_file());
+    }
+
+    #[test]
+    fn several_query_files_are_generated_in_one_run() {
+        let tests_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("src/tests");
+        let output_directory = test_directory("several_queries");
+        let outputs = [
+            output_directory.join("scalars_query.rs"),
+            output_directory.join("unused_fragment_query.rs"),
+        ];
+
+        let mut params = unset_params();
+        params.query_paths = vec![
+            tests_dir.join("scalars_query.graphql"),
+            tests_dir.join("unused_fragment_*.graphql"),
+        ];
+        params.schema_path = Some(tests_dir.join("scalars_schema.graphql"));
+        params.no_formatting = Some(true);
+        params.output_directory = Some(output_directory);
+
+        generate_code(params).unwrap();
+
+        for output in &outputs {
+            let generated = std::fs::read_to_string(output).unwrap();
+            assert!(generated.contains("pub mod events_query"), "{}", generated);
+        }
+    }
+
+    #[test]
+    fn query_files_are_generated_once_and_in_different_files() {
+        let tests_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("src/tests");
+        let directory = test_directory("output_files");
+        let output_directory = directory.join("out");
+        for subdirectory in &["a", "b", "out"] {
+            std::fs::create_dir_all(directory.join(subdirectory)).unwrap();
+        }
+        let query = std::fs::read_to_string(tests_dir.join("scalars_query.graphql")).unwrap();
+        std::fs::write(directory.join("a/query.graphql"), &query).unwrap();
+        std::fs::write(directory.join("b/query.graphql"), &query).unwrap();
+
+        let generate = |query_paths: Vec<PathBuf>| {
+            let mut params = unset_params();
+            params.query_paths = query_paths;
+            params.schema_path = Some(tests_dir.join("scalars_schema.graphql"));
+            params.no_formatting = Some(true);
+            params.output_directory = Some(output_directory.clone());
+            generate_code(params).map_err(|err| err.to_string())
+        };
+
+        // The file matched by both paths is generated once.
+        assert!(generate(vec![
+            directory.join("a/query.graphql"),
+            directory.join("a/*.graphql"),
+        ])
+        .is_ok());
+        assert_eq!(
+            generate(vec![directory.join("*/query.graphql")]).unwrap_err(),
+            format!(
+                "The query files {} and {} would both be generated in {}. Rename one of them, or generate them in different output directories.",
+                directory.join("a/query.graphql").display(),
+                directory.join("b/query.graphql").display(),
+                output_directory.join("query.rs").display()
+            )
+        );
+    }
+
+    #[test]
+    fn derives_for_types_are_checked_against_all_the_query_files() {
+        let tests_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("src/tests");
+        let query_directory = test_directory("derives_for");
+        std::fs::write(
+            query_directory.join("events_query.graphql"),
+            "query EventsQuery { events { name } }",
+        )
+        .unwrap();
+        std::fs::write(
+            query_directory.join("unused_query.graphql"),
+            "query UnusedQuery { unused }",
+        )
+        .unwrap();
+
+        let generate = |derives_for: &str| {
+            let mut params = unset_params();
+            params.query_paths = vec![query_directory.join("*_query.graphql")];
+            params.schema_path = Some(tests_dir.join("scalars_schema.graphql"));
+            params.derives_for = Some(derives_for.to_owned());
+            params.no_formatting = Some(true);
+            params.output_directory = Some(query_directory.clone());
+
+            generate_code(params).map_err(|err| err.to_string())
+        };
+
+        // The type is only generated for one of the query files.
+        assert!(generate("EventsQueryEvents: Default").is_ok());
+        assert_eq!(
+            generate("EventsQueryEvent: Default").unwrap_err(),
+            "The derives_for type `EventsQueryEvent` is not generated, or the option does not apply to it."
+        );
+    }
+
+    #[test]
+    fn config_operations_are_checked_against_all_the_query_files() {
+        let tests_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("src/tests");
+        let query_directory = test_directory("config_operations");
+        std::fs::write(
+            query_directory.join("events_query.graphql"),
+            "query EventsQuery { events { name } }",
+        )
+        .unwrap();
+        std::fs::write(
+            query_directory.join("unused_query.graphql"),
+            "query UnusedQuery { unused }",
+        )
+        .unwrap();
+        let config_path = query_directory.join("config.toml");
+
+        let generate = |config: &str| {
+            std::fs::write(&config_path, config).unwrap();
+            let mut params = unset_params();
+            params.query_paths = vec![query_directory.join("*_query.graphql")];
+            params.schema_path = Some(tests_dir.join("scalars_schema.graphql"));
+            params.config = Some(config_path.clone());
+            params.deny_warnings = Some(true);
+            params.no_formatting = Some(true);
+            params.output_directory = Some(query_directory.clone());
+
+            generate_code(params).map_err(|err| err.to_string())
+        };
+
+        // Each operation is in one of the query files only.
+        assert!(generate("[operations.EventsQuery]\n\n[operations.UnusedQuery]\n").is_ok());
+        assert_eq!(
+            generate("[operations.EventQuery]\n").unwrap_err(),
+            "1 warning(s) reported, and warnings are denied."
+        );
+    }
+
+    #[test]
+    fn the_file_header_is_written_at_the_top_of_the_generated_files() {
+        let tests_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("src/tests");
+        let output_directory = test_directory("file_header");
+
+        let mut params = unset_params();
+        params.query_paths = vec![tests_dir.join("scalars_query.graphql")];
+        params.schema_path = Some(tests_dir.join("scalars_schema.graphql"));
+        params.no_formatting = Some(true);
+        params.output_directory = Some(output_directory.clone());
+        params.file_header = Some("Copyright (c) Example Corp.\n\nDO NOT EDIT.".to_owned());
+
+        generate_code(params).unwrap();
+
+        let generated = std::fs::read_to_string(output_directory.join("scalars_query.rs")).unwrap();
+        assert!(
+            generated.starts_with(
+                "// Copyright (c) Example Corp.\n//\n// DO NOT EDIT.\n// @generated\n\n# [allow"
+            ),
+            "{}",
+            generated
+        );
+    }
+
+    #[test]
+    fn the_generated_items_allow_the_clippy_lints() {
+        let tests_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("../graphql_client/tests/clippy_pedantic");
+        let output_directory = test_directory("clippy_pedantic");
+
+        let mut params = unset_params();
+        params.query_paths = vec![tests_dir.join("query.graphql")];
+        params.schema_path = Some(tests_dir.join("schema.graphql"));
+        params.response_derives = Some("Debug, PartialEq".to_owned());
+        params.no_formatting = Some(true);
+        params.output_directory = Some(output_directory.clone());
+
+        generate_code(params).unwrap();
+
+        // The `clippy_pedantic` tests of graphql_client check the code generated by the derive.
+        // The CLI adds the operation struct, which allows the lints like the other items outside
+        // the module.
+        let generated = std::fs::read_to_string(output_directory.join("query.rs")).unwrap();
+        let allow = "# [allow (clippy :: all , clippy :: pedantic)]";
+
+        assert!(generated.starts_with(&format!("{} pub struct PedanticQuery ;", allow)));
+        assert!(generated.contains(
+            "pub mod pedantic_query { # ! [allow (dead_code , clippy :: all , clippy :: pedantic)]"
+        ));
+        assert!(generated.contains(&format!(
+            "{} impl graphql_client :: GraphQLQuery for PedanticQuery",
+            allow
+        )));
+    }
+
+    #[test]
+    fn config_files_set_options_by_operation() {
+        let path = test_directory("config").join("graphql-client.toml");
+        std::fs::write(
+            &path,
+            "[operations.RepoView]\nresponse_derives = \"Clone\"\n\n[operations.RepoStars]\n",
+        )
+        .unwrap();
+
+        let config = read_config(&path).unwrap();
+
+        assert_eq!(config.operations.len(), 2);
+        assert_eq!(
+            config.operations["RepoView"].response_derives.as_deref(),
+            Some("Clone")
+        );
+        assert_eq!(config.operations["RepoStars"], OperationOptions::default());
+
+        std::fs::write(
+            &path,
+            "[operations.RepoView]\nresponse_derive = \"Clone\"\n",
+        )
+        .unwrap();
+
+        let error = format!("{:#}", read_config(&path).unwrap_err());
+        assert!(
+            error.contains("unknown field `response_derive`"),
+            "{}",
+            error
+        );
+    }
+
+    #[test]
+    fn config_file_paths_are_relative_to_the_config_file() {
+        let directory = test_directory("relative_config");
+        let path = directory.join("graphql-client.toml");
+        std::fs::write(
+            &path,
+            "query_path = 'queries/*.graphql'\n\
+             schema_path = 'schema.graphql'\n\
+             output_directory = '/tmp/generated'\n",
+        )
+        .unwrap();
+
+        let config = read_config(&path).unwrap();
+
+        assert_eq!(config.query_path, Some(directory.join("queries/*.graphql")));
+        assert_eq!(config.schema_path, Some(directory.join("schema.graphql")));
+        assert_eq!(
+            config.output_directory,
+            Some(PathBuf::from("/tmp/generated"))
+        );
+
+        std::fs::write(&path, "schema_path = 'https://example.com/graphql'\n").unwrap();
+
+        let config = read_config(&path).unwrap();
+
+        assert_eq!(
+            config.schema_path,
+            Some(PathBuf::from("https://example.com/graphql"))
+        );
+    }
+
+    /// Parameters with none of the flags set.
+    fn unset_params() -> CliCodegenParams {
+        CliCodegenParams {
+            query_paths: Vec::new(),
+            schema_path: None,
+            schema_url: None,
+            schema_out: None,
+            headers: Vec::new(),
+            authorization: None,
+            schema_cache: None,
+            schema_cache_max_age: None,
+            selected_operation: None,
+            fragment: None,
+            variables_derives: None,
+            response_derives: None,
+            derives_for: None,
+            deprecation_strategy: None,
+            no_formatting: Some(false),
+            module_visibility: None,
+            output_directory: None,
+            serde_crate: None,
+            emit_scalar_stubs: Some(false),
+            config: None,
+            print_config: false,
+            deny_warnings: Some(false),
+            forbidden_fields: None,
+            allowed_types: None,
+            file_header: None,
+        }
+    }
+
+    fn config(toml: &str) -> CliConfig {
+        toml::from_str(toml).unwrap()
+    }
+
+    #[test]
+    fn config_file_values_apply_when_the_flags_are_not_set() {
+        let params = unset_params()
+            .with_config(config(
+                "query_path = 'queries/*.graphql'\n\
+                 schema_path = 'schema.graphql'\n\
+                 headers = ['X-Api-Key: secret']\n\
+                 schema_cache_max_age = 60\n\
+                 response_derives = 'Debug'\n\
+                 serde_crate = 'my_crate::serde'\n",
+            ))
+            .unwrap();
+
+        assert_eq!(params.query_paths, vec![PathBuf::from("queries/*.graphql")]);
+        assert_eq!(params.schema_path, Some(PathBuf::from("schema.graphql")));
+        assert_eq!(params.headers, vec!["X-Api-Key: secret".parse().unwrap()]);
+        assert_eq!(params.schema_cache_max_age, Some(60));
+        assert_eq!(params.response_derives.as_deref(), Some("Debug"));
+        assert_eq!(
+            params.serde_crate,
+            Some(syn::parse_str("my_crate::serde").unwrap())
+        );
+    }
+
+    #[test]
+    fn flags_override_the_config_file_values() {
+        let mut params = unset_params();
+        params.query_paths = vec![PathBuf::from("query.graphql")];
+        params.headers = vec!["X-Api-Key: flag".parse().unwrap()];
+        params.schema_cache_max_age = Some(10);
+        params.response_derives = Some("Clone".to_owned());
+        params.serde_crate = Some(syn::parse_str("serde").unwrap());
+
+        let params = params
+            .with_config(config(
+                "query_path = 'queries/*.graphql'\n\
+                 headers = ['X-Api-Key: file', 'X-Other: file']\n\
+                 schema_cache_max_age = 60\n\
+                 response_derives = 'Debug'\n\
+                 serde_crate = 'my_crate::serde'\n",
+            ))
+            .unwrap();
+
+        assert_eq!(params.query_paths, vec![PathBuf::from("query.graphql")]);
+        assert_eq!(params.headers, vec!["X-Api-Key: flag".parse().unwrap()]);
+        assert_eq!(params.schema_cache_max_age, Some(10));
+        assert_eq!(params.response_derives.as_deref(), Some("Clone"));
+        assert_eq!(params.serde_crate, Some(syn::parse_str("serde").unwrap()));
+    }
+
+    #[test]
+    fn several_query_paths_are_read_from_and_printed_to_the_config_file() {
+        let params = unset_params()
+            .with_config(config(
+                "query_path = 'query.graphql'\n\
+                 query_paths = ['queries/*.graphql']\n",
+            ))
+            .unwrap();
+
+        assert_eq!(
+            params.query_paths,
+            vec![
+                PathBuf::from("query.graphql"),
+                PathBuf::from("queries/*.graphql")
+            ]
+        );
+        assert_eq!(
+            params.to_config_file(BTreeMap::new()).unwrap(),
+            "query_paths = [\"query.graphql\", \"queries/*.graphql\"]\n\
+             no_formatting = false\n\
+             emit_scalar_stubs = false\n\
+             deny_warnings = false\n\
+             \n\
+             [operations]\n"
+        );
+    }
+
+    #[test]
+    fn parameters_set_nowhere_keep_their_default() {
+        let params = unset_params().with_config(CliConfig::default()).unwrap();
+
+        assert!(params.query_paths.is_empty());
+        assert!(params.headers.is_empty());
+        assert_eq!(params.schema_cache_max_age, None);
+        assert_eq!(params.response_derives, None);
+        assert_eq!(params.serde_crate, None);
+        assert_eq!(params.deny_warnings, Some(false));
+    }
+
+    #[test]
+    fn boolean_flags_override_the_file_both_ways() {
+        for (flag, file, expected) in [
+            (None, false, false),
+            (None, true, true),
+            (Some(true), false, true),
+            (Some(false), true, false),
+        ] {
+            let mut params = unset_params();
+            params.deny_warnings = flag;
+            let params = params
+                .with_config(config(&format!("deny_warnings = {}", file)))
+                .unwrap();
+
+            assert_eq!(
+                params.deny_warnings,
+                Some(expected),
+                "flag: {:?}, file: {}",
+                flag,
+                file
+            );
+        }
+    }
+
+    #[test]
+    fn a_schema_on_the_command_line_replaces_the_schema_of_the_file() {
+        let file = "schema_url = 'https://example.com/graphql'";
+
+        let mut params = unset_params();
+        params.schema_path = Some(PathBuf::from("schema.graphql"));
+        let params = params.with_config(config(file)).unwrap();
+        assert_eq!(params.schema_path, Some(PathBuf::from("schema.graphql")));
+        assert_eq!(params.schema_url, None);
+
+        let params = unset_params().with_config(config(file)).unwrap();
+        assert_eq!(params.schema_path, None);
+        assert_eq!(
+            params.schema_url.as_deref(),
+            Some("https://example.com/graphql")
+        );
+    }
+
+    #[test]
+    fn invalid_config_file_values_are_reported() {
+        let error = unset_params()
+            .with_config(config("serde_crate = 'not a path'"))
+            .err()
+            .unwrap();
+        assert_eq!(
+            error.to_string(),
+            "The serde_crate of the config file, `not a path`, is not a valid path."
+        );
+
+        assert!(unset_params()
+            .with_config(config("headers = ['no colon']"))
+            .is_err());
+    }
+
+    #[test]
+    fn the_merged_parameters_are_printed_as_a_config_file() {
+        let mut params = unset_params();
+        params.response_derives = Some("Clone".to_owned());
+        params.deny_warnings = Some(true);
+        let mut file = config(
+            "schema_path = 'schema.graphql'\n\
+             headers = ['X-Api-Key: secret']\n\
+             authorization = 'Bearer secret'\n\
+             serde_crate = 'my_crate::serde'\n\
+             response_derives = 'Debug'\n\
+             \n\
+             [operations.RepoView]\n\
+             response_derives = 'PartialEq'\n",
+        );
+        let operations = std::mem::take(&mut file.operations);
+
+        let printed = params
+            .with_config(file)
+            .unwrap()
+            .to_config_file(operations)
+            .unwrap();
+
+        assert_eq!(
+            printed,
+            "schema_path = \"schema.graphql\"\n\
+             headers = [\"X-Api-Key: ***\"]\n\
+             authorization = \"***\"\n\
+             response_derives = \"Clone\"\n\
+             no_formatting = false\n\
+             serde_crate = \"my_crate::serde\"\n\
+             emit_scalar_stubs = false\n\
+             deny_warnings = true\n\
+             [operations.RepoView]\n\
+             response_derives = \"PartialEq\"\n"
+        );
+        // The printed config is a valid config file.
+        config(&printed);
+    }
+
+    /// Answer a single HTTP request on a local port with the given status and body. Returns the URL
+    /// and a handle to the head of the request.
+    fn serve_once(status: &'static str, body: String) -> (String, std::thread::JoinHandle<String>) {
+        use std::io::{BufRead, BufReader, Read};
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/graphql", listener.local_addr().unwrap());
+
+        let handle = std::thread::spawn(move || {
+            let mut reader = BufReader::new(listener.accept().unwrap().0);
+            let mut head = String::new();
+            let mut content_length = 0;
+
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if line == "\r\n" {
+                    break;
+                }
+                if let Some(value) = line.to_lowercase().strip_prefix("content-length:") {
+                    content_length = value.trim().parse().unwrap();
+                }
+                head.push_str(&line);
+            }
+
+            reader.read_exact(&mut vec![0; content_length]).unwrap();
+            write!(
+                reader.get_mut(),
+                "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                status,
+                body.len(),
+                body
+            )
+            .unwrap();
+
+            head
+        });
+
+        (url, handle)
+    }
+
+    fn schema_url_params(schema_url: String, output_directory: &Path) -> CliCodegenParams {
+        let tests_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("src/tests");
+
+        CliCodegenParams {
+            query_paths: vec![tests_dir.join("version_query.graphql")],
+            schema_url: Some(schema_url),
+            schema_out: Some(output_directory.join("schema.json")),
+            headers: vec!["X-Api-Key: secret".parse().unwrap()],
+            no_formatting: Some(true),
+            output_directory: Some(output_directory.to_owned()),
+            ..unset_params()
+        }
+    }
+
+    #[test]
+    fn the_schema_can_be_introspected_from_an_endpoint() {
+        let schema = std::fs::read_to_string(
+            PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("src/tests/version_schema.json"),
+        )
+        .unwrap();
+        let output_directory = test_directory("schema_url_output");
+        let (url, request) = serve_once("200 OK", schema);
+
+        generate_code(schema_url_params(url, &output_directory)).unwrap();
+
+        assert!(request.join().unwrap().contains("x-api-key: secret"));
+        assert!(output_directory.join("version_query.rs").is_file());
+
+        let schema_out: serde_json::Value = serde_json::from_str(
+            &std::fs::read_to_string(output_directory.join("schema.json")).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(schema_out["data"]["__schema"]["queryType"]["name"], "Query");
+    }
+
+    #[test]
+    fn schema_paths_can_be_urls_with_a_cache() {
+        let schema = std::fs::read_to_string(
+            PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("src/tests/version_schema.json"),
+        )
+        .unwrap();
+        let output_directory = test_directory("schema_cache_output");
+        // The server only answers once: the second generation uses the cache.
+        let (url, request) = serve_once("200 OK", schema);
+
+        for _ in 0..2 {
+            let mut params = schema_url_params(url.clone(), &output_directory);
+            params.schema_path = Some(PathBuf::from(params.schema_url.take().unwrap()));
+            params.headers = Vec::new();
+            params.authorization = Some("token".to_owned());
+            params.schema_cache = Some(output_directory.join("cache"));
+
+            generate_code(params).unwrap();
+        }
+
+        assert!(request
+            .join()
+            .unwrap()
+            .contains("authorization: Bearer token"));
+        assert!(output_directory.join("version_query.rs").is_file());
+    }
+
+    #[test]
+    fn failed_introspection_queries_are_reported_with_the_url() {
+        let output_directory = test_directory("schema_url_error");
+        let (url, _) = serve_once("500 Internal Server Error", "{}".to_owned());
+
+        let error = generate_code(schema_url_params(url.clone(), &output_directory))
+            .unwrap_err()
+            .to_string();
+
+        assert_eq!(
+            error,
+            format!(
+                "The introspection query to {} failed with the HTTP status 500 Internal Server Error.",
+                url
+            )
+        );
+    }
+
+    #[test]
+    fn warnings_fail_the_generation_when_they_are_denied() {
+        let tests_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("src/tests");
+        let output_directory = test_directory("warnings_output");
+
+        let generate = |deny_warnings: bool| {
+            generate_code(CliCodegenParams {
+                query_paths: vec![tests_dir.join("unused_fragment_query.graphql")],
+                schema_path: Some(tests_dir.join("scalars_schema.graphql")),
+                no_formatting: Some(true),
+                output_directory: Some(output_directory.clone()),
+                deny_warnings: Some(deny_warnings),
+                ..unset_params()
+            })
+        };
+
+        assert!(generate(false).is_ok());
+
+        let error = generate(true).unwrap_err().to_string();
+        assert!(error.contains(
+            "unused_fragment_query.graphql: 1 warning(s) reported, and warnings are denied."
+        ));
+    }
+
+    #[test]
+    fn warnings_are_formatted_with_their_location() {
+        let warning = Warning {
+            code: graphql_client_codegen::warnings::WarningCode::UnusedFragment,
+            message: "The fragment `EventDate` is not used by any operation.".to_owned(),
+            location: Some(graphql_client_codegen::warnings::Location { line: 7, column: 1 }),
+        };
+
+        assert_eq!(
+            format_warning("warning", Path::new("queries/events.graphql"), &warning),
+            "warning[unused_fragment]: The fragment `EventDate` is not used by any operation.\n  --> queries/events.graphql:7:1"
+        );
+    }
+}
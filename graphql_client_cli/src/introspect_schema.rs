@@ -3,6 +3,7 @@ use graphql_client::GraphQLQuery;
 use reqwest::header::{HeaderMap, HeaderValue, ACCEPT, CONTENT_TYPE};
 use std::path::PathBuf;
 use std::str::FromStr;
+use std::time::Duration;
 
 #[derive(GraphQLQuery)]
 #[graphql(
@@ -13,6 +14,9 @@ use std::str::FromStr;
 #[allow(dead_code)]
 struct IntrospectionQuery;
 
+/// How long to wait for the endpoint to answer the introspection query.
+const INTROSPECTION_TIMEOUT: Duration = Duration::from_secs(30);
+
 pub fn introspect_schema(
     location: &str,
     output: Option<PathBuf>,
@@ -26,13 +30,55 @@ pub fn introspect_schema(
         None => Box::new(::std::io::stdout()),
     };
 
+    let mut res = send_introspection_query(location, authorization, headers)?;
+
+    if res.status().is_success() {
+        // do nothing
+    } else if res.status().is_server_error() {
+        println!("server error!");
+    } else {
+        println!("Something else happened. Status: {:?}", res.status());
+    }
+
+    let json: serde_json::Value = res.json()?;
+    serde_json::to_writer_pretty(out, &json)?;
+    Ok(())
+}
+
+/// Run the introspection query against the endpoint and return the body of the response, which
+/// must be successful.
+pub fn fetch_schema(
+    location: &str,
+    authorization: Option<String>,
+    headers: Vec<Header>,
+) -> anyhow::Result<String> {
+    let mut res = send_introspection_query(location, authorization, headers)?;
+
+    if !res.status().is_success() {
+        return Err(format_err!(
+            "The introspection query to {} failed with the HTTP status {}.",
+            location,
+            res.status()
+        ));
+    }
+
+    Ok(res.text()?)
+}
+
+fn send_introspection_query(
+    location: &str,
+    authorization: Option<String>,
+    headers: Vec<Header>,
+) -> anyhow::Result<reqwest::Response> {
     let request_body: graphql_client::QueryBody<()> = graphql_client::QueryBody {
         variables: (),
-        query: introspection_query::QUERY,
+        query: introspection_query::QUERY.into(),
         operation_name: introspection_query::OPERATION_NAME,
     };
 
-    let client = reqwest::Client::new();
+    let client = reqwest::Client::builder()
+        .timeout(INTROSPECTION_TIMEOUT)
+        .build()?;
 
     let mut req_builder = client.post(location).headers(construct_headers());
 
@@ -44,19 +90,17 @@ pub fn introspect_schema(
         req_builder = req_builder.bearer_auth(token.as_str());
     };
 
-    let mut res = req_builder.json(&request_body).send()?;
-
-    if res.status().is_success() {
-        // do nothing
-    } else if res.status().is_server_error() {
-        println!("server error!");
-    } else {
-        println!("Something else happened. Status: {:?}", res.status());
-    }
-
-    let json: serde_json::Value = res.json()?;
-    serde_json::to_writer_pretty(out, &json)?;
-    Ok(())
+    req_builder.json(&request_body).send().map_err(|err| {
+        if err.is_timeout() {
+            format_err!(
+                "The introspection query to {} timed out after {} seconds.",
+                location,
+                INTROSPECTION_TIMEOUT.as_secs()
+            )
+        } else {
+            format_err!("The introspection query to {} failed: {}", location, err)
+        }
+    })
 }
 
 fn construct_headers() -> HeaderMap {
@@ -112,10 +156,31 @@ impl FromStr for Header {
     }
 }
 
+impl Header {
+    /// The header with its value replaced by `***`, for printing.
+    pub(crate) fn redacted(&self) -> String {
+        format!("{}: ***", self.name)
+    }
+}
+
+impl std::fmt::Display for Header {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.name, self.value)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn the_introspection_query_is_the_one_the_codegen_deserializes() {
+        assert_eq!(
+            introspection_query::QUERY,
+            graphql_client_codegen::INTROSPECTION_QUERY
+        );
+    }
+
     #[test]
     fn it_errors_invalid_headers() {
         // https://tools.ietf.org/html/rfc7230#section-3.2
@@ -4,11 +4,18 @@ use log::Level;
 #[cfg(feature = "rustfmt")]
 extern crate rustfmt_nightly as rustfmt;
 
+mod gen_tests;
 mod generate;
 mod introspect_schema;
+mod migrate;
+mod schema_cache;
+mod schema_info;
+mod validate;
 use std::path::PathBuf;
 use structopt::StructOpt;
 
+// The arguments are parsed once, the size of the variants does not matter.
+#[allow(clippy::large_enum_variant)]
 #[derive(StructOpt)]
 #[structopt(author, about)]
 enum Cli {
@@ -31,14 +38,41 @@ enum Cli {
     },
     #[structopt(name = "generate")]
     Generate {
-        /// Path to GraphQL schema file (.json or .graphql).
-        #[structopt(short = "s", long = "schema-path")]
-        schema_path: PathBuf,
-        /// Path to the GraphQL query file.
-        query_path: PathBuf,
+        /// Path to GraphQL schema file (.json or .graphql), or the http:// or https:// URL of a
+        /// GraphQL endpoint to introspect.
+        #[structopt(short = "s", long = "schema-path", conflicts_with = "schema-url")]
+        schema_path: Option<PathBuf>,
+        /// The URL of a GraphQL endpoint to introspect for the schema, instead of a schema file.
+        #[structopt(long = "schema-url")]
+        schema_url: Option<String>,
+        /// Where to write the JSON for the schema introspected from a URL.
+        #[structopt(parse(from_os_str), long = "schema-out")]
+        schema_out: Option<PathBuf>,
+        /// Custom headers for the introspection query of a schema URL.
+        /// --header 'X-Name: Value'
+        #[structopt(long = "header")]
+        headers: Vec<introspect_schema::Header>,
+        /// Set the contents of the Authorization header for the introspection query of a schema
+        /// URL.
+        #[structopt(long = "authorization")]
+        authorization: Option<String>,
+        /// A directory to cache the schemas introspected from URLs in.
+        #[structopt(parse(from_os_str), long = "schema-cache")]
+        schema_cache: Option<PathBuf>,
+        /// How many seconds a cached schema is used before the endpoint is introspected again.
+        /// Default value is 600.
+        #[structopt(long = "schema-cache-max-age")]
+        schema_cache_max_age: Option<u64>,
+        /// Paths to the GraphQL query files, or glob patterns matching several query files, e.g.
+        /// 'queries/**/*.graphql'. The code of each query file is generated in its own file, and
+        /// the schema is only read once.
+        query_paths: Vec<PathBuf>,
         /// Name of target query. If you don't set this parameter, cli generate all queries in query file.
         #[structopt(long = "selected-operation")]
         selected_operation: Option<String>,
+        /// Name of a fragment to generate a standalone module for, instead of the operations.
+        #[structopt(long = "fragment")]
+        fragment: Option<String>,
         /// Additional derives that will be added to the generated structs and enums for the variables.
         /// --variables-derives='Serialize,PartialEq'
         #[structopt(short = "I", long = "variables-derives")]
@@ -47,6 +81,10 @@ enum Cli {
         /// --output-derives='Serialize,PartialEq'
         #[structopt(short = "O", long = "response-derives")]
         response_derives: Option<String>,
+        /// Additional derives for specific generated types only.
+        /// --derives-for='ResponseData: Clone, PartialEq; MyQueryUser: Hash'
+        #[structopt(long = "derives-for")]
+        derives_for: Option<String>,
         /// You can choose deprecation strategy from allow, deny, or warn.
         /// Default value is warn.
         #[structopt(short = "d", long = "deprecation-strategy")]
@@ -54,8 +92,11 @@ enum Cli {
         /// If you don't want to execute rustfmt to generated code, set this option.
         /// Default value is false.
         /// Formating feature is disabled as default installation.
-        #[structopt(long = "no-formatting")]
+        #[structopt(long = "no-formatting", overrides_with = "formatting")]
         no_formatting: bool,
+        /// Format the generated code, even if the config file sets `no_formatting`.
+        #[structopt(long = "formatting", overrides_with = "no-formatting")]
+        formatting: bool,
         /// You can choose module and target struct visibility from pub and private.
         /// Default value is pub.
         #[structopt(short = "m", long = "module-visibility")]
@@ -66,6 +107,113 @@ enum Cli {
         /// file, with the same name and the .rs extension.
         #[structopt(short = "o", long = "output-directory")]
         output_directory: Option<PathBuf>,
+        /// The path to the serde crate used in the generated code, equivalent to the
+        /// `#[serde(crate = "...")]` attribute.
+        /// --serde-crate='my_crate::serde'
+        #[structopt(long = "serde-crate", parse(try_from_str = syn::parse_str))]
+        serde_crate: Option<syn::Path>,
+        /// Append a commented block listing the custom scalars used by the generated code,
+        /// which have to be defined in its parent module.
+        #[structopt(long = "emit-scalar-stubs", overrides_with = "no-emit-scalar-stubs")]
+        emit_scalar_stubs: bool,
+        /// Do not append the custom scalars block, even if the config file sets
+        /// `emit_scalar_stubs`.
+        #[structopt(long = "no-emit-scalar-stubs", overrides_with = "emit-scalar-stubs")]
+        no_emit_scalar_stubs: bool,
+        /// A TOML file with the parameters of the command, named like the flags in snake case, and
+        /// options for specific operations, overriding the global ones. The flags override the
+        /// values of the file.
+        /// schema_path = 'schema.graphql' [operations.RepoView] response_derives = 'Clone'
+        #[structopt(parse(from_os_str), long = "config")]
+        config: Option<PathBuf>,
+        /// Print the parameters, merged with the ones of the config file, as a config file
+        /// instead of generating code.
+        #[structopt(long = "print-config")]
+        print_config: bool,
+        /// Fail when code generation reports warnings, like unused fragments, instead of only
+        /// printing them.
+        #[structopt(long = "deny-warnings", overrides_with = "no-deny-warnings")]
+        deny_warnings: bool,
+        /// Only print the warnings, even if the config file sets `deny_warnings`.
+        #[structopt(long = "no-deny-warnings", overrides_with = "deny-warnings")]
+        no_deny_warnings: bool,
+        /// Fields that must never be selected, as schema coordinates. Code generation fails when
+        /// a query selects one of them, directly or through an interface.
+        /// --forbidden-fields='User.email,User.ssn'
+        #[structopt(long = "forbidden-fields")]
+        forbidden_fields: Option<String>,
+        /// The only types whose fields can be selected, including the root operation types.
+        /// --allowed-types='Query,User'
+        #[structopt(long = "allowed-types")]
+        allowed_types: Option<String>,
+        /// A license or "do not edit" header, written as a comment block at the top of the
+        /// generated files. It ends with the `@generated` marker of generated files.
+        /// --file-header='Copyright (c) Example Corp. DO NOT EDIT.'
+        #[structopt(long = "file-header")]
+        file_header: Option<String>,
+    },
+    /// Apply field renames from a new version of the schema to query files. Renamed fields are
+    /// aliased to their old name, so the shape of the responses does not change.
+    #[structopt(name = "migrate")]
+    Migrate {
+        /// Path to the new GraphQL schema file (.json or .graphql).
+        #[structopt(short = "s", long = "schema-path")]
+        schema_path: PathBuf,
+        /// A field rename, for fields of the given object or interface type.
+        /// --rename 'User.name=displayName'
+        #[structopt(long = "rename", required = true)]
+        renames: Vec<graphql_client_codegen::migration::FieldRename>,
+        /// Print the changes instead of writing the query files.
+        #[structopt(long = "dry-run")]
+        dry_run: bool,
+        /// Paths to the GraphQL query files.
+        #[structopt(required = true)]
+        query_paths: Vec<PathBuf>,
+    },
+    /// Generate a Rust test file checking that the enums used by the queries serialize to and
+    /// deserialize from their GraphQL values.
+    #[structopt(name = "gen-tests")]
+    GenTests {
+        /// Path to the GraphQL schema file (.json or .graphql).
+        #[structopt(short = "s", long = "schema-path")]
+        schema_path: PathBuf,
+        /// Where to write the test file.
+        #[structopt(parse(from_os_str), long = "output")]
+        output: PathBuf,
+        /// The path to the module with the code generated for the query files, one module per
+        /// query file, named after it: the enums of `user.graphql` are in `<module-path>::user`.
+        /// --module-path='my_crate::queries'
+        #[structopt(
+            long = "module-path",
+            default_value = "crate",
+            parse(try_from_str = syn::parse_str)
+        )]
+        module_path: syn::Path,
+        /// Paths to the GraphQL query files.
+        #[structopt(required = true)]
+        query_paths: Vec<PathBuf>,
+    },
+    /// Check that query files are valid against a schema, without generating code. Each error is
+    /// printed on its own line, prefixed with the path of the query file and the line and column
+    /// of the error, and the command fails if there is any.
+    #[structopt(name = "validate")]
+    Validate {
+        /// Path to the GraphQL schema file (.json or .graphql).
+        #[structopt(short = "s", long = "schema-path")]
+        schema_path: PathBuf,
+        /// Paths to the GraphQL query files, or glob patterns matching several query files, e.g.
+        /// 'queries/**/*.graphql'.
+        #[structopt(required = true)]
+        query_paths: Vec<PathBuf>,
+    },
+    /// Describe a schema without generating code: the number of types of each kind, the root
+    /// types, and the features the schema uses, with their support by the code generation. The
+    /// command fails if the schema uses unsupported features.
+    #[structopt(name = "schema-info")]
+    SchemaInfo {
+        /// Path to the GraphQL schema file (.json or .graphql).
+        #[structopt(short = "s", long = "schema-path")]
+        schema_path: PathBuf,
     },
 }
 
@@ -83,24 +231,101 @@ fn main() -> anyhow::Result<()> {
         Cli::Generate {
             variables_derives,
             response_derives,
+            derives_for,
             deprecation_strategy,
             module_visibility,
             no_formatting,
+            formatting,
             output_directory,
-            query_path,
+            query_paths,
             schema_path,
+            schema_url,
+            schema_out,
+            headers,
+            authorization,
+            schema_cache,
+            schema_cache_max_age,
             selected_operation,
+            fragment,
+            serde_crate,
+            emit_scalar_stubs,
+            no_emit_scalar_stubs,
+            config,
+            print_config,
+            deny_warnings,
+            no_deny_warnings,
+            forbidden_fields,
+            allowed_types,
+            file_header,
         } => generate::generate_code(generate::CliCodegenParams {
             variables_derives,
             response_derives,
+            derives_for,
             deprecation_strategy,
             module_visibility,
-            no_formatting,
+            no_formatting: flag(no_formatting, formatting),
             output_directory,
-            query_path,
+            query_paths,
             schema_path,
+            schema_url,
+            schema_out,
+            headers,
+            authorization,
+            schema_cache,
+            schema_cache_max_age,
             selected_operation,
+            fragment,
+            serde_crate,
+            emit_scalar_stubs: flag(emit_scalar_stubs, no_emit_scalar_stubs),
+            config,
+            print_config,
+            deny_warnings: flag(deny_warnings, no_deny_warnings),
+            forbidden_fields,
+            allowed_types,
+            file_header,
+        }),
+        Cli::Migrate {
+            schema_path,
+            renames,
+            dry_run,
+            query_paths,
+        } => migrate::migrate(migrate::CliMigrateParams {
+            schema_path,
+            renames,
+            query_paths,
+            dry_run,
+        }),
+        Cli::GenTests {
+            schema_path,
+            output,
+            module_path,
+            query_paths,
+        } => gen_tests::gen_tests(gen_tests::CliGenTestsParams {
+            schema_path,
+            query_paths,
+            output,
+            module_path,
         }),
+        Cli::Validate {
+            schema_path,
+            query_paths,
+        } => validate::validate(validate::CliValidateParams {
+            schema_path,
+            query_paths,
+        }),
+        Cli::SchemaInfo { schema_path } => {
+            schema_info::schema_info(schema_info::CliSchemaInfoParams { schema_path })
+        }
+    }
+}
+
+/// A boolean parameter from its flag and the negation of the flag: `None` when neither is given.
+/// When both are, the last one overrides the other one.
+fn flag(on: bool, off: bool) -> Option<bool> {
+    match (on, off) {
+        (true, _) => Some(true),
+        (_, true) => Some(false),
+        (false, false) => None,
     }
 }
 
@@ -138,3 +363,21 @@ fn colored_level<'a>(style: &'a mut Style, level: Level) -> StyledValue<'a, &'st
         Level::Error => style.set_color(Color::Red).value("ERROR"),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    /// A new empty directory for a test, named after the test and the test process, so that the
+    /// tests running in parallel do not share it.
+    pub(crate) fn test_directory(name: &str) -> PathBuf {
+        let directory = std::env::temp_dir().join(format!(
+            "graphql_client_cli_{}_{}",
+            name,
+            std::process::id()
+        ));
+        let _ = std::fs::remove_dir_all(&directory);
+        std::fs::create_dir_all(&directory).unwrap();
+        directory
+    }
+}
@@ -0,0 +1,139 @@
+use anyhow::*;
+use graphql_client_codegen::migration::{FieldRename, Migration};
+use std::path::PathBuf;
+
+pub(crate) struct CliMigrateParams {
+    pub schema_path: PathBuf,
+    pub renames: Vec<FieldRename>,
+    pub query_paths: Vec<PathBuf>,
+    pub dry_run: bool,
+}
+
+pub(crate) fn migrate(params: CliMigrateParams) -> Result<()> {
+    let CliMigrateParams {
+        schema_path,
+        renames,
+        query_paths,
+        dry_run,
+    } = params;
+
+    let migration = Migration::new(&schema_path, renames).map_err(|fail| fail.compat())?;
+
+    for query_path in query_paths {
+        let (query, byte_order_mark) =
+            graphql_client_codegen::read_file_and_byte_order_mark(&query_path)
+                .map_err(|fail| fail.compat())
+                .with_context(|| format!("Reading {}", query_path.display()))?;
+        let migrated = migration
+            .migrate(&query)
+            .map_err(|fail| fail.compat())
+            .with_context(|| format!("Migrating {}", query_path.display()))?;
+
+        let migrated = match migrated {
+            Some(migrated) => migrated,
+            None => continue,
+        };
+
+        if dry_run {
+            print!(
+                "{}",
+                diff(&query_path.display().to_string(), &query, &migrated)
+            );
+        } else {
+            // The file keeps its byte order mark.
+            let migrated = if byte_order_mark {
+                format!("{}{}", graphql_client_codegen::BYTE_ORDER_MARK, migrated)
+            } else {
+                migrated
+            };
+            std::fs::write(&query_path, migrated)
+                .with_context(|| format!("Writing {}", query_path.display()))?;
+        }
+    }
+
+    Ok(())
+}
+
+/// A line diff between two versions of a file, in the unified diff style without hunk headers.
+fn diff(path: &str, old: &str, new: &str) -> String {
+    let old: Vec<&str> = old.lines().collect();
+    let new: Vec<&str> = new.lines().collect();
+
+    // Length of the longest common subsequence of old[i..] and new[j..].
+    let mut lcs = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lcs[i][j] = if old[i] == new[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut out = format!("--- {}\n+++ {}\n", path, path);
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && old[i] == new[j] {
+            out.push_str(&format!(" {}\n", old[i]));
+            i += 1;
+            j += 1;
+        } else if i < old.len() && (j == new.len() || lcs[i + 1][j] >= lcs[i][j + 1]) {
+            out.push_str(&format!("-{}\n", old[i]));
+            i += 1;
+        } else {
+            out.push_str(&format!("+{}\n", new[j]));
+            j += 1;
+        }
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn diff_shows_changed_lines() {
+        assert_eq!(
+            diff(
+                "query.graphql",
+                "query {\n  name\n}\n",
+                "query {\n  name: displayName\n}\n"
+            ),
+            "--- query.graphql\n+++ query.graphql\n query {\n-  name\n+  name: displayName\n }\n"
+        );
+    }
+
+    #[test]
+    fn byte_order_marks_are_kept() {
+        let directory = std::env::temp_dir().join(format!(
+            "graphql_client_cli_migrate_bom_{}",
+            std::process::id()
+        ));
+        std::fs::create_dir_all(&directory).unwrap();
+        let schema_path = directory.join("schema.graphql");
+        let query_path = directory.join("query.graphql");
+        std::fs::write(
+            &schema_path,
+            "type Query { me: User! }\ntype User { displayName: String! }\n",
+        )
+        .unwrap();
+        std::fs::write(&query_path, "\u{feff}query Me { me { name } }\n").unwrap();
+
+        migrate(CliMigrateParams {
+            schema_path,
+            renames: vec!["User.name=displayName".parse().unwrap()],
+            query_paths: vec![query_path.clone()],
+            dry_run: false,
+        })
+        .unwrap();
+
+        assert_eq!(
+            std::fs::read_to_string(&query_path).unwrap(),
+            "\u{feff}query Me { me { name: displayName } }\n"
+        );
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+}
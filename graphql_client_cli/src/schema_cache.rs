@@ -0,0 +1,84 @@
+use anyhow::*;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+/// A directory where the schemas introspected from endpoints are kept, keyed by URL.
+pub(crate) struct SchemaCache {
+    pub directory: PathBuf,
+    /// How long a cached schema is used before the endpoint is introspected again.
+    pub max_age: Duration,
+}
+
+impl SchemaCache {
+    /// The cached schema for this URL, unless it is missing or too old.
+    pub(crate) fn get(&self, url: &str) -> Option<String> {
+        let path = self.path(url);
+        let age = std::fs::metadata(&path)
+            .and_then(|metadata| metadata.modified())
+            .ok()
+            .and_then(|modified| SystemTime::now().duration_since(modified).ok())?;
+
+        if age >= self.max_age {
+            return None;
+        }
+
+        std::fs::read_to_string(path).ok()
+    }
+
+    pub(crate) fn put(&self, url: &str, schema: &str) -> Result<()> {
+        std::fs::create_dir_all(&self.directory).with_context(|| {
+            format!(
+                "Could not create the schema cache directory {}",
+                self.directory.display()
+            )
+        })?;
+
+        let path = self.path(url);
+        std::fs::write(&path, schema)
+            .with_context(|| format!("Could not write the cached schema {}", path.display()))
+    }
+
+    /// The file for the schema of this URL: the SHA-256 hash of the URL, so it is a valid file
+    /// name.
+    fn path(&self, url: &str) -> PathBuf {
+        use sha2::Digest;
+
+        let hash: String = sha2::Sha256::digest(url.as_bytes())
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect();
+
+        Path::new(&self.directory).join(hash).with_extension("json")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::test_directory;
+
+    #[test]
+    fn schemas_are_cached_by_url() {
+        let cache = SchemaCache {
+            directory: test_directory("schema_cache"),
+            max_age: Duration::from_secs(600),
+        };
+
+        cache.put("https://example.com/graphql", "{}").unwrap();
+
+        assert_eq!(cache.get("https://example.com/graphql").unwrap(), "{}");
+        assert_eq!(cache.get("https://example.com/other"), None);
+    }
+
+    #[test]
+    fn old_schemas_are_not_used() {
+        let cache = SchemaCache {
+            directory: test_directory("schema_cache_expired"),
+            max_age: Duration::from_secs(0),
+        };
+
+        cache.put("https://example.com/graphql", "{}").unwrap();
+
+        assert_eq!(cache.get("https://example.com/graphql"), None);
+    }
+}
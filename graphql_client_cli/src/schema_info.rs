@@ -0,0 +1,135 @@
+use anyhow::{format_err, Result};
+use graphql_client_codegen::schema::SchemaInfo;
+use std::path::PathBuf;
+
+pub(crate) struct CliSchemaInfoParams {
+    pub schema_path: PathBuf,
+}
+
+pub(crate) fn schema_info(params: CliSchemaInfoParams) -> Result<()> {
+    let CliSchemaInfoParams { schema_path } = params;
+
+    let info = graphql_client_codegen::schema_info(&schema_path)
+        .map_err(|fail| fail.compat())
+        .map_err(|err| format_err!("Reading {}: {}", schema_path.display(), err))?;
+
+    print!("{}", report(&info));
+
+    let unsupported: Vec<String> = info
+        .unsupported_features()
+        .map(|feature| feature.to_string())
+        .collect();
+
+    if !unsupported.is_empty() {
+        return Err(format_err!(
+            "The schema uses features that are not supported: {}.",
+            unsupported.join(", ")
+        ));
+    }
+
+    Ok(())
+}
+
+/// The counts of the kinds of types, the root types, and the features with their support.
+fn report(info: &SchemaInfo) -> String {
+    let mut report = String::new();
+    let counts = [
+        ("objects", info.objects),
+        ("interfaces", info.interfaces),
+        ("unions", info.unions),
+        ("enums", info.enums),
+        ("input objects", info.inputs),
+        ("scalars", info.scalars),
+        ("directives", info.directives),
+    ];
+
+    for (kind, count) in &counts {
+        report.push_str(&format!("{}: {}\n", kind, count));
+    }
+
+    let roots = [
+        ("query", Some(&info.query_type)),
+        ("mutation", info.mutation_type.as_ref()),
+        ("subscription", info.subscription_type.as_ref()),
+    ];
+
+    for (operation_type, root) in &roots {
+        report.push_str(&format!(
+            "{} type: {}\n",
+            operation_type,
+            root.map(String::as_str).unwrap_or("none")
+        ));
+    }
+
+    report.push_str("features:\n");
+
+    if info.features.is_empty() {
+        report.push_str("  none\n");
+    }
+
+    for feature in &info.features {
+        report.push_str(&format!(
+            "  {}: {} ({})\n",
+            feature,
+            feature.support(),
+            feature.note()
+        ));
+    }
+
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_report_lists_the_counts_roots_and_features() {
+        let schema_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("../graphql_client_codegen/src/tests/schema_features_schema.graphql");
+        let info = graphql_client_codegen::schema_info(&schema_path)
+            .map_err(|fail| fail.compat())
+            .unwrap();
+
+        assert_eq!(
+            report(&info),
+            "objects: 3\n\
+             interfaces: 1\n\
+             unions: 1\n\
+             enums: 1\n\
+             input objects: 1\n\
+             scalars: 1\n\
+             directives: 2\n\
+             query type: Query\n\
+             mutation type: none\n\
+             subscription type: Subscription\n\
+             features:\n  \
+             subscriptions: supported (subscription operations are generated like queries)\n  \
+             custom scalars: supported (the parent module of the generated code defines a type for each of them)\n  \
+             deprecations: supported (the deprecation strategy applies to the deprecated fields)\n  \
+             custom directives: ignored (the directives of the queries are sent as written, without checking their definitions)\n  \
+             type extensions: unsupported (the extensions are dropped, so the fields they add are unknown to the queries)\n  \
+             @oneOf input objects: unsupported (the input objects are generated with all their fields, and serialize the unset ones as null)\n"
+        );
+    }
+
+    #[test]
+    fn unsupported_features_fail_the_command() {
+        let tests_dir =
+            PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../graphql_client_codegen/src/tests");
+
+        let err = schema_info(CliSchemaInfoParams {
+            schema_path: tests_dir.join("schema_features_schema.graphql"),
+        })
+        .unwrap_err();
+
+        assert_eq!(
+            err.to_string(),
+            "The schema uses features that are not supported: type extensions, @oneOf input objects."
+        );
+        assert!(schema_info(CliSchemaInfoParams {
+            schema_path: tests_dir.join("star_wars_schema.graphql"),
+        })
+        .is_ok());
+    }
+}
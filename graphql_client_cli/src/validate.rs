@@ -0,0 +1,121 @@
+use crate::generate::expand_query_path;
+use anyhow::{format_err, Result};
+use graphql_client_codegen::{
+    CodegenErrors, CodegenMode, GraphQLClientCodegenOptions, LocatedError,
+};
+use std::path::{Path, PathBuf};
+
+pub(crate) struct CliValidateParams {
+    pub schema_path: PathBuf,
+    pub query_paths: Vec<PathBuf>,
+}
+
+pub(crate) fn validate(params: CliValidateParams) -> Result<()> {
+    let CliValidateParams {
+        schema_path,
+        query_paths,
+    } = params;
+
+    let mut query_files = Vec::new();
+    for query_path in &query_paths {
+        query_files.extend(expand_query_path(query_path)?);
+    }
+
+    let errors = validation_errors(&schema_path, &query_files);
+
+    for error in &errors {
+        println!("{}", error);
+    }
+
+    if !errors.is_empty() {
+        return Err(format_err!(
+            "{} error(s) in the {} query file(s).",
+            errors.len(),
+            query_files.len()
+        ));
+    }
+
+    Ok(())
+}
+
+/// The errors of the query files, one line per error, prefixed with the path of the file and the
+/// position of the error in the file, when it is known. The code for all the operations of each
+/// file is generated and dropped, so that the errors are the ones of the `generate` command.
+fn validation_errors(schema_path: &Path, query_files: &[PathBuf]) -> Vec<String> {
+    let mut lines = Vec::new();
+
+    for query_path in query_files {
+        let generated = graphql_client_codegen::generate_code(
+            query_path.to_owned(),
+            schema_path,
+            GraphQLClientCodegenOptions::new(CodegenMode::Cli),
+        );
+
+        let error = match generated {
+            Ok(_) => continue,
+            Err(error) => error,
+        };
+        let errors = match error.downcast::<CodegenErrors>() {
+            Ok(errors) => errors.errors,
+            Err(error) => vec![error],
+        };
+
+        for error in errors {
+            let (location, error) = match error.downcast::<LocatedError>() {
+                Ok(located) => (
+                    format!(
+                        "{}:{}:{}",
+                        query_path.display(),
+                        located.location.line,
+                        located.location.column
+                    ),
+                    located.error,
+                ),
+                Err(error) => (query_path.display().to_string(), error),
+            };
+            // Multi-line messages are joined, for one line per error.
+            let message = error.to_string();
+            let message: Vec<&str> = message
+                .lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty())
+                .collect();
+            lines.push(format!("{}: {}", location, message.join(" ")));
+        }
+    }
+
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn all_the_errors_of_the_query_files_are_reported() {
+        let tests_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("src/tests");
+        let schema_path = tests_dir.join("scalars_schema.graphql");
+
+        let errors = validation_errors(
+            &schema_path,
+            &[
+                tests_dir.join("scalars_query.graphql"),
+                tests_dir.join("invalid_query.graphql"),
+            ],
+        );
+
+        let invalid_query = tests_dir.join("invalid_query.graphql");
+        let expected: Vec<String> = vec![
+            "4:5: Could not find field `endsAt` on `Event`. Available fields: `__typename, name, startsAt`.",
+            "5:8: Unknown fragment: EventDetails",
+            "1:20: The variable `$source` of the operation `BrokenEvents` has the type `Link`, which is not a scalar, enum or input object of the schema.",
+            // The errors of the other operations of the file are reported too.
+            "11:5: Could not find field `title` on `Event`. Available fields: `__typename, name, startsAt`.",
+        ]
+        .into_iter()
+        .map(|error| format!("{}:{}", invalid_query.display(), error))
+        .collect();
+
+        assert_eq!(errors, expected);
+    }
+}
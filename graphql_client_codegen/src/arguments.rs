@@ -0,0 +1,103 @@
+//! Validation of the literal arguments of the selected fields, so that a typo in a hardcoded enum
+//! argument (`issues(orderBy: CREATD_AT)`) is a code generation error instead of a server error.
+
+use crate::objects::GqlObjectField;
+use crate::query::QueryContext;
+use crate::selection::SelectionField;
+use failure::format_err;
+use graphql_parser::query::Value;
+
+/// Fail when an enum value in the literal arguments of a field is not a value of its enum. The
+/// values in lists and in input objects are checked too. The arguments that are not in the schema
+/// are left to the server.
+pub(crate) fn validate_enum_arguments(
+    context: &QueryContext<'_, '_>,
+    type_name: &str,
+    schema_field: &GqlObjectField<'_>,
+    field: &SelectionField<'_>,
+) -> Result<(), failure::Error> {
+    for (argument, value) in field.arguments {
+        let argument_type = match schema_field
+            .arguments
+            .iter()
+            .find(|(name, _)| name == argument)
+        {
+            Some((_, argument_type)) => argument_type,
+            None => continue,
+        };
+
+        validate_value(context, value, argument_type.inner_name_str(), argument).map_err(
+            |(path, enum_value, enum_name, values)| {
+                format_err!(
+                    "The argument `{}` of the field `{}.{}` is `{}`, which is not a value of the `{}` enum. The values are: {}.",
+                    path,
+                    type_name,
+                    field.name,
+                    enum_value,
+                    enum_name,
+                    values.join(", ")
+                )
+            },
+        )?;
+    }
+
+    Ok(())
+}
+
+/// The invalid enum value of a literal: its path in the argument (`orderBy.field`), the value, the
+/// name of the enum and its values.
+type InvalidEnumValue<'a> = (String, &'a str, &'a str, Vec<&'a str>);
+
+fn validate_value<'a>(
+    context: &QueryContext<'_, 'a>,
+    value: &'a Value,
+    type_name: &str,
+    path: &str,
+) -> Result<(), InvalidEnumValue<'a>> {
+    match value {
+        Value::Enum(enum_value) => {
+            let enm = match context.schema.enums.get(type_name) {
+                Some(enm) => enm,
+                None => return Ok(()),
+            };
+
+            if enm
+                .variants
+                .iter()
+                .any(|variant| variant.name == enum_value)
+            {
+                return Ok(());
+            }
+
+            Err((
+                path.to_owned(),
+                enum_value,
+                enm.name,
+                enm.variants.iter().map(|variant| variant.name).collect(),
+            ))
+        }
+        // The items of a list have the type of the list, without the list.
+        Value::List(values) => values
+            .iter()
+            .try_for_each(|value| validate_value(context, value, type_name, path)),
+        Value::Object(fields) => {
+            let input = match context.schema.inputs.get(type_name) {
+                Some(input) => input,
+                None => return Ok(()),
+            };
+
+            fields
+                .iter()
+                .try_for_each(|(name, value)| match input.fields.get(name.as_str()) {
+                    Some(field) => validate_value(
+                        context,
+                        value,
+                        field.type_.inner_name_str(),
+                        &format!("{}.{}", path, name),
+                    ),
+                    None => Ok(()),
+                })
+        }
+        _ => Ok(()),
+    }
+}
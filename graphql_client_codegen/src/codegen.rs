@@ -6,68 +6,341 @@ use crate::schema;
 use crate::selection::Selection;
 use failure::*;
 use graphql_parser::query;
-use proc_macro2::TokenStream;
+use proc_macro2::{Ident, Span, TokenStream};
 use quote::*;
 
+/// The error for an operation whose root type, like `Mutation`, is not defined by the schema.
+#[derive(Debug)]
+struct OperationTypeNotInSchema {
+    operation_name: String,
+    operation_type: String,
+    root_name: String,
+}
+
+impl std::fmt::Display for OperationTypeNotInSchema {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "The {} `{}` cannot be generated: the schema has no `{}` type.",
+            self.operation_type, self.operation_name, self.root_name
+        )
+    }
+}
+
+impl Fail for OperationTypeNotInSchema {}
+
 /// Selects the first operation matching `struct_name`. Returns `None` when the query document defines no operation, or when the selected operation does not match any defined operation.
 pub(crate) fn select_operation<'query>(
     query: &'query query::Document,
     struct_name: &str,
     norm: Normalization,
-) -> Option<Operation<'query>> {
-    let operations = all_operations(query);
+) -> Result<Option<Operation<'query>>, failure::Error> {
+    let operations = all_operations(query)?;
 
-    operations
-        .iter()
-        .find(|op| norm.operation(&op.name) == struct_name)
-        .map(ToOwned::to_owned)
+    Ok(operations
+        .into_iter()
+        .find(|op| norm.operation(&op.name) == struct_name))
 }
 
-pub(crate) fn all_operations(query: &query::Document) -> Vec<Operation<'_>> {
+/// All the operations in the query document. Fails on anonymous operations.
+pub(crate) fn all_operations(
+    query: &query::Document,
+) -> Result<Vec<Operation<'_>>, failure::Error> {
     let mut operations: Vec<Operation<'_>> = Vec::new();
 
     for definition in &query.definitions {
         if let query::Definition::Operation(op) = definition {
-            operations.push(op.into());
+            operations.push(std::convert::TryFrom::try_from(op)?);
         }
     }
-    operations
+    Ok(operations)
 }
 
 /// The main code generation function.
+///
+/// Returns the generated items along with the names of the types that make up the facade module:
+/// `Variables`, the response data struct, and the enums, input objects and fragments used by the operation.
+/// The last value is whether `Variables` has a lifetime.
 pub(crate) fn response_for_query(
     schema: &schema::Schema<'_>,
     query: &query::Document,
     operation: &Operation<'_>,
     options: &crate::GraphQLClientCodegenOptions,
-) -> Result<TokenStream, failure::Error> {
+) -> Result<(TokenStream, Vec<Ident>, bool), failure::Error> {
+    let mut context = query_context(schema, query, options)?;
+    let root_name = operation.root_name(context.schema);
+
+    if options.cursor_newtypes() {
+        context.cursors = crate::cursors::ConnectionCursors::collect(
+            &context,
+            root_name,
+            &operation.selection,
+            &operation.name,
+            &operation.variables,
+        )?;
+    }
+
+    // The `Cow` of the borrowed lists needs their items to be `Clone`.
+    if options.variables_lifetime() {
+        let types = operation.borrowed_list_types(&context);
+        context.derive_clone_for(types.iter().map(String::as_str));
+    }
+
+    let selection = {
+        let opt_definition = context.schema.objects.get(&root_name);
+        let definition = if let Some(definition) = opt_definition {
+            definition
+        } else {
+            return Err(OperationTypeNotInSchema {
+                operation_name: operation.name.clone(),
+                operation_type: format!("{:?}", operation.operation_type).to_lowercase(),
+                root_name: root_name.to_owned(),
+            }
+            .into());
+        };
+        let prefix = &operation.name;
+        let multiple_subscription_fields =
+            operation.is_subscription() && operation.selection.len() > 1;
+        let allow_multiple_fields = options.allow_multiple_subscription_fields();
+        // The server may only send some of the root fields.
+        let optional_root_fields = if multiple_subscription_fields && allow_multiple_fields {
+            Some(operation.selection.conditional())
+        } else {
+            None
+        };
+        let selection = optional_root_fields
+            .as_ref()
+            .unwrap_or(&operation.selection);
+
+        let root_fields = if multiple_subscription_fields && !allow_multiple_fields {
+            let error = format_err!(
+                "The subscription `{}` selects {} root fields.\n{}",
+                operation.name,
+                selection.len(),
+                crate::constants::MULTIPLE_SUBSCRIPTION_FIELDS_ERROR
+            );
+            // The error is located at the first extra root field.
+            match selection.into_iter().nth(1) {
+                Some(item) => Err(crate::errors::at(item.position(), error)),
+                None => Err(error),
+            }
+        } else {
+            Ok(())
+        };
+
+        crate::errors::join(
+            root_fields,
+            crate::errors::join(
+                definition.field_impls_for_selection(&context, selection, prefix),
+                definition.response_fields_for_selection(&context, selection, prefix),
+            ),
+        )
+        .map(|((), selection)| selection)
+    };
+
+    // The errors of the selection, the variables and the fragments are reported together.
+    let ((definitions, response_data_fields), (variables_struct, shared_definitions)) =
+        crate::errors::join(
+            selection,
+            crate::errors::join(
+                operation.expand_variables(&context),
+                shared_definitions(&context),
+            ),
+        )?;
+
+    let response_struct_name = options.response_struct_ident()?;
+    let response_data_fields = crate::skip_serde::response_struct_fields(
+        &context,
+        &response_struct_name,
+        &response_data_fields,
+    )?;
+    let (response_derives, response_debug_impl) = crate::compact_debug::response_struct_derives(
+        &context,
+        &response_struct_name,
+        &response_data_fields,
+    )?;
+    let (response_serde_attribute, response_serde_impls) =
+        crate::response_serde::response_struct_serde(
+            &context,
+            &response_struct_name,
+            root_name,
+            &response_data_fields,
+        )?;
+    let (response_data_fields, response_getters) = crate::getters::response_struct_fields(
+        &context,
+        &response_struct_name,
+        &response_data_fields,
+    )?;
+
+    // The operations without variables have no builder.
+    let variables_builder = if operation.variables.is_empty() {
+        None
+    } else {
+        Some(Ident::new("VariablesBuilder", Span::call_site()))
+    };
+    let facade_items: Vec<Ident> = std::iter::once(Ident::new("Variables", Span::call_site()))
+        .chain(variables_builder)
+        .chain(std::iter::once(response_struct_name.clone()))
+        .chain(shared_type_names(&context))
+        .chain(context.cursors.newtype_idents())
+        .collect();
+
+    let cursor_definitions = context.cursors.definitions();
+
+    let tokens = quote! {
+        #shared_definitions
+
+        #cursor_definitions
+
+        #(#definitions)*
+
+        #variables_struct
+
+        #response_derives
+        #response_serde_attribute
+        pub struct #response_struct_name {
+            #(#response_data_fields,)*
+        }
+
+        #response_serde_impls
+
+        #response_debug_impl
+
+        #response_getters
+
+    };
+
+    let tokens = if options.dedupe_types() {
+        crate::dedupe::dedupe_types(tokens, &facade_items)?
+    } else {
+        tokens
+    };
+
+    Ok((
+        tokens,
+        facade_items,
+        operation.has_variables_lifetime(&context),
+    ))
+}
+
+/// Generate the types for a single fragment, the fragments it spreads and the enums, input objects and
+/// scalars they use, without any operation.
+///
+/// Returns the generated items along with the names of the fragments, in the order of the query document.
+pub(crate) fn response_for_fragment<'query>(
+    schema: &schema::Schema<'_>,
+    query: &'query query::Document,
+    fragment_name: &str,
+    options: &crate::GraphQLClientCodegenOptions,
+) -> Result<(TokenStream, Vec<&'query str>), failure::Error> {
+    let context = query_context(schema, query, options)?;
+
+    match context.fragments.get(fragment_name).map(|fragment| &fragment.on) {
+        Some(crate::fragments::FragmentTarget::Union(_)) => {
+            return Err(format_err!(
+                "The fragment `{}` is defined on a union, it can only be generated as part of an operation.",
+                fragment_name
+            ))
+        }
+        Some(_) => context.require_fragment(fragment_name),
+        None => {
+            return Err(format_err!(
+                "The fragment `{}` is not defined in the query document. Defined fragments: {}",
+                fragment_name,
+                context.fragments.keys().cloned().collect::<Vec<_>>().join(", ")
+            ))
+        }
+    }
+
+    let shared_definitions = shared_definitions(&context)?;
+    let required_fragments = query
+        .definitions
+        .iter()
+        .filter_map(|definition| match definition {
+            query::Definition::Fragment(fragment) => Some(fragment.name.as_str()),
+            query::Definition::Operation(_) => None,
+        })
+        .filter(|name| {
+            context
+                .fragments
+                .get(name)
+                .map(|fragment| fragment.is_required.get())
+                .unwrap_or(false)
+        })
+        .collect();
+
+    Ok((shared_definitions, required_fragments))
+}
+
+/// The context for generating code for an operation or a fragment of the query document.
+fn query_context<'query, 'schema>(
+    schema: &'schema schema::Schema<'schema>,
+    query: &'query query::Document,
+    options: &crate::GraphQLClientCodegenOptions,
+) -> Result<QueryContext<'query, 'schema>, failure::Error> {
+    crate::rust_with::validate(query)?;
+
     let mut context = QueryContext::new(
         schema,
         options.deprecation_strategy(),
         options.normalization(),
-        options.serde_crate().map(|c| c.clone()),
+        options.serde_crate().cloned(),
+    );
+
+    context.rename_all = options.rename_all();
+    context.null_coercion = options.null_coercion();
+    context.facade = options.facade();
+    context.compact_debug = options.compact_debug();
+    context.private_fields_with_getters = options.private_fields_with_getters();
+    context.union_member_traits = options.union_member_traits();
+    context.untagged_unions = options.untagged_unions();
+    context.graphql_error_names = options.graphql_error_names();
+    context.enum_descriptions = options.enum_descriptions();
+    context.exhaustive_enums = options.exhaustive_enums();
+    context.enum_other_variant = options.enum_other_variant_ident()?;
+    context.enum_tests_schema = options
+        .enum_tests_schema()
+        .map(|path| path.to_string_lossy().into_owned());
+    context.skip_serializing_none = options.skip_serializing_none();
+    context.variables_lifetime = options.variables_lifetime();
+    context.date_times = crate::date_times::DateTimeScalars::new(
+        options.date_time_crate(),
+        options.date_time_scalars(),
     );
 
     if let Some(derives) = options.variables_derives() {
-        context.ingest_variables_derives(&derives)?;
+        context.ingest_variables_derives(derives)?;
     }
 
     if let Some(derives) = options.response_derives() {
-        context.ingest_response_derives(&derives)?;
+        context.ingest_response_derives(derives)?;
+    }
+
+    if let Some(derives_for) = options.derives_for() {
+        context.ingest_derives_for(derives_for)?;
+    }
+
+    if let Some(redact) = options.redact() {
+        context.ingest_redact(redact)?;
     }
 
-    let mut definitions = Vec::new();
+    if let Some(skip_serde) = options.skip_serde() {
+        context.ingest_skip_serde(skip_serde)?;
+    }
 
     for definition in &query.definitions {
         match definition {
             query::Definition::Operation(_op) => (),
             query::Definition::Fragment(fragment) => {
-                let &query::TypeCondition::On(ref on) = &fragment.type_condition;
+                let query::TypeCondition::On(on) = &fragment.type_condition;
                 let on = schema.fragment_target(on).ok_or_else(|| {
-                    format_err!(
-                        "Fragment {} is defined on unknown type: {}",
-                        &fragment.name,
-                        on,
+                    crate::errors::at(
+                        fragment.position,
+                        format_err!(
+                            "Fragment {} is defined on unknown type: {}",
+                            &fragment.name,
+                            on,
+                        ),
                     )
                 })?;
                 context.fragments.insert(
@@ -83,65 +356,42 @@ pub(crate) fn response_for_query(
         }
     }
 
-    let response_data_fields = {
-        let root_name = operation.root_name(&context.schema);
-        let opt_definition = context.schema.objects.get(&root_name);
-        let definition = if let Some(definition) = opt_definition {
-            definition
-        } else {
-            panic!(
-                "operation type '{:?}' not in schema",
-                operation.operation_type
-            );
-        };
-        let prefix = &operation.name;
-        let selection = &operation.selection;
-
-        if operation.is_subscription() && selection.len() > 1 {
-            return Err(format_err!(
-                "{}",
-                crate::constants::MULTIPLE_SUBSCRIPTION_FIELDS_ERROR
-            ));
-        }
-
-        definitions.extend(definition.field_impls_for_selection(&context, &selection, &prefix)?);
-        definition.response_fields_for_selection(&context, &selection, &prefix)?
-    };
+    Ok(context)
+}
 
-    let enum_definitions = context.schema.enums.values().filter_map(|enm| {
-        if enm.is_required.get() {
-            Some(enm.to_rust(&context))
-        } else {
-            None
-        }
-    });
-    let fragment_definitions: Result<Vec<TokenStream>, _> = context
-        .fragments
-        .values()
-        .filter_map(|fragment| {
+/// The built-in scalar aliases, and the custom scalars, input objects, enums and fragments that were required.
+fn shared_definitions(context: &QueryContext<'_, '_>) -> Result<TokenStream, failure::Error> {
+    let fragment_definitions =
+        crate::errors::collect(context.fragments.values().filter_map(|fragment| {
             if fragment.is_required.get() {
-                Some(fragment.to_rust(&context))
+                Some(fragment.to_rust(context))
             } else {
                 None
             }
-        })
-        .collect();
-    let fragment_definitions = fragment_definitions?;
-    let variables_struct = operation.expand_variables(&context);
+        }));
 
-    let input_object_definitions: Result<Vec<TokenStream>, _> = context
-        .schema
-        .inputs
-        .values()
-        .filter_map(|i| {
+    let input_object_definitions =
+        crate::errors::collect(context.schema.inputs.values().filter_map(|i| {
             if i.is_required.get() {
-                Some(i.to_rust(&context))
+                Some(i.to_rust(context))
             } else {
                 None
             }
-        })
-        .collect();
-    let input_object_definitions = input_object_definitions?;
+        }));
+
+    // The fragments and input objects above require the enums of their fields.
+    let enum_definitions =
+        crate::errors::collect(context.schema.enums.values().filter_map(|enm| {
+            if enm.is_required.get() {
+                Some(enm.to_rust(context))
+            } else {
+                None
+            }
+        }));
+    let ((fragment_definitions, input_object_definitions), enum_definitions) = crate::errors::join(
+        crate::errors::join(fragment_definitions, input_object_definitions),
+        enum_definitions,
+    )?;
 
     let scalar_definitions: Vec<TokenStream> = context
         .schema
@@ -149,15 +399,13 @@ pub(crate) fn response_for_query(
         .values()
         .filter_map(|s| {
             if s.is_required.get() {
-                Some(s.to_rust(context.normalization))
+                Some(s.to_rust(context))
             } else {
                 None
             }
         })
         .collect();
 
-    let response_derives = context.response_derives();
-
     Ok(quote! {
         use serde::{Serialize, Deserialize};
 
@@ -177,16 +425,30 @@ pub(crate) fn response_for_query(
         #(#enum_definitions)*
 
         #(#fragment_definitions)*
-
-        #(#definitions)*
-
-        #variables_struct
-
-        #response_derives
-
-        pub struct ResponseData {
-            #(#response_data_fields,)*
-        }
-
     })
 }
+
+/// The names of the required enums, input objects and fragments.
+fn shared_type_names<'a>(context: &'a QueryContext<'_, '_>) -> impl Iterator<Item = Ident> + 'a {
+    context
+        .schema
+        .enums
+        .values()
+        .filter(|enm| enm.is_required.get())
+        .map(move |enm| enm.name_ident(context.normalization))
+        .chain(
+            context
+                .schema
+                .inputs
+                .values()
+                .filter(|input| input.is_required.get())
+                .map(move |input| input.name_ident(context.normalization)),
+        )
+        .chain(
+            context
+                .fragments
+                .values()
+                .filter(|fragment| fragment.is_required.get())
+                .map(|fragment| Ident::new(fragment.name, Span::call_site())),
+        )
+}
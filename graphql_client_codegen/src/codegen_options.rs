@@ -1,11 +1,21 @@
+use crate::date_times::DateTimeCrate;
 use crate::deprecation::DeprecationStrategy;
-use crate::normalization::Normalization;
-use proc_macro2::Ident;
+use crate::normalization::{Normalization, RenameAll};
+use heck::SnakeCase;
+use proc_macro2::{Ident, Span, TokenStream};
+use std::borrow::Cow;
+use std::collections::BTreeMap;
 use std::path::{Path, PathBuf};
 use syn::Visibility;
 
+/// The default name of the generated struct for the response data.
+const RESPONSE_STRUCT_NAME: &str = "ResponseData";
+
+/// The marker of generated files, recognized by code review tools.
+const GENERATED_MARKER: &str = "@generated";
+
 /// Which context is this code generation effort taking place.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum CodegenMode {
     /// The graphql-client CLI.
     Cli,
@@ -13,7 +23,22 @@ pub enum CodegenMode {
     Derive,
 }
 
+/// Options overriding the global ones for a single operation of the query document.
+#[derive(Debug, Clone, Default, PartialEq, serde::Deserialize, serde::Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct OperationOptions {
+    /// Comma-separated list of additional traits to derive for variables, in place of the global
+    /// ones.
+    pub variables_derives: Option<String>,
+    /// Comma-separated list of additional traits to derive for responses, in place of the global
+    /// ones.
+    pub response_derives: Option<String>,
+    /// Additional traits to derive for specific types, in place of the global ones.
+    pub derives_for: Option<String>,
+}
+
 /// Used to configure code generation.
+#[derive(Clone)]
 pub struct GraphQLClientCodegenOptions {
     /// Which context is this code generation effort taking place.
     pub mode: CodegenMode,
@@ -27,6 +52,12 @@ pub struct GraphQLClientCodegenOptions {
     variables_derives: Option<String>,
     /// Comma-separated list of additional traits we want to derive for responses.
     response_derives: Option<String>,
+    /// Additional traits to derive for specific types.
+    derives_for: Option<String>,
+    /// The fields printed as `***` by the `Debug` impls of specific types.
+    redact: Option<String>,
+    /// The fields of specific types left out of serialization and deserialization.
+    skip_serde: Option<String>,
     /// The deprecation strategy to adopt.
     deprecation_strategy: Option<DeprecationStrategy>,
     /// Target module visibility.
@@ -34,14 +65,88 @@ pub struct GraphQLClientCodegenOptions {
     /// A path to a file to include in the module to force Cargo to take into account changes in
     /// the query files when recompiling.
     query_file: Option<PathBuf>,
+    /// Query files defining fragments that the query file can spread.
+    included_query_files: Vec<PathBuf>,
+    /// Send the included query files to the server along with the query file.
+    query_with_included_files: bool,
     /// A path to a file to include in the module to force Cargo to take into account changes in
     /// the schema files when recompiling.
     schema_file: Option<PathBuf>,
     /// Normalization pattern for query types and names.
     normalization: Normalization,
+    /// The casing of the response fields and enum variants, overriding the one of the
+    /// normalization.
+    rename_all: Option<RenameAll>,
     /// Path to the serde we use for derive impls.
     /// It is equivallent to the like the #[serde(crate = "...")] attribute
     serde_crate: Option<syn::Path>,
+    /// The type of the `extensions` of the errors in the responses, from the parent module of the
+    /// generated module.
+    error_extensions: Option<syn::Path>,
+    /// Deserialize `null` as the default value for nullable list and string fields.
+    null_coercion: bool,
+    /// Leave the variables that are `None` out of the serialized variables.
+    skip_serializing_none: bool,
+    /// Borrow the strings and lists of the `Variables` struct for a lifetime.
+    variables_lifetime: bool,
+    /// Generate the subscriptions that select several root fields, with optional root fields.
+    allow_multiple_subscription_fields: bool,
+    /// Leave the checks of the options against all the generated code to the caller.
+    defer_option_checks: bool,
+    /// Generate a stable `api` module re-exporting the public types of the operation.
+    facade: bool,
+    /// Name of the generated struct for the response data. Defaults to `ResponseData`.
+    response_struct_name: Option<String>,
+    /// Name of the generated module. Defaults to the operation (or fragment) name in snake case.
+    module_name: Option<String>,
+    /// The maximum number of list items shown by the `Debug` impls of response structs, if they
+    /// should be compact.
+    compact_debug: Option<usize>,
+    /// Keep the `@idempotent` operation directive in the query sent to the server.
+    keep_idempotent_directive: bool,
+    /// Make the fields of response structs private, with getters.
+    private_fields_with_getters: bool,
+    /// Merge the structurally identical selection types.
+    dedupe_types: bool,
+    /// Generate the types for this fragment only, instead of the operations.
+    fragment: Option<String>,
+    /// Generate a `SELECTED_FIELDS` constant with the paths of the selected fields.
+    emit_selected_fields: bool,
+    /// Generate a sealed trait implemented by the variant structs of each union.
+    union_member_traits: bool,
+    /// Generate a newtype for the cursors of each connection.
+    cursor_newtypes: bool,
+    /// The crate providing the types of the date and time scalars.
+    date_time_crate: DateTimeCrate,
+    /// Comma-separated list of the date and time scalars.
+    date_time_scalars: Option<String>,
+    /// Generate the union and interface enums with `#[serde(untagged)]` instead of tagging them
+    /// with `__typename`.
+    untagged_unions: bool,
+    /// Name the GraphQL field and type in the errors about missing non-null fields of responses.
+    graphql_error_names: bool,
+    /// Generate a `description` method returning the schema description of each enum variant.
+    enum_descriptions: bool,
+    /// Leave `#[non_exhaustive]` out of the generated enums.
+    exhaustive_enums: bool,
+    /// Name of the enum variant for the values unknown to the schema. Defaults to `Other`.
+    enum_other_variant: Option<String>,
+    /// The schema file the generated enums are tested against.
+    enum_tests_schema: Option<PathBuf>,
+    /// Comma-separated list of the `Type.field` schema coordinates that must not be selected.
+    forbidden_fields: Option<String>,
+    /// Comma-separated list of the only types whose fields can be selected.
+    allowed_types: Option<String>,
+    /// The options overriding the global ones, by operation name.
+    operation_options: BTreeMap<String, OperationOptions>,
+    /// Embed the query with `include_bytes!` instead of a string literal.
+    query_as_bytes: bool,
+    /// A transform applied to the code generated for each operation or fragment.
+    post_process: Option<fn(TokenStream) -> TokenStream>,
+    /// Expand self-recursive fragments this many levels deep instead of boxing them.
+    expand_recursive_fragments: Option<usize>,
+    /// A license or "do not edit" header for the generated code.
+    file_header: Option<String>,
 }
 
 impl GraphQLClientCodegenOptions {
@@ -51,15 +156,53 @@ impl GraphQLClientCodegenOptions {
             mode,
             variables_derives: Default::default(),
             response_derives: Default::default(),
+            derives_for: Default::default(),
+            redact: Default::default(),
+            skip_serde: Default::default(),
             deprecation_strategy: Default::default(),
             module_visibility: Default::default(),
             operation_name: Default::default(),
             struct_ident: Default::default(),
             struct_name: Default::default(),
             query_file: Default::default(),
+            included_query_files: Default::default(),
+            query_with_included_files: false,
             schema_file: Default::default(),
             normalization: Normalization::None,
+            rename_all: Default::default(),
             serde_crate: Default::default(),
+            error_extensions: Default::default(),
+            null_coercion: false,
+            skip_serializing_none: false,
+            variables_lifetime: false,
+            allow_multiple_subscription_fields: false,
+            defer_option_checks: false,
+            facade: false,
+            response_struct_name: Default::default(),
+            module_name: Default::default(),
+            compact_debug: Default::default(),
+            keep_idempotent_directive: false,
+            private_fields_with_getters: false,
+            dedupe_types: false,
+            fragment: Default::default(),
+            emit_selected_fields: false,
+            union_member_traits: false,
+            cursor_newtypes: false,
+            date_time_crate: DateTimeCrate::Off,
+            date_time_scalars: Default::default(),
+            untagged_unions: false,
+            graphql_error_names: false,
+            enum_descriptions: false,
+            exhaustive_enums: true,
+            enum_other_variant: Default::default(),
+            enum_tests_schema: Default::default(),
+            forbidden_fields: Default::default(),
+            allowed_types: Default::default(),
+            operation_options: Default::default(),
+            query_as_bytes: false,
+            post_process: Default::default(),
+            expand_recursive_fragments: Default::default(),
+            file_header: Default::default(),
         }
     }
 
@@ -101,6 +244,42 @@ impl GraphQLClientCodegenOptions {
         self.response_derives = Some(response_derives);
     }
 
+    /// Additional traits to derive for specific generated types only, on top of the variables and
+    /// response derives, in the `TypeName: Derive, Derive; OtherTypeName: Derive` format
+    /// (`ResponseData: Clone, PartialEq`).
+    pub fn set_derives_for(&mut self, derives_for: String) {
+        self.derives_for = Some(derives_for);
+    }
+
+    /// Additional traits to derive for specific generated types.
+    pub fn derives_for(&self) -> Option<&str> {
+        self.derives_for.as_deref()
+    }
+
+    /// Fields of specific generated types printed as `***` by their `Debug` impl, in the
+    /// `TypeName: field, field; OtherTypeName: field` format (`Variables: password, token`), with
+    /// the field names of the generated structs. `Debug` is then implemented instead of derived.
+    pub fn set_redact(&mut self, redact: String) {
+        self.redact = Some(redact);
+    }
+
+    /// The fields printed as `***` by the `Debug` impls of specific generated types.
+    pub fn redact(&self) -> Option<&str> {
+        self.redact.as_deref()
+    }
+
+    /// Fields of specific generated response types that are never (de)serialized, for data the
+    /// client stores in them, in the `TypeName: field, field; OtherTypeName: field` format
+    /// (`HeroHero: display_name`). They get `#[serde(skip)]`, so their type must be `Default`.
+    pub fn set_skip_serde(&mut self, skip_serde: String) {
+        self.skip_serde = Some(skip_serde);
+    }
+
+    /// The fields of specific generated types left out of serialization and deserialization.
+    pub fn skip_serde(&self) -> Option<&str> {
+        self.skip_serde.as_deref()
+    }
+
     /// The deprecation strategy to adopt.
     pub fn set_deprecation_strategy(&mut self, deprecation_strategy: DeprecationStrategy) {
         self.deprecation_strategy = Some(deprecation_strategy);
@@ -134,6 +313,31 @@ impl GraphQLClientCodegenOptions {
         self.query_file.as_deref()
     }
 
+    /// Query files defining fragments that the query file can spread, parsed along with it as a
+    /// single query document. By default, the query sent to the server is the query file followed
+    /// by the fragments of the included query files it spreads, see
+    /// `set_query_with_included_files`.
+    pub fn set_included_query_files(&mut self, paths: Vec<PathBuf>) {
+        self.included_query_files = paths;
+    }
+
+    /// The query files defining fragments that the query file can spread.
+    pub fn included_query_files(&self) -> &[PathBuf] {
+        &self.included_query_files
+    }
+
+    /// Send the included query files to the server, followed by the query file, instead of the
+    /// query file and the included fragments it spreads. The `QUERY` constant of the generated
+    /// modules is then the concatenation of the files.
+    pub fn set_query_with_included_files(&mut self, query_with_included_files: bool) {
+        self.query_with_included_files = query_with_included_files;
+    }
+
+    /// Whether the included query files are sent to the server along with the query file.
+    pub fn query_with_included_files(&self) -> bool {
+        self.query_with_included_files
+    }
+
     /// The identifier to use when referring to the struct implementing GraphQLQuery, if any.
     pub fn set_struct_ident(&mut self, ident: Ident) {
         self.struct_ident = Some(ident);
@@ -154,6 +358,20 @@ impl GraphQLClientCodegenOptions {
         self.normalization
     }
 
+    /// Set the casing of the generated response fields and enum variants, overriding the one of the
+    /// normalization. `RenameAll::None` keeps the names of the schema as they are. The fields whose
+    /// Rust name differs from the GraphQL name get a serde rename. The input objects and variables
+    /// keep the casing of the normalization.
+    pub fn set_rename_all(&mut self, rename_all: RenameAll) {
+        self.rename_all = Some(rename_all);
+    }
+
+    /// The casing of the response fields and enum variants, if it overrides the one of the
+    /// normalization.
+    pub fn rename_all(&self) -> Option<RenameAll> {
+        self.rename_all
+    }
+
     /// Set the path to the serde we use for derive impls.
     pub fn set_serde_crate(&mut self, serde_crate: syn::Path) {
         self.serde_crate = serde_crate.into();
@@ -163,4 +381,473 @@ impl GraphQLClientCodegenOptions {
     pub fn serde_crate(&self) -> Option<&syn::Path> {
         self.serde_crate.as_ref()
     }
+
+    /// The type of the `extensions` of the errors returned by the server, aliased as `Extensions`
+    /// in the generated modules, along with a `Response` alias for the responses. Like custom
+    /// scalars, the path is resolved from the parent module of the generated module, unless it
+    /// starts with `crate` or `::`.
+    pub fn set_error_extensions(&mut self, error_extensions: syn::Path) {
+        self.error_extensions = Some(error_extensions);
+    }
+
+    /// The type of the `extensions` of the errors returned by the server.
+    pub fn error_extensions(&self) -> Option<&syn::Path> {
+        self.error_extensions.as_ref()
+    }
+
+    /// Deserialize `null` as the default value (empty vec, empty string) for nullable list and
+    /// string fields in responses, instead of wrapping them in an `Option`.
+    pub fn set_null_coercion(&mut self, null_coercion: bool) {
+        self.null_coercion = null_coercion;
+    }
+
+    /// Whether `null` is deserialized as the default value for nullable list and string fields.
+    pub fn null_coercion(&self) -> bool {
+        self.null_coercion
+    }
+
+    /// Leave the optional variables that are `None` out of the serialized variables instead of
+    /// sending `null`, for servers that reject `null` for optional arguments. Off by default, since
+    /// servers may treat a `null` argument differently from an absent one.
+    pub fn set_skip_serializing_none(&mut self, skip_serializing_none: bool) {
+        self.skip_serializing_none = skip_serializing_none;
+    }
+
+    /// Whether the variables that are `None` are left out of the serialized variables.
+    pub fn skip_serializing_none(&self) -> bool {
+        self.skip_serializing_none
+    }
+
+    /// Generate `Variables<'a>`, borrowing the `String` and `ID` variables as `&'a str` and the
+    /// lists as `Cow<'a, [T]>`. Use the `build_query` function of the operation struct to send
+    /// borrowed variables. The input objects stay owned, including their strings, and derive
+    /// `Clone` when they are in a list; the custom scalars in lists must be `Clone`.
+    pub fn set_variables_lifetime(&mut self, variables_lifetime: bool) {
+        self.variables_lifetime = variables_lifetime;
+    }
+
+    /// Whether the strings and lists of the `Variables` struct are borrowed.
+    pub fn variables_lifetime(&self) -> bool {
+        self.variables_lifetime
+    }
+
+    /// Generate the subscriptions that select several root fields, which the spec forbids but some
+    /// servers accept, instead of failing. The root fields of their response data are optional.
+    pub fn set_allow_multiple_subscription_fields(
+        &mut self,
+        allow_multiple_subscription_fields: bool,
+    ) {
+        self.allow_multiple_subscription_fields = allow_multiple_subscription_fields;
+    }
+
+    /// Whether the subscriptions that select several root fields are generated.
+    pub fn allow_multiple_subscription_fields(&self) -> bool {
+        self.allow_multiple_subscription_fields
+    }
+
+    /// Leave the checks of the options against all the generated code to the caller, for the
+    /// callers generating several query files with the same options. The unapplied type names of
+    /// `derives_for`, `redact` and `skip_serde` are returned in
+    /// `GeneratedCode::unapplied_type_options`, and the unknown operations of
+    /// `set_operation_options` are left to `warnings::unknown_operations`.
+    pub fn set_defer_option_checks(&mut self, defer_option_checks: bool) {
+        self.defer_option_checks = defer_option_checks;
+    }
+
+    /// Whether the checks of the options against all the generated code are left to the caller.
+    pub fn defer_option_checks(&self) -> bool {
+        self.defer_option_checks
+    }
+
+    /// Generate an `api` module alongside the generated items, re-exporting the operation struct,
+    /// `Variables`, `ResponseData`, enums, input objects and fragments. The nested selection
+    /// structs are hidden from the docs, since their names change whenever the query does.
+    pub fn set_facade(&mut self, facade: bool) {
+        self.facade = facade;
+    }
+
+    /// Whether an `api` facade module should be generated.
+    pub fn facade(&self) -> bool {
+        self.facade
+    }
+
+    /// Rename the generated `ResponseData` struct.
+    pub fn set_response_struct_name(&mut self, response_struct_name: String) {
+        self.response_struct_name = Some(response_struct_name);
+    }
+
+    /// The name of the generated struct for the response data.
+    pub fn response_struct_name(&self) -> &str {
+        self.response_struct_name
+            .as_deref()
+            .unwrap_or(RESPONSE_STRUCT_NAME)
+    }
+
+    /// The identifier of the generated struct for the response data, failing if the configured
+    /// name is not a valid Rust identifier.
+    pub(crate) fn response_struct_ident(&self) -> Result<Ident, failure::Error> {
+        syn::parse_str(self.response_struct_name()).map_err(|_| {
+            failure::format_err!(
+                "The response struct name `{}` is not a valid Rust identifier.",
+                self.response_struct_name()
+            )
+        })
+    }
+
+    /// Name the generated module instead of using the operation (or fragment) name in snake case,
+    /// e.g. when two derives in the same file would generate modules with the same name
+    /// (`FooQuery` and `Foo_Query`). In CLI mode, every operation of the query document would get
+    /// the same module.
+    pub fn set_module_name(&mut self, module_name: String) {
+        self.module_name = Some(module_name);
+    }
+
+    /// The name of the generated module, if it is not the operation (or fragment) name.
+    pub fn module_name(&self) -> Option<&str> {
+        self.module_name.as_deref()
+    }
+
+    /// The identifier of the module generated for the operation or fragment with this name,
+    /// failing if the configured module name is not a valid Rust identifier.
+    pub(crate) fn module_ident(&self, operation_name: &str) -> Result<Ident, failure::Error> {
+        match self.module_name() {
+            Some(module_name) => syn::parse_str(module_name).map_err(|_| {
+                failure::format_err!(
+                    "The module name `{}` is not a valid Rust identifier.",
+                    module_name
+                )
+            }),
+            None => Ok(Ident::new(
+                &operation_name.to_snake_case(),
+                Span::call_site(),
+            )),
+        }
+    }
+
+    /// Generate compact `Debug` impls for the response structs with list or string fields, instead
+    /// of deriving `Debug`: lists are truncated to their first `max_items` items, and long strings
+    /// are truncated. Only has an effect if `Debug` is part of the response derives.
+    pub fn set_compact_debug(&mut self, max_items: usize) {
+        self.compact_debug = Some(max_items);
+    }
+
+    /// The maximum number of list items shown by the `Debug` impls of response structs, if they
+    /// should be compact.
+    pub fn compact_debug(&self) -> Option<usize> {
+        self.compact_debug
+    }
+
+    /// Keep the `@idempotent` operation directive in the query sent to the server, for servers
+    /// that understand it. It is stripped by default.
+    pub fn set_keep_idempotent_directive(&mut self, keep_idempotent_directive: bool) {
+        self.keep_idempotent_directive = keep_idempotent_directive;
+    }
+
+    /// Whether the `@idempotent` operation directive is kept in the query sent to the server.
+    pub fn keep_idempotent_directive(&self) -> bool {
+        self.keep_idempotent_directive
+    }
+
+    /// Make the fields of response structs private, and generate a
+    /// `pub fn field(&self) -> &FieldType` getter for each of them.
+    pub fn set_private_fields_with_getters(&mut self, private_fields_with_getters: bool) {
+        self.private_fields_with_getters = private_fields_with_getters;
+    }
+
+    /// Whether the fields of response structs are private, with getters.
+    pub fn private_fields_with_getters(&self) -> bool {
+        self.private_fields_with_getters
+    }
+
+    /// Generate a single struct (or enum) for the selections of an operation with the same shape,
+    /// e.g. `author { login avatarUrl }` under several parents, to make the generated code of big
+    /// operations smaller. The type generated for the first occurrence is used everywhere.
+    pub fn set_dedupe_types(&mut self, dedupe_types: bool) {
+        self.dedupe_types = dedupe_types;
+    }
+
+    /// Whether structurally identical selection types are merged.
+    pub fn dedupe_types(&self) -> bool {
+        self.dedupe_types
+    }
+
+    /// Generate a module with the types of a single fragment of the query document, the fragments
+    /// it spreads and the enums, input objects and scalars they use, along with a
+    /// `FRAGMENT_SOURCE` constant. No operation is generated.
+    pub fn set_fragment(&mut self, fragment: String) {
+        self.fragment = Some(fragment);
+    }
+
+    /// The fragment to generate a standalone module for, if any.
+    pub fn fragment(&self) -> Option<&str> {
+        self.fragment.as_deref()
+    }
+
+    /// Generate a `SELECTED_FIELDS` constant listing the dotted paths of the fields selected by
+    /// the operation (`viewer.repositories.nodes.name`), with the fragment spreads expanded, for
+    /// field usage analytics.
+    pub fn set_emit_selected_fields(&mut self, emit_selected_fields: bool) {
+        self.emit_selected_fields = emit_selected_fields;
+    }
+
+    /// Whether the `SELECTED_FIELDS` constant is generated.
+    pub fn emit_selected_fields(&self) -> bool {
+        self.emit_selected_fields
+    }
+
+    /// Generate a sealed `MyUnionMember` trait for each union selection `MyUnion`, implemented by
+    /// the structs of its variants, to bound generic code (`fn handle<M: MyUnionMember>(m: M)`).
+    pub fn set_union_member_traits(&mut self, union_member_traits: bool) {
+        self.union_member_traits = union_member_traits;
+    }
+
+    /// Whether the union member traits are generated.
+    pub fn union_member_traits(&self) -> bool {
+        self.union_member_traits
+    }
+
+    /// Generate a newtype for the cursors of each Relay connection selected by an operation, named
+    /// after the struct of the connection field (`RepoViewRepositoryIssuesCursor`), so that
+    /// passing the cursor of a connection to another one does not compile. It is used for the
+    /// cursor fields of its `pageInfo` and `edges`, and the variables of its `after` and `before`
+    /// arguments. The fragments and the variables with a default value keep `String` cursors.
+    pub fn set_cursor_newtypes(&mut self, cursor_newtypes: bool) {
+        self.cursor_newtypes = cursor_newtypes;
+    }
+
+    /// Whether the cursors of connections have a newtype.
+    pub fn cursor_newtypes(&self) -> bool {
+        self.cursor_newtypes
+    }
+
+    /// Map the date and time scalars to the types of `chrono` or `time` instead of aliasing them
+    /// from the parent module. The scalars are the ones of `set_date_time_scalars`, sent by the
+    /// server as ISO 8601 strings. Defaults to `DateTimeCrate::Off`.
+    pub fn set_date_time_crate(&mut self, date_time_crate: DateTimeCrate) {
+        self.date_time_crate = date_time_crate;
+    }
+
+    /// The crate providing the types of the date and time scalars.
+    pub fn date_time_crate(&self) -> DateTimeCrate {
+        self.date_time_crate
+    }
+
+    /// Comma-separated list of the scalars mapped by `set_date_time_crate`, case-insensitive,
+    /// e.g. `DateTime, Timestamp`. Defaults to `DateTime`, `DateTimeUtc`, `Date`, `Time` and
+    /// `ISO8601DateTime`.
+    pub fn set_date_time_scalars(&mut self, date_time_scalars: String) {
+        self.date_time_scalars = Some(date_time_scalars);
+    }
+
+    /// Comma-separated list of the date and time scalars.
+    pub fn date_time_scalars(&self) -> Option<&str> {
+        self.date_time_scalars.as_deref()
+    }
+
+    /// Generate the enums of union and interface selections with `#[serde(untagged)]`, for servers
+    /// that leave `__typename` out of the responses. A response is deserialized as the first
+    /// variant it matches, so the responses matching several variants are ambiguous.
+    pub fn set_untagged_unions(&mut self, untagged_unions: bool) {
+        self.untagged_unions = untagged_unions;
+    }
+
+    /// Whether the enums of union and interface selections are untagged.
+    pub fn untagged_unions(&self) -> bool {
+        self.untagged_unions
+    }
+
+    /// Report the non-null fields missing from a response with their GraphQL name and the GraphQL
+    /// type selecting them, e.g. "missing non-null field `createdAt` on `Issue`", instead of
+    /// serde's "missing field `createdAt`". The other deserialization errors are left as they are.
+    pub fn set_graphql_error_names(&mut self, graphql_error_names: bool) {
+        self.graphql_error_names = graphql_error_names;
+    }
+
+    /// Whether the errors about missing fields name the GraphQL type.
+    pub fn graphql_error_names(&self) -> bool {
+        self.graphql_error_names
+    }
+
+    /// Generate a `description` method on each enum, returning the description of the variant in
+    /// the schema, or `None` for `Other` and the variants without one.
+    pub fn set_enum_descriptions(&mut self, enum_descriptions: bool) {
+        self.enum_descriptions = enum_descriptions;
+    }
+
+    /// Whether enums have a `description` method.
+    pub fn enum_descriptions(&self) -> bool {
+        self.enum_descriptions
+    }
+
+    /// Generate the enums with `#[non_exhaustive]` when this is `false`, so that the values added
+    /// to the schema are not breaking changes for the crates matching on them. The values unknown
+    /// to the schema are deserialized as `Other` either way. Defaults to `true`.
+    pub fn set_exhaustive_enums(&mut self, exhaustive_enums: bool) {
+        self.exhaustive_enums = exhaustive_enums;
+    }
+
+    /// Whether the generated enums are left without `#[non_exhaustive]`.
+    pub fn exhaustive_enums(&self) -> bool {
+        self.exhaustive_enums
+    }
+
+    /// Name the variant the values unknown to the schema are deserialized as, instead of `Other`,
+    /// e.g. when an enum of the schema has an `Other` value.
+    pub fn set_enum_other_variant(&mut self, enum_other_variant: String) {
+        self.enum_other_variant = Some(enum_other_variant);
+    }
+
+    /// The name of the enum variant for the values unknown to the schema.
+    pub fn enum_other_variant(&self) -> &str {
+        self.enum_other_variant.as_deref().unwrap_or("Other")
+    }
+
+    /// Generate a `#[test]` for each enum, failing when its values differ from the ones of the enum
+    /// in this schema file. The tests need the `codegen-tests` feature of `graphql_client`: the
+    /// derive sets this option when the feature is enabled.
+    pub fn set_enum_tests_schema(&mut self, schema_path: PathBuf) {
+        self.enum_tests_schema = Some(schema_path);
+    }
+
+    /// The schema file the generated enums are tested against, if they are.
+    pub fn enum_tests_schema(&self) -> Option<&Path> {
+        self.enum_tests_schema.as_deref()
+    }
+
+    /// The identifier of the enum variant for unknown values, failing if the configured name is
+    /// not a valid Rust identifier.
+    pub(crate) fn enum_other_variant_ident(&self) -> Result<Ident, failure::Error> {
+        syn::parse_str(self.enum_other_variant()).map_err(|_| {
+            failure::format_err!(
+                "The enum variant name `{}` is not a valid Rust identifier.",
+                self.enum_other_variant()
+            )
+        })
+    }
+
+    /// Comma-separated list of schema coordinates (`User.email, User.ssn`) of fields that must
+    /// never be selected. Code generation fails with a policy error when an operation or fragment
+    /// selects one of them, directly or on an interface the type implements.
+    pub fn set_forbidden_fields(&mut self, forbidden_fields: String) {
+        self.forbidden_fields = Some(forbidden_fields);
+    }
+
+    /// Comma-separated list of the fields that must never be selected.
+    pub fn forbidden_fields(&self) -> Option<&str> {
+        self.forbidden_fields.as_deref()
+    }
+
+    /// Comma-separated list of the object, interface and union types whose fields can be selected,
+    /// including the root operation types (`Query, User`). Code generation fails with a policy
+    /// error when an operation or fragment selects a field on any other type.
+    pub fn set_allowed_types(&mut self, allowed_types: String) {
+        self.allowed_types = Some(allowed_types);
+    }
+
+    /// Comma-separated list of the types whose fields can be selected.
+    pub fn allowed_types(&self) -> Option<&str> {
+        self.allowed_types.as_deref()
+    }
+
+    /// Override some of the options for the operation with this name, when generating the code for
+    /// all the operations of a query document. A warning is reported if there is no such operation.
+    pub fn set_operation_options(&mut self, operation_name: String, options: OperationOptions) {
+        self.operation_options.insert(operation_name, options);
+    }
+
+    /// Embed the query with `include_bytes!` instead of a string literal, for very big query
+    /// documents. `QUERY` is still a `&str`. The query file is included when the query is sent
+    /// as-is, otherwise a copy written to `OUT_DIR`, which needs a build script. Derive mode only.
+    pub fn set_query_as_bytes(&mut self, query_as_bytes: bool) {
+        self.query_as_bytes = query_as_bytes;
+    }
+
+    /// Whether the query is embedded with `include_bytes!`.
+    pub fn query_as_bytes(&self) -> bool {
+        self.query_as_bytes
+    }
+
+    /// Transform the code generated for each operation or fragment (its module, preceded by the
+    /// operation struct in CLI mode) before it is output, e.g. to add attributes or rename items.
+    pub fn set_post_process(&mut self, post_process: fn(TokenStream) -> TokenStream) {
+        self.post_process = Some(post_process);
+    }
+
+    /// The code generated for an operation or fragment, transformed by the post-processing hook if
+    /// one is set.
+    pub(crate) fn post_process(&self, module: TokenStream) -> TokenStream {
+        match self.post_process {
+            Some(post_process) => post_process(module),
+            None => module,
+        }
+    }
+
+    /// Expand each self-recursive fragment this many levels deep (`Fragment`, `FragmentLevel2`...)
+    /// instead of boxing it, in the generated types and in the query. The deepest level leaves out
+    /// the recursive field, so the data nested deeper is not requested.
+    pub fn set_expand_recursive_fragments(&mut self, levels: usize) {
+        self.expand_recursive_fragments = Some(levels);
+    }
+
+    /// The number of levels self-recursive fragments are expanded to, if they are not boxed.
+    pub fn expand_recursive_fragments(&self) -> Option<usize> {
+        self.expand_recursive_fragments
+    }
+
+    /// A license or "do not edit" header for the generated code: a comment block at the top of the
+    /// files written by the CLI, and the doc comment of the modules generated by the derive. It
+    /// ends with the `@generated` marker, unless it already has it.
+    pub fn set_file_header(&mut self, file_header: String) {
+        self.file_header = Some(file_header);
+    }
+
+    /// The lines of the header of the generated code, ending with the `@generated` marker, if a
+    /// header is set.
+    pub fn file_header_lines(&self) -> Option<Vec<&str>> {
+        self.file_header.as_deref().map(|header| {
+            let mut lines: Vec<&str> = header.lines().collect();
+            if !header.contains(GENERATED_MARKER) {
+                lines.push(GENERATED_MARKER);
+            }
+            lines
+        })
+    }
+
+    /// The options overriding the global ones, by operation name.
+    pub fn operation_options(&self) -> &BTreeMap<String, OperationOptions> {
+        &self.operation_options
+    }
+
+    /// The options for the operation with this name: the global options, with the operation's
+    /// overrides merged over them.
+    pub(crate) fn for_operation(&self, operation_name: &str) -> Cow<'_, Self> {
+        match self.operation_options.get(operation_name) {
+            Some(overrides) => {
+                let mut options = self.clone();
+                options.merge(overrides);
+                Cow::Owned(options)
+            }
+            None => Cow::Borrowed(self),
+        }
+    }
+
+    fn merge(&mut self, overrides: &OperationOptions) {
+        let OperationOptions {
+            variables_derives,
+            response_derives,
+            derives_for,
+        } = overrides;
+
+        if let Some(variables_derives) = variables_derives {
+            self.variables_derives = Some(variables_derives.clone());
+        }
+
+        if let Some(response_derives) = response_derives {
+            self.response_derives = Some(response_derives.clone());
+        }
+
+        if let Some(derives_for) = derives_for {
+            self.derives_for = Some(derives_for.clone());
+        }
+    }
 }
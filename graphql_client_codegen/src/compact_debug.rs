@@ -0,0 +1,214 @@
+use crate::query::QueryContext;
+use failure::format_err;
+use proc_macro2::{Ident, TokenStream};
+use quote::quote;
+use syn::parse::Parser;
+
+/// The derives and, when the `compact_debug` or `redact` options apply to it, the manual `Debug` impl for a
+/// response struct.
+///
+/// With `compact_debug`, only the structs with list or string fields get a manual impl. The other ones keep the
+/// derived `Debug`.
+pub(crate) fn response_struct_derives(
+    context: &QueryContext<'_, '_>,
+    name: &Ident,
+    fields: &[TokenStream],
+) -> Result<(TokenStream, Option<TokenStream>), failure::Error> {
+    let struct_name = name.to_string();
+    let redacted_fields = context.redacted_fields(&struct_name);
+
+    let max_items = context
+        .compact_debug
+        .filter(|_| context.derives_response_debug());
+
+    if max_items.is_none() && redacted_fields.is_empty() {
+        return Ok((context.response_derives(&struct_name), None));
+    }
+
+    let fields = parse_fields(fields)?;
+    check_redacted_fields(&struct_name, &fields, redacted_fields)?;
+
+    let compacts = max_items.is_some() && fields.iter().any(|field| is_compacted(&field.ty));
+    let redacts = !redacted_fields.is_empty() && context.derives_response_debug_for(&struct_name);
+
+    if !compacts && !redacts {
+        return Ok((context.response_derives(&struct_name), None));
+    }
+
+    let debug_impl = debug_impl(name, &TokenStream::new(), &fields, |ident, ty| {
+        if redacted_fields.iter().any(|field| ident == field) {
+            return quote!(&format_args!("***"));
+        }
+
+        match max_items {
+            Some(max_items) if is_compacted(ty) => {
+                quote!(&graphql_client::compact_debug::Compact(&self.#ident, #max_items))
+            }
+            _ => quote!(&self.#ident),
+        }
+    });
+
+    Ok((
+        context.response_derives_without_debug(&struct_name),
+        Some(debug_impl),
+    ))
+}
+
+/// The derives and, when some of its fields are redacted, the manual `Debug` impl for the `Variables` struct.
+pub(crate) fn variables_struct_derives(
+    context: &QueryContext<'_, '_>,
+    name: &Ident,
+    generics: &TokenStream,
+    fields: &[TokenStream],
+) -> Result<(TokenStream, Option<TokenStream>), failure::Error> {
+    let struct_name = name.to_string();
+    let redacted_fields = context.redacted_fields(&struct_name);
+
+    if redacted_fields.is_empty() {
+        return Ok((context.variables_derives(&struct_name), None));
+    }
+
+    let fields = parse_fields(fields)?;
+    check_redacted_fields(&struct_name, &fields, redacted_fields)?;
+
+    if !context.derives_variables_debug_for(&struct_name) {
+        return Ok((context.variables_derives(&struct_name), None));
+    }
+
+    let debug_impl = debug_impl(name, generics, &fields, |ident, _| {
+        if redacted_fields.iter().any(|field| ident == field) {
+            quote!(&format_args!("***"))
+        } else {
+            quote!(&self.#ident)
+        }
+    });
+
+    Ok((
+        context.variables_derives_without_debug(&struct_name),
+        Some(debug_impl),
+    ))
+}
+
+fn parse_fields(fields: &[TokenStream]) -> Result<Vec<syn::Field>, failure::Error> {
+    Ok(fields
+        .iter()
+        .map(|field| syn::Field::parse_named.parse2(field.clone()))
+        .collect::<Result<Vec<_>, _>>()?)
+}
+
+/// Redacting a field the struct does not have is an error, since the field that should have been redacted
+/// would be printed.
+fn check_redacted_fields(
+    struct_name: &str,
+    fields: &[syn::Field],
+    redacted_fields: &[String],
+) -> Result<(), failure::Error> {
+    for redacted_field in redacted_fields {
+        let exists = fields
+            .iter()
+            .filter_map(|field| field.ident.as_ref())
+            .any(|ident| ident == redacted_field);
+
+        if !exists {
+            return Err(format_err!(
+                "The redacted field `{}` is not a field of `{}`.",
+                redacted_field,
+                struct_name
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+/// A `Debug` impl printing the struct like the derived one, with the values given by `field_value`.
+fn debug_impl(
+    name: &Ident,
+    generics: &TokenStream,
+    fields: &[syn::Field],
+    field_value: impl Fn(&Ident, &syn::Type) -> TokenStream,
+) -> TokenStream {
+    let debug_fields = fields.iter().map(|field| {
+        let ident = field.ident.as_ref().expect("struct fields are named");
+        let name = ident.to_string();
+        let value = field_value(ident, &field.ty);
+
+        quote!(.field(#name, #value))
+    });
+    let struct_name = name.to_string();
+
+    quote! {
+        impl #generics std::fmt::Debug for #name #generics {
+            #[allow(deprecated)]
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.debug_struct(#struct_name)
+                    #(#debug_fields)*
+                    .finish()
+            }
+        }
+    }
+}
+
+/// Whether the field is a list or a string, optional or not. Those are the fields that get truncated.
+fn is_compacted(ty: &syn::Type) -> bool {
+    let segment = match ty {
+        syn::Type::Path(path) => path.path.segments.last(),
+        _ => None,
+    };
+
+    match segment {
+        Some(segment) if segment.ident == "Vec" || segment.ident == "String" => true,
+        Some(segment) if segment.ident == "Option" => match &segment.arguments {
+            syn::PathArguments::AngleBracketed(arguments) => {
+                arguments.args.iter().any(|argument| match argument {
+                    syn::GenericArgument::Type(ty) => is_compacted(ty),
+                    _ => false,
+                })
+            }
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lists_and_strings_are_compacted() {
+        let compacted = |ty: &str| is_compacted(&syn::parse_str(ty).unwrap());
+
+        assert!(compacted("Vec<Option<Dog>>"));
+        assert!(compacted("Option<Vec<Dog>>"));
+        assert!(compacted("String"));
+        assert!(compacted("Option<String>"));
+        assert!(!compacted("Option<Int>"));
+        assert!(!compacted("Box<Dog>"));
+        assert!(!compacted("ID"));
+    }
+
+    #[test]
+    fn small_structs_keep_derived_debug() {
+        let schema = crate::schema::Schema::new();
+        let mut context = QueryContext::new_empty(&schema);
+        context.ingest_response_derives("Debug").unwrap();
+        context.compact_debug = Some(3);
+
+        let name = Ident::new("Dog", proc_macro2::Span::call_site());
+        let (derives, debug_impl) =
+            response_struct_derives(&context, &name, &[quote!(pub age: Option<Int>)]).unwrap();
+
+        assert_eq!(derives.to_string(), "# [derive (Debug , Deserialize)]");
+        assert!(debug_impl.is_none());
+
+        let (derives, debug_impl) =
+            response_struct_derives(&context, &name, &[quote!(pub names: Vec<String>)]).unwrap();
+
+        assert_eq!(derives.to_string(), "# [derive (Deserialize)]");
+        assert!(debug_impl
+            .unwrap()
+            .to_string()
+            .contains("graphql_client :: compact_debug :: Compact (& self . names , 3usize)"));
+    }
+}
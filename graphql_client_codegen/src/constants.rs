@@ -17,10 +17,11 @@ pub(crate) fn typename_field() -> GqlObjectField<'static> {
     GqlObjectField {
         description: None,
         name: TYPENAME_FIELD,
-        /// Non-nullable, see spec:
-        /// https://github.com/facebook/graphql/blob/master/spec/Section%204%20--%20Introspection.md
+        // Non-nullable, see spec:
+        // https://github.com/facebook/graphql/blob/master/spec/Section%204%20--%20Introspection.md
         type_: FieldType::new(string_type()),
         deprecation: DeprecationStatus::Current,
+        arguments: Vec::new(),
     }
 }
 
@@ -30,9 +31,9 @@ Multiple-field queries on the root subscription field are forbidden by the spec.
 See: https://github.com/facebook/graphql/blob/master/spec/Section%205%20--%20Validation.md#subscription-operation-definitions
 "##;
 
-/// Error message when a selection set is the root of a query.
+/// Error message when a selection set is the root of a query with other operations.
 pub(crate) const SELECTION_SET_AT_ROOT: &str = r#"
-Operations in queries must be named.
+Operations in query documents with several operations must be named.
 
 Instead of this:
 
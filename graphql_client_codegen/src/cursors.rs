@@ -0,0 +1,246 @@
+//! The `cursor_newtypes` option: a newtype for the cursors of each Relay connection selected by an
+//! operation, so that passing the cursor of a connection to another one does not compile.
+
+use crate::field_type::FieldType;
+use crate::query::QueryContext;
+use crate::selection::{Selection, SelectionItem};
+use crate::variables::Variable;
+use failure::format_err;
+use graphql_parser::query::Value;
+use proc_macro2::{Ident, Span, TokenStream};
+use quote::quote;
+use std::collections::BTreeMap;
+
+/// The field of connection types with the pagination cursors.
+const PAGE_INFO_FIELD: &str = "pageInfo";
+/// The cursor fields of the page info.
+const PAGE_INFO_CURSOR_FIELDS: &[&str] = &["startCursor", "endCursor"];
+/// The field of connection types with the edges.
+const EDGES_FIELD: &str = "edges";
+/// The cursor field of the edges.
+const EDGE_CURSOR_FIELD: &str = "cursor";
+/// The connection arguments taking a cursor.
+const CURSOR_ARGUMENTS: &[&str] = &["after", "before"];
+
+/// The cursor newtypes of an operation. Only the cursors of type `String` get a newtype.
+#[derive(Debug, Default)]
+pub(crate) struct ConnectionCursors {
+    /// The newtype of the cursor fields, by struct name and response key of the field.
+    fields: BTreeMap<(String, String), String>,
+    /// The newtype of the variables passed to the `after` and `before` arguments of a connection.
+    variables: BTreeMap<String, String>,
+    /// The newtypes, named after the struct of the connection field:
+    /// `RepoViewRepositoryIssuesCursor` for `repository.issues` in `RepoView`, so that the
+    /// connections with the same name in different places get different newtypes.
+    newtypes: Vec<String>,
+}
+
+impl ConnectionCursors {
+    /// The cursors of the connections selected by an operation. The connections are the fields
+    /// of a type with a `pageInfo` field. The connections selected in fragments keep `String`
+    /// cursors, since the fragment structs are shared by all the connections spreading them.
+    pub(crate) fn collect(
+        context: &QueryContext<'_, '_>,
+        root_name: &str,
+        selection: &Selection<'_>,
+        prefix: &str,
+        variables: &[Variable<'_>],
+    ) -> Result<Self, failure::Error> {
+        let mut cursors = ConnectionCursors::default();
+        cursors.visit(context, root_name, selection, prefix, variables)?;
+        Ok(cursors)
+    }
+
+    fn visit(
+        &mut self,
+        context: &QueryContext<'_, '_>,
+        type_name: &str,
+        selection: &Selection<'_>,
+        prefix: &str,
+        variables: &[Variable<'_>],
+    ) -> Result<(), failure::Error> {
+        for item in selection {
+            let field = match item {
+                SelectionItem::Field(field) => field,
+                SelectionItem::FragmentSpread(_) | SelectionItem::InlineFragment(_) => continue,
+            };
+            // Unknown fields are reported when the structs are generated.
+            let field_type = match schema_field_type(context, type_name, field.name) {
+                Some(field_type) => field_type,
+                None => continue,
+            };
+            let field_prefix = selection.field_prefix(prefix, field);
+            let type_name = field_type.inner_name_str();
+
+            if schema_field_type(context, type_name, PAGE_INFO_FIELD).is_some() {
+                let newtype = format!("{}Cursor", field_prefix);
+
+                for (argument, value) in field.arguments {
+                    match value {
+                        Value::Variable(variable)
+                            if CURSOR_ARGUMENTS.contains(&argument.as_str()) =>
+                        {
+                            self.add_variable(variable, &newtype, variables)?
+                        }
+                        _ => (),
+                    }
+                }
+
+                self.add_fields(context, type_name, &field.fields, &field_prefix, &newtype);
+
+                if !self.newtypes.contains(&newtype) {
+                    self.newtypes.push(newtype);
+                }
+            }
+
+            self.visit(context, type_name, &field.fields, &field_prefix, variables)?;
+        }
+
+        Ok(())
+    }
+
+    /// Register the cursor fields of the page info and of the edges of a connection.
+    fn add_fields(
+        &mut self,
+        context: &QueryContext<'_, '_>,
+        connection_type: &str,
+        selection: &Selection<'_>,
+        prefix: &str,
+        newtype: &str,
+    ) {
+        for item in selection {
+            let field = match item {
+                SelectionItem::Field(field) => field,
+                SelectionItem::FragmentSpread(_) | SelectionItem::InlineFragment(_) => continue,
+            };
+            let cursor_fields = match field.name {
+                PAGE_INFO_FIELD => PAGE_INFO_CURSOR_FIELDS,
+                EDGES_FIELD => &[EDGE_CURSOR_FIELD],
+                _ => continue,
+            };
+            let field_type = match schema_field_type(context, connection_type, field.name) {
+                Some(field_type) => field_type,
+                None => continue,
+            };
+            let struct_name = selection.field_prefix(prefix, field);
+
+            for item in &field.fields {
+                let cursor_field = match item {
+                    SelectionItem::Field(cursor_field)
+                        if cursor_fields.contains(&cursor_field.name) =>
+                    {
+                        cursor_field
+                    }
+                    _ => continue,
+                };
+                let cursor_type =
+                    schema_field_type(context, field_type.inner_name_str(), cursor_field.name);
+
+                if cursor_type.map(FieldType::inner_name_str)
+                    == Some(crate::constants::string_type())
+                {
+                    let key = cursor_field.alias.unwrap_or(cursor_field.name);
+                    self.fields
+                        .insert((struct_name.clone(), key.to_owned()), newtype.to_owned());
+                }
+            }
+        }
+    }
+
+    /// Register a variable passed to the `after` or `before` argument of a connection.
+    fn add_variable(
+        &mut self,
+        name: &str,
+        newtype: &str,
+        variables: &[Variable<'_>],
+    ) -> Result<(), failure::Error> {
+        // The variables with a default value keep their type, since the default is a string.
+        let is_string = variables.iter().any(|variable| {
+            variable.name == name
+                && variable.default.is_none()
+                && variable.ty.inner_name_str() == crate::constants::string_type()
+        });
+
+        if !is_string {
+            return Ok(());
+        }
+
+        match self.variables.get(name) {
+            Some(other) if other != newtype => Err(format_err!(
+                "The variable `${}` is the cursor of two connections, with the `{}` and `{}` types. Use a variable for each connection.",
+                name,
+                other,
+                newtype
+            )),
+            _ => {
+                self.variables.insert(name.to_owned(), newtype.to_owned());
+                Ok(())
+            }
+        }
+    }
+
+    /// The type of a field of a response struct, if it is a cursor.
+    pub(crate) fn field_type(
+        &self,
+        context: &QueryContext<'_, '_>,
+        struct_name: &str,
+        field: &str,
+        field_type: &FieldType<'_>,
+    ) -> Option<TokenStream> {
+        self.fields
+            .get(&(struct_name.to_owned(), field.to_owned()))
+            .map(|newtype| field_type.renamed(newtype).to_rust(context, newtype))
+    }
+
+    /// The type of a variable, if it is a cursor.
+    pub(crate) fn variable_type(
+        &self,
+        context: &QueryContext<'_, '_>,
+        variable: &Variable<'_>,
+    ) -> Option<TokenStream> {
+        self.variables
+            .get(variable.name)
+            .map(|newtype| variable.ty.renamed(newtype).to_rust(context, newtype))
+    }
+
+    /// The names of the newtypes.
+    pub(crate) fn newtype_idents(&self) -> impl Iterator<Item = Ident> + '_ {
+        self.newtypes
+            .iter()
+            .map(|newtype| Ident::new(newtype, Span::call_site()))
+    }
+
+    /// The definitions of the newtypes. They are serialized as the cursor string.
+    pub(crate) fn definitions(&self) -> TokenStream {
+        let newtypes = self.newtype_idents().map(|newtype| {
+            quote! {
+                #[derive(Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+                #[serde(transparent)]
+                pub struct #newtype(pub String);
+
+                impl #newtype {
+                    /// The cursor, as returned by the server.
+                    pub fn as_str(&self) -> &str {
+                        &self.0
+                    }
+                }
+            }
+        });
+
+        quote!(#(#newtypes)*)
+    }
+}
+
+/// The type of a field of an object or interface.
+fn schema_field_type<'schema>(
+    context: &QueryContext<'_, 'schema>,
+    type_name: &str,
+    field_name: &str,
+) -> Option<&'schema FieldType<'schema>> {
+    context
+        .schema
+        .object_or_interface_fields(type_name)?
+        .iter()
+        .find(|field| field.name == field_name)
+        .map(|field| &field.type_)
+}
@@ -0,0 +1,197 @@
+//! The `date_time_crate` option: the date and time scalars most schemas have, in ISO 8601, are
+//! mapped to the types of `chrono` or `time` instead of being aliased from the parent module.
+
+use crate::field_type::FieldType;
+use proc_macro2::TokenStream;
+use quote::quote;
+use std::collections::BTreeMap;
+
+/// The scalars mapped to date and time types by default, when a date and time crate is chosen.
+pub(crate) const DEFAULT_DATE_TIME_SCALARS: &[&str] =
+    &["DateTime", "DateTimeUtc", "Date", "Time", "ISO8601DateTime"];
+
+/// The crate providing the types of the date and time scalars.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum DateTimeCrate {
+    /// `chrono`, with its `serde` feature: date-times are `chrono::DateTime<chrono::Utc>`, dates
+    /// `chrono::NaiveDate` and times `chrono::NaiveTime`.
+    Chrono,
+    /// `time`, with the `time` feature of `graphql_client`: date-times are `time::OffsetDateTime`,
+    /// dates `time::Date` and times `time::Time`, (de)serialized by
+    /// `graphql_client::serde_helpers::iso8601`.
+    Time,
+    /// The date and time scalars are custom scalars like the others.
+    Off,
+}
+
+impl std::str::FromStr for DateTimeCrate {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, ()> {
+        match s.trim() {
+            "chrono" => Ok(DateTimeCrate::Chrono),
+            "time" => Ok(DateTimeCrate::Time),
+            "off" => Ok(DateTimeCrate::Off),
+            _ => Err(()),
+        }
+    }
+}
+
+/// What a date and time scalar holds, from its name: `Date` for the names ending with `date`,
+/// `Time` for the names ending with `time` that are not date-times, `DateTime` otherwise.
+#[derive(Debug, PartialEq, Clone, Copy)]
+enum DateTimeKind {
+    DateTime,
+    Date,
+    Time,
+}
+
+impl DateTimeKind {
+    fn from_scalar_name(name: &str) -> Self {
+        let name = name.to_lowercase();
+
+        if name.contains("datetime") {
+            DateTimeKind::DateTime
+        } else if name.ends_with("date") {
+            DateTimeKind::Date
+        } else if name.ends_with("time") {
+            DateTimeKind::Time
+        } else {
+            DateTimeKind::DateTime
+        }
+    }
+}
+
+/// The kinds of the date and time scalars, by lowercase scalar name.
+#[derive(Debug)]
+pub(crate) struct DateTimeScalars {
+    date_time_crate: DateTimeCrate,
+    kinds: BTreeMap<String, DateTimeKind>,
+}
+
+impl Default for DateTimeScalars {
+    fn default() -> Self {
+        DateTimeScalars::new(DateTimeCrate::Off, None)
+    }
+}
+
+impl DateTimeScalars {
+    /// The mapping of the scalars named in the comma-separated `scalars` list, or of the
+    /// [`DEFAULT_DATE_TIME_SCALARS`], to the types of the crate. The names are case-insensitive.
+    pub(crate) fn new(date_time_crate: DateTimeCrate, scalars: Option<&str>) -> Self {
+        let names: Vec<&str> = match scalars {
+            Some(scalars) => scalars
+                .split(',')
+                .map(str::trim)
+                .filter(|name| !name.is_empty())
+                .collect(),
+            None => DEFAULT_DATE_TIME_SCALARS.to_vec(),
+        };
+
+        let kinds = names
+            .into_iter()
+            .map(|name| (name.to_lowercase(), DateTimeKind::from_scalar_name(name)))
+            .collect();
+
+        DateTimeScalars {
+            date_time_crate,
+            kinds,
+        }
+    }
+
+    /// The Rust type of the scalar, if it is a date and time scalar. The paths are absolute, so
+    /// that the parent module needs no alias for the scalar.
+    pub(crate) fn rust_type(&self, scalar_name: &str) -> Option<TokenStream> {
+        let kind = self.kinds.get(&scalar_name.to_lowercase())?;
+
+        let ty = match (self.date_time_crate, kind) {
+            (DateTimeCrate::Chrono, DateTimeKind::DateTime) => {
+                quote!(::chrono::DateTime<::chrono::Utc>)
+            }
+            (DateTimeCrate::Chrono, DateTimeKind::Date) => quote!(::chrono::NaiveDate),
+            (DateTimeCrate::Chrono, DateTimeKind::Time) => quote!(::chrono::NaiveTime),
+            (DateTimeCrate::Time, DateTimeKind::DateTime) => quote!(::time::OffsetDateTime),
+            (DateTimeCrate::Time, DateTimeKind::Date) => quote!(::time::Date),
+            (DateTimeCrate::Time, DateTimeKind::Time) => quote!(::time::Time),
+            (DateTimeCrate::Off, _) => return None,
+        };
+
+        Some(ty)
+    }
+
+    /// The serde attribute of a field of this type. Only the `time` types need one, since they are
+    /// not (de)serialized as ISO 8601 strings by default. The helpers handle the optional values
+    /// and the lists.
+    pub(crate) fn serde_attribute(&self, ty: &FieldType<'_>) -> Option<TokenStream> {
+        if self.date_time_crate != DateTimeCrate::Time || !self.contains(ty.inner_name_str()) {
+            return None;
+        }
+
+        let with = "graphql_client::serde_helpers::iso8601";
+
+        let attribute = if ty.is_optional() {
+            quote!(#[serde(default, with = #with)])
+        } else {
+            quote!(#[serde(with = #with)])
+        };
+
+        Some(attribute)
+    }
+
+    /// Whether the scalar is mapped to a date and time type.
+    pub(crate) fn contains(&self, scalar_name: &str) -> bool {
+        self.rust_type(scalar_name).is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scalar_kinds_are_inferred_from_their_names() {
+        let scalars = DateTimeScalars::new(
+            DateTimeCrate::Chrono,
+            Some("Timestamp, birth_date, OpeningTime, ISO8601DateTime"),
+        );
+
+        let rust_type = |name| scalars.rust_type(name).map(|ty| ty.to_string());
+
+        assert_eq!(
+            rust_type("Timestamp").unwrap(),
+            quote!(::chrono::DateTime<::chrono::Utc>).to_string()
+        );
+        assert_eq!(
+            rust_type("BIRTH_DATE").unwrap(),
+            quote!(::chrono::NaiveDate).to_string()
+        );
+        assert_eq!(
+            rust_type("openingTime").unwrap(),
+            quote!(::chrono::NaiveTime).to_string()
+        );
+        assert_eq!(
+            rust_type("Iso8601DateTime").unwrap(),
+            quote!(::chrono::DateTime<::chrono::Utc>).to_string()
+        );
+        // The list replaces the default scalars.
+        assert_eq!(rust_type("DateTime"), None);
+    }
+
+    #[test]
+    fn time_maps_the_default_scalars() {
+        let scalars = DateTimeScalars::new(DateTimeCrate::Time, None);
+
+        let rust_type = |name| scalars.rust_type(name).map(|ty| ty.to_string());
+
+        assert_eq!(
+            rust_type("DateTime").unwrap(),
+            quote!(::time::OffsetDateTime).to_string()
+        );
+        assert_eq!(rust_type("date").unwrap(), quote!(::time::Date).to_string());
+        assert_eq!(rust_type("Time").unwrap(), quote!(::time::Time).to_string());
+        // The scalars left out of the list keep their alias.
+        let scalars = DateTimeScalars::new(DateTimeCrate::Time, Some("DateTime, Date"));
+        assert!(!scalars.contains("Time"));
+        assert!(!DateTimeScalars::new(DateTimeCrate::Off, None).contains("DateTime"));
+    }
+}
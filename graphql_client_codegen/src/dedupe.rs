@@ -0,0 +1,176 @@
+use proc_macro2::{Ident, Literal, TokenStream, TokenTree};
+use quote::ToTokens;
+use std::collections::{BTreeSet, HashMap};
+
+/// Merge the structurally identical selection structs and enums of an operation, with the `dedupe_types` option.
+///
+/// The first generated type of each shape is kept, and the other ones are replaced with it. Since merging
+/// children can make their parents identical, this is repeated until no more types are merged. The `kept`
+/// types (`Variables`, the response data, enums, input objects and fragments) are never merged.
+pub(crate) fn dedupe_types(
+    tokens: TokenStream,
+    kept: &[Ident],
+) -> Result<TokenStream, failure::Error> {
+    let mut items = syn::parse2::<syn::File>(tokens)?.items;
+    let kept: BTreeSet<String> = kept.iter().map(ToString::to_string).collect();
+
+    loop {
+        let mut canonical_names: HashMap<String, String> = HashMap::new();
+        let mut renames: HashMap<String, String> = HashMap::new();
+
+        for name in items.iter().filter_map(type_name) {
+            if kept.contains(&name) {
+                continue;
+            }
+
+            let shape = shape(&items, &name);
+
+            match canonical_names.get(&shape) {
+                Some(canonical_name) => {
+                    renames.insert(name, canonical_name.clone());
+                }
+                None => {
+                    canonical_names.insert(shape, name);
+                }
+            }
+        }
+
+        if renames.is_empty() {
+            break;
+        }
+
+        items = items
+            .into_iter()
+            .filter(|item| match owner(item) {
+                Some(owner) => !renames.contains_key(&owner),
+                None => true,
+            })
+            .map(|item| syn::parse2(rename(item.into_token_stream(), &renames)))
+            .collect::<Result<_, _>>()?;
+    }
+
+    Ok(items.iter().map(ToTokens::to_token_stream).collect())
+}
+
+/// The name of a struct or enum.
+fn type_name(item: &syn::Item) -> Option<String> {
+    match item {
+        syn::Item::Struct(item) => Some(item.ident.to_string()),
+        syn::Item::Enum(item) => Some(item.ident.to_string()),
+        _ => None,
+    }
+}
+
+/// The type an item defines, or implements traits or methods for.
+fn owner(item: &syn::Item) -> Option<String> {
+    match item {
+        syn::Item::Impl(item) => match &*item.self_ty {
+            syn::Type::Path(path) => path.path.get_ident().map(ToString::to_string),
+            _ => None,
+        },
+        _ => type_name(item),
+    }
+}
+
+/// The tokens of the type and its impls, without its name.
+fn shape(items: &[syn::Item], name: &str) -> String {
+    let mut placeholder = HashMap::new();
+    placeholder.insert(name.to_owned(), "__Self".to_owned());
+
+    items
+        .iter()
+        .filter(|item| owner(item).as_deref() == Some(name))
+        .map(|item| rename(item.to_token_stream(), &placeholder).to_string())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Replace the type names, and the string literals with the exact type names (used by `Debug` impls).
+fn rename(tokens: TokenStream, renames: &HashMap<String, String>) -> TokenStream {
+    tokens
+        .into_iter()
+        .map(|token| match token {
+            TokenTree::Ident(ident) => match renames.get(&ident.to_string()) {
+                Some(new_name) => TokenTree::Ident(Ident::new(new_name, ident.span())),
+                None => TokenTree::Ident(ident),
+            },
+            TokenTree::Literal(literal) => {
+                let string = literal.to_string();
+                let new_name = string
+                    .strip_prefix('"')
+                    .and_then(|string| string.strip_suffix('"'))
+                    .and_then(|name| renames.get(name));
+
+                match new_name {
+                    Some(new_name) => TokenTree::Literal(Literal::string(new_name)),
+                    None => TokenTree::Literal(literal),
+                }
+            }
+            TokenTree::Group(group) => {
+                let mut new_group =
+                    proc_macro2::Group::new(group.delimiter(), rename(group.stream(), renames));
+                new_group.set_span(group.span());
+                TokenTree::Group(new_group)
+            }
+            punct => punct,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proc_macro2::Span;
+    use quote::quote;
+
+    #[test]
+    fn identical_types_are_merged_until_fixpoint() {
+        let tokens = quote! {
+            pub struct QAuthor { pub login: String }
+            pub struct QPostAuthor { pub login: String }
+            pub struct QPost { pub author: QAuthor }
+            pub struct QDraft { pub author: QPostAuthor }
+            pub struct ResponseData { pub post: QPost, pub draft: QDraft, pub author: QPostAuthor }
+        };
+        let kept = [Ident::new("ResponseData", Span::call_site())];
+
+        let deduped = dedupe_types(tokens, &kept).unwrap();
+
+        assert_eq!(
+            deduped.to_string(),
+            quote! {
+                pub struct QAuthor { pub login: String }
+                pub struct QPost { pub author: QAuthor }
+                pub struct ResponseData { pub post: QPost, pub draft: QPost, pub author: QAuthor }
+            }
+            .to_string()
+        );
+    }
+
+    #[test]
+    fn impls_and_debug_names_follow_the_canonical_type() {
+        let tokens = quote! {
+            pub struct A { pub tags: Vec<String> }
+            impl std::fmt::Debug for A {
+                fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                    f.debug_struct("A").finish()
+                }
+            }
+            pub struct B { pub tags: Vec<String> }
+            impl std::fmt::Debug for B {
+                fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                    f.debug_struct("B").finish()
+                }
+            }
+            pub struct Kept { pub tags: Vec<String> }
+        };
+        let kept = [Ident::new("Kept", Span::call_site())];
+
+        let deduped = dedupe_types(tokens, &kept).unwrap().to_string();
+
+        assert!(deduped.contains("pub struct A"));
+        assert!(!deduped.contains("pub struct B"));
+        assert!(!deduped.contains("\"B\""));
+        assert!(deduped.contains("pub struct Kept"));
+    }
+}
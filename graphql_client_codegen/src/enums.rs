@@ -1,3 +1,5 @@
+use failure::*;
+use heck::SnakeCase;
 use proc_macro2::{Ident, Span, TokenStream};
 use quote::quote;
 use std::cell::Cell;
@@ -18,7 +20,50 @@ pub struct GqlEnum<'schema> {
     pub is_required: Cell<bool>,
 }
 
+/// An enum of the schema that the generated code uses.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UsedEnum {
+    /// The generated module the enum is defined in (`my_query`).
+    pub module: String,
+    /// The name of the generated enum.
+    pub name: String,
+    /// The variants of the generated enum, as `(Rust variant, GraphQL value)` pairs.
+    pub variants: Vec<(String, String)>,
+    /// The variant the values unknown to the schema are deserialized as (`Other`).
+    pub other_variant: String,
+}
+
 impl<'schema> GqlEnum<'schema> {
+    /// The name of the generated enum.
+    pub(crate) fn name_ident(&self, norm: crate::normalization::Normalization) -> Ident {
+        let name = crate::shared::keyword_replace(&format!("{}{}", ENUMS_PREFIX, self.name));
+        let name = norm.enum_name(name);
+        Ident::new(&name, Span::call_site())
+    }
+
+    /// The generated enum, as defined in `module`.
+    pub(crate) fn to_used_enum(
+        &self,
+        module: &str,
+        norm: crate::normalization::Normalization,
+        rename_all: Option<crate::normalization::RenameAll>,
+        other_variant: &str,
+    ) -> UsedEnum {
+        UsedEnum {
+            module: module.to_owned(),
+            name: self.name_ident(norm).to_string(),
+            variants: self
+                .variants
+                .iter()
+                .map(|v| {
+                    let name = crate::normalization::enum_variant_name(norm, rename_all, v.name);
+                    (name, v.name.to_owned())
+                })
+                .collect(),
+            other_variant: other_variant.to_owned(),
+        }
+    }
+
     /**
      * About rust keyword escaping: variant_names and constructors must be escaped,
      * variant_str not.
@@ -29,14 +74,30 @@ impl<'schema> GqlEnum<'schema> {
     pub(crate) fn to_rust(
         &self,
         query_context: &crate::query::QueryContext<'_, '_>,
-    ) -> TokenStream {
-        let derives = query_context.response_enum_derives();
+    ) -> Result<TokenStream, failure::Error> {
         let norm = query_context.normalization;
+        let variant_name =
+            |value| crate::normalization::enum_variant_name(norm, query_context.rename_all, value);
+        let derives = query_context.response_enum_derives(&self.name_ident(norm).to_string());
+        let other = &query_context.enum_other_variant;
+
+        if let Some(variant) = self
+            .variants
+            .iter()
+            .find(|v| *other == variant_name(v.name))
+        {
+            return Err(format_err!(
+                "The enum `{}` has the value `{}`, which is also the name of the variant for the values unknown to the schema. Set `enum_other_variant` to another name.",
+                self.name,
+                variant.name
+            ));
+        }
+
         let variant_names: Vec<TokenStream> = self
             .variants
             .iter()
             .map(|v| {
-                let name = norm.enum_variant(crate::shared::keyword_replace(&v.name));
+                let name = variant_name(v.name);
                 let name = Ident::new(&name, Span::call_site());
 
                 let description = &v.description;
@@ -46,13 +107,12 @@ impl<'schema> GqlEnum<'schema> {
             })
             .collect();
         let variant_names = &variant_names;
-        let name_ident = norm.enum_name(format!("{}{}", ENUMS_PREFIX, self.name));
-        let name_ident = Ident::new(&name_ident, Span::call_site());
+        let name_ident = self.name_ident(norm);
         let constructors: Vec<_> = self
             .variants
             .iter()
             .map(|v| {
-                let name = norm.enum_variant(crate::shared::keyword_replace(&v.name));
+                let name = variant_name(v.name);
                 let v = Ident::new(&name, Span::call_site());
 
                 quote!(#name_ident::#v)
@@ -64,32 +124,130 @@ impl<'schema> GqlEnum<'schema> {
 
         let name = name_ident;
 
-        quote! {
+        let description_method = if query_context.enum_descriptions {
+            let descriptions: Vec<TokenStream> = self
+                .variants
+                .iter()
+                .map(|v| match v.description {
+                    // The SDL parser keeps the line breaks around block strings.
+                    Some(description) => {
+                        let description = description.trim();
+                        quote!(Some(#description))
+                    }
+                    None => quote!(None),
+                })
+                .collect();
+
+            Some(quote! {
+                /// The description of the variant in the schema, or `None` for `Other` and the variants without one.
+                pub fn description(&self) -> Option<&'static str> {
+                    match *self {
+                        #(#constructors => #descriptions,)*
+                        #name::#other(_) => None,
+                    }
+                }
+            })
+        } else {
+            None
+        };
+
+        let schema_test = query_context.enum_tests_schema.as_ref().map(|schema_path| {
+            let test_name = Ident::new(
+                &format!("{}_matches_the_schema", self.name.to_snake_case()),
+                Span::call_site(),
+            );
+            let graphql_name = self.name;
+
+            quote! {
+                /// Fails when the values of the enum changed in the schema since the code was generated.
+                #[cfg(test)]
+                #[test]
+                pub fn #test_name() {
+                    ::graphql_client::codegen_tests::assert_enum_values(#schema_path, #graphql_name, #name::VARIANTS);
+                }
+            }
+        });
+
+        let non_exhaustive = if query_context.exhaustive_enums {
+            None
+        } else {
+            Some(quote!(#[non_exhaustive]))
+        };
+
+        Ok(quote! {
             #derives
+            #non_exhaustive
             pub enum #name {
                 #(#variant_names,)*
-                Other(String),
+                #other(String),
             }
 
-            impl serde::Serialize for #name {
-                fn serialize<S: serde::Serializer>(&self, ser: S) -> serde::export::Result<S::Ok, S::Error> {
-                    ser.serialize_str(match *self {
+            impl #name {
+                /// The value of the variant in GraphQL.
+                pub fn as_str(&self) -> &str {
+                    match *self {
                         #(#constructors => #variant_str,)*
-                        #name::Other(ref s) => &s,
-                    })
+                        #name::#other(ref s) => &s,
+                    }
+                }
+
+                /// The values of the enum in the schema.
+                pub const VARIANTS: &'static [&'static str] = &[#(#variant_str),*];
+
+                /// The variants of the enum in the schema, in the same order as `VARIANTS`, without `Other`.
+                pub fn all() -> &'static [Self] {
+                    const ALL: &[#name] = &[#(#constructors),*];
+                    ALL
+                }
+
+                /// The variant with the given GraphQL value, or `None` for values unknown to the schema.
+                ///
+                /// With `as_str_value`, this is the way to convert between the copies of the enum generated for
+                /// different operations. See `graphql_client::convert_enum!`.
+                pub fn from_str_value(s: &str) -> Option<Self> {
+                    match s {
+                        #(#variant_str => Some(#constructors),)*
+                        _ => None,
+                    }
+                }
+
+                /// The variant with the given GraphQL value, or `Other` for values unknown to the schema.
+                pub fn from_value(s: String) -> Self {
+                    #name::from_str_value(&s).unwrap_or(#name::#other(s))
+                }
+
+                /// The GraphQL value of the variant, or `None` for `Other`.
+                pub fn as_str_value(&self) -> Option<&'static str> {
+                    match *self {
+                        #(#constructors => Some(#variant_str),)*
+                        #name::#other(_) => None,
+                    }
+                }
+
+                #description_method
+            }
+
+            impl AsRef<str> for #name {
+                fn as_ref(&self) -> &str {
+                    self.as_str()
+                }
+            }
+
+            impl serde::Serialize for #name {
+                fn serialize<S: serde::Serializer>(&self, ser: S) -> std::result::Result<S::Ok, S::Error> {
+                    ser.serialize_str(self.as_str())
                 }
             }
 
             impl<'de> serde::Deserialize<'de> for #name {
-                fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> serde::export::Result<Self, D::Error> {
+                fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
                     let s = <String>::deserialize(deserializer)?;
 
-                    match s.as_str() {
-                        #(#variant_str => Ok(#constructors),)*
-                        _ => Ok(#name::Other(s)),
-                    }
+                    Ok(#name::from_value(s))
                 }
             }
-        }
+
+            #schema_test
+        })
     }
 }
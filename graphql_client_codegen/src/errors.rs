@@ -0,0 +1,195 @@
+//! Code generation errors reported together, so a query document with several problems can be
+//! fixed in one go.
+
+use crate::warnings::Location;
+use failure::*;
+use graphql_parser::Pos;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+/// Several independent errors found while generating the code for an operation or fragment.
+#[derive(Debug)]
+pub struct CodegenErrors {
+    /// The errors, in the order of the selections they were found in.
+    pub errors: Vec<failure::Error>,
+}
+
+impl fmt::Display for CodegenErrors {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} errors:", self.errors.len())?;
+
+        for error in &self.errors {
+            write!(f, "\n- {}", error)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Fail for CodegenErrors {}
+
+/// An error at a position in a query document.
+#[derive(Debug)]
+pub struct LocatedError {
+    /// The error itself.
+    pub error: failure::Error,
+    /// Where the error is in the query document.
+    pub location: Location,
+    /// The query file, once it is known.
+    pub file: Option<PathBuf>,
+}
+
+impl fmt::Display for LocatedError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.file {
+            Some(file) => write!(
+                f,
+                "{} ({}:{}:{})",
+                self.error,
+                file.display(),
+                self.location.line,
+                self.location.column
+            ),
+            None => write!(
+                f,
+                "{} (line {}, column {})",
+                self.error, self.location.line, self.location.column
+            ),
+        }
+    }
+}
+
+impl Fail for LocatedError {}
+
+/// The error located at the position. The errors that already have a location keep it, since it
+/// is the position of a nested selection, and each of several errors is located separately.
+pub(crate) fn at(position: Pos, error: failure::Error) -> failure::Error {
+    let error = match error.downcast::<CodegenErrors>() {
+        Ok(nested) => {
+            let errors = nested
+                .errors
+                .into_iter()
+                .map(|error| at(position, error))
+                .collect();
+            return CodegenErrors { errors }.into();
+        }
+        Err(error) => error,
+    };
+
+    if error.downcast_ref::<LocatedError>().is_some() {
+        return error;
+    }
+
+    LocatedError {
+        error,
+        location: position.into(),
+        file: None,
+    }
+    .into()
+}
+
+/// The errors with a location, named after the query file they are located in.
+pub(crate) fn in_query_file(mut error: failure::Error, query_path: &Path) -> failure::Error {
+    if let Some(nested) = error.downcast_mut::<CodegenErrors>() {
+        for error in &mut nested.errors {
+            if let Some(located) = error.downcast_mut::<LocatedError>() {
+                located.file = Some(query_path.to_owned());
+            }
+        }
+    } else if let Some(located) = error.downcast_mut::<LocatedError>() {
+        located.file = Some(query_path.to_owned());
+    }
+
+    error
+}
+
+/// A single error for all the errors: the error itself when there is only one, [`CodegenErrors`]
+/// otherwise. The lists of errors are flattened.
+pub(crate) fn combine(errors: Vec<failure::Error>) -> failure::Error {
+    let mut flattened = Vec::with_capacity(errors.len());
+
+    for error in errors {
+        match error.downcast::<CodegenErrors>() {
+            Ok(nested) => flattened.extend(nested.errors),
+            Err(error) => flattened.push(error),
+        }
+    }
+
+    if flattened.len() == 1 {
+        return flattened.remove(0);
+    }
+
+    CodegenErrors { errors: flattened }.into()
+}
+
+/// All the successful values, or all the errors.
+pub(crate) fn collect<T>(
+    results: impl IntoIterator<Item = Result<T, failure::Error>>,
+) -> Result<Vec<T>, failure::Error> {
+    let mut values = Vec::new();
+    let mut errors = Vec::new();
+
+    for result in results {
+        match result {
+            Ok(value) => values.push(value),
+            Err(error) => errors.push(error),
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(values)
+    } else {
+        Err(combine(errors))
+    }
+}
+
+/// Both values, or the errors of both results.
+pub(crate) fn join<A, B>(
+    a: Result<A, failure::Error>,
+    b: Result<B, failure::Error>,
+) -> Result<(A, B), failure::Error> {
+    match (a, b) {
+        (Ok(a), Ok(b)) => Ok((a, b)),
+        (Err(error), Ok(_)) | (Ok(_), Err(error)) => Err(error),
+        (Err(a), Err(b)) => Err(combine(vec![a, b])),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn single_errors_are_not_wrapped() {
+        let error = collect(vec![Ok(1), Err(format_err!("first"))]).unwrap_err();
+
+        assert_eq!(error.to_string(), "first");
+        assert!(error.downcast_ref::<CodegenErrors>().is_none());
+    }
+
+    #[test]
+    fn nested_errors_are_flattened() {
+        let nested: Result<(), _> = Err(combine(vec![format_err!("first"), format_err!("second")]));
+
+        let error = join(nested, Err::<(), _>(format_err!("third"))).unwrap_err();
+
+        assert_eq!(error.to_string(), "3 errors:\n- first\n- second\n- third");
+    }
+
+    #[test]
+    fn errors_keep_the_innermost_location() {
+        let outer = Pos { line: 1, column: 3 };
+        let inner = at(Pos { line: 2, column: 5 }, format_err!("first"));
+
+        let error = at(outer, combine(vec![inner, format_err!("second")]));
+
+        assert_eq!(
+            error.to_string(),
+            "2 errors:\n- first (line 2, column 5)\n- second (line 1, column 3)"
+        );
+        assert_eq!(
+            in_query_file(error, Path::new("queries/query.graphql")).to_string(),
+            "2 errors:\n- first (queries/query.graphql:2:5)\n- second (queries/query.graphql:1:3)"
+        );
+    }
+}
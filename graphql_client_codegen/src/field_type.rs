@@ -38,13 +38,51 @@ impl<'a> FieldType<'a> {
         self
     }
 
-    #[cfg(test)]
+    /// The same type, with another inner type name, e.g. `[Int!]` becomes `[MyInt!]`.
+    pub(crate) fn renamed<'b>(&self, name: &'b str) -> FieldType<'b> {
+        FieldType {
+            name,
+            qualifiers: self.qualifiers.clone(),
+        }
+    }
+
+    /// Wrap the type in a non-null qualifier, e.g. `[Int]` becomes `[Int]!`.
     pub(crate) fn nonnull(mut self) -> Self {
         self.qualifiers.insert(0, GraphqlTypeQualifier::Required);
         self
     }
 
+    /// Remove the outer non-null qualifier, if any, e.g. `[Int!]!` becomes `[Int!]`.
+    pub(crate) fn nullable(mut self) -> Self {
+        if !self.is_optional() {
+            self.qualifiers.remove(0);
+        }
+        self
+    }
+
+    /// The type of the elements of a list type, e.g. `[Int!]!` becomes `Int!`. Other types are
+    /// returned as-is.
+    pub(crate) fn list_element(&self) -> Self {
+        if !self.is_list() {
+            return self.clone();
+        }
+
+        let list_position = self
+            .qualifiers
+            .iter()
+            .position(|qualifier| qualifier == &GraphqlTypeQualifier::List)
+            .expect("list qualifier");
+
+        FieldType {
+            name: self.name,
+            qualifiers: self.qualifiers[list_position + 1..].to_vec(),
+        }
+    }
+
     /// Takes a field type with its name.
+    ///
+    /// Nullable types and list elements are wrapped in an `Option`: `[String]` is
+    /// `Option<Vec<Option<String>>>` and `[String!]!` is `Vec<String>`.
     pub(crate) fn to_rust(&self, context: &QueryContext<'_, '_>, prefix: &str) -> TokenStream {
         let prefix: &str = if prefix.is_empty() {
             self.inner_name_str()
@@ -82,7 +120,43 @@ impl<'a> FieldType<'a> {
         let full_name = norm.field_type(crate::shared::keyword_replace(&full_name));
 
         let full_name = Ident::new(&full_name, Span::call_site());
-        let mut qualified = quote!(#full_name);
+
+        self.qualify(quote!(#full_name), |element| quote!(Vec<#element>))
+    }
+
+    /// The type of a variable of the `Variables<'a>` struct generated with the `variables_lifetime`
+    /// option: strings and IDs are borrowed as `&'a str`, and lists as `Cow<'a, [T]>`. `None` for
+    /// the types that hold neither, which are owned as with [`FieldType::to_rust`].
+    pub(crate) fn to_borrowed_rust(&self, context: &QueryContext<'_, '_>) -> Option<TokenStream> {
+        if !self.borrows() {
+            return None;
+        }
+
+        let inner = if self.is_string() {
+            quote!(&'a str)
+        } else {
+            FieldType::new(self.name).nonnull().to_rust(context, "")
+        };
+
+        Some(self.qualify(inner, |element| quote!(::std::borrow::Cow<'a, [#element]>)))
+    }
+
+    /// Whether the type is borrowed in a `Variables<'a>` struct: a string, an ID, or a list.
+    pub(crate) fn borrows(&self) -> bool {
+        self.is_string() || self.is_indirected()
+    }
+
+    fn is_string(&self) -> bool {
+        self.name == "String" || self.name == "ID"
+    }
+
+    /// Wrap the inner type in the `Option`s and the lists of the qualifiers.
+    fn qualify(
+        &self,
+        inner: TokenStream,
+        list: impl Fn(TokenStream) -> TokenStream,
+    ) -> TokenStream {
+        let mut qualified = inner;
 
         let mut non_null = false;
 
@@ -93,12 +167,12 @@ impl<'a> FieldType<'a> {
                 // We are in non-null context, and we wrap the non-null type into a list.
                 // We switch back to null context.
                 (true, GraphqlTypeQualifier::List) => {
-                    qualified = quote!(Vec<#qualified>);
+                    qualified = list(qualified);
                     non_null = false;
                 }
                 // We are in nullable context, and we wrap the nullable type into a list.
                 (false, GraphqlTypeQualifier::List) => {
-                    qualified = quote!(Vec<Option<#qualified>>);
+                    qualified = list(quote!(Option<#qualified>));
                 }
                 // We are in non-nullable context, but we can't double require a type
                 // (!!).
@@ -128,13 +202,21 @@ impl<'a> FieldType<'a> {
     ///
     /// Note: a list of nullable values is considered nullable only if the list itself is nullable.
     pub fn is_optional(&self) -> bool {
-        if let Some(qualifier) = self.qualifiers.get(0) {
+        if let Some(qualifier) = self.qualifiers.first() {
             qualifier != &GraphqlTypeQualifier::Required
         } else {
             true
         }
     }
 
+    /// Is the outermost type a list, nullable or not? e.g. `[Int]` and `[Int!]!` are lists, `Int` is not.
+    pub fn is_list(&self) -> bool {
+        self.qualifiers
+            .iter()
+            .find(|qualifier| *qualifier != &GraphqlTypeQualifier::Required)
+            == Some(&GraphqlTypeQualifier::List)
+    }
+
     /// A type is indirected if it is a (flat or nested) list type, optional or not.
     ///
     /// We use this to determine whether a type needs to be boxed for recursion.
@@ -209,11 +291,11 @@ fn from_json_type_inner(inner: &introspection_response::TypeRef) -> FieldType<'_
         ) {
             (Some(__TypeKind::NON_NULL), Some(new_inner), _) => {
                 qualifiers.push(GraphqlTypeQualifier::Required);
-                inner = &new_inner;
+                inner = new_inner;
             }
             (Some(__TypeKind::LIST), Some(new_inner), _) => {
                 qualifiers.push(GraphqlTypeQualifier::List);
-                inner = &new_inner;
+                inner = new_inner;
             }
             (Some(_), None, Some(name)) => return FieldType { name, qualifiers },
             _ => panic!("Non-convertible type in JSON schema: {:?}", inner),
@@ -279,4 +361,86 @@ mod tests {
         };
         assert_eq!(FieldType::from(&ty), FieldType::new("Cat").nonnull());
     }
+
+    #[test]
+    fn list_element_strips_the_outer_list() {
+        // [[Cat!]]!
+        let ty = FieldType::new("Cat").nonnull().list().list().nonnull();
+
+        assert_eq!(ty.list_element(), FieldType::new("Cat").nonnull().list());
+        assert_eq!(
+            ty.list_element().list_element(),
+            FieldType::new("Cat").nonnull()
+        );
+        assert_eq!(FieldType::new("Cat").list_element(), FieldType::new("Cat"));
+    }
+
+    /// The introspection type reference for a type of the GraphQL schema language.
+    fn type_ref(ty: &GqlParserType) -> TypeRef {
+        match ty {
+            GqlParserType::NamedType(name) => TypeRef {
+                kind: Some(__TypeKind::SCALAR),
+                name: Some(name.clone()),
+                of_type: None,
+            },
+            GqlParserType::ListType(inner) => TypeRef {
+                kind: Some(__TypeKind::LIST),
+                name: None,
+                of_type: Some(Box::new(type_ref(inner))),
+            },
+            GqlParserType::NonNullType(inner) => TypeRef {
+                kind: Some(__TypeKind::NON_NULL),
+                name: None,
+                of_type: Some(Box::new(type_ref(inner))),
+            },
+        }
+    }
+
+    #[test]
+    fn list_nullability_is_mapped_to_rust_types() {
+        let cases = [
+            ("String", "Option<String>"),
+            ("String!", "String"),
+            ("[String]", "Option<Vec<Option<String>>>"),
+            ("[String]!", "Vec<Option<String>>"),
+            ("[String!]", "Option<Vec<String>>"),
+            ("[String!]!", "Vec<String>"),
+            ("[[String!]]!", "Vec<Option<Vec<String>>>"),
+            ("[[String]!]", "Option<Vec<Vec<Option<String>>>>"),
+        ];
+        let schema = crate::schema::Schema::new();
+        let context = QueryContext::new_empty(&schema);
+
+        for (graphql_type, rust_type) in cases.iter() {
+            let document =
+                graphql_parser::parse_schema(&format!("type T {{ f: {} }}", graphql_type)).unwrap();
+            let field_type = match &document.definitions[0] {
+                graphql_parser::schema::Definition::TypeDefinition(
+                    graphql_parser::schema::TypeDefinition::Object(object),
+                ) => &object.fields[0].field_type,
+                _ => unreachable!(),
+            };
+
+            assert_eq!(
+                FieldType::from(field_type)
+                    .to_rust(&context, "")
+                    .to_string()
+                    .replace(' ', ""),
+                *rust_type,
+                "{}",
+                graphql_type
+            );
+
+            // Schemas from introspection responses describe the same types.
+            let introspected = FullTypeFieldsType {
+                type_ref: type_ref(field_type),
+            };
+            assert_eq!(
+                FieldType::from(&introspected),
+                FieldType::from(field_type),
+                "{}",
+                graphql_type
+            );
+        }
+    }
 }
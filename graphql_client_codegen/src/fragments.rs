@@ -2,6 +2,7 @@ use crate::query::QueryContext;
 use crate::selection::Selection;
 use proc_macro2::TokenStream;
 use std::cell::Cell;
+use std::collections::BTreeSet;
 
 /// Represents which type a fragment is defined on. This is the type mentioned in the fragment's `on` clause.
 #[derive(Debug, PartialEq)]
@@ -42,10 +43,10 @@ impl<'query> GqlFragment<'query> {
     ) -> Result<TokenStream, failure::Error> {
         match self.on {
             FragmentTarget::Object(obj) => {
-                obj.response_for_selection(context, &self.selection, &self.name)
+                obj.response_for_selection(context, &self.selection, self.name)
             }
             FragmentTarget::Interface(iface) => {
-                iface.response_for_selection(context, &self.selection, &self.name)
+                iface.response_for_selection(context, &self.selection, self.name)
             }
             FragmentTarget::Union(_) => {
                 unreachable!("Wrong code path. Fragment on unions are treated differently.")
@@ -53,12 +54,20 @@ impl<'query> GqlFragment<'query> {
         }
     }
 
-    pub(crate) fn is_recursive(&self) -> bool {
-        self.selection.contains_fragment(&self.name)
+    /// Whether the fragment spreads itself, in the fields and inline fragments it selects or through
+    /// the other fragments it spreads, including on the members of interfaces and unions. The
+    /// spreads of recursive fragments are boxed.
+    pub(crate) fn is_recursive(&self, context: &QueryContext<'query, '_>) -> bool {
+        self.selection
+            .contains_fragment(self.name, context, &mut BTreeSet::new())
     }
 
     pub(crate) fn require<'schema>(&self, context: &QueryContext<'query, 'schema>) {
-        self.is_required.set(true);
+        // Recursive fragments are only walked once.
+        if self.is_required.replace(true) {
+            return;
+        }
+
         self.selection.require_items(context);
     }
 }
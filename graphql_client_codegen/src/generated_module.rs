@@ -1,7 +1,8 @@
 use crate::codegen_options::*;
-use heck::*;
+use failure::format_err;
 use proc_macro2::{Ident, Span, TokenStream};
 use quote::quote;
+use std::path::{Path, PathBuf};
 
 /// This struct contains the parameters necessary to generate code for a given operation.
 pub(crate) struct GeneratedModule<'a> {
@@ -13,19 +14,21 @@ pub(crate) struct GeneratedModule<'a> {
 }
 
 impl<'a> GeneratedModule<'a> {
-    /// Generate the items for the variables and the response that will go inside the module.
-    fn build_impls(&self) -> Result<TokenStream, failure::Error> {
-        Ok(crate::codegen::response_for_query(
-            &self.schema,
-            &self.query_document,
-            &self.operation,
-            &self.options,
-        )?)
+    /// Generate the items for the variables and the response that will go inside the module,
+    /// along with the names of the items re-exported by the facade and whether `Variables` has a
+    /// lifetime.
+    fn build_impls(&self) -> Result<(TokenStream, Vec<Ident>, bool), failure::Error> {
+        crate::codegen::response_for_query(
+            self.schema,
+            self.query_document,
+            self.operation,
+            self.options,
+        )
     }
 
     /// Generate the module and all the code inside.
     pub(crate) fn to_token_stream(&self) -> Result<TokenStream, failure::Error> {
-        let module_name = Ident::new(&self.operation.name.to_snake_case(), Span::call_site());
+        let module_name = self.options.module_ident(&self.operation.name)?;
         let module_visibility = &self.options.module_visibility();
         let operation_name_literal = &self.operation.name;
         let operation_name_ident = self
@@ -34,61 +37,342 @@ impl<'a> GeneratedModule<'a> {
             .operation(operation_name_literal);
         let operation_name_ident = Ident::new(&operation_name_ident, Span::call_site());
 
-        // Force cargo to refresh the generated code when the query file changes.
-        let query_include = self
-            .options
-            .query_file()
-            .map(|path| {
-                let path = path.to_str();
-                quote!(
-                    const __QUERY_WORKAROUND: &str = include_str!(#path);
-                )
-            })
-            .unwrap_or_else(|| quote! {});
+        let query_include = query_include(self.options);
 
         let query_string = &self.query_string;
-        let impls = self.build_impls()?;
+        let query_sha256 = sha256_hex(query_string);
+        let query = if self.options.query_as_bytes() {
+            let path = query_bytes_path(self.options, query_string, &query_sha256)?;
+            let path = path.to_str().ok_or_else(|| {
+                format_err!(
+                    "The `query_as_bytes` option cannot include {} in the generated code, since its path is not valid UTF-8.",
+                    path.display()
+                )
+            })?;
+            quote! {
+                match ::std::str::from_utf8(include_bytes!(#path)) {
+                    Ok(query) => query,
+                    Err(_) => panic!("The query is not valid UTF-8."),
+                }
+            }
+        } else {
+            quote!(#query_string)
+        };
+        let operation_type = match self.operation.operation_type {
+            crate::operations::OperationType::Query => quote!(Query),
+            crate::operations::OperationType::Mutation => quote!(Mutation),
+            crate::operations::OperationType::Subscription => quote!(Subscription),
+        };
+        let directives = self.operation.expand_directives()?;
+        let idempotent = self.operation.is_idempotent();
+        let selected_fields = if self.options.emit_selected_fields() {
+            Some(self.operation.expand_selected_fields(self.query_document))
+        } else {
+            None
+        };
+        let (impls, facade_items, variables_lifetime) = self.build_impls()?;
+        let response_struct_name = self.options.response_struct_ident()?;
+
+        let header = module_header(self.options);
 
         let struct_declaration: Option<_> = match self.options.mode {
-            CodegenMode::Cli => Some(quote!(#module_visibility struct #operation_name_ident;)),
+            CodegenMode::Cli => Some(quote!(
+                #[allow(clippy::all, clippy::pedantic)]
+                #module_visibility struct #operation_name_ident;
+            )),
             // The struct is already present in derive mode.
             CodegenMode::Derive => None,
         };
 
+        let facade = if self.options.facade() {
+            // The operation struct lives next to the module, and can't be re-exported with more
+            // visibility than it was declared with.
+            let operation_visibility = match module_visibility {
+                syn::Visibility::Public(_) => quote!(pub),
+                syn::Visibility::Crate(_) => quote!(pub(crate)),
+                syn::Visibility::Restricted(restricted) if restricted.path.is_ident("crate") => {
+                    quote!(pub(crate))
+                }
+                _ => quote!(pub(in super::super)),
+            };
+
+            Some(quote!(
+                /// The stable public types for this operation.
+                #[allow(unused_imports)]
+                pub mod api {
+                    #operation_visibility use super::super::#operation_name_ident;
+                    pub use super::{#(#facade_items),*};
+                }
+            ))
+        } else {
+            None
+        };
+
         let serde_use = self
             .options
             .serde_crate()
             .map(|path| quote!(use #path as serde;))
             .unwrap_or_default();
 
+        // The `GraphQLQuery` impl takes `'static` variables, since its `Variables` type can't have a
+        // lifetime: the `build_query` function of the operation struct takes any lifetime, and is
+        // the one called as `Operation::build_query` instead of the trait method.
+        let (variables_type, borrowed_build_query) = if variables_lifetime {
+            (
+                quote!(#module_name::Variables<'static>),
+                Some(quote! {
+                    #[allow(clippy::all, clippy::pedantic)]
+                    impl #operation_name_ident {
+                        /// The body of the request, with variables borrowed for any lifetime.
+                        pub fn build_query<'a>(variables: #module_name::Variables<'a>) -> ::graphql_client::QueryBody<#module_name::Variables<'a>> {
+                            ::graphql_client::QueryBody {
+                                variables,
+                                query: ::std::borrow::Cow::Borrowed(#module_name::QUERY),
+                                operation_name: #module_name::OPERATION_NAME,
+                            }
+                        }
+                    }
+                }),
+            )
+        } else {
+            (quote!(#module_name::Variables), None)
+        };
+
+        let error_extensions = self.options.error_extensions().map(|path| {
+            let is_absolute = path.leading_colon.is_some()
+                || path
+                    .segments
+                    .first()
+                    .map(|segment| segment.ident == "crate")
+                    .unwrap_or(false);
+            let path = if is_absolute {
+                quote!(#path)
+            } else {
+                quote!(super::#path)
+            };
+
+            quote! {
+                /// The type of the `extensions` of the errors in the responses to this operation.
+                pub type Extensions = #path;
+                /// The response to this operation, with typed error extensions.
+                pub type Response = ::graphql_client::Response<#response_struct_name, Extensions>;
+            }
+        });
+
         Ok(quote!(
             #struct_declaration
 
+            #header
             #module_visibility mod #module_name {
-                #![allow(dead_code)]
+                #![allow(dead_code, clippy::all, clippy::pedantic)]
 
                 pub const OPERATION_NAME: &'static str = #operation_name_literal;
-                pub const QUERY: &'static str = #query_string;
+                /// The whole query document, with all its operations and fragments. The server
+                /// executes the one named `OPERATION_NAME`.
+                pub const QUERY: &'static str = #query;
+                pub const QUERY_SHA256: &'static str = #query_sha256;
+                #directives
+                pub const IDEMPOTENT: bool = #idempotent;
+                #selected_fields
 
                 #query_include
 
                 #serde_use
                 #impls
+
+                #error_extensions
+
+                #facade
             }
 
+            #[allow(clippy::all, clippy::pedantic)]
             impl graphql_client::GraphQLQuery for #operation_name_ident {
-                type Variables = #module_name::Variables;
-                type ResponseData = #module_name::ResponseData;
+                type Variables = #variables_type;
+                type ResponseData = #module_name::#response_struct_name;
+
+                const OPERATION_NAME: &'static str = #module_name::OPERATION_NAME;
+                const QUERY: &'static str = #module_name::QUERY;
+                const OPERATION_TYPE: ::graphql_client::OperationType = ::graphql_client::OperationType::#operation_type;
+                const QUERY_SHA256: &'static str = #module_name::QUERY_SHA256;
 
                 fn build_query(variables: Self::Variables) -> ::graphql_client::QueryBody<Self::Variables> {
                     graphql_client::QueryBody {
                         variables,
-                        query: #module_name::QUERY,
+                        query: ::std::borrow::Cow::Borrowed(#module_name::QUERY),
                         operation_name: #module_name::OPERATION_NAME,
                     }
 
                 }
+
+                fn is_idempotent() -> bool {
+                    #module_name::IDEMPOTENT
+                }
+            }
+
+            #borrowed_build_query
+        ))
+    }
+}
+
+/// The file header as the doc comment of the module in derive mode. The CLI writes it at the top of
+/// the generated file instead.
+fn module_header(options: &crate::GraphQLClientCodegenOptions) -> TokenStream {
+    let lines = match (&options.mode, options.file_header_lines()) {
+        (CodegenMode::Derive, Some(lines)) => lines,
+        _ => return TokenStream::new(),
+    };
+
+    let docs = lines.into_iter().map(|line| {
+        let line = if line.is_empty() {
+            String::new()
+        } else {
+            format!(" {}", line)
+        };
+        quote!(#[doc = #line])
+    });
+
+    quote!(#(#docs)*)
+}
+
+/// Force cargo to refresh the generated code when the query file or the included query files change.
+fn query_include(options: &crate::GraphQLClientCodegenOptions) -> TokenStream {
+    let query_file = options.query_file().map(|path| {
+        let path = path.to_str();
+        quote!(
+            const __QUERY_WORKAROUND: &str = include_str!(#path);
+        )
+    });
+    let included_query_files = options.included_query_files().iter().map(|path| {
+        let path = path.to_str();
+        quote!(
+            const _: &str = include_str!(#path);
+        )
+    });
+
+    quote! {
+        #query_file
+        #(#included_query_files)*
+    }
+}
+
+/// The path of a file with the query, for `include_bytes!`: the query file itself when the query is
+/// sent as-is, otherwise a copy of the query written to `OUT_DIR`. Only the derive can include a
+/// file: the code generated by the CLI would include it from the machine it was generated on.
+fn query_bytes_path(
+    options: &crate::GraphQLClientCodegenOptions,
+    query_string: &str,
+    query_sha256: &str,
+) -> Result<PathBuf, failure::Error> {
+    if let CodegenMode::Cli = options.mode {
+        return Err(format_err!(
+            "The `query_as_bytes` option is only supported by the derive: the code generated by the CLI would include the query from a path on the machine it was generated on."
+        ));
+    }
+
+    if let Some(query_file) = options.query_file() {
+        if std::fs::read(query_file)
+            .map(|contents| contents == query_string.as_bytes())
+            .unwrap_or(false)
+        {
+            return Ok(query_file.to_owned());
+        }
+    }
+
+    let out_dir = std::env::var_os("OUT_DIR").ok_or_else(|| {
+        format_err!(
+            "The `query_as_bytes` option writes the query sent to the server to OUT_DIR when it differs from the query file, but OUT_DIR is only set for crates with a build script."
+        )
+    })?;
+    let path = Path::new(&out_dir).join(format!("graphql_client_query_{}.graphql", query_sha256));
+    std::fs::write(&path, query_string)?;
+
+    Ok(path)
+}
+
+/// The SHA-256 hash of a string, as lowercase hexadecimal.
+fn sha256_hex(s: &str) -> String {
+    use sha2::Digest;
+
+    sha2::Sha256::digest(s.as_bytes())
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+/// The parameters necessary to generate a standalone module for a fragment.
+pub(crate) struct GeneratedFragmentModule<'a> {
+    pub fragment_name: &'a str,
+    pub query_document: &'a graphql_parser::query::Document,
+    pub schema: &'a crate::schema::Schema<'a>,
+    pub options: &'a crate::GraphQLClientCodegenOptions,
+}
+
+impl<'a> GeneratedFragmentModule<'a> {
+    /// Generate the module and all the code inside.
+    pub(crate) fn to_token_stream(&self) -> Result<TokenStream, failure::Error> {
+        let module_name = self.options.module_ident(self.fragment_name)?;
+        let module_visibility = &self.options.module_visibility();
+        let fragment_name = self.fragment_name;
+
+        let query_include = query_include(self.options);
+
+        let (impls, fragment_names) = crate::codegen::response_for_fragment(
+            self.schema,
+            self.query_document,
+            self.fragment_name,
+            self.options,
+        )?;
+
+        // The fragment and the fragments it spreads, so they can be appended to the queries using them.
+        let fragments_document = graphql_parser::query::Document {
+            definitions: self
+                .query_document
+                .definitions
+                .iter()
+                .filter(|definition| match definition {
+                    graphql_parser::query::Definition::Fragment(fragment) => {
+                        fragment_names.contains(&fragment.name.as_str())
+                    }
+                    graphql_parser::query::Definition::Operation(_) => false,
+                })
+                .cloned()
+                .collect(),
+        };
+        let fragment_source = crate::strip_client_directives(&fragments_document, self.options)
+            .unwrap_or_else(|| fragments_document.to_string());
+
+        let serde_use = self
+            .options
+            .serde_crate()
+            .map(|path| quote!(use #path as serde;))
+            .unwrap_or_default();
+
+        let header = module_header(self.options);
+
+        Ok(quote!(
+            #header
+            #module_visibility mod #module_name {
+                #![allow(dead_code, clippy::all, clippy::pedantic)]
+
+                pub const FRAGMENT_NAME: &'static str = #fragment_name;
+                pub const FRAGMENT_SOURCE: &'static str = #fragment_source;
+
+                #query_include
+
+                #serde_use
+                #impls
             }
         ))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn sha256_hex_is_lowercase_hexadecimal() {
+        assert_eq!(
+            super::sha256_hex("abc"),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+    }
+}
@@ -0,0 +1,121 @@
+use crate::query::QueryContext;
+use proc_macro2::{Ident, TokenStream};
+use quote::quote;
+use syn::parse::Parser;
+
+/// The fields of a response struct and, when the `private_fields_with_getters` option is enabled, the getters for them.
+///
+/// With the option, the fields are private: serde can still deserialize them, since the derived impls live in the same module.
+pub(crate) fn response_struct_fields(
+    context: &QueryContext<'_, '_>,
+    name: &Ident,
+    fields: &[TokenStream],
+) -> Result<(Vec<TokenStream>, Option<TokenStream>), failure::Error> {
+    if !context.private_fields_with_getters {
+        return Ok((fields.to_vec(), None));
+    }
+
+    let mut fields = fields
+        .iter()
+        .map(|field| syn::Field::parse_named.parse2(field.clone()))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let getters = fields.iter().map(|field| {
+        let ident = field.ident.as_ref().expect("response fields are named");
+        // The getters carry the docs and deprecation of their field.
+        let attributes = field
+            .attrs
+            .iter()
+            .filter(|attr| attr.path.is_ident("doc") || attr.path.is_ident("deprecated"));
+
+        let ty = match boxed_type(&field.ty) {
+            Some(inner) => inner,
+            None => &field.ty,
+        };
+
+        quote! {
+            #(#attributes)*
+            #[allow(deprecated)]
+            pub fn #ident(&self) -> &#ty {
+                &self.#ident
+            }
+        }
+    });
+    let getters = quote! {
+        impl #name {
+            #(#getters)*
+        }
+    };
+
+    for field in fields.iter_mut() {
+        field.vis = syn::Visibility::Inherited;
+    }
+
+    Ok((
+        fields.iter().map(|field| quote!(#field)).collect(),
+        Some(getters),
+    ))
+}
+
+/// The inner type of a `Box`, so the getters for recursive fragments return a plain reference.
+fn boxed_type(ty: &syn::Type) -> Option<&syn::Type> {
+    let segment = match ty {
+        syn::Type::Path(path) => path.path.segments.last()?,
+        _ => return None,
+    };
+
+    if segment.ident != "Box" {
+        return None;
+    }
+
+    match &segment.arguments {
+        syn::PathArguments::AngleBracketed(arguments) => {
+            arguments.args.iter().find_map(|argument| match argument {
+                syn::GenericArgument::Type(ty) => Some(ty),
+                _ => None,
+            })
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fields_are_private_with_getters() {
+        let schema = crate::schema::Schema::new();
+        let mut context = QueryContext::new_empty(&schema);
+        let name = Ident::new("Dog", proc_macro2::Span::call_site());
+        let fields = [
+            quote!(#[doc = "The name"] pub name: String),
+            quote!(#[serde(flatten)] pub dog_fields: Box<DogFields>),
+        ];
+
+        let (public_fields, getters) = response_struct_fields(&context, &name, &fields).unwrap();
+        assert_eq!(public_fields[0].to_string(), fields[0].to_string());
+        assert!(getters.is_none());
+
+        context.private_fields_with_getters = true;
+        let (private_fields, getters) = response_struct_fields(&context, &name, &fields).unwrap();
+
+        assert_eq!(
+            private_fields
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>(),
+            vec![
+                "# [doc = \"The name\"] name : String",
+                "# [serde (flatten)] dog_fields : Box < DogFields >",
+            ]
+        );
+
+        let getters = getters.unwrap().to_string();
+        assert!(getters.contains(
+            "# [doc = \"The name\"] # [allow (deprecated)] pub fn name (& self) -> & String"
+        ));
+        assert!(getters.contains("pub fn dog_fields (& self) -> & DogFields"));
+        assert!(!getters.contains("serde"));
+    }
+}
@@ -0,0 +1,44 @@
+use graphql_parser::query::{Definition, Document, OperationDefinition};
+
+/// The name of the operation directive marking mutations as safe to retry.
+pub(crate) const IDEMPOTENT_DIRECTIVE: &str = "idempotent";
+
+/// Remove the `@idempotent` operation directives, which the server does not know about, from the query document. Returns whether there was one.
+pub(crate) fn strip_directives(document: &mut Document) -> bool {
+    let mut stripped = false;
+
+    for definition in document.definitions.iter_mut() {
+        let directives = match definition {
+            Definition::Operation(OperationDefinition::Query(q)) => &mut q.directives,
+            Definition::Operation(OperationDefinition::Mutation(m)) => &mut m.directives,
+            Definition::Operation(OperationDefinition::Subscription(s)) => &mut s.directives,
+            Definition::Operation(OperationDefinition::SelectionSet(_))
+            | Definition::Fragment(_) => continue,
+        };
+
+        let directives_count = directives.len();
+        directives.retain(|directive| directive.name != IDEMPOTENT_DIRECTIVE);
+        stripped |= directives.len() != directives_count;
+    }
+
+    stripped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn idempotent_directives_are_stripped() {
+        let mut document =
+            graphql_parser::parse_query("mutation M @idempotent @owner(team: \"a\") { name }")
+                .unwrap();
+
+        assert!(strip_directives(&mut document));
+        let stripped = document.to_string();
+        assert!(!stripped.contains("@idempotent"));
+        assert!(stripped.contains("@owner"));
+
+        assert!(!strip_directives(&mut document));
+    }
+}
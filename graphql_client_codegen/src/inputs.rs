@@ -2,12 +2,13 @@ use crate::deprecation::DeprecationStatus;
 use crate::objects::GqlObjectField;
 use crate::query::QueryContext;
 use crate::schema::Schema;
+use failure::format_err;
 use graphql_introspection_query::introspection_response;
-use heck::SnakeCase;
+use graphql_parser::query::Value;
 use proc_macro2::{Ident, Span, TokenStream};
 use quote::quote;
 use std::cell::Cell;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 /// Represents an input object type from a GraphQL schema
 #[derive(Debug, Clone, PartialEq)]
@@ -15,6 +16,11 @@ pub struct GqlInput<'schema> {
     pub description: Option<&'schema str>,
     pub name: &'schema str,
     pub fields: HashMap<&'schema str, GqlObjectField<'schema>>,
+    /// The default values of the fields, from the schema.
+    pub default_values: HashMap<&'schema str, Value>,
+    /// The default values of an introspected schema that could not be parsed, with the parse
+    /// error, by field name. They are reported when the input object is generated.
+    pub invalid_default_values: BTreeMap<&'schema str, (String, String)>,
     pub is_required: Cell<bool>,
 }
 
@@ -25,7 +31,7 @@ impl<'schema> GqlInput<'schema> {
         }
         self.is_required.set(true);
         self.fields.values().for_each(|field| {
-            schema.require(&field.type_.inner_name_str());
+            schema.require(field.type_.inner_name_str());
         })
     }
 
@@ -59,8 +65,40 @@ impl<'schema> GqlInput<'schema> {
         })
     }
 
-    fn is_recursive_without_indirection(&self, context: &QueryContext<'_, '_>) -> bool {
-        self.contains_type_without_indirection(context, &self.name)
+    pub(crate) fn is_recursive_without_indirection(&self, context: &QueryContext<'_, '_>) -> bool {
+        self.contains_type_without_indirection(context, self.name)
+    }
+
+    /// The name of the generated struct.
+    ///
+    /// This prevents generated code like "pub struct crate" for a schema input like "input crate { ... }".
+    /// It works in tandem with renamed struct Variables field types, eg: pub struct Variables { pub criteria : crate_ , }
+    pub(crate) fn name_ident(&self, norm: crate::normalization::Normalization) -> Ident {
+        let name = crate::shared::keyword_replace(self.name);
+        let name = norm.input_name(name);
+        Ident::new(&name, Span::call_site())
+    }
+
+    /// Whether a field of the generated struct is boxed, because its type is recursive.
+    pub(crate) fn is_boxed(context: &QueryContext<'_, '_>, field: &GqlObjectField<'_>) -> bool {
+        context
+            .schema
+            .inputs
+            .get(field.type_.inner_name_str())
+            .map(|input| input.is_recursive_without_indirection(context))
+            .unwrap_or(false)
+    }
+
+    /// The Rust type of a field of the generated struct.
+    fn field_type(context: &QueryContext<'_, '_>, field: &GqlObjectField<'_>) -> TokenStream {
+        let ty = field.type_.to_rust(context, "");
+
+        // If the type is recursive, we have to box it
+        if Self::is_boxed(context, field) {
+            quote! { Box<#ty> }
+        } else {
+            quote!(#ty)
+        }
     }
 
     pub(crate) fn to_rust(
@@ -69,41 +107,143 @@ impl<'schema> GqlInput<'schema> {
     ) -> Result<TokenStream, failure::Error> {
         let norm = context.normalization;
         let mut fields: Vec<&GqlObjectField<'_>> = self.fields.values().collect();
-        fields.sort_unstable_by(|a, b| a.name.cmp(&b.name));
+        fields.sort_unstable_by(|a, b| a.name.cmp(b.name));
         let fields = fields.iter().map(|field| {
-            let ty = field.type_.to_rust(&context, "");
-
-            // If the type is recursive, we have to box it
-            let ty = if let Some(input) = context.schema.inputs.get(field.type_.inner_name_str()) {
-                if input.is_recursive_without_indirection(context) {
-                    quote! { Box<#ty> }
-                } else {
-                    quote!(#ty)
-                }
-            } else {
-                quote!(#ty)
-            };
+            let ty = Self::field_type(context, field);
 
-            context.schema.require(&field.type_.inner_name_str());
-            let name = crate::shared::keyword_replace(&field.name.to_snake_case());
-            let rename = crate::shared::field_rename_annotation(&field.name, &name);
-            let name = norm.field_name(name);
-            let name = Ident::new(&name, Span::call_site());
+            context.schema.require(field.type_.inner_name_str());
+            let name = norm.field_ident(field.name)?;
+            let rename = crate::shared::field_rename_annotation(field.name, &name.to_string());
+            let date_time = context.date_times.serde_attribute(&field.type_);
 
-            quote!(#rename pub #name: #ty)
+            Ok(quote!(#rename #date_time pub #name: #ty))
         });
-        let variables_derives = context.variables_derives();
+        let (fields, ()) =
+            crate::errors::join(crate::errors::collect(fields), self.check_default_values())?;
+        let name = self.name_ident(norm);
+        let variables_derives = context.variables_derives(&name.to_string());
+        let defaults = self.expand_defaults(context, &name)?;
 
-        // Prevent generated code like "pub struct crate" for a schema input like "input crate { ... }"
-        // This works in tandem with renamed struct Variables field types, eg: pub struct Variables { pub criteria : crate_ , }
-        let name = crate::shared::keyword_replace(&self.name);
-        let name = norm.input_name(name);
-        let name = Ident::new(&name, Span::call_site());
         Ok(quote! {
             #variables_derives
             pub struct #name {
                 #(#fields,)*
             }
+
+            #defaults
+        })
+    }
+
+    /// The errors for the default values of the schema that could not be parsed.
+    fn check_default_values(&self) -> Result<(), failure::Error> {
+        if self.invalid_default_values.is_empty() {
+            return Ok(());
+        }
+
+        let errors = self
+            .invalid_default_values
+            .iter()
+            .map(|(field_name, (default_value, error))| {
+                format_err!(
+                    "The default value `{}` of the input field `{}.{}` is not a valid GraphQL value: {}",
+                    default_value,
+                    self.name,
+                    field_name,
+                    error
+                )
+            })
+            .collect();
+
+        Err(crate::errors::combine(errors))
+    }
+
+    /// The name of the function returning the schema default value of a field.
+    pub(crate) fn default_value_constructor_name(
+        &self,
+        field_name: &str,
+        norm: crate::normalization::Normalization,
+    ) -> Ident {
+        Ident::new(
+            &format!("default_{}", norm.field_name(field_name)),
+            Span::call_site(),
+        )
+    }
+
+    /// A function returning the default value of each field with one in the schema, and a
+    /// `Default` impl when all the fields can be left out: they have a default value or they are
+    /// optional.
+    fn expand_defaults(
+        &self,
+        context: &QueryContext<'_, '_>,
+        name: &Ident,
+    ) -> Result<TokenStream, failure::Error> {
+        if self.default_values.is_empty() {
+            return Ok(quote!());
+        }
+
+        let norm = context.normalization;
+        let mut fields: Vec<&GqlObjectField<'_>> = self.fields.values().collect();
+        fields.sort_unstable_by(|a, b| a.name.cmp(b.name));
+
+        let constructors = fields.iter().filter_map(|field| {
+            let default = self.default_values.get(field.name)?;
+            let fn_name = self.default_value_constructor_name(field.name, norm);
+            let ty = Self::field_type(context, field);
+            let value = crate::variables::graphql_parser_value_to_literal(
+                default,
+                context,
+                &field.type_,
+                field.type_.is_optional(),
+                false,
+            );
+            let value = if Self::is_boxed(context, field) {
+                quote!(Box::new(#value))
+            } else {
+                value
+            };
+
+            Some(quote! {
+                pub fn #fn_name() -> #ty {
+                    #value
+                }
+            })
+        });
+
+        let default_impl = if !context.derives_variables_default_for(&name.to_string())
+            && fields.iter().all(|field| {
+                self.default_values.contains_key(field.name) || field.type_.is_optional()
+            }) {
+            let defaults = fields.iter().map(|field| {
+                let field_name = norm.field_ident(field.name)?;
+
+                if self.default_values.contains_key(field.name) {
+                    let fn_name = self.default_value_constructor_name(field.name, norm);
+                    Ok(quote!(#field_name: Self::#fn_name()))
+                } else {
+                    Ok(quote!(#field_name: None))
+                }
+            });
+            let defaults = crate::errors::collect(defaults)?;
+
+            Some(quote! {
+                impl ::std::default::Default for #name {
+                    fn default() -> Self {
+                        #name {
+                            #(#defaults,)*
+                        }
+                    }
+                }
+            })
+        } else {
+            None
+        };
+
+        Ok(quote! {
+            impl #name {
+                #(#constructors)*
+            }
+
+            #default_impl
         })
     }
 }
@@ -125,10 +265,17 @@ impl<'schema> std::convert::From<&'schema graphql_parser::schema::InputObjectTyp
                         name: &field.name,
                         type_: crate::field_type::FieldType::from(&field.value_type),
                         deprecation: DeprecationStatus::Current,
+                        arguments: Vec::new(),
                     };
                     (name, field)
                 })
                 .collect(),
+            default_values: schema_input
+                .fields
+                .iter()
+                .filter_map(|field| Some((field.name.as_str(), field.default_value.clone()?)))
+                .collect(),
+            invalid_default_values: BTreeMap::new(),
             is_required: false.into(),
         }
     }
@@ -136,6 +283,29 @@ impl<'schema> std::convert::From<&'schema graphql_parser::schema::InputObjectTyp
 
 impl<'schema> std::convert::From<&'schema introspection_response::FullType> for GqlInput<'schema> {
     fn from(schema_input: &'schema introspection_response::FullType) -> GqlInput<'schema> {
+        let mut default_values = HashMap::new();
+        let mut invalid_default_values = BTreeMap::new();
+        let introspected_default_values = schema_input
+            .input_fields
+            .iter()
+            .flatten()
+            .filter_map(Option::as_ref)
+            .filter_map(|f| {
+                let name = f.input_value.name.as_ref()?.as_str();
+                Some((name, f.input_value.default_value.as_ref()?))
+            });
+
+        for (name, default_value) in introspected_default_values {
+            match parse_default_value(default_value) {
+                Ok(value) => {
+                    default_values.insert(name, value);
+                }
+                Err(error) => {
+                    invalid_default_values.insert(name, (default_value.clone(), error));
+                }
+            }
+        }
+
         GqlInput {
             description: schema_input.description.as_deref(),
             name: schema_input.name.as_deref().expect("unnamed input object"),
@@ -154,7 +324,7 @@ impl<'schema> std::convert::From<&'schema introspection_response::FullType> for
                         .as_str();
                     let field = GqlObjectField {
                         description: None,
-                        name: &name,
+                        name,
                         type_: f
                             .input_value
                             .type_
@@ -162,15 +332,37 @@ impl<'schema> std::convert::From<&'schema introspection_response::FullType> for
                             .map(|s| s.into())
                             .expect("type on input object field"),
                         deprecation: DeprecationStatus::Current,
+                        arguments: Vec::new(),
                     };
                     (name, field)
                 })
                 .collect(),
+            default_values,
+            invalid_default_values,
             is_required: false.into(),
         }
     }
 }
 
+/// Parse a default value of an introspection response, which is a GraphQL literal in a string.
+/// The error is the message of the parser.
+fn parse_default_value(default_value: &str) -> Result<Value, String> {
+    use graphql_parser::query::{Definition, OperationDefinition};
+
+    // The value is parsed as the default value of a variable.
+    let query = format!("query($value: Int = {}) {{ a }}", default_value);
+    let document = graphql_parser::parse_query(&query).map_err(|err| err.to_string())?;
+
+    match document.definitions.into_iter().next() {
+        Some(Definition::Operation(OperationDefinition::Query(mut query))) => Ok(query
+            .variable_definitions
+            .remove(0)
+            .default_value
+            .expect("default value of the variable")),
+        _ => unreachable!("the default value is parsed in a query"),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -190,6 +382,7 @@ mod tests {
                         name: "pawsCount",
                         type_: FieldType::new(float_type()).nonnull(),
                         deprecation: DeprecationStatus::Current,
+                        arguments: Vec::new(),
                     },
                 ),
                 (
@@ -199,6 +392,7 @@ mod tests {
                         name: "offsprings",
                         type_: FieldType::new("Cat").nonnull().list().nonnull(),
                         deprecation: DeprecationStatus::Current,
+                        arguments: Vec::new(),
                     },
                 ),
                 (
@@ -208,19 +402,22 @@ mod tests {
                         name: "requirements",
                         type_: FieldType::new("CatRequirements"),
                         deprecation: DeprecationStatus::Current,
+                        arguments: Vec::new(),
                     },
                 ),
             ]
             .into_iter()
             .collect(),
+            default_values: HashMap::new(),
+            invalid_default_values: BTreeMap::new(),
             is_required: false.into(),
         };
 
         let expected: String = vec![
-            "# [ derive ( Clone , Serialize ) ] ",
+            "# [derive (Clone , Serialize)] ",
             "pub struct Cat { ",
             "pub offsprings : Vec < Cat > , ",
-            "# [ serde ( rename = \"pawsCount\" ) ] ",
+            "# [serde (rename = \"pawsCount\")] ",
             "pub paws_count : Float , ",
             "pub requirements : Option < CatRequirements > , ",
             "}",
@@ -241,4 +438,75 @@ mod tests {
             expected
         );
     }
+
+    #[test]
+    fn introspected_default_values_are_parsed() {
+        let input: introspection_response::FullType = serde_json::from_str(
+            r#"{
+                "kind": "INPUT_OBJECT",
+                "name": "IssueFilter",
+                "inputFields": [
+                    {
+                        "name": "first",
+                        "type": { "kind": "SCALAR", "name": "Int", "ofType": null },
+                        "defaultValue": "10"
+                    },
+                    {
+                        "name": "orderBy",
+                        "type": { "kind": "INPUT_OBJECT", "name": "IssueOrder", "ofType": null },
+                        "defaultValue": "{field: CREATED_AT, labels: [\"bug\"]}"
+                    },
+                    {
+                        "name": "labels",
+                        "type": { "kind": "SCALAR", "name": "String", "ofType": null },
+                        "defaultValue": null
+                    }
+                ]
+            }"#,
+        )
+        .unwrap();
+        let input = GqlInput::from(&input);
+
+        assert_eq!(input.default_values.len(), 2);
+        assert_eq!(input.default_values["first"].to_string(), "10");
+        assert_eq!(
+            input.default_values["orderBy"].to_string(),
+            "{field: CREATED_AT, labels: [\"bug\"]}"
+        );
+    }
+
+    #[test]
+    fn invalid_introspected_default_values_are_errors() {
+        let input: introspection_response::FullType = serde_json::from_str(
+            r#"{
+                "kind": "INPUT_OBJECT",
+                "name": "IssueFilter",
+                "inputFields": [
+                    {
+                        "name": "first",
+                        "type": { "kind": "SCALAR", "name": "Int", "ofType": null },
+                        "defaultValue": "{field:"
+                    }
+                ]
+            }"#,
+        )
+        .unwrap();
+        let input = GqlInput::from(&input);
+        assert!(input.default_values.is_empty());
+        assert_eq!(input.invalid_default_values["first"].0, "{field:");
+
+        let mut schema = crate::schema::Schema::new();
+        schema.inputs.insert(input.name, input);
+        let context = QueryContext::new_empty(&schema);
+        let error = context.schema.inputs["IssueFilter"]
+            .to_rust(&context)
+            .unwrap_err()
+            .to_string();
+
+        assert!(
+            error.contains("The default value `{field:` of the input field `IssueFilter.first`"),
+            "{}",
+            error
+        );
+    }
 }
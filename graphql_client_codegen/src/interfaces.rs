@@ -3,12 +3,12 @@ use crate::objects::GqlObjectField;
 use crate::query::QueryContext;
 use crate::selection::{Selection, SelectionField, SelectionFragmentSpread, SelectionItem};
 use crate::shared::*;
-use crate::unions::union_variants;
+use crate::unions::{union_variants, untagged_variants};
 use failure::*;
 use proc_macro2::{Ident, Span, TokenStream};
 use quote::quote;
 use std::cell::Cell;
-use std::collections::HashSet;
+use std::collections::BTreeSet;
 
 /// A GraphQL interface (simplified schema representation).
 ///
@@ -18,7 +18,7 @@ pub struct GqlInterface<'schema> {
     /// The documentation for the interface. Extracted from the schema.
     pub description: Option<&'schema str>,
     /// The set of object types implementing this interface.
-    pub implemented_by: HashSet<&'schema str>,
+    pub implemented_by: BTreeSet<&'schema str>,
     /// The name of the interface. Should match 1-to-1 to its name in the GraphQL schema.
     pub name: &'schema str,
     /// The interface's fields. Analogous to object fields.
@@ -35,12 +35,12 @@ impl<'schema> GqlInterface<'schema> {
         selection: &'query Selection<'query>,
         query_context: &QueryContext<'_, '_>,
     ) -> Selection<'query> {
-        (&selection)
+        selection
             .into_iter()
             // Only keep what we can handle
             .filter(|f| match f {
                 SelectionItem::Field(f) => f.name != TYPENAME_FIELD,
-                SelectionItem::FragmentSpread(SelectionFragmentSpread { fragment_name }) => {
+                SelectionItem::FragmentSpread(SelectionFragmentSpread { fragment_name, .. }) => {
                     // only if the fragment refers to the interface’s own fields (to take into account type-refining fragments)
                     let fragment = query_context
                         .fragments
@@ -62,12 +62,12 @@ impl<'schema> GqlInterface<'schema> {
         selection: &'query Selection<'_>,
         query_context: &QueryContext<'_, '_>,
     ) -> Selection<'query> {
-        (&selection)
+        selection
             .into_iter()
             // Only keep what we can handle
             .filter(|f| match f {
                 SelectionItem::InlineFragment(_) => true,
-                SelectionItem::FragmentSpread(SelectionFragmentSpread { fragment_name }) => {
+                SelectionItem::FragmentSpread(SelectionFragmentSpread { fragment_name, .. }) => {
                     let fragment = query_context
                         .fragments
                         .get(fragment_name)
@@ -92,7 +92,7 @@ impl<'schema> GqlInterface<'schema> {
         GqlInterface {
             name,
             description,
-            implemented_by: HashSet::new(),
+            implemented_by: BTreeSet::new(),
             fields: vec![],
             is_required: false.into(),
         }
@@ -121,7 +121,7 @@ impl<'schema> GqlInterface<'schema> {
         prefix: &str,
     ) -> Result<Vec<TokenStream>, failure::Error> {
         response_fields_for_selection(
-            &self.name,
+            self.name,
             &self.fields,
             context,
             &self.object_selection(selection, context),
@@ -136,63 +136,107 @@ impl<'schema> GqlInterface<'schema> {
         selection: &Selection<'_>,
         prefix: &str,
     ) -> Result<TokenStream, failure::Error> {
-        let name = Ident::new(&prefix, Span::call_site());
-        let derives = query_context.response_derives();
+        let name = Ident::new(prefix, Span::call_site());
+        let derives = query_context.response_derives(prefix);
 
-        selection.extract_typename(query_context).ok_or_else(|| {
-            format_err!(
+        // Untagged enums are deserialized without `__typename`.
+        let has_typename = selection.extract_typename(query_context).is_some();
+        let typename = if has_typename || query_context.untagged_unions {
+            Ok(())
+        } else {
+            Err(format_err!(
                 "Missing __typename in selection for the {} interface (type: {})",
                 prefix,
                 self.name
-            )
-        })?;
-
-        let object_fields =
-            self.response_fields_for_selection(query_context, &selection, prefix)?;
-
-        let object_children = self.field_impls_for_selection(query_context, &selection, prefix)?;
+            ))
+        };
 
-        let union_selection = self.union_selection(&selection, &query_context);
+        let union_selection = self.union_selection(selection, query_context);
 
-        let (mut union_variants, union_children, used_variants) =
-            union_variants(&union_selection, query_context, prefix, &self.name)?;
+        let (_, ((object_fields, object_children), variants)) = crate::errors::join(
+            typename,
+            crate::errors::join(
+                crate::errors::join(
+                    self.response_fields_for_selection(query_context, selection, prefix),
+                    self.field_impls_for_selection(query_context, selection, prefix),
+                ),
+                union_variants(&union_selection, query_context, prefix, self.name),
+            ),
+        )?;
+        let (mut union_variants, union_children, used_variants, _) = variants;
 
-        for used_variant in used_variants.iter() {
-            if !self.implemented_by.contains(used_variant) {
-                return Err(format_err!(
+        let not_implementing: Vec<failure::Error> = used_variants
+            .iter()
+            .filter(|used_variant| !self.implemented_by.contains(*used_variant))
+            .map(|used_variant| {
+                format_err!(
                     "Type {} does not implement the {} interface",
                     used_variant,
                     self.name,
-                ));
-            }
+                )
+            })
+            .collect();
+
+        if !not_implementing.is_empty() {
+            return Err(crate::errors::combine(not_implementing));
         }
 
-        // Add the non-selected variants to the generated enum's variants.
-        union_variants.extend(
-            self.implemented_by
-                .iter()
-                .filter(|obj| used_variants.iter().find(|v| v == obj).is_none())
-                .map(|v| {
-                    let v = Ident::new(v, Span::call_site());
-                    quote!(#v)
-                }),
-        );
+        let mut not_selected = self
+            .implemented_by
+            .iter()
+            .filter(|obj| used_variants.iter().find(|v| v == obj).is_none())
+            .peekable();
+
+        // Without `__typename`, the enum is only generated for the inline fragments.
+        let untagged = !has_typename && !used_variants.is_empty();
+        let serde_attribute = if untagged {
+            union_variants = untagged_variants(
+                &union_selection,
+                query_context,
+                prefix,
+                self.name,
+                union_variants,
+                &used_variants,
+                not_selected.peek().is_some(),
+            )?;
+            quote!(#[serde(untagged)])
+        } else {
+            // Add the non-selected variants to the generated enum's variants.
+            union_variants.extend(not_selected.map(|v| {
+                let v = Ident::new(v, Span::call_site());
+                quote!(#v)
+            }));
+            quote!(#[serde(tag = "__typename")])
+        };
 
         let attached_enum_name = Ident::new(&format!("{}On", name), Span::call_site());
-        let (attached_enum, last_object_field) =
-            if selection.extract_typename(query_context).is_some() {
-                let attached_enum = quote! {
-                    #derives
-                    #[serde(tag = "__typename")]
-                    pub enum #attached_enum_name {
-                        #(#union_variants,)*
-                    }
-                };
-                let last_object_field = quote!(#[serde(flatten)] pub on: #attached_enum_name,);
-                (Some(attached_enum), Some(last_object_field))
-            } else {
-                (None, None)
+        let attributes = query_context.selection_attributes(prefix);
+        let attached_enum_attributes =
+            query_context.selection_attributes(&attached_enum_name.to_string());
+        let (attached_enum, last_object_field) = if has_typename || untagged {
+            let attached_enum = quote! {
+                #derives
+                #attached_enum_attributes
+                #serde_attribute
+                pub enum #attached_enum_name {
+                    #(#union_variants,)*
+                }
             };
+            let last_object_field = quote!(#[serde(flatten)] pub on: #attached_enum_name);
+            (Some(attached_enum), Some(last_object_field))
+        } else {
+            (None, None)
+        };
+
+        let mut fields = object_fields;
+        fields.extend(last_object_field);
+        let fields = crate::skip_serde::response_struct_fields(query_context, &name, &fields)?;
+        let (struct_derives, debug_impl) =
+            crate::compact_debug::response_struct_derives(query_context, &name, &fields)?;
+        let (serde_attribute, serde_impls) =
+            crate::response_serde::response_struct_serde(query_context, &name, self.name, &fields)?;
+        let (fields, getters) =
+            crate::getters::response_struct_fields(query_context, &name, &fields)?;
 
         Ok(quote! {
 
@@ -202,11 +246,18 @@ impl<'schema> GqlInterface<'schema> {
 
             #attached_enum
 
-            #derives
+            #struct_derives
+            #serde_attribute
+            #attributes
             pub struct #name {
-                #(#object_fields,)*
-                #last_object_field
+                #(#fields,)*
             }
+
+            #serde_impls
+
+            #debug_impl
+
+            #getters
         })
     }
 }
@@ -214,13 +265,14 @@ impl<'schema> GqlInterface<'schema> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use graphql_parser::Pos;
 
     // to be improved
     #[test]
     fn union_selection_works() {
         let iface = GqlInterface {
             description: None,
-            implemented_by: HashSet::new(),
+            implemented_by: BTreeSet::new(),
             name: "MyInterface",
             fields: vec![],
             is_required: Cell::new(true),
@@ -234,6 +286,10 @@ mod tests {
                 alias: None,
                 name: "__typename",
                 fields: Selection::new_empty(),
+                rust_with: None,
+                conditional: false,
+                arguments: &[],
+                position: Pos::default(),
             });
         let selection = Selection::from_vec(vec![typename_field.clone()]);
 
@@ -248,7 +304,7 @@ mod tests {
     fn object_selection_works() {
         let iface = GqlInterface {
             description: None,
-            implemented_by: HashSet::new(),
+            implemented_by: BTreeSet::new(),
             name: "MyInterface",
             fields: vec![],
             is_required: Cell::new(true),
@@ -262,6 +318,10 @@ mod tests {
                 alias: None,
                 name: "__typename",
                 fields: Selection::new_empty(),
+                rust_with: None,
+                conditional: false,
+                arguments: &[],
+                position: Pos::default(),
             });
         let selection: Selection<'_> = vec![typename_field].into_iter().collect();
 
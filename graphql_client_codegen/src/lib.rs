@@ -11,8 +11,13 @@ use lazy_static::*;
 use proc_macro2::TokenStream;
 use quote::*;
 
+mod arguments;
 mod codegen;
 mod codegen_options;
+mod compact_debug;
+mod cursors;
+/// Date and time scalars.
+pub mod date_times;
 /// Deprecation-related code
 pub mod deprecation;
 mod query;
@@ -20,30 +25,62 @@ mod query;
 pub mod schema;
 
 mod constants;
+mod dedupe;
 mod enums;
+mod errors;
 mod field_type;
 mod fragments;
 mod generated_module;
+mod getters;
+mod idempotent;
 mod inputs;
 mod interfaces;
+/// Migration of query documents to a new version of the schema.
+pub mod migration;
 /// Normalization-related code
 pub mod normalization;
 mod objects;
 mod operations;
+mod policy;
+mod pragmas;
+mod recursive_fragments;
+mod response_serde;
+mod rust_with;
 mod scalars;
 mod selection;
 mod shared;
+mod skip_serde;
+#[allow(non_local_definitions)]
 mod unions;
 mod variables;
+/// Warnings about the query document.
+pub mod warnings;
 
 #[cfg(test)]
 mod tests;
 
-pub use crate::codegen_options::{CodegenMode, GraphQLClientCodegenOptions};
+pub use crate::codegen_options::{CodegenMode, GraphQLClientCodegenOptions, OperationOptions};
+pub use crate::enums::UsedEnum;
+pub use crate::errors::{CodegenErrors, LocatedError};
+pub use crate::query::UnappliedTypeOption;
+pub use graphql_introspection_query::{introspection_query, INTROSPECTION_QUERY};
 
 use std::collections::HashMap;
 
-type CacheMap<T> = std::sync::Mutex<HashMap<std::path::PathBuf, T>>;
+/// Set to `1` to read the schema and query files every time code is generated, instead of caching
+/// them for the lifetime of the process.
+const NO_CACHE_ENV_VAR: &str = "GRAPHQL_CLIENT_NO_CACHE";
+
+/// The contents of a file, with the modification time and the length of the file when it was read.
+/// The file is read again when they change, since long-lived processes like rust-analyzer keep the
+/// cache while the files are edited.
+struct CacheEntry<T> {
+    modified: Option<std::time::SystemTime>,
+    len: u64,
+    value: T,
+}
+
+type CacheMap<T> = std::sync::Mutex<HashMap<std::path::PathBuf, CacheEntry<T>>>;
 
 lazy_static! {
     static ref SCHEMA_CACHE: CacheMap<String> = CacheMap::default();
@@ -57,32 +94,153 @@ pub fn generate_module_token_stream(
     schema_path: &std::path::Path,
     options: GraphQLClientCodegenOptions,
 ) -> Result<TokenStream, failure::Error> {
-    use std::collections::hash_map;
-    // We need to qualify the query with the path to the crate it is part of
-    let (query_string, query) = {
-        let mut lock = QUERY_CACHE.lock().expect("query cache is poisoned");
-        match lock.entry(query_path) {
-            hash_map::Entry::Occupied(o) => o.get().clone(),
-            hash_map::Entry::Vacant(v) => {
-                let query_string = read_file(v.key())?;
-                let query = graphql_parser::parse_query(&query_string)?;
-                v.insert((query_string, query)).clone()
+    generate_code(query_path, schema_path, options).map(|generated| generated.tokens)
+}
+
+/// The result of code generation.
+pub struct GeneratedCode {
+    /// The generated modules.
+    pub tokens: TokenStream,
+    /// The names of the custom scalars referenced by the generated code, which aliases them to
+    /// `super::ScalarName`. They have to be defined next to the generated modules.
+    pub referenced_scalars: Vec<String>,
+    /// The problems found in the query document that did not prevent code generation.
+    pub warnings: Vec<warnings::Warning>,
+    /// The enums the generated code uses, each with the first module it is defined in.
+    pub used_enums: Vec<UsedEnum>,
+    /// The type names of the `derives_for`, `redact` and `skip_serde` options that no generated
+    /// type applies to, with the `defer_option_checks` option. Without it, they are errors.
+    pub unapplied_type_options: Vec<UnappliedTypeOption>,
+    /// The names of the operations of the query document.
+    pub operation_names: Vec<String>,
+}
+
+/// Generates Rust code given a query document, a schema and options, along with the custom
+/// scalars the generated code expects to find in its parent module.
+pub fn generate_code(
+    query_path: std::path::PathBuf,
+    schema_path: &std::path::Path,
+    options: GraphQLClientCodegenOptions,
+) -> Result<GeneratedCode, failure::Error> {
+    let parsed_schema = read_schema(schema_path)?;
+
+    generate_code_with_schema(query_path, &parsed_schema, options)
+}
+
+/// Read a schema file without generating code, and describe what it contains, including the
+/// features the code generation does not support.
+pub fn schema_info(schema_path: &std::path::Path) -> Result<schema::SchemaInfo, failure::Error> {
+    let parsed_schema = read_schema(schema_path)?;
+
+    Ok(schema::SchemaInfo::from(&parsed_schema))
+}
+
+/// Like [generate_code], with a schema that was already parsed, e.g. from the response to an
+/// introspection query.
+pub fn generate_code_with_schema(
+    query_path: std::path::PathBuf,
+    parsed_schema: &schema::ParsedSchema,
+    mut options: GraphQLClientCodegenOptions,
+) -> Result<GeneratedCode, failure::Error> {
+    let (query_string, query) = read_query(&query_path)?;
+    pragmas::apply(&query_path, &query_string, &mut options)?;
+    let struct_name = options.struct_ident().map(ToString::to_string);
+    let (query_string, query) =
+        operations::name_anonymous_operation(query_string, query, struct_name.as_deref());
+
+    // The fragments of the included query files can be spread in the query file. The ones it
+    // spreads are sent to the server after it, and all of them are sent before it with the
+    // `query_with_included_files` option: `sent_query` is the document sent to the server when it
+    // is not the whole query document.
+    let (query_string, query, sent_query, included_fragments) =
+        if options.included_query_files().is_empty() {
+            (query_string, query, None, Vec::new())
+        } else {
+            let (included_string, included) =
+                read_included_query_files(&query_path, &query, &options)?;
+            let included_fragments = fragment_names(&included);
+            let mut combined = included;
+            combined
+                .definitions
+                .extend(query.definitions.iter().cloned());
+
+            if options.query_with_included_files() {
+                let query_string = format!("{}\n{}", included_string, query_string);
+                (query_string, combined, None, included_fragments)
+            } else {
+                let spread = spread_included_fragments(&query, &combined, &included_fragments);
+                let query_string = if spread.definitions.is_empty() {
+                    query_string
+                } else {
+                    format!("{}\n{}", query_string, spread)
+                };
+                let mut sent_query = query;
+                sent_query.definitions.extend(spread.definitions);
+                (query_string, combined, Some(sent_query), included_fragments)
             }
+        };
+
+    let query_string = query_to_send(
+        query_string,
+        sent_query.as_ref().unwrap_or(&query),
+        &options,
+    )?;
+    let query = match options.expand_recursive_fragments() {
+        Some(levels) => recursive_fragments::expand(&query, levels)?.unwrap_or(query),
+        None => query,
+    };
+    // The located errors name the query file. With included query files, the location may be in
+    // one of them, so they only give the line and column.
+    let in_query_file = |error| {
+        if options.included_query_files().is_empty() {
+            errors::in_query_file(error, &query_path)
+        } else {
+            error
         }
     };
 
+    if let Some(fragment_name) = options.fragment() {
+        let schema = schema::Schema::from(parsed_schema);
+
+        if let Some(policy) = policy::FieldPolicy::new(&schema, &options)? {
+            policy.check_fragment(&query_path, &query, fragment_name)?;
+        }
+
+        let tokens = generated_module::GeneratedFragmentModule {
+            fragment_name,
+            query_document: &query,
+            schema: &schema,
+            options: &options,
+        }
+        .to_token_stream()
+        .map_err(in_query_file)?;
+        let mut used_enums = Vec::new();
+        let module = options.module_ident(fragment_name)?.to_string();
+        record_used_enums(&schema, &options, &module, &mut used_enums);
+        let unapplied_type_options = check_type_options(&schema, &options, vec![&options])?;
+
+        return Ok(GeneratedCode {
+            tokens: options.post_process(tokens),
+            referenced_scalars: referenced_scalars(&schema, &options),
+            warnings: Vec::new(),
+            used_enums,
+            unapplied_type_options,
+            operation_names: owned_operation_names(&query),
+        });
+    }
+
     // Determine which operation we are generating code for. This will be used in operationName.
-    let operations = options
-        .operation_name
-        .as_ref()
-        .and_then(|operation_name| {
-            codegen::select_operation(&query, &operation_name, options.normalization())
-        })
-        .map(|op| vec![op]);
+    let operations = match &options.operation_name {
+        Some(operation_name) => {
+            codegen::select_operation(&query, operation_name, options.normalization())?
+        }
+        None => None,
+    }
+    .map(|op| vec![op]);
 
     let operations = match (operations, &options.mode) {
         (Some(ops), _) => ops,
-        (None, &CodegenMode::Cli) => codegen::all_operations(&query),
+        (None, &CodegenMode::Cli) => codegen::all_operations(&query)?,
         (None, &CodegenMode::Derive) => {
             return Err(derive_operation_not_found_error(
                 options.struct_ident(),
@@ -91,22 +249,346 @@ pub fn generate_module_token_stream(
         }
     };
 
+    check_module_names(&operations, &options)?;
+
+    let schema = schema::Schema::from(parsed_schema);
+
+    if let Some(policy) = policy::FieldPolicy::new(&schema, &options)? {
+        errors::collect(
+            operations
+                .iter()
+                .map(|operation| policy.check_operation(&query_path, &query, operation)),
+        )?;
+    }
+
+    // The generated modules. The errors of all the operations are reported together.
+    let mut used_enums = Vec::new();
+    let modules = errors::collect(operations.iter().map(|operation| {
+        let generated = generated_module::GeneratedModule {
+            query_string: query_string.as_str(),
+            schema: &schema,
+            query_document: &query,
+            operation,
+            options: &options.for_operation(&operation.name),
+        }
+        .to_token_stream()?;
+        let module = options.module_ident(&operation.name)?.to_string();
+        record_used_enums(&schema, &options, &module, &mut used_enums);
+        Ok(options.post_process(generated))
+    }))
+    .map_err(in_query_file)?;
+
+    let modules = quote! { #(#modules)* };
+    let operation_options: Vec<_> = operations
+        .iter()
+        .map(|operation| options.for_operation(&operation.name))
+        .collect();
+    let unapplied_type_options = check_type_options(
+        &schema,
+        &options,
+        std::iter::once(&options).chain(operation_options.iter().map(AsRef::as_ref)),
+    )?;
+
+    Ok(GeneratedCode {
+        tokens: modules,
+        referenced_scalars: referenced_scalars(&schema, &options),
+        warnings: warnings::collect(&schema, &query, &operations, &options, &included_fragments),
+        used_enums,
+        unapplied_type_options,
+        operation_names: owned_operation_names(&query),
+    })
+}
+
+/// The value cached for the file, or the value `read` returns if the file changed since it was
+/// cached, or if caching is disabled.
+fn cached<T: Clone>(
+    cache: &CacheMap<T>,
+    path: &std::path::Path,
+    read: impl FnOnce(&std::path::Path) -> Result<T, failure::Error>,
+) -> Result<T, failure::Error> {
+    if std::env::var(NO_CACHE_ENV_VAR).as_deref() == Ok("1") {
+        return read(path);
+    }
+
+    // Without a modification time, the file is read every time.
+    let metadata = std::fs::metadata(path).ok();
+    let modified = metadata
+        .as_ref()
+        .and_then(|metadata| metadata.modified().ok());
+    let len = metadata.map_or(0, |metadata| metadata.len());
+
+    let mut lock = cache.lock().expect("cache is poisoned");
+    if let Some(entry) = lock.get(path) {
+        if modified.is_some() && entry.modified == modified && entry.len == len {
+            return Ok(entry.value.clone());
+        }
+    }
+
+    let value = read(path)?;
+    lock.insert(
+        path.to_owned(),
+        CacheEntry {
+            modified,
+            len,
+            value: value.clone(),
+        },
+    );
+    Ok(value)
+}
+
+/// The text and the parsed document of a query file.
+fn read_query(
+    query_path: &std::path::Path,
+) -> Result<(String, graphql_parser::query::Document), failure::Error> {
+    cached(&QUERY_CACHE, query_path, |path| {
+        let query_string = read_file(path)?;
+        let query = graphql_parser::parse_query(&query_string)?;
+        Ok((query_string, query))
+    })
+}
+
+/// The concatenated text and the definitions of the included query files. They can only define
+/// fragments, and a fragment can only be defined in one of the files.
+fn read_included_query_files(
+    query_path: &std::path::Path,
+    query: &graphql_parser::query::Document,
+    options: &GraphQLClientCodegenOptions,
+) -> Result<(String, graphql_parser::query::Document), failure::Error> {
+    let mut fragment_files: HashMap<String, &std::path::Path> = fragment_names(query)
+        .into_iter()
+        .map(|name| (name, query_path))
+        .collect();
+    let mut query_strings = Vec::with_capacity(options.included_query_files().len());
+    let mut definitions = Vec::new();
+
+    for path in options.included_query_files() {
+        let (query_string, included) = read_query(path)?;
+
+        for definition in &included.definitions {
+            let fragment = match definition {
+                graphql_parser::query::Definition::Fragment(fragment) => fragment,
+                graphql_parser::query::Definition::Operation(_) => {
+                    return Err(format_err!(
+                        "The included query file {} defines an operation. Included query files can only define fragments.",
+                        path.display()
+                    ))
+                }
+            };
+
+            if let Some(other_path) = fragment_files.insert(fragment.name.clone(), path) {
+                return Err(format_err!(
+                    "The fragment `{}` is defined in both {} and {}.",
+                    fragment.name,
+                    other_path.display(),
+                    path.display()
+                ));
+            }
+        }
+
+        query_strings.push(query_string);
+        definitions.extend(included.definitions);
+    }
+
+    Ok((
+        query_strings.join("\n"),
+        graphql_parser::query::Document { definitions },
+    ))
+}
+
+/// The fragments of the included query files that the query file spreads, directly or through other
+/// fragments, in the order of the included query files.
+fn spread_included_fragments(
+    query: &graphql_parser::query::Document,
+    combined: &graphql_parser::query::Document,
+    included_fragments: &[String],
+) -> graphql_parser::query::Document {
+    let mut spread = std::collections::BTreeSet::new();
+
+    for definition in &query.definitions {
+        warnings::spread_fragments(combined, warnings::selection_set(definition), &mut spread);
+    }
+
+    let definitions = combined
+        .definitions
+        .iter()
+        .filter(|definition| match definition {
+            graphql_parser::query::Definition::Fragment(fragment) => {
+                spread.contains(fragment.name.as_str())
+                    && included_fragments.contains(&fragment.name)
+            }
+            graphql_parser::query::Definition::Operation(_) => false,
+        })
+        .cloned()
+        .collect();
+
+    graphql_parser::query::Document { definitions }
+}
+
+fn fragment_names(query: &graphql_parser::query::Document) -> Vec<String> {
+    query
+        .definitions
+        .iter()
+        .filter_map(|definition| match definition {
+            graphql_parser::query::Definition::Fragment(fragment) => Some(fragment.name.clone()),
+            graphql_parser::query::Definition::Operation(_) => None,
+        })
+        .collect()
+}
+
+/// The query sent to the server: the recursive fragments are expanded like in the generated
+/// types, so that the server returns as many levels as they have, and the directives that only
+/// drive code generation are left out, since the server does not know about them.
+fn query_to_send(
+    query_string: String,
+    query: &graphql_parser::query::Document,
+    options: &GraphQLClientCodegenOptions,
+) -> Result<String, failure::Error> {
+    let expanded = match options.expand_recursive_fragments() {
+        Some(levels) => recursive_fragments::expand(query, levels)?,
+        None => None,
+    };
+    let (query_string, query) = match &expanded {
+        Some(expanded) => (expanded.to_string(), expanded),
+        None => (query_string, query),
+    };
+
+    Ok(strip_client_directives(query, options).unwrap_or(query_string))
+}
+
+/// The query document without the directives the server does not know about: `@rust_with`, and `@idempotent` unless it is kept. Returns `None` when there is no such directive, so the original query string can be used as-is.
+pub(crate) fn strip_client_directives(
+    document: &graphql_parser::query::Document,
+    options: &GraphQLClientCodegenOptions,
+) -> Option<String> {
+    let mut document = document.clone();
+    let mut stripped = rust_with::strip_directives(&mut document);
+
+    if !options.keep_idempotent_directive() {
+        stripped |= idempotent::strip_directives(&mut document);
+    }
+
+    if stripped {
+        Some(document.to_string())
+    } else {
+        None
+    }
+}
+
+/// Fail when two operations would be generated in the same module, like `getUser` and `get_user`,
+/// or any two operations with the `module_name` option.
+fn check_module_names(
+    operations: &[operations::Operation<'_>],
+    options: &GraphQLClientCodegenOptions,
+) -> Result<(), failure::Error> {
+    let mut modules: HashMap<String, &str> = HashMap::new();
+
+    for operation in operations {
+        let module = options.module_ident(&operation.name)?.to_string();
+
+        if let Some(other) = modules.insert(module.clone(), &operation.name) {
+            return Err(format_err!(
+                "The operations `{}` and `{}` would both be generated in the module `{}`. Rename one of them, or generate them separately with a different `module_name`.",
+                other,
+                operation.name,
+                module
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+/// The names of the operations of the query document, for `GeneratedCode::operation_names`.
+fn owned_operation_names(query: &graphql_parser::query::Document) -> Vec<String> {
+    warnings::operation_names(query)
+        .into_iter()
+        .map(str::to_owned)
+        .collect()
+}
+
+/// The type names of the type options that no generated type applies to, which are errors unless
+/// the check is deferred to the caller.
+fn check_type_options<'a>(
+    schema: &schema::Schema<'_>,
+    options: &GraphQLClientCodegenOptions,
+    generated_options: impl IntoIterator<Item = &'a GraphQLClientCodegenOptions>,
+) -> Result<Vec<UnappliedTypeOption>, failure::Error> {
+    let unapplied = query::unapplied_type_options(schema, generated_options)?;
+
+    if unapplied.is_empty() || options.defer_option_checks() {
+        Ok(unapplied)
+    } else {
+        Err(query::unapplied_type_options_error(schema, &unapplied))
+    }
+}
+
+/// Add the enums required so far that are not in `used_enums` yet, as defined in the module of
+/// the operation or fragment that was just generated.
+fn record_used_enums(
+    schema: &schema::Schema<'_>,
+    options: &GraphQLClientCodegenOptions,
+    module: &str,
+    used_enums: &mut Vec<UsedEnum>,
+) {
+    for enm in schema.enums.values().filter(|enm| enm.is_required.get()) {
+        let used_enum = enm.to_used_enum(
+            module,
+            options.normalization(),
+            options.rename_all(),
+            options.enum_other_variant(),
+        );
+
+        if !used_enums.iter().any(|used| used.name == used_enum.name) {
+            used_enums.push(used_enum);
+        }
+    }
+}
+
+/// The names of the custom scalars used by the generated code.
+fn referenced_scalars(
+    schema: &schema::Schema<'_>,
+    options: &GraphQLClientCodegenOptions,
+) -> Vec<String> {
+    let date_times =
+        date_times::DateTimeScalars::new(options.date_time_crate(), options.date_time_scalars());
+
+    schema
+        .scalars
+        .values()
+        .filter(|scalar| scalar.is_required.get() && !date_times.contains(scalar.name))
+        .map(|scalar| options.normalization().scalar_name(scalar.name).into_owned())
+        .collect()
+}
+
+/// The error for a schema file that is neither a `.graphql` (or `.gql`) file nor a `.json`
+/// introspection response.
+#[derive(Debug)]
+pub struct UnsupportedSchemaExtension {
+    /// The extension of the schema file, `INVALID` when it has none.
+    pub extension: String,
+}
+
+impl std::fmt::Display for UnsupportedSchemaExtension {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Unsupported extension for the GraphQL schema: {} (only .json and .graphql are supported)",
+            self.extension
+        )
+    }
+}
+
+impl Fail for UnsupportedSchemaExtension {}
+
+/// Read and parse a schema file, going through the schema cache.
+fn read_schema(schema_path: &std::path::Path) -> Result<schema::ParsedSchema, failure::Error> {
     let schema_extension = schema_path
         .extension()
         .and_then(std::ffi::OsStr::to_str)
         .unwrap_or("INVALID");
 
     // Check the schema cache.
-    let schema_string: String = {
-        let mut lock = SCHEMA_CACHE.lock().expect("schema cache is poisoned");
-        match lock.entry(schema_path.to_path_buf()) {
-            hash_map::Entry::Occupied(o) => o.get().clone(),
-            hash_map::Entry::Vacant(v) => {
-                let schema_string = read_file(v.key())?;
-                (*v.insert(schema_string)).to_string()
-            }
-        }
-    };
+    let schema_string: String = cached(&SCHEMA_CACHE, schema_path, read_file)?;
 
     let parsed_schema = match schema_extension {
                         "graphql" | "gql" => {
@@ -117,36 +599,28 @@ pub fn generate_module_token_stream(
                             let parsed: graphql_introspection_query::introspection_response::IntrospectionResponse = serde_json::from_str(&schema_string)?;
                             schema::ParsedSchema::Json(parsed)
                         }
-                        extension => panic!("Unsupported extension for the GraphQL schema: {} (only .json and .graphql are supported)", extension)
+                        extension => return Err(UnsupportedSchemaExtension { extension: extension.to_owned() }.into()),
                     };
 
-    let schema = schema::Schema::from(&parsed_schema);
-
-    // The generated modules.
-    let mut modules = Vec::with_capacity(operations.len());
-
-    for operation in &operations {
-        let generated = generated_module::GeneratedModule {
-            query_string: query_string.as_str(),
-            schema: &schema,
-            query_document: &query,
-            operation,
-            options: &options,
-        }
-        .to_token_stream()?;
-        modules.push(generated);
-    }
+    Ok(parsed_schema)
+}
 
-    let modules = quote! { #(#modules)* };
+/// The byte order mark some editors start UTF-8 files with.
+pub const BYTE_ORDER_MARK: char = '\u{feff}';
 
-    Ok(modules)
+/// Read a query or schema file. A leading byte order mark is ignored, and files that are not valid UTF-8 are rejected with the offset of the first invalid byte.
+pub fn read_file(path: &std::path::Path) -> Result<String, failure::Error> {
+    read_file_and_byte_order_mark(path).map(|(contents, _)| contents)
 }
 
-fn read_file(path: &std::path::Path) -> Result<String, failure::Error> {
+/// Read a file like [`read_file`], along with whether it starts with a byte order mark, to keep it when the file is written back.
+pub fn read_file_and_byte_order_mark(
+    path: &std::path::Path,
+) -> Result<(String, bool), failure::Error> {
     use std::fs;
     use std::io::prelude::*;
 
-    let mut out = String::new();
+    let mut out = Vec::new();
     let mut file = fs::File::open(path).map_err(|io_err| {
         let err: failure::Error = io_err.into();
         err.context(format!(
@@ -157,8 +631,25 @@ fn read_file(path: &std::path::Path) -> Result<String, failure::Error> {
             path.display()
         ))
     })?;
-    file.read_to_string(&mut out)?;
-    Ok(out)
+    file.read_to_end(&mut out)?;
+
+    let out = String::from_utf8(out).map_err(|err| {
+        format_err!(
+            r#"
+            Could not read file with path: {}
+            The file is not valid UTF-8 (invalid byte at offset {}).
+            Hint: GraphQL documents must be UTF-8 encoded. Try re-saving the file as UTF-8.
+            "#,
+            path.display(),
+            err.utf8_error().valid_up_to(),
+        )
+    })?;
+
+    // Some editors start UTF-8 files with a byte order mark, which the GraphQL parser rejects.
+    Ok(match out.strip_prefix(BYTE_ORDER_MARK) {
+        Some(without_bom) => (without_bom.to_owned(), true),
+        None => (out, false),
+    })
 }
 
 /// In derive mode, build an error when the operation with the same name as the struct is not found.
@@ -176,26 +667,30 @@ fn derive_operation_not_found_error(
         .iter()
         .filter_map(|definition| match definition {
             Definition::Operation(op) => match op {
-                OperationDefinition::Mutation(m) => Some(m.name.as_ref().unwrap()),
-                OperationDefinition::Query(m) => Some(m.name.as_ref().unwrap()),
-                OperationDefinition::Subscription(m) => Some(m.name.as_ref().unwrap()),
-                OperationDefinition::SelectionSet(_) => {
-                    unreachable!("Bare selection sets are not supported.")
+                OperationDefinition::Mutation(m) => {
+                    Some(m.name.as_deref().unwrap_or("(anonymous mutation)"))
+                }
+                OperationDefinition::Query(m) => {
+                    Some(m.name.as_deref().unwrap_or("(anonymous query)"))
                 }
+                OperationDefinition::Subscription(m) => {
+                    Some(m.name.as_deref().unwrap_or("(anonymous subscription)"))
+                }
+                OperationDefinition::SelectionSet(_) => Some("(anonymous query)"),
             },
             _ => None,
         })
         .fold(String::new(), |mut acc, item| {
-            acc.push_str(&item);
+            acc.push_str(item);
             acc.push_str(", ");
             acc
         });
 
     let available_operations = available_operations.trim_end_matches(", ");
 
-    return format_err!(
+    format_err!(
         "The struct name does not match any defined operation in the query file.\nStruct name: {}\nDefined operations: {}",
         struct_ident,
         available_operations,
-    );
+    )
 }
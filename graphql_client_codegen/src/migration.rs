@@ -0,0 +1,446 @@
+use crate::schema::{ParsedSchema, Schema};
+use failure::*;
+use graphql_parser::query::{
+    Definition, OperationDefinition, Selection, SelectionSet, TypeCondition,
+};
+use graphql_parser::Pos;
+
+/// A field rename, written `Type.oldField=newField`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldRename {
+    /// The object or interface the field is defined on.
+    pub type_name: String,
+    /// The name of the field in the queries.
+    pub old_name: String,
+    /// The new name of the field in the schema.
+    pub new_name: String,
+}
+
+impl std::str::FromStr for FieldRename {
+    type Err = failure::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let malformed = || format_err!("Invalid rename: {} (expected Type.oldField=newField)", s);
+
+        let (field, new_name) = split_once(s, '=').ok_or_else(malformed)?;
+        let (type_name, old_name) = split_once(field, '.').ok_or_else(malformed)?;
+
+        if [type_name, old_name, new_name]
+            .iter()
+            .any(|part| part.trim().is_empty())
+        {
+            return Err(malformed());
+        }
+
+        Ok(FieldRename {
+            type_name: type_name.trim().to_owned(),
+            old_name: old_name.trim().to_owned(),
+            new_name: new_name.trim().to_owned(),
+        })
+    }
+}
+
+fn split_once(s: &str, separator: char) -> Option<(&str, &str)> {
+    let index = s.find(separator)?;
+    Some((&s[..index], &s[index + separator.len_utf8()..]))
+}
+
+/// Applies field renames from a new version of a schema to query documents.
+///
+/// Renamed fields keep their name in the response: `oldField` becomes `oldField: newField` when it had no alias.
+/// The rest of the documents, comments and formatting included, is left as written.
+pub struct Migration {
+    schema: ParsedSchema,
+    renames: Vec<FieldRename>,
+}
+
+impl Migration {
+    /// Load the new schema (.json or .graphql) the queries should be migrated to.
+    pub fn new(
+        schema_path: &std::path::Path,
+        renames: Vec<FieldRename>,
+    ) -> Result<Migration, failure::Error> {
+        Ok(Migration {
+            schema: crate::read_schema(schema_path)?,
+            renames,
+        })
+    }
+
+    /// Apply the renames to a query document. Returns `None` when the document is unchanged.
+    pub fn migrate(&self, query_string: &str) -> Result<Option<String>, failure::Error> {
+        let schema = Schema::from(&self.schema);
+        let mut query = graphql_parser::parse_query(query_string)?;
+        let mut edits = Vec::new();
+
+        for definition in query.definitions.iter_mut() {
+            let (parent, selection_set) = match definition {
+                Definition::Operation(OperationDefinition::Query(q)) => {
+                    (schema.query_type.unwrap_or("Query"), &mut q.selection_set)
+                }
+                Definition::Operation(OperationDefinition::SelectionSet(s)) => {
+                    (schema.query_type.unwrap_or("Query"), s)
+                }
+                Definition::Operation(OperationDefinition::Mutation(m)) => (
+                    schema.mutation_type.unwrap_or("Mutation"),
+                    &mut m.selection_set,
+                ),
+                Definition::Operation(OperationDefinition::Subscription(s)) => (
+                    schema.subscription_type.unwrap_or("Subscription"),
+                    &mut s.selection_set,
+                ),
+                Definition::Fragment(fragment) => {
+                    let TypeCondition::On(on) = &fragment.type_condition;
+                    (on.as_str(), &mut fragment.selection_set)
+                }
+            };
+            // The fragment's type condition is borrowed from the definition being edited.
+            let parent = parent.to_owned();
+
+            self.migrate_selection_set(&schema, &parent, selection_set, &mut edits)?;
+        }
+
+        if edits.is_empty() {
+            return Ok(None);
+        }
+
+        Ok(Some(apply_edits(query_string, &edits)?))
+    }
+
+    fn migrate_selection_set(
+        &self,
+        schema: &Schema<'_>,
+        parent: &str,
+        selection_set: &mut SelectionSet,
+        edits: &mut Vec<Edit>,
+    ) -> Result<(), failure::Error> {
+        for item in selection_set.items.iter_mut() {
+            match item {
+                Selection::Field(field) => {
+                    if let Some(rename) = self
+                        .renames
+                        .iter()
+                        .find(|rename| rename.type_name == parent && rename.old_name == field.name)
+                    {
+                        edits.push(Edit {
+                            position: field.position,
+                            aliased: field.alias.is_some(),
+                            old_name: field.name.clone(),
+                            new_name: rename.new_name.clone(),
+                        });
+                        if field.alias.is_none() {
+                            field.alias = Some(field.name.clone());
+                        }
+                        field.name = rename.new_name.clone();
+                    }
+
+                    if field.selection_set.items.is_empty() {
+                        continue;
+                    }
+
+                    let field_type =
+                        field_type_name(schema, parent, &field.name).ok_or_else(|| {
+                            format_err!("Could not find field `{}` on `{}`", field.name, parent)
+                        })?;
+
+                    self.migrate_selection_set(
+                        schema,
+                        &field_type,
+                        &mut field.selection_set,
+                        edits,
+                    )?;
+                }
+                Selection::InlineFragment(inline) => {
+                    let on = match &inline.type_condition {
+                        Some(TypeCondition::On(on)) => on.clone(),
+                        None => parent.to_owned(),
+                    };
+
+                    self.migrate_selection_set(schema, &on, &mut inline.selection_set, edits)?;
+                }
+                Selection::FragmentSpread(_) => (),
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// A renamed field, at the position of its first token: its alias, or its name.
+struct Edit {
+    position: Pos,
+    aliased: bool,
+    old_name: String,
+    new_name: String,
+}
+
+/// Splice the renames into the text of the document.
+fn apply_edits(query_string: &str, edits: &[Edit]) -> Result<String, failure::Error> {
+    let tokens = tokens(query_string);
+    let mut replacements = Vec::with_capacity(edits.len());
+
+    for edit in edits {
+        let index = tokens
+            .iter()
+            .position(|(position, _)| *position == edit.position)
+            .ok_or_else(|| format_err!("No field at {} in the query", edit.position))?;
+        // The name follows the alias and the colon.
+        let (range, replacement) = if edit.aliased {
+            (tokens.get(index + 2), edit.new_name.clone())
+        } else {
+            (
+                tokens.get(index),
+                format!("{}: {}", edit.old_name, edit.new_name),
+            )
+        };
+        let range = range
+            .map(|(_, range)| range.clone())
+            .filter(|range| query_string[range.clone()] == edit.old_name)
+            .ok_or_else(|| {
+                format_err!(
+                    "No field `{}` at {} in the query",
+                    edit.old_name,
+                    edit.position
+                )
+            })?;
+
+        replacements.push((range, replacement));
+    }
+
+    replacements.sort_by_key(|(range, _)| std::cmp::Reverse(range.start));
+
+    let mut migrated = query_string.to_owned();
+    for (range, replacement) in replacements {
+        migrated.replace_range(range, &replacement);
+    }
+
+    Ok(migrated)
+}
+
+/// The positions and byte ranges of the tokens of a valid query document. The positions are counted
+/// like `graphql_parser` counts them, so that they match the positions of the parsed document: a tab
+/// is 8 columns, and the characters of comments are not counted.
+fn tokens(source: &str) -> Vec<(Pos, std::ops::Range<usize>)> {
+    let mut tokens = Vec::new();
+    let mut position = Pos { line: 1, column: 1 };
+    let mut offset = 0;
+
+    loop {
+        let mut chars = source[offset..].char_indices();
+        let skipped = loop {
+            let (index, c) = match chars.next() {
+                Some(pair) => pair,
+                None => break source.len() - offset,
+            };
+            match c {
+                '\u{feff}' | '\r' => (),
+                '\t' => position.column += 8,
+                '\n' => {
+                    position.column = 1;
+                    position.line += 1;
+                }
+                ' ' | ',' => position.column += 1,
+                '#' => {
+                    for (_, c) in &mut chars {
+                        if c == '\r' || c == '\n' {
+                            position.column = 1;
+                            position.line += 1;
+                            break;
+                        }
+                    }
+                }
+                _ => break index,
+            }
+        };
+        offset += skipped;
+
+        let rest = &source[offset..];
+        if rest.is_empty() {
+            return tokens;
+        }
+
+        let len = token_len(rest);
+        let token = &rest[..len];
+        tokens.push((position, offset..offset + len));
+
+        if token.starts_with(BLOCK_QUOTES) && token.contains('\n') {
+            position.line += token.matches('\n').count();
+            position.column = token[token.rfind('\n').unwrap() + 1..].chars().count() + 1;
+        } else {
+            position.column += token.chars().count();
+        }
+        offset += len;
+    }
+}
+
+const BLOCK_QUOTES: &str = "\"\"\"";
+
+/// The length in bytes of the token at the start of `rest`.
+fn token_len(rest: &str) -> usize {
+    let mut chars = rest.char_indices();
+    let first = chars.next().map(|(_, c)| c).unwrap_or_default();
+
+    match first {
+        '.' => 3,
+        '_' | 'a'..='z' | 'A'..='Z' => rest
+            .find(|c: char| !(c == '_' || c.is_ascii_alphanumeric()))
+            .unwrap_or(rest.len()),
+        '-' | '0'..='9' => rest
+            .find(|c: char| " \n\r\t,#!$:=@|&()[]{}".contains(c))
+            .unwrap_or(rest.len()),
+        '"' if rest.starts_with(BLOCK_QUOTES) => {
+            let tail = &rest[3..];
+            tail.match_indices(BLOCK_QUOTES)
+                .find(|(end, _)| !tail[..*end].ends_with('\\'))
+                .map(|(end, _)| end + 6)
+                .unwrap_or(rest.len())
+        }
+        '"' => {
+            let mut escaped = false;
+            for (index, c) in chars {
+                if c == '"' && !escaped {
+                    return index + 1;
+                }
+                escaped = !escaped && c == '\\';
+            }
+            rest.len()
+        }
+        first => first.len_utf8(),
+    }
+}
+
+/// The name of the type of a field on an object or interface.
+fn field_type_name(schema: &Schema<'_>, parent: &str, field_name: &str) -> Option<String> {
+    let fields = schema
+        .objects
+        .get(parent)
+        .map(|object| &object.fields)
+        .or_else(|| schema.interfaces.get(parent).map(|iface| &iface.fields))?;
+
+    fields
+        .iter()
+        .find(|field| field.name == field_name)
+        .map(|field| field.type_.inner_name_str().to_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SCHEMA: &str = r#"
+        schema {
+          query: Query
+        }
+
+        type Query {
+          me: User!
+        }
+
+        type User {
+          displayName: String!
+          friends: [User!]!
+        }
+    "#;
+
+    fn migration() -> Migration {
+        Migration {
+            schema: ParsedSchema::GraphQLParser(
+                graphql_parser::schema::parse_schema(SCHEMA).unwrap(),
+            ),
+            renames: vec!["User.name=displayName".parse().unwrap()],
+        }
+    }
+
+    #[test]
+    fn rename_is_parsed() {
+        assert_eq!(
+            "User.name=displayName".parse::<FieldRename>().unwrap(),
+            FieldRename {
+                type_name: "User".to_owned(),
+                old_name: "name".to_owned(),
+                new_name: "displayName".to_owned(),
+            }
+        );
+        assert!("User.name".parse::<FieldRename>().is_err());
+        assert!("name=displayName".parse::<FieldRename>().is_err());
+    }
+
+    #[test]
+    fn renamed_field_is_aliased_to_its_old_name() {
+        let migrated = migration()
+            .migrate("query Me { me { name friends { name } } }")
+            .unwrap();
+
+        assert_eq!(
+            migrated.unwrap(),
+            "query Me { me { name: displayName friends { name: displayName } } }"
+        );
+    }
+
+    #[test]
+    fn existing_alias_is_kept() {
+        let migrated = migration()
+            .migrate("query Me { me { nickname: name } }")
+            .unwrap();
+
+        assert_eq!(
+            migrated.unwrap(),
+            "query Me { me { nickname: displayName } }"
+        );
+    }
+
+    #[test]
+    fn fields_in_fragments_are_renamed() {
+        let migrated = migration()
+            .migrate(
+                "query Me { me { ...UserFields } } fragment UserFields on User { name friends { ... on User { name } } }",
+            )
+            .unwrap();
+
+        assert_eq!(
+            migrated.unwrap(),
+            "query Me { me { ...UserFields } } fragment UserFields on User { name: displayName friends { ... on User { name: displayName } } }"
+        );
+    }
+
+    #[test]
+    fn comments_and_formatting_are_kept() {
+        // Tabs, comments, block strings and non-ASCII strings all shift the positions.
+        let query = "# graphql-client: derive Debug\n\
+                     query Me {\n\
+                     \tme { # the \"é\" user\n\
+                     \t\tnickname :\n\
+                     \t\t\tname,\tname\n\
+                     \t\tfirst: friends(first: \"\"\"\n\
+                     \t\tx\n\
+                     \t\t\"\"\") { name }\n\
+                     \t\tfriends(first: \"é\") { name }\n\
+                     \t}\n\
+                     }\n";
+
+        let migrated = migration().migrate(query).unwrap().unwrap();
+
+        assert_eq!(
+            migrated,
+            "# graphql-client: derive Debug\n\
+             query Me {\n\
+             \tme { # the \"é\" user\n\
+             \t\tnickname :\n\
+             \t\t\tdisplayName,\tname: displayName\n\
+             \t\tfirst: friends(first: \"\"\"\n\
+             \t\tx\n\
+             \t\t\"\"\") { name: displayName }\n\
+             \t\tfriends(first: \"é\") { name: displayName }\n\
+             \t}\n\
+             }\n"
+        );
+    }
+
+    #[test]
+    fn documents_without_renamed_fields_are_unchanged() {
+        let migrated = migration()
+            .migrate("query Me { me { displayName } }")
+            .unwrap();
+
+        assert_eq!(migrated, None);
+    }
+}
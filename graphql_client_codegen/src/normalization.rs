@@ -1,4 +1,6 @@
-use heck::{CamelCase, SnakeCase};
+use failure::*;
+use heck::{CamelCase, MixedCase, SnakeCase};
+use proc_macro2::{Ident, Span};
 use std::borrow::Cow;
 
 /// Normalization conventions available for generated code.
@@ -8,23 +10,19 @@ pub enum Normalization {
     None,
     /// Use Rust naming conventions for generated code.
     Rust,
+    /// Use the names from the schema as they are, for the fields and variables too, so that they
+    /// need no serde renames. Only keywords are escaped.
+    Preserve,
 }
 
 impl Normalization {
     fn camel_case(self, name: Cow<'_, str>) -> Cow<'_, str> {
         match self {
-            Self::None => name,
+            Self::None | Self::Preserve => name,
             Self::Rust => name.to_camel_case().into(),
         }
     }
 
-    fn snake_case(self, name: Cow<'_, str>) -> Cow<'_, str> {
-        match self {
-            Self::None => name,
-            Self::Rust => name.to_snake_case().into(),
-        }
-    }
-
     pub(crate) fn operation<'a, S>(self, op: S) -> Cow<'a, str>
     where
         S: Into<Cow<'a, str>>,
@@ -61,11 +59,28 @@ impl Normalization {
         self.field_type_impl(fty.into())
     }
 
-    pub(crate) fn field_name<'a, S>(self, fnm: S) -> Cow<'a, str>
-    where
-        S: Into<Cow<'a, str>>,
-    {
-        self.snake_case(fnm.into())
+    /// The Rust name of a field, input field or variable from the schema: in snake case, unless the
+    /// names are preserved, and usable as an identifier.
+    pub(crate) fn field_name(self, name: &str) -> String {
+        match self {
+            Self::Preserve => crate::shared::keyword_replace(name),
+            Self::None | Self::Rust => crate::shared::keyword_replace(&name.to_snake_case()),
+        }
+    }
+
+    /// The identifier of a field, input field or variable from the schema. When the names are
+    /// preserved, the names that are not Rust identifiers are rejected instead of being replaced.
+    pub(crate) fn field_ident(self, name: &str) -> Result<Ident, failure::Error> {
+        let rust_name = self.field_name(name);
+
+        if self == Self::Preserve && rust_name != name && !crate::shared::is_keyword(name) {
+            return Err(format_err!(
+                "The field `{}` is not a valid Rust identifier, so it cannot keep its name with `normalization = \"preserve\"`.",
+                name
+            ));
+        }
+
+        Ok(Ident::new(&rust_name, Span::call_site()))
     }
 
     pub(crate) fn input_name<'a, S>(self, inm: S) -> Cow<'a, str>
@@ -90,7 +105,127 @@ impl std::str::FromStr for Normalization {
         match s.trim() {
             "none" => Ok(Normalization::None),
             "rust" => Ok(Normalization::Rust),
+            "preserve" => Ok(Normalization::Preserve),
             _ => Err(()),
         }
     }
 }
+
+/// The casing of the generated response fields and enum variants, overriding the one of the
+/// normalization. The serde renames of the fields are computed against it.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum RenameAll {
+    /// `camelCase` names.
+    CamelCase,
+    /// `snake_case` names.
+    SnakeCase,
+    /// The names from the schema, as they are, e.g. to keep `SCREAMING_SNAKE_CASE` enum values.
+    /// Only keywords are escaped.
+    None,
+}
+
+impl RenameAll {
+    /// The name in this casing, usable as an identifier.
+    pub(crate) fn apply(self, name: &str) -> String {
+        let name = match self {
+            Self::CamelCase => name.to_mixed_case(),
+            Self::SnakeCase => name.to_snake_case(),
+            Self::None => name.to_owned(),
+        };
+
+        crate::shared::keyword_replace(&name)
+    }
+}
+
+impl std::str::FromStr for RenameAll {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, ()> {
+        match s.trim() {
+            "camelCase" => Ok(RenameAll::CamelCase),
+            "snake_case" => Ok(RenameAll::SnakeCase),
+            "none" => Ok(RenameAll::None),
+            _ => Err(()),
+        }
+    }
+}
+
+/// The identifier of a response field: in the casing of `rename_all` when it is set, of the
+/// normalization otherwise.
+pub(crate) fn response_field_ident(
+    norm: Normalization,
+    rename_all: Option<RenameAll>,
+    name: &str,
+) -> Result<Ident, failure::Error> {
+    match rename_all {
+        Some(rename_all) => Ok(Ident::new(&rename_all.apply(name), Span::call_site())),
+        None => norm.field_ident(name),
+    }
+}
+
+/// The name of the variant generated for an enum value: in the casing of `rename_all` when it is
+/// set, of the normalization otherwise.
+pub(crate) fn enum_variant_name(
+    norm: Normalization,
+    rename_all: Option<RenameAll>,
+    value: &str,
+) -> String {
+    match rename_all {
+        Some(rename_all) => rename_all.apply(value),
+        None => norm
+            .enum_variant(crate::shared::keyword_replace(value))
+            .into_owned(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn preserved_field_names_are_only_escaped() {
+        let ident = |name| {
+            Normalization::Preserve
+                .field_ident(name)
+                .unwrap()
+                .to_string()
+        };
+
+        assert_eq!(ident("firstName"), "firstName");
+        assert_eq!(ident("type"), "type_");
+        assert_eq!(
+            Normalization::None.field_ident("firstName").unwrap(),
+            "first_name"
+        );
+    }
+
+    #[test]
+    fn preserved_field_names_must_be_identifiers() {
+        assert_eq!(
+            Normalization::Preserve
+                .field_ident("café")
+                .unwrap_err()
+                .to_string(),
+            "The field `café` is not a valid Rust identifier, so it cannot keep its name with `normalization = \"preserve\"`."
+        );
+        assert_eq!(Normalization::None.field_ident("café").unwrap(), "caf_");
+    }
+
+    #[test]
+    fn rename_all_overrides_the_casing_of_the_normalization() {
+        let variant = |rename_all| enum_variant_name(Normalization::Rust, rename_all, "NEW_HOPE");
+        let field = |rename_all| {
+            response_field_ident(Normalization::None, rename_all, "created_at")
+                .unwrap()
+                .to_string()
+        };
+
+        assert_eq!(variant(None), "NewHope");
+        assert_eq!(variant(Some(RenameAll::None)), "NEW_HOPE");
+        assert_eq!(variant(Some(RenameAll::SnakeCase)), "new_hope");
+        assert_eq!(field(Some(RenameAll::CamelCase)), "createdAt");
+        assert_eq!(field(Some(RenameAll::None)), "created_at");
+        assert_eq!("camelCase".parse(), Ok(RenameAll::CamelCase));
+        assert_eq!("CamelCase".parse::<RenameAll>(), Err(()));
+    }
+}
@@ -24,9 +24,48 @@ pub struct GqlObjectField<'schema> {
     pub name: &'schema str,
     pub type_: FieldType<'schema>,
     pub deprecation: DeprecationStatus,
+    /// The arguments of the field, with their types, sorted by name so that SDL and JSON schemas
+    /// give the same list. Input object fields have none.
+    pub arguments: Vec<(&'schema str, FieldType<'schema>)>,
 }
 
-fn parse_deprecation_info(field: &schema::Field) -> DeprecationStatus {
+/// The arguments of a field of a GraphQL schema.
+pub(crate) fn sdl_field_arguments(field: &schema::Field) -> Vec<(&str, FieldType<'_>)> {
+    let mut arguments: Vec<_> = field
+        .arguments
+        .iter()
+        .map(|argument| {
+            (
+                argument.name.as_str(),
+                FieldType::from(&argument.value_type),
+            )
+        })
+        .collect();
+    arguments.sort_by_key(|(name, _)| *name);
+    arguments
+}
+
+/// The arguments of a field of an introspection response.
+pub(crate) fn introspected_field_arguments(
+    field: &graphql_introspection_query::introspection_response::FullTypeFields,
+) -> Vec<(&str, FieldType<'_>)> {
+    let mut arguments: Vec<_> = field
+        .args
+        .as_deref()
+        .unwrap_or(&[])
+        .iter()
+        .filter_map(Option::as_ref)
+        .filter_map(|argument| {
+            let argument = &argument.input_value;
+            Some((argument.name.as_deref()?, argument.type_.as_ref()?.into()))
+        })
+        .collect();
+    arguments.sort_by_key(|(name, _)| *name);
+    arguments
+}
+
+/// The deprecation of a field of an SDL schema, from its `@deprecated` directive.
+pub(crate) fn parse_deprecation_info(field: &schema::Field) -> DeprecationStatus {
     let deprecated = field
         .directives
         .iter()
@@ -50,6 +89,17 @@ fn parse_deprecation_info(field: &schema::Field) -> DeprecationStatus {
     }
 }
 
+/// The deprecation of a field of an introspection response.
+pub(crate) fn introspected_deprecation_info(
+    field: &graphql_introspection_query::introspection_response::FullTypeFields,
+) -> DeprecationStatus {
+    if field.is_deprecated.unwrap_or(false) {
+        DeprecationStatus::Deprecated(field.deprecation_reason.clone())
+    } else {
+        DeprecationStatus::Current
+    }
+}
+
 impl<'schema> GqlObject<'schema> {
     pub fn new(name: &'schema str, description: Option<&'schema str>) -> GqlObject<'schema> {
         GqlObject {
@@ -64,12 +114,13 @@ impl<'schema> GqlObject<'schema> {
         let description = obj.description.as_deref();
         let mut item = GqlObject::new(&obj.name, description);
         item.fields.extend(obj.fields.iter().map(|f| {
-            let deprecation = parse_deprecation_info(&f);
+            let deprecation = parse_deprecation_info(f);
             GqlObjectField {
                 description: f.description.as_deref(),
                 name: &f.name,
                 type_: FieldType::from(&f.field_type),
                 deprecation,
+                arguments: sdl_field_arguments(f),
             }
         }));
         item
@@ -80,18 +131,17 @@ impl<'schema> GqlObject<'schema> {
     ) -> Self {
         let description = obj.description.as_deref();
         let mut item = GqlObject::new(obj.name.as_ref().expect("missing object name"), description);
-        let fields = obj.fields.as_ref().unwrap().iter().filter_map(|t| {
+        // Root types without any visible field (e.g. the `Query` type of a mutation-only
+        // service) can be introspected with a null field list.
+        let fields = obj.fields.as_deref().unwrap_or(&[]).iter().filter_map(|t| {
             t.as_ref().map(|t| {
-                let deprecation = if t.is_deprecated.unwrap_or(false) {
-                    DeprecationStatus::Deprecated(t.deprecation_reason.clone())
-                } else {
-                    DeprecationStatus::Current
-                };
+                let deprecation = introspected_deprecation_info(t);
                 GqlObjectField {
                     description: t.description.as_deref(),
                     name: t.name.as_ref().expect("field name"),
                     type_: FieldType::from(t.type_.as_ref().expect("field type")),
                     deprecation,
+                    arguments: introspected_field_arguments(t),
                 }
             })
         });
@@ -107,7 +157,7 @@ impl<'schema> GqlObject<'schema> {
         }
         self.is_required.set(true);
         self.fields.iter().for_each(|field| {
-            schema.require(&field.type_.inner_name_str());
+            schema.require(field.type_.inner_name_str());
         })
     }
 
@@ -117,19 +167,36 @@ impl<'schema> GqlObject<'schema> {
         selection: &Selection<'_>,
         prefix: &str,
     ) -> Result<TokenStream, failure::Error> {
-        let derives = query_context.response_derives();
         let name = Ident::new(prefix, Span::call_site());
-        let fields = self.response_fields_for_selection(query_context, selection, prefix)?;
-        let field_impls = self.field_impls_for_selection(query_context, selection, &prefix)?;
+        let (fields, field_impls) = crate::errors::join(
+            self.response_fields_for_selection(query_context, selection, prefix),
+            self.field_impls_for_selection(query_context, selection, prefix),
+        )?;
+        let fields = crate::skip_serde::response_struct_fields(query_context, &name, &fields)?;
+        let (derives, debug_impl) =
+            crate::compact_debug::response_struct_derives(query_context, &name, &fields)?;
+        let (serde_attribute, serde_impls) =
+            crate::response_serde::response_struct_serde(query_context, &name, self.name, &fields)?;
+        let (fields, getters) =
+            crate::getters::response_struct_fields(query_context, &name, &fields)?;
         let description = self.description.as_ref().map(|desc| quote!(#[doc = #desc]));
+        let attributes = query_context.selection_attributes(prefix);
         Ok(quote! {
             #(#field_impls)*
 
             #derives
+            #serde_attribute
             #description
+            #attributes
             pub struct #name {
                 #(#fields,)*
             }
+
+            #serde_impls
+
+            #debug_impl
+
+            #getters
         })
     }
 
@@ -148,7 +215,7 @@ impl<'schema> GqlObject<'schema> {
         selection: &Selection<'_>,
         prefix: &str,
     ) -> Result<Vec<TokenStream>, failure::Error> {
-        response_fields_for_selection(&self.name, &self.fields, query_context, selection, prefix)
+        response_fields_for_selection(self.name, &self.fields, query_context, selection, prefix)
     }
 }
 
@@ -224,4 +291,27 @@ mod test {
         let result = parse_deprecation_info(&mock_field(vec![]));
         assert_eq!(DeprecationStatus::Current, result);
     }
+
+    #[test]
+    fn introspected_object_without_fields() {
+        let full_type: graphql_introspection_query::introspection_response::FullType =
+            serde_json::from_str(
+                r#"{
+                    "kind": "OBJECT",
+                    "name": "Query",
+                    "description": null,
+                    "fields": null,
+                    "inputFields": null,
+                    "interfaces": [],
+                    "enumValues": null,
+                    "possibleTypes": null
+                }"#,
+            )
+            .unwrap();
+
+        let object = GqlObject::from_introspected_schema_json(&full_type);
+
+        assert_eq!(object.name, "Query");
+        assert_eq!(object.fields, vec![typename_field()]);
+    }
 }
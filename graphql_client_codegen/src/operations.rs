@@ -2,10 +2,13 @@ use crate::constants::*;
 use crate::query::QueryContext;
 use crate::selection::Selection;
 use crate::variables::Variable;
-use graphql_parser::query::OperationDefinition;
-use heck::SnakeCase;
+use failure::*;
+use graphql_parser::query::{
+    Definition, Directive, Document, OperationDefinition, Query, Value,
+};
 use proc_macro2::{Span, TokenStream};
 use quote::quote;
+use std::collections::BTreeSet;
 use syn::Ident;
 
 #[derive(Debug, Clone)]
@@ -21,6 +24,7 @@ pub struct Operation<'query> {
     pub operation_type: OperationType,
     pub variables: Vec<Variable<'query>>,
     pub selection: Selection<'query>,
+    pub directives: &'query [Directive],
 }
 
 impl<'query> Operation<'query> {
@@ -36,74 +40,575 @@ impl<'query> Operation<'query> {
     }
 
     pub(crate) fn is_subscription(&self) -> bool {
+        matches!(self.operation_type, OperationType::Subscription)
+    }
+
+    /// Whether the operation can safely be retried: queries and subscriptions, and mutations marked with `@idempotent`.
+    pub(crate) fn is_idempotent(&self) -> bool {
         match self.operation_type {
-            OperationType::Subscription => true,
-            _ => false,
+            OperationType::Query | OperationType::Subscription => true,
+            OperationType::Mutation => self
+                .directives
+                .iter()
+                .any(|directive| directive.name == crate::idempotent::IDEMPOTENT_DIRECTIVE),
+        }
+    }
+
+    /// Generate the `DIRECTIVES` constant, with the directives on the operation and their arguments.
+    pub(crate) fn expand_directives(&self) -> Result<TokenStream, failure::Error> {
+        let mut directives = Vec::with_capacity(self.directives.len());
+
+        for directive in self.directives {
+            let name = &directive.name;
+            let mut arguments = Vec::with_capacity(directive.arguments.len());
+
+            for (argument_name, value) in &directive.arguments {
+                let value = self.directive_argument(name, argument_name, value)?;
+                arguments.push(quote!((#argument_name, #value)));
+            }
+
+            directives.push(quote!(::graphql_client::Directive {
+                name: #name,
+                arguments: &[#(#arguments),*],
+            }));
+        }
+
+        Ok(quote!(
+            pub const DIRECTIVES: &[::graphql_client::Directive] = &[#(#directives),*];
+        ))
+    }
+
+    /// Generate the `SELECTED_FIELDS` constant, with the paths of the fields selected by the operation.
+    pub(crate) fn expand_selected_fields(&self, query: &'query Document) -> TokenStream {
+        let fragments = query
+            .definitions
+            .iter()
+            .filter_map(|definition| match definition {
+                Definition::Fragment(fragment) => Some((
+                    fragment.name.as_str(),
+                    Selection::from(&fragment.selection_set),
+                )),
+                Definition::Operation(_) => None,
+            })
+            .collect();
+        let paths = self.selection.field_paths(&fragments);
+
+        quote!(
+            pub const SELECTED_FIELDS: &[&str] = &[#(#paths),*];
+        )
+    }
+
+    /// The value of a directive argument, as exposed in the `DIRECTIVES` constant.
+    fn directive_argument(
+        &self,
+        directive_name: &str,
+        argument_name: &str,
+        value: &Value,
+    ) -> Result<String, failure::Error> {
+        match value {
+            Value::String(s) => Ok(s.clone()),
+            Value::Int(i) => i.as_i64().map(|i| i.to_string()).ok_or_else(|| {
+                format_err!(
+                    "Argument `{}` of the `@{}` directive on operation `{}` is out of range.",
+                    argument_name,
+                    directive_name,
+                    self.name
+                )
+            }),
+            Value::Float(f) => Ok(f.to_string()),
+            Value::Boolean(b) => Ok(b.to_string()),
+            Value::Enum(en) => Ok(en.clone()),
+            Value::Null => Ok("null".to_owned()),
+            Value::Variable(variable) => Err(format_err!(
+                "Argument `{}` of the `@{}` directive on operation `{}` is the variable `${}`. Only literals are supported in operation directives.",
+                argument_name,
+                directive_name,
+                self.name,
+                variable
+            )),
+            Value::List(_) | Value::Object(_) => Err(format_err!(
+                "Argument `{}` of the `@{}` directive on operation `{}` is not a scalar. Only scalar literals are supported in operation directives.",
+                argument_name,
+                directive_name,
+                self.name
+            )),
         }
     }
 
     /// Generate the Variables struct and all the necessary supporting code.
-    pub(crate) fn expand_variables(&self, context: &QueryContext<'_, '_>) -> TokenStream {
+    pub(crate) fn expand_variables(
+        &self,
+        context: &QueryContext<'_, '_>,
+    ) -> Result<TokenStream, failure::Error> {
         let variables = &self.variables;
-        let variables_derives = context.variables_derives();
+        let struct_name = Ident::new("Variables", Span::call_site());
 
         if variables.is_empty() {
-            return quote! {
+            // The derives are checked for redacted fields, which the unit struct does not have.
+            let (_, variables_debug_impl) = crate::compact_debug::variables_struct_derives(
+                context,
+                &struct_name,
+                &TokenStream::new(),
+                &[],
+            )?;
+            let variables_derives = context.variables_derives_without_serde("Variables");
+            // A derived `Deserialize` would only accept `null`.
+            let deserialize_impl = if context.derives_variables_deserialize_for("Variables") {
+                Some(quote! {
+                    impl<'de> serde::Deserialize<'de> for Variables {
+                        fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> ::std::result::Result<Self, D::Error> {
+                            <serde::de::IgnoredAny as serde::Deserialize>::deserialize(deserializer)?;
+                            Ok(Variables)
+                        }
+                    }
+                })
+            } else {
+                None
+            };
+
+            // A derived `Serialize` would serialize the unit struct as `null`, which some servers
+            // reject: the variables are an empty object.
+            return Ok(quote! {
                 #variables_derives
                 pub struct Variables;
-            };
+
+                impl serde::Serialize for Variables {
+                    fn serialize<S: serde::Serializer>(&self, serializer: S) -> ::std::result::Result<S::Ok, S::Error> {
+                        serde::ser::SerializeMap::end(serializer.serialize_map(Some(0))?)
+                    }
+                }
+
+                #deserialize_impl
+
+                #variables_debug_impl
+            });
         }
 
+        crate::errors::collect(variables.iter().map(|variable| {
+            let type_name = variable.ty.inner_name_str();
+
+            if context.schema.is_input_type(type_name) {
+                Ok(())
+            } else {
+                Err(crate::errors::at(
+                    variable.position,
+                    format_err!(
+                        "The variable `${}` of the operation `{}` has the type `{}`, which is not a scalar, enum or input object of the schema.",
+                        variable.name,
+                        self.name,
+                        type_name
+                    ),
+                ))
+            }
+        }))?;
+
+        // The names are checked when the fields are generated.
+        let field_name = |variable: &Variable<'_>| {
+            let rust_safe_field_name = context.normalization.field_name(variable.name);
+            Ident::new(&rust_safe_field_name, Span::call_site())
+        };
+
+        let borrowed_type = |variable: &Variable<'_>| borrowed_variable_type(context, variable);
+
+        let field_type = |variable: &Variable<'_>| {
+            context
+                .cursors
+                .variable_type(context, variable)
+                .or_else(|| borrowed_type(variable))
+                .unwrap_or_else(|| variable.ty.to_rust(context, ""))
+        };
+
+        let generics = if self.has_variables_lifetime(context) {
+            quote!(<'a>)
+        } else {
+            TokenStream::new()
+        };
+
         let fields = variables.iter().map(|variable| {
-            let ty = variable.ty.to_rust(context, "");
-            let rust_safe_field_name =
-                crate::shared::keyword_replace(&variable.name.to_snake_case());
-            let rename =
-                crate::shared::field_rename_annotation(&variable.name, &rust_safe_field_name);
-            let name = Ident::new(&rust_safe_field_name, Span::call_site());
-
-            quote!(#rename pub #name: #ty)
+            let ty = field_type(variable);
+            let name = context.normalization.field_ident(variable.name)?;
+            let rename = crate::shared::field_rename_annotation(variable.name, &name.to_string());
+            // A derived `Deserialize` only borrows the `&'a str` fields by default.
+            let borrow = if borrowed_type(variable).is_some()
+                && context.derives_variables_deserialize_for("Variables")
+            {
+                Some(quote!(#[serde(borrow)]))
+            } else {
+                None
+            };
+            let skip_none = if context.skip_serializing_none && variable.ty.is_optional() {
+                Some(quote!(#[serde(skip_serializing_if = "Option::is_none")]))
+            } else {
+                None
+            };
+            let date_time = context.date_times.serde_attribute(&variable.ty);
+
+            Ok(quote!(#rename #borrow #skip_none #date_time pub #name: #ty))
         });
+        let fields = crate::errors::collect(fields)?;
+        let (variables_derives, variables_debug_impl) =
+            crate::compact_debug::variables_struct_derives(
+                context,
+                &struct_name,
+                &generics,
+                &fields,
+            )?;
 
         let default_constructors = variables
             .iter()
             .map(|variable| variable.generate_default_value_constructor(context));
 
-        quote! {
+        // The variables that are not optional must be set in the struct.
+        let defaulted_variables: Vec<_> = variables
+            .iter()
+            .filter(|variable| variable.default.is_some() && variable.ty.is_optional())
+            .collect();
+
+        let with_defaults = if defaulted_variables.is_empty() {
+            None
+        } else {
+            let defaults = defaulted_variables.iter().map(|variable| {
+                let name = field_name(variable);
+                let default_value_constructor = variable.default_value_constructor_name();
+
+                quote! {
+                    if self.#name.is_none() {
+                        self.#name = Self::#default_value_constructor();
+                    }
+                }
+            });
+
+            Some(quote! {
+                /// The variables, with the default values from the query for the ones that are not set.
+                pub fn with_defaults(mut self) -> Self {
+                    #(#defaults)*
+                    self
+                }
+            })
+        };
+
+        // `Default` can be implemented when all the variables can be left out: they have a default
+        // value or they are optional.
+        let default_impl = if !context.derives_variables_default()
+            && variables
+                .iter()
+                .all(|variable| variable.default.is_some() || variable.ty.is_optional())
+        {
+            let defaults = variables.iter().map(|variable| {
+                let name = field_name(variable);
+
+                if variable.default.is_some() {
+                    let default_value_constructor = variable.default_value_constructor_name();
+                    quote!(#name: Self::#default_value_constructor())
+                } else {
+                    quote!(#name: None)
+                }
+            });
+
+            Some(quote! {
+                impl #generics ::std::default::Default for Variables #generics {
+                    fn default() -> Self {
+                        Variables {
+                            #(#defaults,)*
+                        }
+                    }
+                }
+            })
+        } else {
+            None
+        };
+
+        let builder = self.expand_variables_builder(field_name, field_type, &generics);
+
+        Ok(quote! {
             #variables_derives
-            pub struct Variables {
+            pub struct Variables #generics {
                 #(#fields,)*
             }
 
-            impl Variables {
+            impl #generics Variables #generics {
                 #(#default_constructors)*
+
+                #with_defaults
+            }
+
+            #default_impl
+
+            #builder
+
+            #variables_debug_impl
+        })
+    }
+
+    /// Whether the `Variables` struct has the `'a` lifetime of the `variables_lifetime` option,
+    /// because some of the variables are borrowed.
+    pub(crate) fn has_variables_lifetime(&self, context: &QueryContext<'_, '_>) -> bool {
+        self.variables
+            .iter()
+            .any(|variable| borrowed_variable_type(context, variable).is_some())
+    }
+
+    /// The generated names of the input objects and enums in the borrowed lists of the variables,
+    /// and of the ones in their fields.
+    pub(crate) fn borrowed_list_types(&self, context: &QueryContext<'_, '_>) -> BTreeSet<String> {
+        let norm = context.normalization;
+        let mut pending: Vec<&str> = self
+            .variables
+            .iter()
+            .filter(|variable| {
+                variable.ty.is_indirected() && borrowed_variable_type(context, variable).is_some()
+            })
+            .map(|variable| variable.ty.inner_name_str())
+            .collect();
+        let mut visited = BTreeSet::new();
+        let mut types = BTreeSet::new();
+
+        while let Some(type_name) = pending.pop() {
+            if !visited.insert(type_name) {
+                continue;
+            }
+
+            if let Some(input) = context.schema.inputs.get(type_name) {
+                types.insert(input.name_ident(norm).to_string());
+                let field_types = input
+                    .fields
+                    .values()
+                    .map(|field| field.type_.inner_name_str());
+                pending.extend(field_types);
+            } else if let Some(enm) = context.schema.enums.get(type_name) {
+                types.insert(enm.name_ident(norm).to_string());
+            }
+        }
+
+        types
+    }
+
+    /// The `VariablesBuilder`, for the operations with many optional variables. The optional
+    /// variables and the ones with a default value are set with its methods, and the other ones are
+    /// the arguments of `build`, so that a missing variable does not compile.
+    fn expand_variables_builder(
+        &self,
+        field_name: impl Fn(&Variable<'_>) -> Ident,
+        field_type: impl Fn(&Variable<'_>) -> TokenStream,
+        generics: &TokenStream,
+    ) -> TokenStream {
+        let (required, settable): (Vec<&Variable<'_>>, Vec<&Variable<'_>>) = self
+            .variables
+            .iter()
+            .partition(|variable| variable.default.is_none() && !variable.ty.is_optional());
+
+        // The fields are `None` until they are set, so that an optional variable explicitly set to
+        // `None` does not get its default value.
+        let builder_fields = settable.iter().map(|variable| {
+            let name = field_name(variable);
+            let ty = field_type(variable);
+
+            quote!(#name: Option<#ty>)
+        });
+
+        // The setters are prefixed, so that they do not clash with `build`.
+        let setters = settable.iter().map(|variable| {
+            let name = field_name(variable);
+            let setter = setter_name(&name);
+            let ty = field_type(variable);
+
+            if variable.ty.is_optional() {
+                quote! {
+                    pub fn #setter(mut self, #name: impl Into<#ty>) -> Self {
+                        self.#name = Some(#name.into());
+                        self
+                    }
+                }
+            } else {
+                quote! {
+                    pub fn #setter(mut self, #name: #ty) -> Self {
+                        self.#name = Some(#name);
+                        self
+                    }
+                }
+            }
+        });
+
+        let build_arguments = required.iter().map(|variable| {
+            let name = field_name(variable);
+            let ty = field_type(variable);
+            quote!(#name: #ty)
+        });
+
+        let build_fields = self.variables.iter().map(|variable| {
+            let name = field_name(variable);
+            let default_value_constructor = variable.default_value_constructor_name();
+
+            match (&variable.default, variable.ty.is_optional()) {
+                (None, false) => quote!(#name),
+                (None, true) => quote!(#name: self.#name.unwrap_or(None)),
+                (Some(_), _) => {
+                    quote!(#name: self.#name.unwrap_or_else(Variables::#default_value_constructor))
+                }
+            }
+        });
+
+        // The borrowed variables may all be arguments of `build`.
+        let lifetime_marker = if generics.is_empty() {
+            None
+        } else {
+            Some(quote!(_lifetime: ::std::marker::PhantomData<&'a ()>,))
+        };
+
+        quote! {
+            #[derive(Default)]
+            pub struct VariablesBuilder #generics {
+                #(#builder_fields,)*
+                #lifetime_marker
+            }
+
+            impl #generics Variables #generics {
+                /// A builder for the variables, with the optional ones and the ones with a default value left out.
+                pub fn builder() -> VariablesBuilder #generics {
+                    VariablesBuilder::default()
+                }
+            }
+
+            impl #generics VariablesBuilder #generics {
+                #(#setters)*
+
+                /// The variables. The ones that were not set are `None`, or their default value if they have one.
+                pub fn build(self, #(#build_arguments),*) -> Variables #generics {
+                    Variables {
+                        #(#build_fields,)*
+                    }
+                }
             }
         }
     }
 }
 
-impl<'query> std::convert::From<&'query OperationDefinition> for Operation<'query> {
-    fn from(definition: &'query OperationDefinition) -> Operation<'query> {
-        match *definition {
+/// The name of the builder method setting the variable with this field name: `with_type` for
+/// `type_`.
+fn setter_name(field_name: &Ident) -> Ident {
+    let field_name = field_name.to_string();
+    let field_name = field_name.trim_start_matches("r#");
+    let name = match field_name.strip_suffix('_') {
+        Some(keyword) if crate::shared::is_keyword(keyword) => keyword,
+        _ => field_name,
+    };
+
+    Ident::new(&format!("with_{}", name), Span::call_site())
+}
+
+/// The type of a variable borrowed for the `'a` lifetime of `Variables`, with the
+/// `variables_lifetime` option. The cursors are owned newtypes.
+fn borrowed_variable_type(
+    context: &QueryContext<'_, '_>,
+    variable: &Variable<'_>,
+) -> Option<TokenStream> {
+    if context.variables_lifetime && context.cursors.variable_type(context, variable).is_none() {
+        variable.ty.to_borrowed_rust(context)
+    } else {
+        None
+    }
+}
+
+impl<'query> std::convert::TryFrom<&'query OperationDefinition> for Operation<'query> {
+    type Error = failure::Error;
+
+    fn try_from(definition: &'query OperationDefinition) -> Result<Operation<'query>, Self::Error> {
+        let operation = match *definition {
             OperationDefinition::Query(ref q) => Operation {
-                name: q.name.clone().expect("unnamed operation"),
+                name: operation_name(&q.name, "query")?,
                 operation_type: OperationType::Query,
                 variables: q.variable_definitions.iter().map(|v| v.into()).collect(),
                 selection: (&q.selection_set).into(),
+                directives: &q.directives,
             },
             OperationDefinition::Mutation(ref m) => Operation {
-                name: m.name.clone().expect("unnamed operation"),
+                name: operation_name(&m.name, "mutation")?,
                 operation_type: OperationType::Mutation,
                 variables: m.variable_definitions.iter().map(|v| v.into()).collect(),
                 selection: (&m.selection_set).into(),
+                directives: &m.directives,
             },
             OperationDefinition::Subscription(ref s) => Operation {
-                name: s.name.clone().expect("unnamed operation"),
+                name: operation_name(&s.name, "subscription")?,
                 operation_type: OperationType::Subscription,
                 variables: s.variable_definitions.iter().map(|v| v.into()).collect(),
                 selection: (&s.selection_set).into(),
+                directives: &s.directives,
             },
-            OperationDefinition::SelectionSet(_) => panic!(SELECTION_SET_AT_ROOT),
+            OperationDefinition::SelectionSet(_) => {
+                return Err(format_err!("{}", SELECTION_SET_AT_ROOT))
+            }
+        };
+
+        Ok(operation)
+    }
+}
+
+/// The name of an operation. The anonymous operation of a document with a single operation is
+/// named by [`name_anonymous_operation`], so the other anonymous operations are an error.
+fn operation_name(name: &Option<String>, keyword: &str) -> Result<String, failure::Error> {
+    name.clone().ok_or_else(|| {
+        format_err!(
+            "Anonymous {keyword} operations are only supported when they are the only operation of the query document. Instead of `{keyword} {{ ... }}`, write `{keyword} MyOperation {{ ... }}`.",
+            keyword = keyword
+        )
+    })
+}
+
+/// Name the operation of a query document with a single anonymous operation (`query { ... }` or
+/// `{ ... }`), since the generated module and the operation name sent to the server need one. The
+/// name is `default_name` (the struct name in derive mode), or `AnonymousQuery`,
+/// `AnonymousMutation` or `AnonymousSubscription`. The query sent to the server is printed from
+/// the renamed document, so that the server finds the operation by its name.
+pub(crate) fn name_anonymous_operation(
+    query_string: String,
+    mut query: Document,
+    default_name: Option<&str>,
+) -> (String, Document) {
+    {
+        let mut operations = query
+            .definitions
+            .iter_mut()
+            .filter_map(|definition| match definition {
+                Definition::Operation(operation) => Some(operation),
+                Definition::Fragment(_) => None,
+            });
+
+        let operation = match (operations.next(), operations.next()) {
+            (Some(operation), None) => operation,
+            _ => return (query_string, query),
+        };
+
+        if let OperationDefinition::SelectionSet(selection_set) = operation {
+            let selection_set = selection_set.clone();
+            *operation = OperationDefinition::Query(Query {
+                position: selection_set.span.0,
+                name: None,
+                variable_definitions: Vec::new(),
+                directives: Vec::new(),
+                selection_set,
+            });
         }
+
+        let (name, keyword) = match operation {
+            OperationDefinition::Query(query) => (&mut query.name, "Query"),
+            OperationDefinition::Mutation(mutation) => (&mut mutation.name, "Mutation"),
+            OperationDefinition::Subscription(subscription) => {
+                (&mut subscription.name, "Subscription")
+            }
+            OperationDefinition::SelectionSet(_) => unreachable!("The selection set is a query."),
+        };
+
+        if name.is_some() {
+            return (query_string, query);
+        }
+
+        *name = Some(
+            default_name
+                .map(ToOwned::to_owned)
+                .unwrap_or_else(|| format!("Anonymous{}", keyword)),
+        );
     }
+
+    (query.to_string(), query)
 }
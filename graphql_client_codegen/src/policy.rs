@@ -0,0 +1,298 @@
+//! The `forbidden_fields` and `allowed_types` options: policies restricting what the operations and
+//! fragments of a query document may select.
+
+use crate::constants::TYPENAME_FIELD;
+use crate::objects::GqlObjectField;
+use crate::schema::Schema;
+use crate::selection::{Selection, SelectionItem};
+use failure::*;
+use graphql_parser::query;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// The fields that must never be selected, and the types whose fields can be selected.
+pub(crate) struct FieldPolicy<'a, 'schema> {
+    schema: &'a Schema<'schema>,
+    /// The forbidden fields, as `(type name, field name)` pairs.
+    forbidden_fields: Vec<(&'a str, &'a str)>,
+    allowed_types: Option<BTreeSet<&'a str>>,
+}
+
+/// What an operation or fragment selects that the policy does not allow.
+#[derive(Debug, PartialEq)]
+enum Violation {
+    /// A forbidden field, selected on the type it is defined on or on an interface it implements.
+    ForbiddenField { forbidden: String, selected: String },
+    /// A field selected on a type that is not allowed.
+    TypeNotAllowed { type_name: String, field: String },
+}
+
+impl<'a, 'schema> FieldPolicy<'a, 'schema> {
+    /// The policy defined by the options, or `None` when they don't restrict the selections.
+    pub(crate) fn new(
+        schema: &'a Schema<'schema>,
+        options: &'a crate::GraphQLClientCodegenOptions,
+    ) -> Result<Option<Self>, failure::Error> {
+        if options.forbidden_fields().is_none() && options.allowed_types().is_none() {
+            return Ok(None);
+        }
+
+        let forbidden_fields = comma_separated(options.forbidden_fields())
+            .map(|coordinate| parse_coordinate(schema, coordinate))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let allowed_types = options
+            .allowed_types()
+            .map(|types| {
+                comma_separated(Some(types))
+                    .map(|type_name| {
+                        if schema.is_composite(type_name) {
+                            Ok(type_name)
+                        } else {
+                            Err(format_err!(
+                                "The allowed type `{}` is not an object, interface or union type of the schema.",
+                                type_name
+                            ))
+                        }
+                    })
+                    .collect::<Result<BTreeSet<_>, _>>()
+            })
+            .transpose()?;
+
+        Ok(Some(FieldPolicy {
+            schema,
+            forbidden_fields,
+            allowed_types,
+        }))
+    }
+
+    /// Check the selection of an operation, including the fragments it spreads.
+    pub(crate) fn check_operation(
+        &self,
+        query_path: &std::path::Path,
+        query: &query::Document,
+        operation: &crate::operations::Operation<'_>,
+    ) -> Result<(), failure::Error> {
+        let fragments = fragments(query);
+        let root_name = operation.root_name(self.schema);
+
+        self.check_selection(
+            root_name,
+            &operation.selection,
+            &fragments,
+            &mut BTreeSet::new(),
+        )
+        .map_err(|violation| {
+            violation.into_error(query_path, &format!("operation `{}`", operation.name))
+        })
+    }
+
+    /// Check the selection of a fragment, including the fragments it spreads.
+    pub(crate) fn check_fragment(
+        &self,
+        query_path: &std::path::Path,
+        query: &query::Document,
+        fragment_name: &str,
+    ) -> Result<(), failure::Error> {
+        let fragments = fragments(query);
+        let (on, selection) = match fragments.get(fragment_name) {
+            Some(fragment) => fragment,
+            // Missing fragments are reported by the code generation.
+            None => return Ok(()),
+        };
+        let mut visited_fragments = BTreeSet::new();
+        visited_fragments.insert(fragment_name);
+
+        self.check_selection(on, selection, &fragments, &mut visited_fragments)
+            .map_err(|violation| {
+                violation.into_error(query_path, &format!("fragment `{}`", fragment_name))
+            })
+    }
+
+    fn check_selection<'q>(
+        &self,
+        type_name: &str,
+        selection: &'q Selection<'q>,
+        fragments: &'q BTreeMap<&'q str, (&'q str, Selection<'q>)>,
+        visited_fragments: &mut BTreeSet<&'q str>,
+    ) -> Result<(), Violation> {
+        for item in selection {
+            match item {
+                SelectionItem::Field(field) => {
+                    if field.name == TYPENAME_FIELD {
+                        continue;
+                    }
+
+                    self.check_field(type_name, field.name)?;
+
+                    // Unknown fields are reported by the code generation.
+                    if let Some(schema_field) = self.schema_field(type_name, field.name) {
+                        self.check_selection(
+                            schema_field.type_.inner_name_str(),
+                            &field.fields,
+                            fragments,
+                            visited_fragments,
+                        )?;
+                    }
+                }
+                SelectionItem::FragmentSpread(spread) => {
+                    if !visited_fragments.insert(spread.fragment_name) {
+                        continue;
+                    }
+
+                    if let Some((on, selection)) = fragments.get(spread.fragment_name) {
+                        self.check_selection(on, selection, fragments, visited_fragments)?;
+                    }
+                }
+                SelectionItem::InlineFragment(inline_fragment) => {
+                    self.check_selection(
+                        inline_fragment.on,
+                        &inline_fragment.fields,
+                        fragments,
+                        visited_fragments,
+                    )?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn check_field(&self, type_name: &str, field: &str) -> Result<(), Violation> {
+        if let Some(allowed_types) = &self.allowed_types {
+            if !allowed_types.contains(type_name) {
+                return Err(Violation::TypeNotAllowed {
+                    type_name: type_name.to_owned(),
+                    field: field.to_owned(),
+                });
+            }
+        }
+
+        let forbidden = self
+            .forbidden_fields
+            .iter()
+            .find(|(forbidden_type, forbidden_field)| {
+                *forbidden_field == field && self.may_resolve_to(type_name, forbidden_type)
+            });
+
+        match forbidden {
+            Some((forbidden_type, forbidden_field)) => Err(Violation::ForbiddenField {
+                forbidden: format!("{}.{}", forbidden_type, forbidden_field),
+                selected: format!("{}.{}", type_name, field),
+            }),
+            None => Ok(()),
+        }
+    }
+
+    /// Whether a field selected on `selected_type` may be the field of the same name on `other_type`: they are
+    /// the same type, or one is an interface implemented by the other.
+    fn may_resolve_to(&self, selected_type: &str, other_type: &str) -> bool {
+        let implements = |object: &str, interface: &str| {
+            self.schema
+                .interfaces
+                .get(interface)
+                .map(|iface| iface.implemented_by.contains(object))
+                .unwrap_or(false)
+        };
+
+        selected_type == other_type
+            || implements(other_type, selected_type)
+            || implements(selected_type, other_type)
+    }
+
+    fn schema_field(&self, type_name: &str, field_name: &str) -> Option<&GqlObjectField<'_>> {
+        self.schema
+            .object_or_interface_fields(type_name)?
+            .iter()
+            .find(|field| field.name == field_name)
+    }
+}
+
+impl Violation {
+    fn into_error(self, query_path: &std::path::Path, selected_by: &str) -> failure::Error {
+        match self {
+            Violation::ForbiddenField {
+                forbidden,
+                ref selected,
+            } if &forbidden == selected => format_err!(
+                "Policy error in {}: the {} selects the forbidden field `{}`.",
+                query_path.display(),
+                selected_by,
+                forbidden,
+            ),
+            Violation::ForbiddenField {
+                forbidden,
+                selected,
+            } => format_err!(
+                "Policy error in {}: the {} selects the forbidden field `{}` (as `{}`).",
+                query_path.display(),
+                selected_by,
+                forbidden,
+                selected,
+            ),
+            Violation::TypeNotAllowed { type_name, field } => format_err!(
+                "Policy error in {}: the {} selects `{}.{}`, but `{}` is not in the allowed types.",
+                query_path.display(),
+                selected_by,
+                type_name,
+                field,
+                type_name,
+            ),
+        }
+    }
+}
+
+/// Parse a `Type.field` schema coordinate, checking that the field exists.
+fn parse_coordinate<'a>(
+    schema: &Schema<'_>,
+    coordinate: &'a str,
+) -> Result<(&'a str, &'a str), failure::Error> {
+    let mut parts = coordinate.splitn(2, '.');
+    let (type_name, field_name) = match (parts.next(), parts.next()) {
+        (Some(type_name), Some(field_name)) => (type_name, field_name),
+        _ => {
+            return Err(format_err!(
+                "The forbidden field `{}` is not of the form `Type.field`.",
+                coordinate
+            ))
+        }
+    };
+
+    let exists = schema
+        .object_or_interface_fields(type_name)
+        .map(|fields| fields.iter().any(|field| field.name == field_name))
+        .unwrap_or(false);
+
+    if !exists {
+        return Err(format_err!(
+            "The forbidden field `{}` is not a field of an object or interface type of the schema.",
+            coordinate
+        ));
+    }
+
+    Ok((type_name, field_name))
+}
+
+fn comma_separated(list: Option<&str>) -> impl Iterator<Item = &str> {
+    list.into_iter()
+        .flat_map(|list| list.split(','))
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+}
+
+/// The fragments of the query document by name, with their type condition.
+fn fragments(query: &query::Document) -> BTreeMap<&str, (&str, Selection<'_>)> {
+    query
+        .definitions
+        .iter()
+        .filter_map(|definition| match definition {
+            query::Definition::Fragment(fragment) => {
+                let query::TypeCondition::On(on) = &fragment.type_condition;
+                Some((
+                    fragment.name.as_str(),
+                    (on.as_str(), Selection::from(&fragment.selection_set)),
+                ))
+            }
+            query::Definition::Operation(_) => None,
+        })
+        .collect()
+}
@@ -0,0 +1,139 @@
+//! Options set by `# graphql-client: option = value` comments at the top of a query file, for the
+//! settings that belong with the queries of the file rather than with the derive or the CLI
+//! invocation.
+
+use crate::GraphQLClientCodegenOptions;
+use failure::*;
+use std::path::Path;
+
+/// The prefix of the pragma comments.
+const PRAGMA_PREFIX: &str = "graphql-client:";
+
+/// The options pragmas can set.
+const PRAGMA_OPTIONS: &[&str] = &["normalization", "variables_derives", "response_derives"];
+
+/// Override the options with the pragmas of the query file. Only the comments before the first
+/// definition are read, so that a commented-out pragma further down has no effect.
+pub(crate) fn apply(
+    query_path: &Path,
+    query_string: &str,
+    options: &mut GraphQLClientCodegenOptions,
+) -> Result<(), failure::Error> {
+    let comments = query_string
+        .lines()
+        .map(str::trim)
+        .take_while(|line| line.is_empty() || line.starts_with('#'))
+        .filter_map(|line| line.strip_prefix('#'));
+
+    for comment in comments {
+        let pragma = match comment.trim().strip_prefix(PRAGMA_PREFIX) {
+            Some(pragma) => pragma,
+            None => continue,
+        };
+
+        let (option, value) = parse_pragma(pragma).ok_or_else(|| {
+            format_err!(
+                "Invalid pragma `#{}` in {}: expected `# {} option = value`.",
+                comment,
+                query_path.display(),
+                PRAGMA_PREFIX
+            )
+        })?;
+
+        match option {
+            "normalization" => {
+                let normalization = value.parse().map_err(|_| {
+                    format_err!(
+                        "Invalid normalization `{}` in the pragma of {}: expected `none`, `rust` or `preserve`.",
+                        value,
+                        query_path.display()
+                    )
+                })?;
+                options.set_normalization(normalization);
+            }
+            "variables_derives" => options.set_variables_derives(value.to_owned()),
+            "response_derives" => options.set_response_derives(value.to_owned()),
+            _ => {
+                return Err(format_err!(
+                    "The pragma of {} sets `{}`, which cannot be set by a pragma. The options that can are: {}.",
+                    query_path.display(),
+                    option,
+                    PRAGMA_OPTIONS.join(", ")
+                ))
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// The option and the value of `option = value`. The value can be quoted.
+fn parse_pragma(pragma: &str) -> Option<(&str, &str)> {
+    let mut parts = pragma.splitn(2, '=');
+    let option = parts.next()?.trim();
+    let value = parts.next()?.trim();
+    let value = value
+        .strip_prefix('"')
+        .and_then(|value| value.strip_suffix('"'))
+        .unwrap_or(value);
+
+    if option.is_empty() || value.is_empty() {
+        return None;
+    }
+
+    Some((option, value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::normalization::Normalization;
+    use crate::CodegenMode;
+
+    fn apply_to_defaults(query: &str) -> Result<GraphQLClientCodegenOptions, String> {
+        let mut options = GraphQLClientCodegenOptions::new(CodegenMode::Cli);
+        apply(Path::new("query.graphql"), query, &mut options).map_err(|err| err.to_string())?;
+        Ok(options)
+    }
+
+    fn pragma_error(query: &str) -> String {
+        match apply_to_defaults(query) {
+            Ok(_) => panic!("The pragmas of {:?} were accepted.", query),
+            Err(err) => err,
+        }
+    }
+
+    #[test]
+    fn leading_pragmas_override_the_options() {
+        let options = apply_to_defaults(
+            "# The user queries.\n\
+             # graphql-client: normalization = rust\n\
+             #graphql-client: response_derives = \"Debug, PartialEq\"\n\
+             \n\
+             query UserQuery { user { name } }\n\
+             # graphql-client: variables_derives = Clone\n",
+        )
+        .unwrap();
+
+        assert_eq!(options.normalization(), Normalization::Rust);
+        assert_eq!(options.response_derives(), Some("Debug, PartialEq"));
+        // The pragmas after the first definition are ignored.
+        assert_eq!(options.variables_derives(), None);
+    }
+
+    #[test]
+    fn invalid_pragmas_are_rejected() {
+        assert_eq!(
+            pragma_error("# graphql-client: normalization\nquery Q { a }"),
+            "Invalid pragma `# graphql-client: normalization` in query.graphql: expected `# graphql-client: option = value`."
+        );
+        assert_eq!(
+            pragma_error("# graphql-client: normalization = kebab\nquery Q { a }"),
+            "Invalid normalization `kebab` in the pragma of query.graphql: expected `none`, `rust` or `preserve`."
+        );
+        assert_eq!(
+            pragma_error("# graphql-client: module_name = users\nquery Q { a }"),
+            "The pragma of query.graphql sets `module_name`, which cannot be set by a pragma. The options that can are: normalization, variables_derives, response_derives."
+        );
+    }
+}
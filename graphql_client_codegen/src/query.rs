@@ -16,8 +16,49 @@ pub(crate) struct QueryContext<'query, 'schema: 'query> {
     pub schema: &'schema Schema<'schema>,
     pub deprecation_strategy: DeprecationStrategy,
     pub normalization: Normalization,
+    /// The casing of the response fields and enum variants, if it overrides the one of the
+    /// normalization.
+    pub rename_all: Option<crate::normalization::RenameAll>,
+    /// Whether nullable list and string fields should be deserialized to their default value
+    /// instead of an `Option`.
+    pub null_coercion: bool,
+    /// Whether the selection structs that are not part of the facade module should be hidden from the docs.
+    pub facade: bool,
+    /// The maximum number of list items shown by the `Debug` impl of response structs, when compact debug output is enabled.
+    pub compact_debug: Option<usize>,
+    /// Whether the fields of response structs are private, with getters.
+    pub private_fields_with_getters: bool,
+    /// Whether the variant structs of unions implement a sealed member trait.
+    pub union_member_traits: bool,
+    /// Whether the enums of union and interface selections are untagged, for responses without `__typename`.
+    pub untagged_unions: bool,
+    /// Whether the errors about missing fields in responses name the GraphQL type of the struct.
+    pub graphql_error_names: bool,
+    /// Whether enums have a `description` method returning the schema description of the variants.
+    pub enum_descriptions: bool,
+    /// Whether enums are left without `#[non_exhaustive]`.
+    pub exhaustive_enums: bool,
+    /// The variant of enums for the values unknown to the schema.
+    pub enum_other_variant: Ident,
+    /// The schema file the enums are tested against, if they are.
+    pub enum_tests_schema: Option<String>,
+    /// The cursor newtypes of the connections selected by the operation.
+    pub cursors: crate::cursors::ConnectionCursors,
+    /// The types of the date and time scalars.
+    pub date_times: crate::date_times::DateTimeScalars,
+    /// Whether the variables that are `None` are left out of the serialized variables instead of
+    /// being serialized as `null`.
+    pub skip_serializing_none: bool,
+    /// Whether the strings and lists of the `Variables` struct are borrowed for its `'a` lifetime.
+    pub variables_lifetime: bool,
     variables_derives: Vec<Ident>,
     response_derives: Vec<Ident>,
+    /// The additional derives for specific types, by generated type name.
+    derives_for: BTreeMap<String, Vec<Ident>>,
+    /// The fields printed as `***` by the `Debug` impls, by generated type name.
+    redacted_fields: BTreeMap<String, Vec<String>>,
+    /// The fields with `#[serde(skip)]`, by generated type name.
+    skipped_fields: BTreeMap<String, Vec<String>>,
     serde_crate_path: Option<Path>,
 }
 
@@ -34,16 +75,35 @@ impl<'query, 'schema> QueryContext<'query, 'schema> {
             schema,
             deprecation_strategy,
             normalization,
+            rename_all: None,
+            null_coercion: false,
+            facade: false,
+            compact_debug: None,
+            private_fields_with_getters: false,
+            union_member_traits: false,
+            untagged_unions: false,
+            graphql_error_names: false,
+            enum_descriptions: false,
+            exhaustive_enums: true,
+            enum_other_variant: Ident::new("Other", Span::call_site()),
+            enum_tests_schema: None,
+            cursors: Default::default(),
+            date_times: Default::default(),
+            skip_serializing_none: false,
+            variables_lifetime: false,
             serde_crate_path,
             variables_derives: vec![Ident::new("Serialize", Span::call_site())],
             response_derives: vec![Ident::new("Deserialize", Span::call_site())],
+            derives_for: BTreeMap::new(),
+            redacted_fields: BTreeMap::new(),
+            skipped_fields: BTreeMap::new(),
         }
     }
 
     /// Mark a fragment as required, so code is actually generated for it.
     pub(crate) fn require_fragment(&self, typename_: &str) {
         if let Some(fragment) = self.fragments.get(typename_) {
-            fragment.require(&self);
+            fragment.require(self);
         }
     }
 
@@ -55,9 +115,28 @@ impl<'query, 'schema> QueryContext<'query, 'schema> {
             schema,
             deprecation_strategy: DeprecationStrategy::Allow,
             normalization: Normalization::None,
+            rename_all: None,
+            null_coercion: false,
+            facade: false,
+            compact_debug: None,
+            private_fields_with_getters: false,
+            union_member_traits: false,
+            untagged_unions: false,
+            graphql_error_names: false,
+            enum_descriptions: false,
+            exhaustive_enums: true,
+            enum_other_variant: Ident::new("Other", Span::call_site()),
+            enum_tests_schema: None,
+            cursors: Default::default(),
+            date_times: Default::default(),
+            skip_serializing_none: false,
+            variables_lifetime: false,
             serde_crate_path: None,
             variables_derives: vec![Ident::new("Serialize", Span::call_site())],
             response_derives: vec![Ident::new("Deserialize", Span::call_site())],
+            derives_for: BTreeMap::new(),
+            redacted_fields: BTreeMap::new(),
+            skipped_fields: BTreeMap::new(),
         }
     }
 
@@ -75,22 +154,40 @@ impl<'query, 'schema> QueryContext<'query, 'schema> {
             Ok(None) // we already expand enums separately
         } else if let Some(obj) = self.schema.objects.get(ty) {
             obj.is_required.set(true);
-            obj.response_for_selection(self, &selection, prefix)
+            obj.response_for_selection(self, selection, prefix)
                 .map(Some)
         } else if let Some(iface) = self.schema.interfaces.get(ty) {
             iface.is_required.set(true);
             iface
-                .response_for_selection(self, &selection, prefix)
+                .response_for_selection(self, selection, prefix)
                 .map(Some)
         } else if let Some(unn) = self.schema.unions.get(ty) {
             unn.is_required.set(true);
-            unn.response_for_selection(self, &selection, prefix)
+            unn.response_for_selection(self, selection, prefix)
                 .map(Some)
         } else {
             Err(format_err!("Unknown type: {}", ty))
         }
     }
 
+    /// Whether a response field of this type is deserialized with `null` coerced to the type's
+    /// default value. This only applies to nullable lists and strings.
+    pub(crate) fn coerces_null(&self, ty: &crate::field_type::FieldType<'_>) -> bool {
+        self.null_coercion
+            && ty.is_optional()
+            && (ty.is_list() || ty.inner_name_str() == crate::constants::string_type())
+    }
+
+    /// The attributes for the struct or enum generated for a selection. When generating a facade,
+    /// everything but the fragment structs is hidden from the docs.
+    pub(crate) fn selection_attributes(&self, name: &str) -> TokenStream {
+        if self.facade && !self.fragments.contains_key(name) {
+            quote!(#[doc(hidden)])
+        } else {
+            quote!()
+        }
+    }
+
     pub(crate) fn ingest_response_derives(
         &mut self,
         attribute_value: &str,
@@ -129,8 +226,105 @@ impl<'query, 'schema> QueryContext<'query, 'schema> {
         Ok(())
     }
 
-    pub(crate) fn variables_derives(&self) -> TokenStream {
-        let derives: BTreeSet<&Ident> = self.variables_derives.iter().collect();
+    /// Ingest the additional derives for specific types, in the `TypeName: Derive, Derive; OtherTypeName: Derive`
+    /// format.
+    pub(crate) fn ingest_derives_for(
+        &mut self,
+        attribute_value: &str,
+    ) -> Result<(), failure::Error> {
+        for (type_name, derives) in type_entries(attribute_value, "derives_for", "Derive")? {
+            self.derives_for
+                .entry(type_name.to_owned())
+                .or_default()
+                .extend(
+                    derives
+                        .into_iter()
+                        .map(|derive| Ident::new(derive, Span::call_site())),
+                );
+        }
+
+        Ok(())
+    }
+
+    /// The additional derives for the type with this generated name.
+    /// Derive `Clone` for the types with these generated names, in addition to the other derives.
+    pub(crate) fn derive_clone_for<'a>(&mut self, type_names: impl IntoIterator<Item = &'a str>) {
+        for type_name in type_names {
+            let derives = self.derives_for.entry(type_name.to_owned()).or_default();
+
+            if !derives.iter().any(|derive| derive == "Clone") {
+                derives.push(Ident::new("Clone", Span::call_site()));
+            }
+        }
+    }
+
+    fn derives_for(&self, type_name: &str) -> impl Iterator<Item = &Ident> {
+        self.record_lookup("derives_for", type_name);
+        self.derives_for.get(type_name).into_iter().flatten()
+    }
+
+    /// Ingest the fields to redact in the `Debug` impls, in the `TypeName: field, field; OtherTypeName: field`
+    /// format.
+    pub(crate) fn ingest_redact(&mut self, attribute_value: &str) -> Result<(), failure::Error> {
+        for (type_name, fields) in type_entries(attribute_value, "redact", "field")? {
+            self.redacted_fields
+                .entry(type_name.to_owned())
+                .or_default()
+                .extend(fields.into_iter().map(str::to_owned));
+        }
+
+        Ok(())
+    }
+
+    /// The fields of the type with this generated name that are printed as `***` by its `Debug` impl.
+    pub(crate) fn redacted_fields(&self, type_name: &str) -> &[String] {
+        self.record_lookup("redact", type_name);
+        self.redacted_fields
+            .get(type_name)
+            .map(Vec::as_slice)
+            .unwrap_or(&[])
+    }
+
+    /// Ingest the fields left out of (de)serialization, in the `TypeName: field, field; OtherTypeName: field`
+    /// format.
+    pub(crate) fn ingest_skip_serde(
+        &mut self,
+        attribute_value: &str,
+    ) -> Result<(), failure::Error> {
+        for (type_name, fields) in type_entries(attribute_value, "skip_serde", "field")? {
+            self.skipped_fields
+                .entry(type_name.to_owned())
+                .or_default()
+                .extend(fields.into_iter().map(str::to_owned));
+        }
+
+        Ok(())
+    }
+
+    /// The fields of the type with this generated name that are left out of (de)serialization.
+    pub(crate) fn skipped_fields(&self, type_name: &str) -> &[String] {
+        self.record_lookup("skip_serde", type_name);
+        self.skipped_fields
+            .get(type_name)
+            .map(Vec::as_slice)
+            .unwrap_or(&[])
+    }
+
+    /// Record that the generated type with this name can be named by the option, see
+    /// `unapplied_type_options`.
+    fn record_lookup(&self, option_name: &'static str, type_name: &str) {
+        self.schema
+            .type_option_lookups
+            .borrow_mut()
+            .insert((option_name, type_name.to_owned()));
+    }
+
+    pub(crate) fn variables_derives(&self, type_name: &str) -> TokenStream {
+        let derives: BTreeSet<&Ident> = self
+            .variables_derives
+            .iter()
+            .chain(self.derives_for(type_name))
+            .collect();
         let derives = derives.iter();
         let serde_crate_attr = self.serde_crate_attr();
 
@@ -140,8 +334,29 @@ impl<'query, 'schema> QueryContext<'query, 'schema> {
         }
     }
 
-    pub(crate) fn response_derives(&self) -> TokenStream {
-        let derives: BTreeSet<&Ident> = self.response_derives.iter().collect();
+    pub(crate) fn response_derives(&self, type_name: &str) -> TokenStream {
+        let derives: BTreeSet<&Ident> = self
+            .response_derives
+            .iter()
+            .chain(self.derives_for(type_name))
+            .collect();
+        let derives = derives.iter();
+        let serde_crate_attr = self.serde_crate_attr();
+
+        quote! {
+            #[derive( #(#derives),* )]
+            #serde_crate_attr
+        }
+    }
+
+    /// The response derives, for structs that implement `Debug` manually.
+    pub(crate) fn response_derives_without_debug(&self, type_name: &str) -> TokenStream {
+        let derives: BTreeSet<&Ident> = self
+            .response_derives
+            .iter()
+            .chain(self.derives_for(type_name))
+            .filter(|derive| *derive != "Debug")
+            .collect();
         let derives = derives.iter();
         let serde_crate_attr = self.serde_crate_attr();
 
@@ -151,7 +366,89 @@ impl<'query, 'schema> QueryContext<'query, 'schema> {
         }
     }
 
-    pub(crate) fn response_enum_derives(&self) -> TokenStream {
+    /// Whether `Default` is derived for the `Variables` struct.
+    pub(crate) fn derives_variables_default(&self) -> bool {
+        self.derives_variables_default_for("Variables")
+    }
+
+    /// Whether `Default` is derived for the variables type with this generated name.
+    pub(crate) fn derives_variables_default_for(&self, type_name: &str) -> bool {
+        self.variables_derives
+            .iter()
+            .chain(self.derives_for(type_name))
+            .any(|derive| derive == "Default")
+    }
+
+    /// Whether `Debug` is part of the response derives.
+    pub(crate) fn derives_response_debug(&self) -> bool {
+        self.response_derives.iter().any(|derive| derive == "Debug")
+    }
+
+    /// Whether `Debug` is derived for the response type with this generated name.
+    pub(crate) fn derives_response_debug_for(&self, type_name: &str) -> bool {
+        self.response_derives
+            .iter()
+            .chain(self.derives_for(type_name))
+            .any(|derive| derive == "Debug")
+    }
+
+    /// Whether `Serialize` is derived for the response type with this generated name.
+    pub(crate) fn derives_response_serialize_for(&self, type_name: &str) -> bool {
+        self.response_derives
+            .iter()
+            .chain(self.derives_for(type_name))
+            .any(|derive| derive == "Serialize")
+    }
+
+    /// Whether `Debug` is derived for the variables type with this generated name.
+    pub(crate) fn derives_variables_debug_for(&self, type_name: &str) -> bool {
+        self.variables_derives
+            .iter()
+            .chain(self.derives_for(type_name))
+            .any(|derive| derive == "Debug")
+    }
+
+    /// Whether `Deserialize` is derived for the variables type with this generated name.
+    pub(crate) fn derives_variables_deserialize_for(&self, type_name: &str) -> bool {
+        self.variables_derives
+            .iter()
+            .chain(self.derives_for(type_name))
+            .any(|derive| derive == "Deserialize")
+    }
+
+    /// The variables derives, for structs that implement `Serialize` and `Deserialize` manually.
+    pub(crate) fn variables_derives_without_serde(&self, type_name: &str) -> TokenStream {
+        let derives: BTreeSet<&Ident> = self
+            .variables_derives
+            .iter()
+            .chain(self.derives_for(type_name))
+            .filter(|derive| *derive != "Serialize" && *derive != "Deserialize")
+            .collect();
+        let derives = derives.iter();
+
+        quote! {
+            #[derive( #(#derives),* )]
+        }
+    }
+
+    /// The variables derives, for structs that implement `Debug` manually.
+    pub(crate) fn variables_derives_without_debug(&self, type_name: &str) -> TokenStream {
+        let derives: BTreeSet<&Ident> = self
+            .variables_derives
+            .iter()
+            .chain(self.derives_for(type_name))
+            .filter(|derive| *derive != "Debug")
+            .collect();
+        let derives = derives.iter();
+        let serde_crate_attr = self.serde_crate_attr();
+
+        quote! {
+            #[derive( #(#derives),* )]
+            #serde_crate_attr
+        }
+    }
+
+    pub(crate) fn response_enum_derives(&self, type_name: &str) -> TokenStream {
         let always_derives = [
             Ident::new("Eq", Span::call_site()),
             Ident::new("PartialEq", Span::call_site()),
@@ -166,6 +463,7 @@ impl<'query, 'schema> QueryContext<'query, 'schema> {
             })
             .collect();
         enum_derives.extend(always_derives.iter());
+        enum_derives.extend(self.derives_for(type_name));
         quote! {
             #[derive( #(#enum_derives),* )]
         }
@@ -182,6 +480,127 @@ impl<'query, 'schema> QueryContext<'query, 'schema> {
     }
 }
 
+/// A type name of the `derives_for`, `redact` or `skip_serde` option that no generated type looked
+/// up, like a misspelled or stale type name, so the option does not apply to anything.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct UnappliedTypeOption {
+    /// The name of the option (`redact`).
+    pub option: &'static str,
+    /// The type name, as written in the option.
+    pub type_name: String,
+}
+
+impl std::fmt::Display for UnappliedTypeOption {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "The {} type `{}` is not generated, or the option does not apply to it.",
+            self.option, self.type_name
+        )
+    }
+}
+
+/// The type names of the `derives_for`, `redact` and `skip_serde` options that no generated type
+/// looked up. The options of all the generated modules are checked against the types of all of
+/// them.
+pub(crate) fn unapplied_type_options<'a>(
+    schema: &Schema<'_>,
+    options: impl IntoIterator<Item = &'a crate::GraphQLClientCodegenOptions>,
+) -> Result<Vec<UnappliedTypeOption>, failure::Error> {
+    let lookups = schema.type_option_lookups.borrow();
+    let mut unapplied = BTreeSet::new();
+
+    for options in options {
+        let option_values = [
+            ("derives_for", "Derive", options.derives_for()),
+            ("redact", "field", options.redact()),
+            ("skip_serde", "field", options.skip_serde()),
+        ];
+
+        for (option, item, value) in option_values.iter() {
+            let value = match value {
+                Some(value) => value,
+                None => continue,
+            };
+
+            for (type_name, _) in type_entries(value, option, item)? {
+                if !lookups.contains(&(*option, type_name.to_owned())) {
+                    unapplied.insert(UnappliedTypeOption {
+                        option,
+                        type_name: type_name.to_owned(),
+                    });
+                }
+            }
+        }
+    }
+
+    Ok(unapplied.into_iter().collect())
+}
+
+/// The error for the unapplied type names, listing the types each option applies to.
+pub(crate) fn unapplied_type_options_error(
+    schema: &Schema<'_>,
+    unapplied: &[UnappliedTypeOption],
+) -> failure::Error {
+    let lookups = schema.type_option_lookups.borrow();
+    let errors = unapplied
+        .iter()
+        .map(|unapplied| {
+            let types: Vec<&str> = lookups
+                .iter()
+                .filter(|(option, _)| *option == unapplied.option)
+                .map(|(_, type_name)| type_name.as_str())
+                .collect();
+
+            format_err!("{} Types it applies to: {}", unapplied, types.join(", "))
+        })
+        .collect();
+
+    crate::errors::combine(errors)
+}
+
+/// The entries of an option in the `TypeName: item, item; OtherTypeName: item` format.
+fn type_entries<'a>(
+    attribute_value: &'a str,
+    option_name: &str,
+    item: &str,
+) -> Result<Vec<(&'a str, Vec<&'a str>)>, failure::Error> {
+    let mut entries = Vec::new();
+
+    for entry in attribute_value.split(';').map(str::trim) {
+        if entry.is_empty() {
+            continue;
+        }
+
+        let mut parts = entry.splitn(2, ':');
+        let (type_name, items) = match (parts.next(), parts.next()) {
+            (Some(type_name), Some(items)) if !type_name.trim().is_empty() => {
+                (type_name.trim(), items)
+            }
+            _ => {
+                return Err(format_err!(
+                    "Invalid {} entry `{}`, expected `TypeName: {}, {}`.",
+                    option_name,
+                    entry,
+                    item,
+                    item
+                ))
+            }
+        };
+
+        entries.push((
+            type_name,
+            items
+                .split(',')
+                .map(str::trim)
+                .filter(|item| !item.is_empty())
+                .collect(),
+        ));
+    }
+
+    Ok(entries)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -196,8 +615,8 @@ mod tests {
             .unwrap();
 
         assert_eq!(
-            context.response_derives().to_string(),
-            "# [ derive ( Deserialize , PartialEq , PartialOrd , Serialize ) ]"
+            context.response_derives("Response").to_string(),
+            "# [derive (Deserialize , PartialEq , PartialOrd , Serialize)]"
         );
     }
 
@@ -206,8 +625,8 @@ mod tests {
         let schema = crate::schema::Schema::new();
         let context = QueryContext::new_empty(&schema);
         assert_eq!(
-            context.response_enum_derives().to_string(),
-            "# [ derive ( Eq , PartialEq ) ]"
+            context.response_enum_derives("Episode").to_string(),
+            "# [derive (Eq , PartialEq)]"
         );
     }
 
@@ -221,8 +640,8 @@ mod tests {
             .unwrap();
 
         assert_eq!(
-            context.response_enum_derives().to_string(),
-            "# [ derive ( Eq , PartialEq , PartialOrd ) ]"
+            context.response_enum_derives("Episode").to_string(),
+            "# [derive (Eq , PartialEq , PartialOrd)]"
         );
     }
 
@@ -236,4 +655,43 @@ mod tests {
             .is_ok());
         assert!(context.ingest_response_derives("Serialize").is_err());
     }
+
+    #[test]
+    fn derives_for_apply_to_their_type_only() {
+        let schema = crate::schema::Schema::new();
+        let mut context = QueryContext::new_empty(&schema);
+
+        context
+            .ingest_derives_for("ResponseData: Clone, PartialEq; Episode: Hash")
+            .unwrap();
+
+        assert_eq!(
+            context.response_derives("ResponseData").to_string(),
+            "# [derive (Clone , Deserialize , PartialEq)]"
+        );
+        assert_eq!(
+            context.response_derives("MyQueryHero").to_string(),
+            "# [derive (Deserialize)]"
+        );
+        assert_eq!(
+            context.response_enum_derives("Episode").to_string(),
+            "# [derive (Eq , Hash , PartialEq)]"
+        );
+        assert!(context.ingest_derives_for("Clone").is_err());
+    }
+
+    #[test]
+    fn redacted_fields_apply_to_their_type_only() {
+        let schema = crate::schema::Schema::new();
+        let mut context = QueryContext::new_empty(&schema);
+
+        context
+            .ingest_redact("Variables: password, token; LoginLogin: token")
+            .unwrap();
+
+        assert_eq!(context.redacted_fields("Variables"), ["password", "token"]);
+        assert_eq!(context.redacted_fields("LoginLogin"), ["token"]);
+        assert!(context.redacted_fields("ResponseData").is_empty());
+        assert!(context.ingest_redact("password").is_err());
+    }
 }
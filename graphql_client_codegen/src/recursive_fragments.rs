@@ -0,0 +1,225 @@
+//! The `expand_recursive_fragments` option: self-recursive fragments expanded to a fixed depth
+//! instead of being boxed.
+
+use failure::*;
+use graphql_parser::query::{Definition, Document, FragmentDefinition, Selection, SelectionSet};
+
+/// The name of the fragment expanding `fragment_name` at this level, starting at 1.
+fn level_name(fragment_name: &str, level: usize) -> String {
+    if level == 1 {
+        fragment_name.to_owned()
+    } else {
+        format!("{}Level{}", fragment_name, level)
+    }
+}
+
+/// The query document with each self-recursive fragment replaced by `levels` fragments: the first
+/// one keeps the name of the fragment, `FragmentLevel2`, `FragmentLevel3`... are spread in its place
+/// by the previous level, and the last level leaves out the recursive spread, as well as the
+/// fields and inline fragments that only selected it.
+///
+/// Returns `None` when the document has no self-recursive fragment.
+pub(crate) fn expand(
+    document: &Document,
+    levels: usize,
+) -> Result<Option<Document>, failure::Error> {
+    if levels == 0 {
+        return Err(format_err!(
+            "expand_recursive_fragments must be at least 1."
+        ));
+    }
+
+    let fragment_names: Vec<&str> = document
+        .definitions
+        .iter()
+        .filter_map(|definition| match definition {
+            Definition::Fragment(fragment) => Some(fragment.name.as_str()),
+            Definition::Operation(_) => None,
+        })
+        .collect();
+
+    let mut expanded = false;
+    let mut definitions = Vec::with_capacity(document.definitions.len());
+
+    for definition in &document.definitions {
+        let fragment = match definition {
+            Definition::Fragment(fragment) if spreads(&fragment.selection_set, &fragment.name) => {
+                fragment
+            }
+            _ => {
+                definitions.push(definition.clone());
+                continue;
+            }
+        };
+
+        for level in 1..=levels {
+            definitions.push(Definition::Fragment(expand_level(
+                fragment,
+                level,
+                levels,
+                &fragment_names,
+            )?));
+        }
+        expanded = true;
+    }
+
+    if !expanded {
+        return Ok(None);
+    }
+
+    Ok(Some(Document { definitions }))
+}
+
+fn expand_level(
+    fragment: &FragmentDefinition,
+    level: usize,
+    levels: usize,
+    fragment_names: &[&str],
+) -> Result<FragmentDefinition, failure::Error> {
+    let name = level_name(&fragment.name, level);
+
+    if level > 1 && fragment_names.contains(&name.as_str()) {
+        return Err(format_err!(
+            "Cannot expand the recursive fragment `{}`: the query document already has a fragment named `{}`.",
+            fragment.name,
+            name
+        ));
+    }
+
+    let next_level = if level < levels {
+        Some(level_name(&fragment.name, level + 1))
+    } else {
+        None
+    };
+    let selection_set = replace_spreads(
+        &fragment.selection_set,
+        &fragment.name,
+        next_level.as_deref(),
+    );
+
+    if selection_set.items.is_empty() {
+        return Err(format_err!(
+            "Cannot expand the recursive fragment `{}`: it selects nothing but itself.",
+            fragment.name
+        ));
+    }
+
+    Ok(FragmentDefinition {
+        name,
+        selection_set,
+        ..fragment.clone()
+    })
+}
+
+/// The selection set with the spreads of `fragment_name` replaced by spreads of `next_level`, or
+/// left out when there is no next level. The fields and inline fragments left with an empty
+/// selection set are left out too.
+fn replace_spreads(
+    selection_set: &SelectionSet,
+    fragment_name: &str,
+    next_level: Option<&str>,
+) -> SelectionSet {
+    let items = selection_set
+        .items
+        .iter()
+        .filter_map(|item| match item {
+            Selection::FragmentSpread(spread) if spread.fragment_name == fragment_name => {
+                next_level.map(|next_level| {
+                    let mut spread = spread.clone();
+                    spread.fragment_name = next_level.to_owned();
+                    Selection::FragmentSpread(spread)
+                })
+            }
+            Selection::FragmentSpread(_) => Some(item.clone()),
+            Selection::Field(field) if field.selection_set.items.is_empty() => Some(item.clone()),
+            Selection::Field(field) => {
+                let selection_set =
+                    replace_spreads(&field.selection_set, fragment_name, next_level);
+                if selection_set.items.is_empty() {
+                    return None;
+                }
+                let mut field = field.clone();
+                field.selection_set = selection_set;
+                Some(Selection::Field(field))
+            }
+            Selection::InlineFragment(inline_fragment) => {
+                let selection_set =
+                    replace_spreads(&inline_fragment.selection_set, fragment_name, next_level);
+                if selection_set.items.is_empty() {
+                    return None;
+                }
+                let mut inline_fragment = inline_fragment.clone();
+                inline_fragment.selection_set = selection_set;
+                Some(Selection::InlineFragment(inline_fragment))
+            }
+        })
+        .collect();
+
+    SelectionSet {
+        items,
+        span: selection_set.span,
+    }
+}
+
+/// Whether the selection set spreads the fragment, in nested fields and inline fragments too.
+fn spreads(selection_set: &SelectionSet, fragment_name: &str) -> bool {
+    selection_set.items.iter().any(|item| match item {
+        Selection::FragmentSpread(spread) => spread.fragment_name == fragment_name,
+        Selection::Field(field) => spreads(&field.selection_set, fragment_name),
+        Selection::InlineFragment(inline_fragment) => {
+            spreads(&inline_fragment.selection_set, fragment_name)
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const QUERY: &str = "
+        query Tree { root { ...Node } }
+
+        fragment Node on Node { name children { ...Node } }
+    ";
+
+    #[test]
+    fn expands_recursive_fragments_to_the_given_depth() {
+        let document = graphql_parser::parse_query(QUERY).unwrap();
+        let expanded = expand(&document, 3).unwrap().unwrap();
+        let expected = graphql_parser::parse_query(
+            "
+            query Tree { root { ...Node } }
+
+            fragment Node on Node { name children { ...NodeLevel2 } }
+
+            fragment NodeLevel2 on Node { name children { ...NodeLevel3 } }
+
+            fragment NodeLevel3 on Node { name }
+            ",
+        )
+        .unwrap();
+
+        assert_eq!(expanded.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn leaves_documents_without_recursive_fragments_alone() {
+        let document = graphql_parser::parse_query(
+            "query Q { root { ...Leaf } } fragment Leaf on Node { name }",
+        )
+        .unwrap();
+
+        assert!(expand(&document, 2).unwrap().is_none());
+    }
+
+    #[test]
+    fn level_names_must_be_free() {
+        let query = format!("{}\nfragment NodeLevel2 on Node {{ name }}", QUERY);
+        let document = graphql_parser::parse_query(&query).unwrap();
+
+        assert_eq!(
+            expand(&document, 2).unwrap_err().to_string(),
+            "Cannot expand the recursive fragment `Node`: the query document already has a fragment named `NodeLevel2`."
+        );
+    }
+}
@@ -0,0 +1,115 @@
+//! The serde impls of the response structs and union enums, when the derived ones do not do:
+//!
+//! - The structs with flattened fields, the fragment spreads and the `on` enums of interfaces, and
+//!   the tagged union enums serialize through `graphql_client::serde_helpers::DistinctKeys`. The
+//!   flattened fields and the tag can select the same fields, like `__typename`, so the keys of the
+//!   serialized structs are deduplicated for the response to deserialize again.
+//! - With the `graphql_error_names` option, the structs deserialize through
+//!   `graphql_client::serde_helpers::GraphQLErrorNames`, which names the GraphQL type of the struct
+//!   in the errors about missing fields.
+
+use crate::query::QueryContext;
+use proc_macro2::{Ident, TokenStream};
+use quote::quote;
+use syn::parse::Parser;
+
+/// The attribute and the `Serialize` and `Deserialize` impls of a response struct selecting fields
+/// of the `graphql_type` type, when it derives `Serialize` and has flattened fields, or with the
+/// `graphql_error_names` option.
+pub(crate) fn response_struct_serde(
+    context: &QueryContext<'_, '_>,
+    name: &Ident,
+    graphql_type: &str,
+    fields: &[TokenStream],
+) -> Result<(Option<TokenStream>, Option<TokenStream>), failure::Error> {
+    let distinct_keys = if context.derives_response_serialize_for(&name.to_string()) {
+        fields
+            .iter()
+            .map(|field| syn::Field::parse_named.parse2(field.clone()))
+            .collect::<Result<Vec<_>, _>>()?
+            .iter()
+            .any(is_flattened)
+    } else {
+        false
+    };
+
+    if !distinct_keys && !context.graphql_error_names {
+        return Ok((None, None));
+    }
+
+    let error_names = Some(graphql_type).filter(|_| context.graphql_error_names);
+
+    Ok(remote_impls(context, name, distinct_keys, error_names))
+}
+
+/// The attribute and the `Serialize` and `Deserialize` impls of a union enum, when it derives
+/// `Serialize` and is tagged with `__typename`. The tag is serialized in the map of the variant
+/// structs, which can also have `__typename` in their flattened fragments.
+pub(crate) fn union_enum_serde(
+    context: &QueryContext<'_, '_>,
+    name: &Ident,
+) -> (Option<TokenStream>, Option<TokenStream>) {
+    if context.untagged_unions || !context.derives_response_serialize_for(&name.to_string()) {
+        return (None, None);
+    }
+
+    remote_impls(context, name, true, None)
+}
+
+/// The derived impls become inherent functions with `#[serde(remote = "Self")]`, and the trait
+/// impls call them, through the serializer and deserializer wrappers.
+fn remote_impls(
+    context: &QueryContext<'_, '_>,
+    name: &Ident,
+    distinct_keys: bool,
+    error_names: Option<&str>,
+) -> (Option<TokenStream>, Option<TokenStream>) {
+    let attribute = quote!(#[serde(remote = "Self")]);
+
+    let serializer = if distinct_keys {
+        quote!(::graphql_client::serde_helpers::DistinctKeys(serializer))
+    } else {
+        quote!(serializer)
+    };
+    let serialize_impl = if context.derives_response_serialize_for(&name.to_string()) {
+        Some(quote! {
+            impl serde::Serialize for #name {
+                fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                    #name::serialize(self, #serializer)
+                }
+            }
+        })
+    } else {
+        None
+    };
+
+    let deserializer = match error_names {
+        Some(graphql_type) => {
+            quote!(::graphql_client::serde_helpers::GraphQLErrorNames(deserializer, #graphql_type))
+        }
+        None => quote!(deserializer),
+    };
+    let impls = quote! {
+        #serialize_impl
+
+        impl<'de> serde::Deserialize<'de> for #name {
+            fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                #name::deserialize(#deserializer)
+            }
+        }
+    };
+
+    (Some(attribute), Some(impls))
+}
+
+fn is_flattened(field: &syn::Field) -> bool {
+    field.attrs.iter().any(|attr| {
+        attr.path.is_ident("serde")
+            && attr
+                .parse_args_with(
+                    syn::punctuated::Punctuated::<syn::Meta, syn::Token![,]>::parse_terminated,
+                )
+                .map(|metas| metas.iter().any(|meta| meta.path().is_ident("flatten")))
+                .unwrap_or(false)
+    })
+}
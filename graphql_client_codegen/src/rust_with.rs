@@ -0,0 +1,285 @@
+use failure::*;
+use graphql_parser::query::{Definition, Document, Field, OperationDefinition, Selection, Value};
+
+/// The name of the query-side directive that customizes the deserialization of a single field.
+pub(crate) const RUST_WITH_DIRECTIVE: &str = "rust_with";
+
+/// The arguments of a `@rust_with(module: "...", ty: "...")` directive on a field.
+///
+/// The field is generated with `#[serde(deserialize_with = "<module>::deserialize")]`, and with the `ty` Rust type instead of the one from the schema if it is provided. When the response type derives `Serialize`, the field also gets `serialize_with = "<module>::serialize"`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RustWith<'query> {
+    /// The path to the module providing the `deserialize` function, and the `serialize` function if the response type derives `Serialize`.
+    pub module: &'query str,
+    /// The Rust type of the field.
+    pub ty: Option<&'query str>,
+}
+
+impl<'query> RustWith<'query> {
+    /// Extract the `@rust_with` directive on a field, if there is one.
+    pub(crate) fn from_field(field: &'query Field) -> Result<Option<Self>, failure::Error> {
+        let directive = match field
+            .directives
+            .iter()
+            .find(|directive| directive.name == RUST_WITH_DIRECTIVE)
+        {
+            Some(directive) => directive,
+            None => return Ok(None),
+        };
+
+        let mut module = None;
+        let mut ty = None;
+
+        for (name, value) in &directive.arguments {
+            let value = match value {
+                Value::String(value) => value.as_str(),
+                other => {
+                    return Err(format_err!(
+                        "The `{}` argument of @{} on field `{}` must be a string, found {}",
+                        name,
+                        RUST_WITH_DIRECTIVE,
+                        field.name,
+                        other,
+                    ))
+                }
+            };
+
+            match name.as_str() {
+                "module" => {
+                    syn::parse_str::<syn::Path>(value).map_err(|_| {
+                        format_err!(
+                            "The `module` argument of @{} on field `{}` is not a valid path: {}",
+                            RUST_WITH_DIRECTIVE,
+                            field.name,
+                            value,
+                        )
+                    })?;
+                    module = Some(value);
+                }
+                "ty" => {
+                    syn::parse_str::<syn::Type>(value).map_err(|_| {
+                        format_err!(
+                            "The `ty` argument of @{} on field `{}` is not a valid type: {}",
+                            RUST_WITH_DIRECTIVE,
+                            field.name,
+                            value,
+                        )
+                    })?;
+                    ty = Some(value);
+                }
+                other => {
+                    return Err(format_err!(
+                        "Unknown argument `{}` for @{} on field `{}` (expected `module` and optionally `ty`)",
+                        other,
+                        RUST_WITH_DIRECTIVE,
+                        field.name,
+                    ))
+                }
+            }
+        }
+
+        let module = module.ok_or_else(|| {
+            format_err!(
+                "Missing `module` argument for @{} on field `{}`",
+                RUST_WITH_DIRECTIVE,
+                field.name,
+            )
+        })?;
+
+        Ok(Some(RustWith { module, ty }))
+    }
+
+    /// The field attribute. The `serialize` function is only required when the response type derives `Serialize`.
+    pub(crate) fn attribute(&self, serialize: bool) -> proc_macro2::TokenStream {
+        let deserialize_with = format!("{}::deserialize", self.module);
+
+        if serialize {
+            let serialize_with = format!("{}::serialize", self.module);
+            quote::quote!(#[serde(deserialize_with = #deserialize_with, serialize_with = #serialize_with)])
+        } else {
+            quote::quote!(#[serde(deserialize_with = #deserialize_with)])
+        }
+    }
+
+    /// The Rust type of the field, if overridden.
+    pub(crate) fn rust_type(&self) -> Option<proc_macro2::TokenStream> {
+        self.ty.map(|ty| {
+            syn::parse_str::<syn::Type>(ty)
+                .map(|ty| quote::quote!(#ty))
+                .expect("validated in from_field")
+        })
+    }
+}
+
+/// Check all the `@rust_with` directives in the query document, so malformed ones fail code generation.
+pub(crate) fn validate(document: &Document) -> Result<(), failure::Error> {
+    fn validate_selection_set(
+        selection_set: &graphql_parser::query::SelectionSet,
+    ) -> Result<(), failure::Error> {
+        for item in &selection_set.items {
+            match item {
+                Selection::Field(field) => {
+                    RustWith::from_field(field)?;
+                    validate_selection_set(&field.selection_set)?;
+                }
+                Selection::InlineFragment(inline) => validate_selection_set(&inline.selection_set)?,
+                Selection::FragmentSpread(_) => (),
+            }
+        }
+
+        Ok(())
+    }
+
+    for definition in &document.definitions {
+        match definition {
+            Definition::Operation(OperationDefinition::Query(q)) => {
+                validate_selection_set(&q.selection_set)?
+            }
+            Definition::Operation(OperationDefinition::Mutation(m)) => {
+                validate_selection_set(&m.selection_set)?
+            }
+            Definition::Operation(OperationDefinition::Subscription(s)) => {
+                validate_selection_set(&s.selection_set)?
+            }
+            Definition::Operation(OperationDefinition::SelectionSet(s)) => {
+                validate_selection_set(s)?
+            }
+            Definition::Fragment(fragment) => validate_selection_set(&fragment.selection_set)?,
+        }
+    }
+
+    Ok(())
+}
+
+/// Remove the `@rust_with` directives, which the server does not know about, from the query document. Returns whether there was one.
+pub(crate) fn strip_directives(document: &mut Document) -> bool {
+    fn strip_selection_set(selection_set: &mut graphql_parser::query::SelectionSet) -> bool {
+        let mut stripped = false;
+
+        for item in selection_set.items.iter_mut() {
+            match item {
+                Selection::Field(field) => {
+                    let directives_count = field.directives.len();
+                    field
+                        .directives
+                        .retain(|directive| directive.name != RUST_WITH_DIRECTIVE);
+                    stripped |= field.directives.len() != directives_count;
+                    stripped |= strip_selection_set(&mut field.selection_set);
+                }
+                Selection::InlineFragment(inline) => {
+                    stripped |= strip_selection_set(&mut inline.selection_set)
+                }
+                Selection::FragmentSpread(_) => (),
+            }
+        }
+
+        stripped
+    }
+
+    let mut stripped = false;
+
+    for definition in document.definitions.iter_mut() {
+        stripped |= match definition {
+            Definition::Operation(OperationDefinition::Query(q)) => {
+                strip_selection_set(&mut q.selection_set)
+            }
+            Definition::Operation(OperationDefinition::Mutation(m)) => {
+                strip_selection_set(&mut m.selection_set)
+            }
+            Definition::Operation(OperationDefinition::Subscription(s)) => {
+                strip_selection_set(&mut s.selection_set)
+            }
+            Definition::Operation(OperationDefinition::SelectionSet(s)) => strip_selection_set(s),
+            Definition::Fragment(fragment) => strip_selection_set(&mut fragment.selection_set),
+        };
+    }
+
+    stripped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn first_field(document: &Document) -> &Field {
+        match &document.definitions[0] {
+            Definition::Operation(OperationDefinition::Query(q)) => match &q.selection_set.items[0]
+            {
+                Selection::Field(field) => field,
+                _ => unreachable!(),
+            },
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn rust_with_is_extracted() {
+        let document = graphql_parser::parse_query(
+            r#"query Q { tags @rust_with(module: "crate::parsers::list", ty: "Vec<String>") }"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            RustWith::from_field(first_field(&document)).unwrap(),
+            Some(RustWith {
+                module: "crate::parsers::list",
+                ty: Some("Vec<String>"),
+            })
+        );
+    }
+
+    #[test]
+    fn rust_with_serializes_only_with_serialize_derived() {
+        let rust_with = RustWith {
+            module: "crate::parsers::list",
+            ty: None,
+        };
+
+        assert_eq!(
+            rust_with.attribute(false).to_string(),
+            r#"# [serde (deserialize_with = "crate::parsers::list::deserialize")]"#
+        );
+        assert_eq!(
+            rust_with.attribute(true).to_string(),
+            r#"# [serde (deserialize_with = "crate::parsers::list::deserialize" , serialize_with = "crate::parsers::list::serialize")]"#
+        );
+    }
+
+    #[test]
+    fn rust_with_unknown_argument_names_the_field() {
+        let document = graphql_parser::parse_query(
+            r#"query Q { tags @rust_with(module: "parsers", type: "Vec<String>") }"#,
+        )
+        .unwrap();
+
+        let err = validate(&document).unwrap_err().to_string();
+        assert!(err.contains("Unknown argument `type`"));
+        assert!(err.contains("field `tags`"));
+    }
+
+    #[test]
+    fn rust_with_missing_module_fails() {
+        let document =
+            graphql_parser::parse_query(r#"query Q { tags @rust_with(ty: "Vec<String>") }"#)
+                .unwrap();
+
+        let err = validate(&document).unwrap_err().to_string();
+        assert!(err.contains("Missing `module` argument"));
+    }
+
+    #[test]
+    fn rust_with_directives_are_stripped() {
+        let mut document = graphql_parser::parse_query(
+            r#"query Q { tags @rust_with(module: "parsers") @include(if: true) name }"#,
+        )
+        .unwrap();
+
+        assert!(strip_directives(&mut document));
+        let stripped = document.to_string();
+        assert!(!stripped.contains("rust_with"));
+        assert!(stripped.contains("@include(if: true)"));
+
+        let mut document = graphql_parser::parse_query("query Q { name }").unwrap();
+        assert!(!strip_directives(&mut document));
+    }
+}
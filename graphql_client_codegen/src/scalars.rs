@@ -1,4 +1,4 @@
-use crate::normalization::Normalization;
+use crate::query::QueryContext;
 use quote::quote;
 use std::cell::Cell;
 
@@ -11,13 +11,17 @@ pub struct Scalar<'schema> {
 
 impl<'schema> Scalar<'schema> {
     // TODO: do something smarter here
-    pub fn to_rust(&self, norm: Normalization) -> proc_macro2::TokenStream {
+    pub fn to_rust(&self, context: &QueryContext<'_, '_>) -> proc_macro2::TokenStream {
         use proc_macro2::{Ident, Span};
 
-        let name = norm.scalar_name(self.name);
+        let name = context.normalization.scalar_name(self.name);
         let ident = Ident::new(&name, Span::call_site());
         let description = &self.description.map(|d| quote!(#[doc = #d]));
 
-        quote!(#description type #ident = super::#ident;)
+        // The date and time scalars are mapped to the types of their crate.
+        match context.date_times.rust_type(self.name) {
+            Some(ty) => quote!(#description type #ident = #ty;),
+            None => quote!(#description type #ident = super::#ident;),
+        }
     }
 }
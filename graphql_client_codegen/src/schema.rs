@@ -1,4 +1,3 @@
-use crate::deprecation::DeprecationStatus;
 use crate::enums::{EnumVariant, GqlEnum};
 use crate::field_type::FieldType;
 use crate::inputs::GqlInput;
@@ -8,6 +7,7 @@ use crate::scalars::Scalar;
 use crate::unions::GqlUnion;
 use failure::*;
 use graphql_parser::{self, schema};
+use std::cell::RefCell;
 use std::collections::{BTreeMap, BTreeSet};
 
 pub(crate) const DEFAULT_SCALARS: &[&str] = &["ID", "String", "Int", "Float", "Boolean"];
@@ -24,6 +24,9 @@ pub(crate) struct Schema<'schema> {
     pub(crate) query_type: Option<&'schema str>,
     pub(crate) mutation_type: Option<&'schema str>,
     pub(crate) subscription_type: Option<&'schema str>,
+    /// The generated types that looked up their entry in the `derives_for`, `redact` and
+    /// `skip_serde` options, by option name, across all the generated modules.
+    pub(crate) type_option_lookups: RefCell<BTreeSet<(&'static str, String)>>,
 }
 
 impl<'schema> Schema<'schema> {
@@ -38,6 +41,7 @@ impl<'schema> Schema<'schema> {
             query_type: None,
             mutation_type: None,
             subscription_type: None,
+            type_option_lookups: RefCell::new(BTreeSet::new()),
         }
     }
 
@@ -46,8 +50,7 @@ impl<'schema> Schema<'schema> {
         impls: BTreeMap<&'schema str, Vec<&'schema str>>,
     ) -> Result<(), failure::Error> {
         impls
-            .into_iter()
-            .map(|(iface_name, implementors)| {
+            .into_iter().try_for_each(|(iface_name, implementors)| {
                 let iface = self
                     .interfaces
                     .get_mut(&iface_name)
@@ -55,7 +58,6 @@ impl<'schema> Schema<'schema> {
                 iface.implemented_by = implementors.iter().cloned().collect();
                 Ok(())
             })
-            .collect()
     }
 
     pub(crate) fn require(&self, typename_: &str) {
@@ -85,6 +87,36 @@ impl<'schema> Schema<'schema> {
         DEFAULT_SCALARS.iter().any(|s| s == &type_name) || self.scalars.contains_key(type_name)
     }
 
+    /// Whether the type can be the type of a variable: a scalar, an enum or an input object.
+    pub(crate) fn is_input_type(&self, type_name: &str) -> bool {
+        self.contains_scalar(type_name)
+            || self.enums.contains_key(type_name)
+            || self.inputs.contains_key(type_name)
+    }
+
+    /// The fields of an object or interface type.
+    pub(crate) fn object_or_interface_fields(
+        &self,
+        type_name: &str,
+    ) -> Option<&[crate::objects::GqlObjectField<'schema>]> {
+        self.objects
+            .get(type_name)
+            .map(|object| object.fields.as_slice())
+            .or_else(|| {
+                self.interfaces
+                    .get(type_name)
+                    .map(|iface| iface.fields.as_slice())
+            })
+    }
+
+    /// Whether the type is an object, interface or union, that is a type whose fields must be
+    /// selected.
+    pub(crate) fn is_composite(&self, type_name: &str) -> bool {
+        self.objects.contains_key(type_name)
+            || self.interfaces.contains_key(type_name)
+            || self.unions.contains_key(type_name)
+    }
+
     pub(crate) fn fragment_target(
         &self,
         target_name: &str,
@@ -127,7 +159,7 @@ impl<'schema> std::convert::From<&'schema graphql_parser::schema::Document> for
 
                         schema
                             .objects
-                            .insert(&obj.name, GqlObject::from_graphql_parser_object(&obj));
+                            .insert(&obj.name, GqlObject::from_graphql_parser_object(obj));
                     }
                     schema::TypeDefinition::Enum(enm) => {
                         schema.enums.insert(
@@ -179,7 +211,8 @@ impl<'schema> std::convert::From<&'schema graphql_parser::schema::Document> for
                                 description: f.description.as_deref(),
                                 name: f.name.as_str(),
                                 type_: FieldType::from(&f.field_type),
-                                deprecation: DeprecationStatus::Current,
+                                deprecation: crate::objects::parse_deprecation_info(f),
+                                arguments: crate::objects::sdl_field_arguments(f),
                             }));
                         schema.interfaces.insert(&interface.name, iface);
                     }
@@ -258,13 +291,12 @@ impl<'schema>
                         .as_ref()
                         .expect("enum variants")
                         .iter()
-                        .map(|t| {
+                        .filter_map(|t| {
                             t.as_ref().map(|t| EnumVariant {
                                 description: t.description.as_deref(),
                                 name: t.name.as_deref().expect("enum variant name"),
                             })
                         })
-                        .filter_map(|t| t)
                         .collect();
                     let enm = GqlEnum {
                         name,
@@ -308,7 +340,7 @@ impl<'schema>
                     for implementing in ty
                         .interfaces
                         .as_deref()
-                        .unwrap_or_else(|| &[])
+                        .unwrap_or(&[])
                         .iter()
                         .filter_map(Option::as_ref)
                         .map(|t| &t.type_ref.name)
@@ -335,7 +367,8 @@ impl<'schema>
                                 description: f.description.as_deref(),
                                 name: f.name.as_ref().expect("field name").as_str(),
                                 type_: FieldType::from(f.type_.as_ref().expect("field type")),
-                                deprecation: DeprecationStatus::Current,
+                                deprecation: crate::objects::introspected_deprecation_info(f),
+                                arguments: crate::objects::introspected_field_arguments(f),
                             }),
                     );
                     schema.interfaces.insert(name, iface);
@@ -355,8 +388,11 @@ impl<'schema>
     }
 }
 
-pub(crate) enum ParsedSchema {
+/// A schema document, before it is turned into a [Schema].
+pub enum ParsedSchema {
+    /// A schema in the GraphQL schema language (`.graphql` files).
     GraphQLParser(graphql_parser::schema::Document),
+    /// The response to an introspection query (`.json` files).
     Json(graphql_introspection_query::introspection_response::IntrospectionResponse),
 }
 
@@ -369,10 +405,340 @@ impl<'schema> From<&'schema ParsedSchema> for Schema<'schema> {
     }
 }
 
+/// The directives every schema has. They are not counted in [SchemaInfo::directives].
+const BUILTIN_DIRECTIVES: &[&str] = &["skip", "include", "deprecated", "specifiedBy"];
+
+/// How the code generation handles a schema feature.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FeatureSupport {
+    /// The generated code takes the feature into account.
+    Supported,
+    /// The schema loads, but the feature has no effect on the generated code.
+    Ignored,
+    /// The generated code is wrong for the parts of the schema using the feature, which fails
+    /// later with unrelated errors. `graphql-client schema-info` reports it as an error.
+    Unsupported,
+}
+
+impl std::fmt::Display for FeatureSupport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            FeatureSupport::Supported => "supported",
+            FeatureSupport::Ignored => "ignored",
+            FeatureSupport::Unsupported => "unsupported",
+        })
+    }
+}
+
+/// A schema feature detected by [SchemaInfo].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum SchemaFeature {
+    /// A subscription root type.
+    Subscriptions,
+    /// Scalars other than the built-in ones.
+    CustomScalars,
+    /// Fields or enum values marked as deprecated.
+    Deprecations,
+    /// Definitions of directives other than the built-in ones.
+    CustomDirectives,
+    /// `extend type` and the other type extensions of SDL schemas.
+    TypeExtensions,
+    /// Input objects with the `@oneOf` directive.
+    OneOfInputObjects,
+    /// Interfaces implementing other interfaces.
+    InterfaceInheritance,
+}
+
+/// The capability table: the support of each feature, with what it means for the generated code.
+/// Keep it up to date when the support of a feature changes.
+const CAPABILITIES: &[(SchemaFeature, FeatureSupport, &str)] = &[
+    (
+        SchemaFeature::Subscriptions,
+        FeatureSupport::Supported,
+        "subscription operations are generated like queries",
+    ),
+    (
+        SchemaFeature::CustomScalars,
+        FeatureSupport::Supported,
+        "the parent module of the generated code defines a type for each of them",
+    ),
+    (
+        SchemaFeature::Deprecations,
+        FeatureSupport::Supported,
+        "the deprecation strategy applies to the deprecated fields",
+    ),
+    (
+        SchemaFeature::CustomDirectives,
+        FeatureSupport::Ignored,
+        "the directives of the queries are sent as written, without checking their definitions",
+    ),
+    (
+        SchemaFeature::TypeExtensions,
+        FeatureSupport::Unsupported,
+        "the extensions are dropped, so the fields they add are unknown to the queries",
+    ),
+    (
+        SchemaFeature::OneOfInputObjects,
+        FeatureSupport::Unsupported,
+        "the input objects are generated with all their fields, and serialize the unset ones as null",
+    ),
+    (
+        SchemaFeature::InterfaceInheritance,
+        FeatureSupport::Unsupported,
+        "the fragments on the implemented interfaces are not matched with the implementing ones",
+    ),
+];
+
+impl SchemaFeature {
+    fn capability(self) -> (FeatureSupport, &'static str) {
+        CAPABILITIES
+            .iter()
+            .find(|(feature, _, _)| *feature == self)
+            .map(|(_, support, note)| (*support, *note))
+            .expect("every feature is in the capability table")
+    }
+
+    /// How the code generation handles the feature.
+    pub fn support(self) -> FeatureSupport {
+        self.capability().0
+    }
+
+    /// What the support of the feature means for the generated code.
+    pub fn note(self) -> &'static str {
+        self.capability().1
+    }
+}
+
+impl std::fmt::Display for SchemaFeature {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            SchemaFeature::Subscriptions => "subscriptions",
+            SchemaFeature::CustomScalars => "custom scalars",
+            SchemaFeature::Deprecations => "deprecations",
+            SchemaFeature::CustomDirectives => "custom directives",
+            SchemaFeature::TypeExtensions => "type extensions",
+            SchemaFeature::OneOfInputObjects => "@oneOf input objects",
+            SchemaFeature::InterfaceInheritance => "interfaces implementing interfaces",
+        })
+    }
+}
+
+/// What a schema contains, from a pass over the schema document that does not generate code. The
+/// introspection types (`__Type`...), the built-in scalars and the built-in directives are not
+/// counted.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SchemaInfo {
+    /// The number of object types.
+    pub objects: usize,
+    /// The number of interfaces.
+    pub interfaces: usize,
+    /// The number of unions.
+    pub unions: usize,
+    /// The number of enums.
+    pub enums: usize,
+    /// The number of input objects.
+    pub inputs: usize,
+    /// The number of custom scalars.
+    pub scalars: usize,
+    /// The number of custom directive definitions.
+    pub directives: usize,
+    /// The name of the query root type.
+    pub query_type: String,
+    /// The name of the mutation root type, if the schema has one.
+    pub mutation_type: Option<String>,
+    /// The name of the subscription root type, if the schema has one.
+    pub subscription_type: Option<String>,
+    /// The features the schema uses.
+    pub features: BTreeSet<SchemaFeature>,
+}
+
+impl SchemaInfo {
+    /// The features of the schema the code generation does not support.
+    pub fn unsupported_features(&self) -> impl Iterator<Item = SchemaFeature> + '_ {
+        self.features
+            .iter()
+            .copied()
+            .filter(|feature| feature.support() == FeatureSupport::Unsupported)
+    }
+}
+
+impl From<&ParsedSchema> for SchemaInfo {
+    fn from(parsed_schema: &ParsedSchema) -> SchemaInfo {
+        let schema = Schema::from(parsed_schema);
+        // Without a schema definition, the root types are the types with the default names.
+        let root_type = |name: Option<&str>, default_name: &str| {
+            name.or_else(|| Some(default_name).filter(|name| schema.objects.contains_key(name)))
+                .map(ToOwned::to_owned)
+        };
+
+        let (directives, mut features) = match parsed_schema {
+            ParsedSchema::GraphQLParser(document) => sdl_directives_and_features(document),
+            ParsedSchema::Json(response) => introspected_directives_and_features(response),
+        };
+        let subscription_type = root_type(schema.subscription_type, "Subscription");
+
+        if subscription_type.is_some() {
+            features.insert(SchemaFeature::Subscriptions);
+        }
+
+        let scalars = schema
+            .scalars
+            .keys()
+            .filter(|name| !DEFAULT_SCALARS.contains(name))
+            .count();
+
+        if scalars > 0 {
+            features.insert(SchemaFeature::CustomScalars);
+        }
+
+        if directives > 0 {
+            features.insert(SchemaFeature::CustomDirectives);
+        }
+
+        SchemaInfo {
+            objects: count_types(&schema.objects),
+            interfaces: count_types(&schema.interfaces),
+            unions: count_types(&schema.unions),
+            enums: count_types(&schema.enums),
+            inputs: count_types(&schema.inputs),
+            scalars,
+            directives,
+            query_type: root_type(schema.query_type, "Query").unwrap_or_else(|| "Query".to_owned()),
+            mutation_type: root_type(schema.mutation_type, "Mutation"),
+            subscription_type,
+            features,
+        }
+    }
+}
+
+/// The number of types that are not introspection types.
+fn count_types<T>(types: &BTreeMap<&str, T>) -> usize {
+    types.keys().filter(|name| !name.starts_with("__")).count()
+}
+
+/// The number of custom directive definitions of an SDL schema, and the features its types use.
+fn sdl_directives_and_features(document: &schema::Document) -> (usize, BTreeSet<SchemaFeature>) {
+    let mut directives = 0;
+    let mut features = BTreeSet::new();
+    let is_deprecated = |directives: &[schema::Directive]| {
+        directives
+            .iter()
+            .any(|directive| directive.name == "deprecated")
+    };
+
+    for definition in &document.definitions {
+        use schema::{Definition, TypeDefinition};
+
+        let feature = match definition {
+            Definition::DirectiveDefinition(directive) => {
+                if !BUILTIN_DIRECTIVES.contains(&directive.name.as_str()) {
+                    directives += 1;
+                }
+                None
+            }
+            Definition::TypeExtension(_) => Some(SchemaFeature::TypeExtensions),
+            Definition::TypeDefinition(TypeDefinition::Object(object))
+                if object
+                    .fields
+                    .iter()
+                    .any(|field| is_deprecated(&field.directives)) =>
+            {
+                Some(SchemaFeature::Deprecations)
+            }
+            Definition::TypeDefinition(TypeDefinition::Interface(interface))
+                if interface
+                    .fields
+                    .iter()
+                    .any(|field| is_deprecated(&field.directives)) =>
+            {
+                Some(SchemaFeature::Deprecations)
+            }
+            Definition::TypeDefinition(TypeDefinition::Enum(enm))
+                if enm
+                    .values
+                    .iter()
+                    .any(|value| is_deprecated(&value.directives)) =>
+            {
+                Some(SchemaFeature::Deprecations)
+            }
+            Definition::TypeDefinition(TypeDefinition::InputObject(input))
+                if input
+                    .directives
+                    .iter()
+                    .any(|directive| directive.name == "oneOf") =>
+            {
+                Some(SchemaFeature::OneOfInputObjects)
+            }
+            _ => None,
+        };
+
+        features.extend(feature);
+    }
+
+    (directives, features)
+}
+
+/// The number of custom directives of an introspection response, and the features its types use.
+/// Introspection responses have no type extensions, and no `@oneOf` in the fields of the
+/// introspection query.
+fn introspected_directives_and_features(
+    response: &graphql_introspection_query::introspection_response::IntrospectionResponse,
+) -> (usize, BTreeSet<SchemaFeature>) {
+    use graphql_introspection_query::introspection_response::__TypeKind;
+
+    let mut features = BTreeSet::new();
+    let root = match response.as_schema().schema.as_ref() {
+        Some(root) => root,
+        None => return (0, features),
+    };
+
+    let directives = root
+        .directives
+        .iter()
+        .flatten()
+        .filter_map(Option::as_ref)
+        .filter_map(|directive| directive.name.as_deref())
+        .filter(|name| !BUILTIN_DIRECTIVES.contains(name))
+        .count();
+
+    for ty in root
+        .types
+        .iter()
+        .flatten()
+        .filter_map(|ty| ty.as_ref().map(|ty| &ty.full_type))
+    {
+        let deprecated_fields = ty
+            .fields
+            .iter()
+            .flatten()
+            .filter_map(Option::as_ref)
+            .any(|field| field.is_deprecated == Some(true));
+        let deprecated_values = ty
+            .enum_values
+            .iter()
+            .flatten()
+            .filter_map(Option::as_ref)
+            .any(|value| value.is_deprecated == Some(true));
+
+        if deprecated_fields || deprecated_values {
+            features.insert(SchemaFeature::Deprecations);
+        }
+
+        let implements_interfaces = ty.interfaces.iter().flatten().any(|iface| iface.is_some());
+
+        if matches!(ty.kind, Some(__TypeKind::INTERFACE)) && implements_interfaces {
+            features.insert(SchemaFeature::InterfaceInheritance);
+        }
+    }
+
+    (directives, features)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::constants::*;
+    use crate::deprecation::DeprecationStatus;
 
     #[test]
     fn build_schema_works() {
@@ -390,42 +756,52 @@ mod tests {
                         name: TYPENAME_FIELD,
                         type_: FieldType::new(string_type()),
                         deprecation: DeprecationStatus::Current,
+                        arguments: Vec::new(),
                     },
                     GqlObjectField {
                         description: None,
                         name: "id",
                         type_: FieldType::new("ID").nonnull(),
                         deprecation: DeprecationStatus::Current,
+                        arguments: Vec::new(),
                     },
                     GqlObjectField {
                         description: None,
                         name: "name",
                         type_: FieldType::new("String").nonnull(),
                         deprecation: DeprecationStatus::Current,
+                        arguments: Vec::new(),
                     },
                     GqlObjectField {
                         description: None,
                         name: "friends",
                         type_: FieldType::new("Character").list(),
                         deprecation: DeprecationStatus::Current,
+                        arguments: Vec::new(),
                     },
                     GqlObjectField {
                         description: None,
                         name: "friendsConnection",
                         type_: FieldType::new("FriendsConnection").nonnull(),
                         deprecation: DeprecationStatus::Current,
+                        arguments: vec![
+                            ("after", FieldType::new("ID")),
+                            ("first", FieldType::new("Int")),
+                        ],
                     },
                     GqlObjectField {
                         description: None,
                         name: "appearsIn",
                         type_: FieldType::new("Episode").list().nonnull(),
                         deprecation: DeprecationStatus::Current,
+                        arguments: Vec::new(),
                     },
                     GqlObjectField {
                         description: None,
                         name: "primaryFunction",
                         type_: FieldType::new("String"),
                         deprecation: DeprecationStatus::Current,
+                        arguments: Vec::new(),
                     },
                 ],
                 is_required: false.into(),
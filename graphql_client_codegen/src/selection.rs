@@ -1,31 +1,46 @@
 use crate::constants::*;
 use failure::*;
-use graphql_parser::query::SelectionSet;
-use std::collections::BTreeMap;
+use graphql_parser::query::{SelectionSet, Value};
+use graphql_parser::Pos;
+use heck::CamelCase;
+use std::collections::{BTreeMap, BTreeSet};
 
 /// A single object field as part of a selection.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq)]
 pub struct SelectionField<'query> {
     pub alias: Option<&'query str>,
     pub name: &'query str,
     pub fields: Selection<'query>,
+    /// The `@rust_with` directive on the field, if any.
+    pub rust_with: Option<crate::rust_with::RustWith<'query>>,
+    /// Whether the field has a `@skip` or `@include` directive, so the server may leave it out of
+    /// the response.
+    pub conditional: bool,
+    /// The arguments of the field, as written in the query.
+    pub arguments: &'query [(String, Value)],
+    /// Where the field is in the query document.
+    pub position: Pos,
 }
 
 /// A spread fragment in a selection (e.g. `...MyFragment`).
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq)]
 pub struct SelectionFragmentSpread<'query> {
     pub fragment_name: &'query str,
+    /// Where the spread is in the query document.
+    pub position: Pos,
 }
 
 /// An inline fragment as part of a selection (e.g. `...on MyThing { name }`).
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq)]
 pub struct SelectionInlineFragment<'query> {
     pub on: &'query str,
     pub fields: Selection<'query>,
+    /// Where the inline fragment is in the query document.
+    pub position: Pos,
 }
 
 /// An element in a query selection.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq)]
 pub enum SelectionItem<'query> {
     Field(SelectionField<'query>),
     FragmentSpread(SelectionFragmentSpread<'query>),
@@ -41,26 +56,35 @@ impl<'query> SelectionItem<'query> {
         }
         None
     }
+
+    /// Where the item is in the query document.
+    pub(crate) fn position(&self) -> Pos {
+        match self {
+            SelectionItem::Field(field) => field.position,
+            SelectionItem::FragmentSpread(spread) => spread.position,
+            SelectionItem::InlineFragment(inline_fragment) => inline_fragment.position,
+        }
+    }
 }
 
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq)]
 pub struct Selection<'query>(Vec<SelectionItem<'query>>);
 
 impl<'query> Selection<'query> {
     pub(crate) fn extract_typename<'s, 'context: 's>(
         &'s self,
         context: &'context crate::query::QueryContext<'_, '_>,
-    ) -> Option<&SelectionField<'_>> {
+    ) -> Option<&'s SelectionField<'s>> {
         // __typename is selected directly
         if let Some(field) = self.0.iter().filter_map(SelectionItem::as_typename).next() {
             return Some(field);
         };
 
         // typename is selected through a fragment
-        (&self)
+        self
             .into_iter()
             .filter_map(|f| match f {
-                SelectionItem::FragmentSpread(SelectionFragmentSpread { fragment_name }) => {
+                SelectionItem::FragmentSpread(SelectionFragmentSpread { fragment_name, .. }) => {
                     Some(fragment_name)
                 }
                 _ => None,
@@ -94,7 +118,7 @@ impl<'query> Selection<'query> {
                             Selection(items)
                         });
                 }
-                SelectionItem::FragmentSpread(SelectionFragmentSpread { fragment_name }) => {
+                SelectionItem::FragmentSpread(SelectionFragmentSpread { fragment_name, .. }) => {
                     let fragment = context
                         .fragments
                         .get(fragment_name)
@@ -108,6 +132,14 @@ impl<'query> Selection<'query> {
                             selected_variants,
                             selection_on,
                         )?;
+                    } else if fragment.is_recursive(context) {
+                        // Inlining the fields of a recursive fragment would never end: the variant
+                        // spreads it instead, and the fragment struct is boxed.
+                        selected_variants
+                            .entry(fragment.on.name())
+                            .or_insert_with(|| Selection(Vec::new()))
+                            .0
+                            .push(item.clone());
                     } else {
                         // Type-refining fragment
                         selected_variants
@@ -144,6 +176,31 @@ impl<'query> Selection<'query> {
         Ok(selected_variants)
     }
 
+    /// Where the first item of the selection is in the query document.
+    pub(crate) fn position(&self) -> Option<Pos> {
+        self.0.first().map(SelectionItem::position)
+    }
+
+    /// Where the selection refines the union or interface to the type: the inline fragment on
+    /// the type, or the spread of a fragment on the type.
+    pub(crate) fn variant_position(
+        &self,
+        context: &crate::query::QueryContext<'_, '_>,
+        on: &str,
+    ) -> Option<Pos> {
+        self.0.iter().find_map(|item| match item {
+            SelectionItem::InlineFragment(inline_fragment) if inline_fragment.on == on => {
+                Some(inline_fragment.position)
+            }
+            SelectionItem::FragmentSpread(spread) => context
+                .fragments
+                .get(spread.fragment_name)
+                .filter(|fragment| fragment.on.name() == on)
+                .map(|_| spread.position),
+            _ => None,
+        })
+    }
+
     #[cfg(test)]
     pub(crate) fn new_empty() -> Selection<'static> {
         Selection(Vec::new())
@@ -154,13 +211,36 @@ impl<'query> Selection<'query> {
         Selection(vec)
     }
 
-    pub(crate) fn contains_fragment(&self, fragment_name: &str) -> bool {
-        (&self).into_iter().any(|item| match item {
-            SelectionItem::Field(field) => field.fields.contains_fragment(fragment_name),
-            SelectionItem::InlineFragment(inline_fragment) => {
-                inline_fragment.fields.contains_fragment(fragment_name)
+    /// Whether the selection spreads the fragment, in its fields and inline fragments, or through
+    /// the other fragments it spreads. `visited` holds the fragments already followed.
+    pub(crate) fn contains_fragment(
+        &self,
+        fragment_name: &str,
+        context: &crate::query::QueryContext<'query, '_>,
+        visited: &mut BTreeSet<&'query str>,
+    ) -> bool {
+        self.0.iter().any(|item| match item {
+            SelectionItem::Field(field) => {
+                field
+                    .fields
+                    .contains_fragment(fragment_name, context, visited)
+            }
+            SelectionItem::InlineFragment(inline_fragment) => inline_fragment
+                .fields
+                .contains_fragment(fragment_name, context, visited),
+            SelectionItem::FragmentSpread(fragment) => {
+                fragment.fragment_name == fragment_name
+                    || (visited.insert(fragment.fragment_name)
+                        && context
+                            .fragments
+                            .get(fragment.fragment_name)
+                            .map(|spread| {
+                                spread
+                                    .selection
+                                    .contains_fragment(fragment_name, context, visited)
+                            })
+                            .unwrap_or(false))
             }
-            SelectionItem::FragmentSpread(fragment) => fragment.fragment_name == fragment_name,
         })
     }
 
@@ -168,13 +248,136 @@ impl<'query> Selection<'query> {
         self.0.len()
     }
 
+    /// The selection with its fields optional, as if they had a `@skip` or `@include` directive.
+    pub(crate) fn conditional(&self) -> Selection<'query> {
+        Selection(
+            self.0
+                .iter()
+                .map(|item| match item {
+                    SelectionItem::Field(field) => SelectionItem::Field(SelectionField {
+                        conditional: true,
+                        ..field.clone()
+                    }),
+                    item => item.clone(),
+                })
+                .collect(),
+        )
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// The name of the types generated for a field of the selection: the prefix of the selection
+    /// followed by the response key of the field. The response keys that only differ by case or
+    /// underscores (`userA` and `user_a`) would get the same name, so all but the first of them in
+    /// alphabetical order are numbered (`MyQueryUserA` and `MyQueryUserA2`). The names do not
+    /// depend on the order of the fields in the query.
+    pub(crate) fn field_prefix(&self, prefix: &str, field: &SelectionField<'_>) -> String {
+        let response_key = field.alias.unwrap_or(field.name);
+        let name = response_key.to_camel_case();
+        let response_keys: BTreeSet<&str> = self
+            .0
+            .iter()
+            .filter_map(|item| match item {
+                SelectionItem::Field(field) => Some(field.alias.unwrap_or(field.name)),
+                _ => None,
+            })
+            .collect();
+        // The numbered names must not be the names of other fields either (`userA2`).
+        let taken: BTreeSet<String> = response_keys
+            .iter()
+            .map(|key| key.to_camel_case())
+            .collect();
+        let mut number = 1;
+
+        for key in response_keys
+            .iter()
+            .filter(|key| key.to_camel_case() == name)
+            .skip(1)
+        {
+            let numbered_name = loop {
+                number += 1;
+                let numbered_name = format!("{}{}", name, number);
+
+                if !taken.contains(&numbered_name) {
+                    break numbered_name;
+                }
+            };
+
+            if *key == response_key {
+                return format!("{}{}", prefix.to_camel_case(), numbered_name);
+            }
+        }
+
+        format!("{}{}", prefix.to_camel_case(), name)
+    }
+
+    /// Mark the fragments spread in the selection as required, including the ones spread in sub-selections.
     pub(crate) fn require_items<'s>(&self, context: &crate::query::QueryContext<'query, 's>) {
-        self.0.iter().for_each(|item| {
-            if let SelectionItem::FragmentSpread(SelectionFragmentSpread { fragment_name }) = item {
+        self.0.iter().for_each(|item| match item {
+            SelectionItem::FragmentSpread(SelectionFragmentSpread { fragment_name, .. }) => {
                 context.require_fragment(fragment_name);
             }
+            SelectionItem::Field(SelectionField { fields, .. }) => fields.require_items(context),
+            SelectionItem::InlineFragment(SelectionInlineFragment { fields, .. }) => {
+                fields.require_items(context)
+            }
         })
     }
+
+    /// The dotted paths of the leaf fields in the selection (e.g. `viewer.repositories.nodes.name`), with the
+    /// fragment spreads expanded. `__typename` is left out, and each path is only listed once.
+    pub(crate) fn field_paths(&self, fragments: &BTreeMap<&str, Selection<'_>>) -> Vec<String> {
+        let mut paths = Vec::new();
+        self.collect_field_paths("", fragments, &mut Vec::new(), &mut paths);
+        paths
+    }
+
+    fn collect_field_paths<'a>(
+        &'a self,
+        prefix: &str,
+        fragments: &'a BTreeMap<&str, Selection<'_>>,
+        expanded_fragments: &mut Vec<&'a str>,
+        paths: &mut Vec<String>,
+    ) {
+        for item in self {
+            match item {
+                SelectionItem::Field(field) if field.name == TYPENAME_FIELD => (),
+                SelectionItem::Field(field) => {
+                    let path = format!("{}{}", prefix, field.name);
+
+                    if field.fields.0.is_empty() {
+                        if !paths.contains(&path) {
+                            paths.push(path);
+                        }
+                    } else {
+                        field.fields.collect_field_paths(
+                            &format!("{}.", path),
+                            fragments,
+                            expanded_fragments,
+                            paths,
+                        );
+                    }
+                }
+                SelectionItem::InlineFragment(inline_fragment) => inline_fragment
+                    .fields
+                    .collect_field_paths(prefix, fragments, expanded_fragments, paths),
+                SelectionItem::FragmentSpread(SelectionFragmentSpread { fragment_name, .. }) => {
+                    // Recursive fragments are only expanded once on each path.
+                    if expanded_fragments.contains(fragment_name) {
+                        continue;
+                    }
+
+                    if let Some(fragment) = fragments.get(fragment_name) {
+                        expanded_fragments.push(fragment_name);
+                        fragment.collect_field_paths(prefix, fragments, expanded_fragments, paths);
+                        expanded_fragments.pop();
+                    }
+                }
+            }
+        }
+    }
 }
 
 impl<'query> std::convert::From<&'query SelectionSet> for Selection<'query> {
@@ -189,10 +392,19 @@ impl<'query> std::convert::From<&'query SelectionSet> for Selection<'query> {
                     alias: f.alias.as_deref(),
                     name: &f.name,
                     fields: (&f.selection_set).into(),
+                    // Malformed directives are reported by `rust_with::validate`.
+                    rust_with: crate::rust_with::RustWith::from_field(f).unwrap_or(None),
+                    conditional: f
+                        .directives
+                        .iter()
+                        .any(|directive| directive.name == "skip" || directive.name == "include"),
+                    arguments: &f.arguments,
+                    position: f.position,
                 }),
                 Selection::FragmentSpread(spread) => {
                     SelectionItem::FragmentSpread(SelectionFragmentSpread {
                         fragment_name: &spread.fragment_name,
+                        position: spread.position,
                     })
                 }
                 Selection::InlineFragment(inline) => {
@@ -201,8 +413,9 @@ impl<'query> std::convert::From<&'query SelectionSet> for Selection<'query> {
                         .as_ref()
                         .expect("Missing `on` clause.");
                     SelectionItem::InlineFragment(SelectionInlineFragment {
-                        on: &name,
+                        on: name,
                         fields: (&inline.selection_set).into(),
+                        position: inline.position,
                     })
                 }
             };
@@ -232,6 +445,62 @@ impl<'a> std::iter::FromIterator<SelectionItem<'a>> for Selection<'a> {
 mod tests {
     use super::*;
 
+    #[test]
+    fn field_paths_expand_fragments() {
+        let query = graphql_parser::parse_query(
+            r#"
+            query Q {
+                viewer {
+                    __typename
+                    login
+                    ...Repositories
+                    repositories { totalCount }
+                }
+            }
+
+            fragment Repositories on User {
+                repositories {
+                    totalCount
+                    nodes { name ... on Repository { owner { ...Owner } } }
+                }
+            }
+
+            fragment Owner on User {
+                login
+                followers { ...Owner }
+            }
+            "#,
+        )
+        .unwrap();
+
+        let mut selection = None;
+        let mut fragments = BTreeMap::new();
+        for definition in &query.definitions {
+            match definition {
+                graphql_parser::query::Definition::Operation(
+                    graphql_parser::query::OperationDefinition::Query(query),
+                ) => selection = Some(Selection::from(&query.selection_set)),
+                graphql_parser::query::Definition::Fragment(fragment) => {
+                    fragments.insert(
+                        fragment.name.as_str(),
+                        Selection::from(&fragment.selection_set),
+                    );
+                }
+                _ => unreachable!(),
+            }
+        }
+
+        assert_eq!(
+            selection.unwrap().field_paths(&fragments),
+            vec![
+                "viewer.login",
+                "viewer.repositories.totalCount",
+                "viewer.repositories.nodes.name",
+                "viewer.repositories.nodes.owner.login",
+            ]
+        );
+    }
+
     #[test]
     fn selection_extract_typename_simple_case() {
         let selection = Selection::new_empty();
@@ -248,6 +517,7 @@ mod tests {
             .0
             .push(SelectionItem::FragmentSpread(SelectionFragmentSpread {
                 fragment_name: "MyFragment",
+                position: Pos::default(),
             }));
 
         let mut fragment_selection = Selection::new_empty();
@@ -257,6 +527,10 @@ mod tests {
                 alias: None,
                 name: "__typename",
                 fields: Selection::new_empty(),
+                rust_with: None,
+                conditional: false,
+                arguments: &[],
+                position: Pos::default(),
             }));
 
         let schema = crate::schema::Schema::new();
@@ -321,40 +595,115 @@ mod tests {
                         alias: None,
                         name: "isCat",
                         fields: Selection(Vec::new()),
+                        rust_with: None,
+                        conditional: false,
+                        arguments: &[],
+                        position: Pos { line: 4, column: 13 },
                     }),
                     SelectionItem::Field(SelectionField {
                         alias: None,
                         name: "isHorse",
                         fields: Selection(Vec::new()),
+                        rust_with: None,
+                        conditional: false,
+                        arguments: &[],
+                        position: Pos { line: 5, column: 13 },
                     }),
                     SelectionItem::FragmentSpread(SelectionFragmentSpread {
                         fragment_name: "Timestamps",
+                        position: Pos { line: 6, column: 16 },
                     }),
                     SelectionItem::Field(SelectionField {
                         alias: None,
                         name: "barks",
                         fields: Selection(Vec::new()),
+                        rust_with: None,
+                        conditional: false,
+                        arguments: &[],
+                        position: Pos { line: 7, column: 13 },
                     }),
                     SelectionItem::InlineFragment(SelectionInlineFragment {
                         on: "Dog",
+                        position: Pos { line: 8, column: 16 },
                         fields: Selection(vec![SelectionItem::Field(SelectionField {
                             alias: None,
                             name: "rating",
                             fields: Selection(Vec::new()),
+                            rust_with: None,
+                            conditional: false,
+                            arguments: &[],
+                            position: Pos { line: 9, column: 17 },
                         })]),
                     }),
                     SelectionItem::Field(SelectionField {
                         alias: None,
                         name: "pawsCount",
                         fields: Selection(Vec::new()),
+                        rust_with: None,
+                        conditional: false,
+                        arguments: &[],
+                        position: Pos { line: 11, column: 13 },
                     }),
                     SelectionItem::Field(SelectionField {
                         alias: Some("aliased"),
                         name: "sillyName",
                         fields: Selection(Vec::new()),
+                        rust_with: None,
+                        conditional: false,
+                        arguments: &[],
+                        position: Pos { line: 12, column: 13 },
                     }),
                 ]),
+                rust_with: None,
+                conditional: false,
+                arguments: &[],
+                position: Pos { line: 3, column: 11 },
             })])
         );
     }
+
+    #[test]
+    fn field_prefixes_of_aliases_with_the_same_rust_name_are_numbered() {
+        let field = |alias: &'static str| {
+            SelectionItem::Field(SelectionField {
+                alias: Some(alias),
+                name: "user",
+                fields: Selection(Vec::new()),
+                rust_with: None,
+                conditional: false,
+                arguments: &[],
+                position: Pos::default(),
+            })
+        };
+        let prefixes = |aliases: &[&'static str]| {
+            let selection = Selection(aliases.iter().map(|alias| field(alias)).collect());
+            selection
+                .0
+                .iter()
+                .map(|item| match item {
+                    SelectionItem::Field(field) => selection.field_prefix("MyQuery", field),
+                    _ => unreachable!(),
+                })
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            prefixes(&["user_a", "userA", "userA2", "UserA"]),
+            [
+                "MyQueryUserA4",
+                "MyQueryUserA3",
+                "MyQueryUserA2",
+                "MyQueryUserA"
+            ]
+        );
+        // The names do not depend on the order of the fields.
+        assert_eq!(
+            prefixes(&["userA", "user_a"]),
+            ["MyQueryUserA", "MyQueryUserA2"]
+        );
+        assert_eq!(
+            prefixes(&["user_a", "userA"]),
+            ["MyQueryUserA2", "MyQueryUserA"]
+        );
+    }
 }
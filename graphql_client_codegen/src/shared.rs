@@ -3,7 +3,7 @@ use crate::objects::GqlObjectField;
 use crate::query::QueryContext;
 use crate::selection::*;
 use failure::*;
-use heck::{CamelCase, SnakeCase};
+use heck::SnakeCase;
 use proc_macro2::{Ident, Span, TokenStream};
 use quote::quote;
 
@@ -70,11 +70,36 @@ const RUST_KEYWORDS: &[&str] = &[
     "yield",
 ];
 
+/// Whether the name is a Rust keyword, escaped by `keyword_replace`.
+pub(crate) fn is_keyword(name: &str) -> bool {
+    RUST_KEYWORDS.binary_search(&name).is_ok()
+}
+
+/// Make a name from the schema usable as a Rust identifier. Keywords get a trailing underscore, the characters
+/// that are not ASCII letters, digits or underscores (e.g. in the Unicode names some servers allow) are replaced
+/// with underscores, and names starting with a digit get a leading underscore.
+///
+/// The wire names are kept with serde renames where needed.
 pub(crate) fn keyword_replace(needle: &str) -> String {
-    match RUST_KEYWORDS.binary_search(&needle) {
-        Ok(index) => [RUST_KEYWORDS[index], "_"].concat(),
-        Err(_) => needle.to_owned(),
+    if let Ok(index) = RUST_KEYWORDS.binary_search(&needle) {
+        return [RUST_KEYWORDS[index], "_"].concat();
+    }
+
+    let mut name: String = needle
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+
+    if name.starts_with(|c: char| c.is_ascii_digit()) {
+        name.insert(0, '_');
     }
+
+    // `_` alone is not an identifier.
+    if name == "_" {
+        name.push('_');
+    }
+
+    name
 }
 
 pub(crate) fn render_object_field(
@@ -82,14 +107,15 @@ pub(crate) fn render_object_field(
     field_type: &TokenStream,
     description: Option<&str>,
     status: &DeprecationStatus,
-    strategy: &DeprecationStrategy,
-) -> Option<TokenStream> {
+    attributes: TokenStream,
+    context: &QueryContext<'_, '_>,
+) -> Result<Option<TokenStream>, failure::Error> {
     #[allow(unused_assignments)]
     let mut deprecation = quote!();
-    match (status, strategy) {
+    match (status, &context.deprecation_strategy) {
         // If the field is deprecated and we are denying usage, don't generate the
         // field in rust at all and short-circuit.
-        (DeprecationStatus::Deprecated(_), DeprecationStrategy::Deny) => return None,
+        (DeprecationStatus::Deprecated(_), DeprecationStrategy::Deny) => return Ok(None),
         // Everything is allowed so there is nothing to do.
         (_, DeprecationStrategy::Allow) => deprecation = quote!(),
         // Current so there is nothing to do.
@@ -105,11 +131,16 @@ pub(crate) fn render_object_field(
     };
 
     let description = description.map(|s| quote!(#[doc = #s]));
-    let rust_safe_field_name = keyword_replace(&field_name.to_snake_case());
-    let name_ident = Ident::new(&rust_safe_field_name, Span::call_site());
-    let rename = crate::shared::field_rename_annotation(&field_name, &rust_safe_field_name);
+    let name_ident = crate::normalization::response_field_ident(
+        context.normalization,
+        context.rename_all,
+        field_name,
+    )?;
+    let rename = crate::shared::field_rename_annotation(field_name, &name_ident.to_string());
 
-    Some(quote!(#description #deprecation #rename pub #name_ident: #field_type))
+    Ok(Some(
+        quote!(#description #deprecation #rename #attributes pub #name_ident: #field_type),
+    ))
 }
 
 pub(crate) fn field_impls_for_selection(
@@ -118,27 +149,28 @@ pub(crate) fn field_impls_for_selection(
     selection: &Selection<'_>,
     prefix: &str,
 ) -> Result<Vec<TokenStream>, failure::Error> {
-    (&selection)
-        .into_iter()
-        .map(|selected| {
-            if let SelectionItem::Field(selected) = selected {
-                let name = &selected.name;
-                let alias = selected.alias.as_ref().unwrap_or(name);
-
-                let ty = fields
-                    .iter()
-                    .find(|f| &f.name == name)
-                    .ok_or_else(|| format_err!("could not find field `{}`", name))?
-                    .type_
-                    .inner_name_str();
-                let prefix = format!("{}{}", prefix.to_camel_case(), alias.to_camel_case());
-                context.maybe_expand_field(&ty, &selected.fields, &prefix)
-            } else {
-                Ok(None)
-            }
-        })
-        .filter_map(|i| i.transpose())
-        .collect()
+    crate::errors::collect(
+        selection
+            .into_iter()
+            .map(|selected| {
+                if let SelectionItem::Field(selected) = selected {
+                    let name = &selected.name;
+
+                    // Unknown fields are reported by `response_fields_for_selection`.
+                    let ty = match fields.iter().find(|f| &f.name == name) {
+                        Some(field) => field.type_.inner_name_str(),
+                        None => return Ok(None),
+                    };
+                    let prefix = selection.field_prefix(prefix, selected);
+                    context
+                        .maybe_expand_field(ty, &selected.fields, &prefix)
+                        .map_err(|err| crate::errors::at(selected.position, err))
+                } else {
+                    Ok(None)
+                }
+            })
+            .filter_map(|i| i.transpose()),
+    )
 }
 
 pub(crate) fn response_fields_for_selection(
@@ -148,77 +180,218 @@ pub(crate) fn response_fields_for_selection(
     selection: &Selection<'_>,
     prefix: &str,
 ) -> Result<Vec<TokenStream>, failure::Error> {
-    (&selection)
+    // All the errors of the selection are reported, not only the first one.
+    let fields = selection
         .into_iter()
-        .map(|item| match item {
-            SelectionItem::Field(f) => {
-                let name = &f.name;
-                let alias = f.alias.as_ref().unwrap_or(name);
-
-                let schema_field = &schema_fields
-                    .iter()
-                    .find(|field| &field.name == name)
-                    .ok_or_else(|| {
-                        format_err!(
-                            "Could not find field `{}` on `{}`. Available fields: `{}`.",
-                            *name,
-                            type_name,
-                            schema_fields
-                                .iter()
-                                .map(|ref field| &field.name)
-                                .fold(String::new(), |mut acc, item| {
-                                    acc.push_str(item);
-                                    acc.push_str(", ");
-                                    acc
-                                })
-                                .trim_end_matches(", ")
-                        )
-                    })?;
-                let ty = schema_field.type_.to_rust(
-                    context,
-                    &format!("{}{}", prefix.to_camel_case(), alias.to_camel_case()),
-                );
-
-                Ok(render_object_field(
-                    alias,
-                    &ty,
-                    schema_field.description.as_ref().cloned(),
-                    &schema_field.deprecation,
-                    &context.deprecation_strategy,
-                ))
-            }
-            SelectionItem::FragmentSpread(fragment) => {
-                let field_name =
-                    Ident::new(&fragment.fragment_name.to_snake_case(), Span::call_site());
-                context.require_fragment(&fragment.fragment_name);
-                let fragment_from_context = context
-                    .fragments
-                    .get(&fragment.fragment_name)
-                    .ok_or_else(|| format_err!("Unknown fragment: {}", &fragment.fragment_name))?;
-                let type_name = Ident::new(&fragment.fragment_name, Span::call_site());
-                let type_name = if fragment_from_context.is_recursive() {
-                    quote!(Box<#type_name>)
-                } else {
-                    quote!(#type_name)
-                };
-                Ok(Some(quote! {
-                    #[serde(flatten)]
-                    pub #field_name: #type_name
-                }))
-            }
-            SelectionItem::InlineFragment(_) => Err(format_err!(
-                "unimplemented: inline fragment on object field"
-            )),
+        .map(|item| {
+            response_field(type_name, schema_fields, context, selection, prefix, item)
+                .map_err(|err| crate::errors::at(item.position(), err))
         })
         .filter_map(|x| match x {
             // Remove empty fields so callers always know a field has some
             // tokens.
             Ok(f) => f.map(Ok),
             Err(err) => Some(Err(err)),
-        })
+        });
+
+    crate::errors::collect(fields)
+}
+
+/// The field of the response struct for a selected item, if the item has one.
+fn response_field(
+    type_name: &str,
+    schema_fields: &[GqlObjectField<'_>],
+    context: &QueryContext<'_, '_>,
+    selection: &Selection<'_>,
+    prefix: &str,
+    item: &SelectionItem<'_>,
+) -> Result<Option<TokenStream>, failure::Error> {
+    match item {
+        SelectionItem::Field(f) => {
+            let name = &f.name;
+            let alias = f.alias.as_ref().unwrap_or(name);
+
+            let schema_field = &schema_fields
+                .iter()
+                .find(|field| &field.name == name)
+                .ok_or_else(|| field_not_found(type_name, schema_fields, name))?;
+            let field_type_name = schema_field.type_.inner_name_str();
+            if f.fields.is_empty() && context.schema.is_composite(field_type_name) {
+                return Err(format_err!(
+                    "field `{}` of type `{}` must have a sub-selection",
+                    name,
+                    field_type_name,
+                ));
+            }
+            crate::arguments::validate_enum_arguments(context, type_name, schema_field, f)?;
+            let field_prefix = selection.field_prefix(prefix, f);
+            // Fields with a `@skip` or `@include` directive may be missing from the response.
+            let field_type = if f.conditional {
+                schema_field.type_.clone().nullable()
+            } else {
+                schema_field.type_.clone()
+            };
+            let (ty, attributes) = if let Some(rust_with) = &f.rust_with {
+                let ty = rust_with
+                    .rust_type()
+                    .unwrap_or_else(|| field_type.to_rust(context, &field_prefix));
+                let serialize = context.derives_response_serialize_for(prefix);
+                (ty, rust_with.attribute(serialize))
+            } else if let Some(ty) = context
+                .cursors
+                .field_type(context, prefix, alias, &field_type)
+            {
+                (ty, quote!())
+            } else if let Some(attribute) = context.date_times.serde_attribute(&field_type) {
+                (field_type.to_rust(context, &field_prefix), attribute)
+            } else if context.coerces_null(&field_type) {
+                // The field is deserialized with the type's default value in place of null,
+                // so there is no need for an Option.
+                let ty = field_type.nonnull().to_rust(context, &field_prefix);
+                let deserialize_with = "graphql_client::serde_helpers::null_as_default";
+                (
+                    ty,
+                    quote!(#[serde(default, deserialize_with = #deserialize_with)]),
+                )
+            } else if f.conditional && !schema_field.type_.is_optional() {
+                // The field is only `None` when it was left out of the response, so it is
+                // left out of the serialized response too.
+                (
+                    field_type.to_rust(context, &field_prefix),
+                    quote!(#[serde(skip_serializing_if = "Option::is_none")]),
+                )
+            } else {
+                (field_type.to_rust(context, &field_prefix), quote!())
+            };
+
+            render_object_field(
+                alias,
+                &ty,
+                schema_field.description.as_ref().cloned(),
+                &schema_field.deprecation,
+                attributes,
+                context,
+            )
+        }
+        SelectionItem::FragmentSpread(fragment) => {
+            let field_name = Ident::new(&fragment.fragment_name.to_snake_case(), Span::call_site());
+            context.require_fragment(fragment.fragment_name);
+            let fragment_from_context = context
+                .fragments
+                .get(&fragment.fragment_name)
+                .ok_or_else(|| format_err!("Unknown fragment: {}", &fragment.fragment_name))?;
+            let type_name = Ident::new(fragment.fragment_name, Span::call_site());
+            let type_name = if fragment_from_context.is_recursive(context) {
+                quote!(Box<#type_name>)
+            } else {
+                quote!(#type_name)
+            };
+            Ok(Some(quote! {
+                #[serde(flatten)]
+                pub #field_name: #type_name
+            }))
+        }
+        SelectionItem::InlineFragment(_) => Err(format_err!(
+            "unimplemented: inline fragment on object field"
+        )),
+    }
+}
+
+/// The error for a selected field missing from the schema type.
+fn field_not_found(
+    type_name: &str,
+    schema_fields: &[GqlObjectField<'_>],
+    name: &str,
+) -> failure::Error {
+    // Every type has `__typename`.
+    if schema_fields
+        .iter()
+        .all(|field| field.name == crate::constants::TYPENAME_FIELD)
+    {
+        return format_err!(
+            "Could not find field `{}` on `{}`: the type has no visible fields. If the schema comes from an introspection query, the server may hide the fields the credentials used for introspection are not allowed to see.",
+            name,
+            type_name,
+        );
+    }
+
+    let suggestions = similar_field_names(name, schema_fields);
+
+    if !suggestions.is_empty() {
+        return format_err!(
+            "Could not find field `{}` on `{}`. Did you mean {}?",
+            name,
+            type_name,
+            suggestions
+                .iter()
+                .map(|suggestion| format!("`{}`", suggestion))
+                .collect::<Vec<_>>()
+                .join(" or ")
+        );
+    }
+
+    format_err!(
+        "Could not find field `{}` on `{}`. Available fields: `{}`.",
+        name,
+        type_name,
+        schema_fields
+            .iter()
+            .map(|field| &field.name)
+            .fold(String::new(), |mut acc, item| {
+                acc.push_str(item);
+                acc.push_str(", ");
+                acc
+            })
+            .trim_end_matches(", ")
+    )
+}
+
+/// The (at most three) names of the fields closest to a field name that was not found, closest first.
+/// Casing is ignored, so `created_at` suggests `createdAt`.
+fn similar_field_names<'a>(name: &str, schema_fields: &'a [GqlObjectField<'_>]) -> Vec<&'a str> {
+    let name = name.to_lowercase();
+    // Allow about one typo every three characters.
+    let max_distance = std::cmp::max(1, name.chars().count() / 3);
+
+    let mut candidates: Vec<(usize, &str)> = schema_fields
+        .iter()
+        .filter(|field| field.name != crate::constants::TYPENAME_FIELD)
+        .map(|field| (levenshtein(&name, &field.name.to_lowercase()), field.name))
+        .filter(|(distance, _)| *distance <= max_distance)
+        .collect();
+
+    candidates.sort();
+
+    candidates
+        .into_iter()
+        .take(3)
+        .map(|(_, field_name)| field_name)
         .collect()
 }
 
+/// The Levenshtein edit distance between two strings: the number of characters to insert, remove
+/// or substitute to go from one to the other.
+fn levenshtein(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    // The distances between the prefix of `a` processed so far and each prefix of `b`.
+    let mut distances: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, a_char) in a.chars().enumerate() {
+        let mut previous_diagonal = distances[0];
+        distances[0] = i + 1;
+
+        for (j, b_char) in b.iter().enumerate() {
+            let substitution = previous_diagonal + if a_char == *b_char { 0 } else { 1 };
+            previous_diagonal = distances[j + 1];
+            distances[j + 1] = substitution
+                .min(distances[j] + 1)
+                .min(previous_diagonal + 1);
+        }
+    }
+
+    distances[b.len()]
+}
+
 /// Given the GraphQL schema name for an object/interface/input object field and
 /// the equivalent rust name, produces a serde annotation to map them during
 /// (de)serialization if it is necessary, otherwise an empty TokenStream.
@@ -230,6 +403,7 @@ pub(crate) fn field_rename_annotation(graphql_name: &str, rust_name: &str) -> Op
     }
 }
 
+#[cfg(test)]
 mod tests {
     #[test]
     fn keyword_replace() {
@@ -238,5 +412,77 @@ mod tests {
         assert_eq!("in_", keyword_replace("in"));
         assert_eq!("fn_", keyword_replace("fn"));
         assert_eq!("struct_", keyword_replace("struct"));
+        assert_eq!("caf_", keyword_replace("café"));
+        assert_eq!("_1st", keyword_replace("1st"));
+        assert_eq!("__", keyword_replace("_"));
+    }
+
+    fn repository_fields() -> Vec<crate::objects::GqlObjectField<'static>> {
+        [
+            "__typename",
+            "id",
+            "name",
+            "createdAt",
+            "updatedAt",
+            "pushedAt",
+            "stargazers",
+        ]
+        .iter()
+        .map(|name| crate::objects::GqlObjectField {
+            description: None,
+            name,
+            type_: crate::field_type::FieldType::new("String"),
+            deprecation: crate::deprecation::DeprecationStatus::Current,
+            arguments: Vec::new(),
+        })
+        .collect()
+    }
+
+    #[test]
+    fn levenshtein() {
+        use super::levenshtein;
+        assert_eq!(levenshtein("name", "name"), 0);
+        assert_eq!(levenshtein("", "name"), 4);
+        assert_eq!(levenshtein("nmae", "name"), 2);
+        assert_eq!(levenshtein("kitten", "sitting"), 3);
+        assert_eq!(levenshtein("café", "cafe"), 1);
+    }
+
+    #[test]
+    fn field_not_found_suggests_fields_with_typos() {
+        let fields = repository_fields();
+
+        assert_eq!(
+            super::field_not_found("Repository", &fields, "stargazer").to_string(),
+            "Could not find field `stargazer` on `Repository`. Did you mean `stargazers`?"
+        );
+        assert_eq!(
+            super::field_not_found("Repository", &fields, "pushdAt").to_string(),
+            "Could not find field `pushdAt` on `Repository`. Did you mean `pushedAt`?"
+        );
+    }
+
+    #[test]
+    fn field_not_found_ignores_casing_mistakes() {
+        let fields = repository_fields();
+
+        assert_eq!(
+            super::field_not_found("Repository", &fields, "created_at").to_string(),
+            "Could not find field `created_at` on `Repository`. Did you mean `createdAt`?"
+        );
+        assert_eq!(
+            super::field_not_found("Repository", &fields, "Name").to_string(),
+            "Could not find field `Name` on `Repository`. Did you mean `name`?"
+        );
+    }
+
+    #[test]
+    fn field_not_found_lists_the_fields_without_suggestions() {
+        let fields = repository_fields();
+
+        assert_eq!(
+            super::field_not_found("Repository", &fields, "owner").to_string(),
+            "Could not find field `owner` on `Repository`. Available fields: `__typename, id, name, createdAt, updatedAt, pushedAt, stargazers`."
+        );
     }
 }
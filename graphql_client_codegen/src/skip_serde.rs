@@ -0,0 +1,58 @@
+//! The `skip_serde` option: response fields left out of serialization and deserialization, for
+//! data computed by the client.
+
+use crate::query::QueryContext;
+use failure::format_err;
+use proc_macro2::{Ident, TokenStream};
+use quote::quote;
+use syn::parse::Parser;
+
+/// The fields of a response struct, with `#[serde(skip)]` on the ones the `skip_serde` option names
+/// for it.
+///
+/// The skipped fields are never serialized, and deserialized with the `Default` value of their
+/// type, so their type must implement `Default`. Their other serde attributes are left out, since
+/// they only apply to the serialized field.
+pub(crate) fn response_struct_fields(
+    context: &QueryContext<'_, '_>,
+    name: &Ident,
+    fields: &[TokenStream],
+) -> Result<Vec<TokenStream>, failure::Error> {
+    let struct_name = name.to_string();
+    let skipped_fields = context.skipped_fields(&struct_name);
+
+    if skipped_fields.is_empty() {
+        return Ok(fields.to_vec());
+    }
+
+    let mut fields = fields
+        .iter()
+        .map(|field| syn::Field::parse_named.parse2(field.clone()))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    for skipped_field in skipped_fields {
+        let field = fields
+            .iter_mut()
+            .find(|field| {
+                field
+                    .ident
+                    .as_ref()
+                    .map(|ident| ident == skipped_field)
+                    .unwrap_or(false)
+            })
+            .ok_or_else(|| {
+                format_err!(
+                    "The skipped field `{}` is not a field of `{}`.",
+                    skipped_field,
+                    struct_name
+                )
+            })?;
+
+        field.attrs.retain(|attr| !attr.path.is_ident("serde"));
+        field
+            .attrs
+            .extend(syn::Attribute::parse_outer.parse2(quote!(#[serde(skip)]))?);
+    }
+
+    Ok(fields.iter().map(|field| quote!(#field)).collect())
+}
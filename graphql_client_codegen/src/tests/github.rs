@@ -43,3 +43,26 @@ fn ast_from_graphql_and_json_produce_the_same_schema() {
         );
     }
 }
+
+#[test]
+fn graphql_and_json_schemas_generate_the_same_code() {
+    use crate::{generate_code, CodegenMode, GraphQLClientCodegenOptions};
+
+    let tests_dir = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("src/tests");
+    let generate = |schema_file: &str| {
+        generate_code(
+            tests_dir.join("github_interfaces_query.graphql"),
+            &tests_dir.join(schema_file),
+            GraphQLClientCodegenOptions::new(CodegenMode::Cli),
+        )
+        .map(|generated| generated.tokens.to_string())
+        .map_err(|err| err.to_string())
+        .unwrap()
+    };
+
+    let from_graphql = generate("github_schema.graphql");
+
+    assert_eq!(from_graphql, generate("github_schema.json"));
+    // The implementations of the interface are listed in the same order every time.
+    assert_eq!(from_graphql, generate("github_schema.graphql"));
+}
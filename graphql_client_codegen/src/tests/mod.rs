@@ -1,5 +1,27 @@
 mod github;
 
+/// The directory of test fixtures, relative to the crate.
+fn fixture_dir(dir: impl AsRef<std::path::Path>) -> std::path::PathBuf {
+    std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join(dir)
+}
+
+/// Generate the code for the `query` and `schema` files of the `dir` fixture directory in CLI mode,
+/// with the options set by `configure`.
+fn generate_fixture(
+    dir: impl AsRef<std::path::Path>,
+    query: &str,
+    schema: &str,
+    configure: impl FnOnce(&mut crate::GraphQLClientCodegenOptions),
+) -> Result<String, String> {
+    let dir = fixture_dir(dir);
+    let mut options = crate::GraphQLClientCodegenOptions::new(crate::CodegenMode::Cli);
+    configure(&mut options);
+
+    crate::generate_module_token_stream(dir.join(query), &dir.join(schema), options)
+        .map(|tokens| tokens.to_string())
+        .map_err(|err| err.to_string())
+}
+
 #[test]
 fn schema_with_keywords_works() {
     use crate::{
@@ -14,7 +36,7 @@ fn schema_with_keywords_works() {
     let schema = Schema::from(&schema);
 
     let options = GraphQLClientCodegenOptions::new(CodegenMode::Cli);
-    let operations = codegen::all_operations(&query);
+    let operations = codegen::all_operations(&query).unwrap();
     for operation in &operations {
         let generated_tokens = generated_module::GeneratedModule {
             query_string,
@@ -41,3 +63,1257 @@ fn schema_with_keywords_works() {
         };
     }
 }
+
+#[test]
+fn facade_hides_selection_structs() {
+    use crate::{
+        codegen, generated_module, schema::Schema, CodegenMode, GraphQLClientCodegenOptions,
+    };
+
+    let query_string = include_str!("star_wars_query.graphql");
+    let query = graphql_parser::parse_query(query_string).expect("Parse star wars query");
+    let schema = graphql_parser::parse_schema(include_str!("star_wars_schema.graphql"))
+        .expect("Parse star wars schema");
+    let schema = Schema::from(&schema);
+
+    let mut options = GraphQLClientCodegenOptions::new(CodegenMode::Cli);
+    options.set_facade(true);
+    let operations = codegen::all_operations(&query).unwrap();
+    let generated_code = generated_module::GeneratedModule {
+        query_string,
+        schema: &schema,
+        query_document: &query,
+        operation: &operations[0],
+        options: &options,
+    }
+    .to_token_stream()
+    .expect("Generate star wars module")
+    .to_string();
+
+    assert!(generated_code.contains("# [doc (hidden)] pub struct StarWarsQueryHero"));
+    assert!(!generated_code.contains("# [doc (hidden)] pub struct ResponseData"));
+    assert!(generated_code.contains(
+        "pub mod api { pub (in super :: super) use super :: super :: StarWarsQuery ; pub use super :: { Variables , VariablesBuilder , ResponseData , Episode } ; }"
+    ));
+}
+
+#[test]
+fn invalid_response_struct_name_is_rejected() {
+    use crate::{
+        codegen, generated_module, schema::Schema, CodegenMode, GraphQLClientCodegenOptions,
+    };
+
+    let query_string = include_str!("star_wars_query.graphql");
+    let query = graphql_parser::parse_query(query_string).expect("Parse star wars query");
+    let schema = graphql_parser::parse_schema(include_str!("star_wars_schema.graphql"))
+        .expect("Parse star wars schema");
+    let schema = Schema::from(&schema);
+
+    let mut options = GraphQLClientCodegenOptions::new(CodegenMode::Cli);
+    options.set_response_struct_name("Star Wars".to_owned());
+    let operations = codegen::all_operations(&query).unwrap();
+    let error = generated_module::GeneratedModule {
+        query_string,
+        schema: &schema,
+        query_document: &query,
+        operation: &operations[0],
+        options: &options,
+    }
+    .to_token_stream()
+    .unwrap_err();
+
+    assert_eq!(
+        error.to_string(),
+        "The response struct name `Star Wars` is not a valid Rust identifier."
+    );
+}
+
+#[test]
+fn invalid_module_name_is_rejected() {
+    use crate::{
+        codegen, generated_module, schema::Schema, CodegenMode, GraphQLClientCodegenOptions,
+    };
+
+    let query_string = include_str!("star_wars_query.graphql");
+    let query = graphql_parser::parse_query(query_string).expect("Parse star wars query");
+    let schema = graphql_parser::parse_schema(include_str!("star_wars_schema.graphql"))
+        .expect("Parse star wars schema");
+    let schema = Schema::from(&schema);
+
+    let mut options = GraphQLClientCodegenOptions::new(CodegenMode::Derive);
+    options.set_module_name("star-wars".to_owned());
+    let operations = codegen::all_operations(&query).unwrap();
+    let error = generated_module::GeneratedModule {
+        query_string,
+        schema: &schema,
+        query_document: &query,
+        operation: &operations[0],
+        options: &options,
+    }
+    .to_token_stream()
+    .unwrap_err();
+
+    assert_eq!(
+        error.to_string(),
+        "The module name `star-wars` is not a valid Rust identifier."
+    );
+}
+
+#[test]
+fn the_file_header_documents_the_module_in_derive_mode() {
+    use crate::{
+        codegen, generated_module, schema::Schema, CodegenMode, GraphQLClientCodegenOptions,
+    };
+
+    let query_string = include_str!("star_wars_query.graphql");
+    let query = graphql_parser::parse_query(query_string).expect("Parse star wars query");
+    let schema = graphql_parser::parse_schema(include_str!("star_wars_schema.graphql"))
+        .expect("Parse star wars schema");
+    let schema = Schema::from(&schema);
+    let operations = codegen::all_operations(&query).unwrap();
+
+    let generate = |mode: CodegenMode, file_header: &str| {
+        let mut options = GraphQLClientCodegenOptions::new(mode);
+        options.set_file_header(file_header.to_owned());
+
+        generated_module::GeneratedModule {
+            query_string,
+            schema: &schema,
+            query_document: &query,
+            operation: &operations[0],
+            options: &options,
+        }
+        .to_token_stream()
+        .unwrap()
+        .to_string()
+    };
+
+    assert!(generate(CodegenMode::Derive, "Copyright (c) Example Corp.\n\nDO NOT EDIT.")
+        .starts_with("# [doc = \" Copyright (c) Example Corp.\"] # [doc = \"\"] # [doc = \" DO NOT EDIT.\"] # [doc = \" @generated\"] mod star_wars_query {"));
+    // The marker is not repeated.
+    assert!(generate(CodegenMode::Derive, "@generated by make codegen")
+        .starts_with("# [doc = \" @generated by make codegen\"] mod star_wars_query {"));
+    // The CLI writes the header as a comment at the top of the file.
+    assert!(!generate(CodegenMode::Cli, "DO NOT EDIT.").contains("DO NOT EDIT."));
+}
+
+#[test]
+fn operations_generated_in_the_same_module_are_rejected() {
+    let generate = |module_name: Option<&str>| {
+        generate_fixture(
+            "src/tests",
+            "module_name_collision_query.graphql",
+            "star_wars_schema.graphql",
+            |options| {
+                if let Some(module_name) = module_name {
+                    options.set_module_name(module_name.to_owned());
+                }
+            },
+        )
+    };
+
+    assert_eq!(
+        generate(None).unwrap_err(),
+        "The operations `getHero` and `get_hero` would both be generated in the module `get_hero`. Rename one of them, or generate them separately with a different `module_name`."
+    );
+    assert_eq!(
+        generate(Some("heroes")).unwrap_err(),
+        "The operations `getHero` and `get_hero` would both be generated in the module `heroes`. Rename one of them, or generate them separately with a different `module_name`."
+    );
+}
+
+#[test]
+fn variables_in_operation_directives_are_rejected() {
+    let query = graphql_parser::parse_query(
+        "query Hero($team: String!) @owner(team: $team) { hero { name } }",
+    )
+    .expect("Parse query");
+
+    let operations = crate::codegen::all_operations(&query).unwrap();
+    let error = operations[0].expand_directives().unwrap_err();
+
+    assert_eq!(
+        error.to_string(),
+        "Argument `team` of the `@owner` directive on operation `Hero` is the variable `$team`. Only literals are supported in operation directives."
+    );
+}
+
+#[test]
+fn subscription_responses_are_generated_like_query_responses() {
+    use crate::{codegen, schema::Schema, CodegenMode, GraphQLClientCodegenOptions};
+
+    let schema = graphql_parser::parse_schema(include_str!("subscription_schema.graphql"))
+        .expect("Parse subscription schema");
+    let schema = Schema::from(&schema);
+    let options = GraphQLClientCodegenOptions::new(CodegenMode::Cli);
+
+    let generate = |query_string: &str| {
+        let query = graphql_parser::parse_query(query_string).expect("Parse query");
+        let operations = codegen::all_operations(&query).unwrap();
+        let (tokens, _, _) =
+            codegen::response_for_query(&schema, &query, &operations[0], &options).unwrap();
+        tokens.to_string()
+    };
+
+    let subscription = generate(
+        "subscription DogBirthdays($filter: String) { dogBirthdays(filter: $filter) { name age } }",
+    );
+    let query = generate(
+        "query DogBirthdays($filter: String) { dogBirthdays(filter: $filter) { name age } }",
+    );
+
+    assert!(subscription.contains("pub filter : Option < String >"));
+    assert!(subscription.contains("pub struct DogBirthdaysDogBirthdays"));
+    assert_eq!(subscription, query);
+}
+
+#[test]
+fn subscriptions_with_multiple_root_fields_are_rejected() {
+    use crate::{codegen, schema::Schema, CodegenMode, GraphQLClientCodegenOptions};
+
+    let schema = graphql_parser::parse_schema(include_str!("subscription_schema.graphql"))
+        .expect("Parse subscription schema");
+    let schema = Schema::from(&schema);
+    let options = GraphQLClientCodegenOptions::new(CodegenMode::Cli);
+    let query = graphql_parser::parse_query(
+        "subscription Dogs($filter: String) { newDogs { name } dogBirthdays(filter: $filter) { name } }",
+    )
+    .expect("Parse query");
+    let operations = codegen::all_operations(&query).unwrap();
+
+    let error = codegen::response_for_query(&schema, &query, &operations[0], &options)
+        .unwrap_err()
+        .to_string();
+
+    assert!(error.starts_with("The subscription `Dogs` selects 2 root fields."));
+    assert!(error.contains("Multiple-field queries on the root subscription field are forbidden"));
+}
+
+#[test]
+fn subscriptions_with_multiple_root_fields_can_be_allowed() {
+    use crate::{codegen, schema::Schema, CodegenMode, GraphQLClientCodegenOptions};
+
+    let schema = graphql_parser::parse_schema(include_str!("subscription_schema.graphql"))
+        .expect("Parse subscription schema");
+    let schema = Schema::from(&schema);
+    let mut options = GraphQLClientCodegenOptions::new(CodegenMode::Cli);
+    options.set_allow_multiple_subscription_fields(true);
+    let query = graphql_parser::parse_query("subscription Dogs { newDogs { name } dogCount }")
+        .expect("Parse query");
+    let operations = codegen::all_operations(&query).unwrap();
+
+    let (tokens, _, _) =
+        codegen::response_for_query(&schema, &query, &operations[0], &options).unwrap();
+    let tokens = tokens.to_string();
+
+    assert!(tokens.contains("pub new_dogs : Option < Vec < Option < DogsNewDogs > > >"));
+    // The non-null root fields are optional too, and left out of the serialized response.
+    assert!(tokens.contains(
+        "# [serde (rename = \"dogCount\")] # [serde (skip_serializing_if = \"Option::is_none\")] pub dog_count : Option < Int >"
+    ));
+}
+
+#[test]
+fn anonymous_subscriptions_with_other_operations_are_rejected() {
+    let query = graphql_parser::parse_query(
+        "subscription { newDogs { name } }\nsubscription Birthdays { dogBirthdays { name } }",
+    )
+    .expect("Parse query");
+    let (_, query) = crate::operations::name_anonymous_operation(String::new(), query, None);
+
+    let error = crate::codegen::all_operations(&query).unwrap_err();
+
+    assert_eq!(
+        error.to_string(),
+        "Anonymous subscription operations are only supported when they are the only operation of the query document. Instead of `subscription { ... }`, write `subscription MyOperation { ... }`."
+    );
+}
+
+#[test]
+fn single_anonymous_operations_are_named_after_their_type() {
+    let name = |query_string: &str| {
+        let query = graphql_parser::parse_query(query_string).expect("Parse query");
+        let (query_string, query) =
+            crate::operations::name_anonymous_operation(query_string.to_owned(), query, None);
+        let operations = crate::codegen::all_operations(&query).unwrap();
+
+        assert!(query_string.contains(&operations[0].name));
+        operations[0].name.clone()
+    };
+
+    assert_eq!(name("{ dogs { name } }"), "AnonymousQuery");
+    assert_eq!(name("query { dogs { name } }"), "AnonymousQuery");
+    assert_eq!(
+        name("subscription($filter: String) { dogBirthdays(filter: $filter) { name } }"),
+        "AnonymousSubscription"
+    );
+    assert_eq!(
+        name("mutation { adopt { name } }\nfragment DogName on Dog { name }"),
+        "AnonymousMutation"
+    );
+}
+
+#[test]
+fn dedupe_types_merges_identical_selections() {
+    use crate::{generate_code, CodegenMode, GraphQLClientCodegenOptions};
+
+    let fixtures = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("../graphql_client/tests/dedupe_types");
+    let generate = |dedupe_types: bool| {
+        let mut options = GraphQLClientCodegenOptions::new(CodegenMode::Cli);
+        options.set_dedupe_types(dedupe_types);
+        generate_code(
+            fixtures.join("query.graphql"),
+            &fixtures.join("schema.graphql"),
+            options,
+        )
+        .unwrap()
+        .tokens
+        .to_string()
+    };
+
+    let structs_count = |code: &str| code.matches("pub struct").count();
+    let generated = generate(false);
+    let deduped = generate(true);
+
+    // Variables, VariablesBuilder, ResponseData, the repository, its owner and the five authors,
+    // the issues, the pull requests and their comments.
+    assert_eq!(structs_count(&generated), 13);
+    // The authors are merged with the owner, the pull requests with the issues.
+    assert_eq!(structs_count(&deduped), 7);
+    assert_eq!(deduped, generate(true));
+}
+
+#[test]
+fn selecting_fields_on_empty_objects_is_reported() {
+    use crate::{generate_code, CodegenMode, GraphQLClientCodegenOptions};
+
+    let schema_path = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("../graphql_client/tests/empty_object/schema.json");
+    let query_path = std::env::temp_dir().join("graphql_client_codegen_empty_object_query.graphql");
+    std::fs::write(&query_path, "query AccountId { account { id } }").unwrap();
+
+    let options = GraphQLClientCodegenOptions::new(CodegenMode::Cli);
+    let error = generate_code(query_path.clone(), &schema_path, options)
+        .err()
+        .expect("Selecting a field on an empty object fails")
+        .to_string();
+
+    assert_eq!(
+        error,
+        format!(
+            "Could not find field `id` on `Account`: the type has no visible fields. If the schema comes from an introspection query, the server may hide the fields the credentials used for introspection are not allowed to see. ({}:1:29)",
+            query_path.display()
+        )
+    );
+}
+
+#[test]
+fn composite_fields_without_sub_selection_are_reported() {
+    use crate::{generate_code, CodegenMode, GraphQLClientCodegenOptions};
+
+    let tests_dir = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("src/tests");
+    let options = GraphQLClientCodegenOptions::new(CodegenMode::Cli);
+    let error = generate_code(
+        tests_dir.join("missing_sub_selection_query.graphql"),
+        &tests_dir.join("star_wars_schema.graphql"),
+        options,
+    )
+    .err()
+    .expect("Selecting an object field without sub-selection fails")
+    .to_string();
+
+    assert_eq!(
+        error,
+        format!(
+            "field `starships` of type `Starship` must have a sub-selection ({}:4:5)",
+            tests_dir
+                .join("missing_sub_selection_query.graphql")
+                .display()
+        )
+    );
+}
+
+#[test]
+fn all_the_errors_of_an_operation_are_reported() {
+    use crate::{generate_code, CodegenErrors, CodegenMode, GraphQLClientCodegenOptions};
+
+    let tests_dir = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("src/tests");
+    let options = GraphQLClientCodegenOptions::new(CodegenMode::Cli);
+    let error = generate_code(
+        tests_dir.join("multiple_errors_query.graphql"),
+        &tests_dir.join("star_wars_schema.graphql"),
+        options,
+    )
+    .err()
+    .expect("Generating code for a query with errors fails");
+
+    assert_eq!(
+        error.downcast_ref::<CodegenErrors>().unwrap().errors.len(),
+        3
+    );
+    let query_path = tests_dir.join("multiple_errors_query.graphql");
+    assert_eq!(
+        error.to_string(),
+        format!(
+            "3 errors:\n\
+             - Could not find field `nam` on `Human`. Did you mean `name`? ({path}:3:5)\n\
+             - Unknown fragment: HumanDetails ({path}:7:8)\n\
+             - Could not find field `lenght` on `Starship`. Did you mean `length`? ({path}:5:7)",
+            path = query_path.display()
+        )
+    );
+}
+
+/// The error generating code for an operation of the forbidden fields fixture, if any.
+fn forbidden_fields_error(
+    operation_name: &str,
+    configure: impl Fn(&mut crate::GraphQLClientCodegenOptions),
+) -> Option<String> {
+    let tests_dir = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("src/tests");
+    let mut options = crate::GraphQLClientCodegenOptions::new(crate::CodegenMode::Cli);
+    options.set_operation_name(operation_name.to_owned());
+    configure(&mut options);
+
+    crate::generate_code(
+        tests_dir.join("forbidden_fields_query.graphql"),
+        &tests_dir.join("forbidden_fields_schema.graphql"),
+        options,
+    )
+    .err()
+    .map(|err| err.to_string())
+}
+
+#[test]
+fn forbidden_fields_are_reported() {
+    let query_path = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("src/tests/forbidden_fields_query.graphql");
+    let forbid = |options: &mut crate::GraphQLClientCodegenOptions| {
+        options.set_forbidden_fields("User.email, User.ssn".to_owned())
+    };
+
+    assert_eq!(forbidden_fields_error("UserLogin", forbid), None);
+    // Selected directly.
+    assert_eq!(
+        forbidden_fields_error("UserEmail", forbid).unwrap(),
+        format!(
+            "Policy error in {}: the operation `UserEmail` selects the forbidden field `User.email`.",
+            query_path.display()
+        )
+    );
+    // Selected on an interface implemented by the type.
+    assert_eq!(
+        forbidden_fields_error("AccountEmail", forbid).unwrap(),
+        format!(
+            "Policy error in {}: the operation `AccountEmail` selects the forbidden field `User.email` (as `Account.email`).",
+            query_path.display()
+        )
+    );
+    // Selected in a fragment on an interface implemented by the type.
+    assert_eq!(
+        forbidden_fields_error("ViewerDetails", forbid).unwrap(),
+        format!(
+            "Policy error in {}: the operation `ViewerDetails` selects the forbidden field `User.email` (as `Account.email`).",
+            query_path.display()
+        )
+    );
+    // Selected in an inline fragment.
+    assert_eq!(
+        forbidden_fields_error("RepositoryOwner", forbid).unwrap(),
+        format!(
+            "Policy error in {}: the operation `RepositoryOwner` selects the forbidden field `User.ssn`.",
+            query_path.display()
+        )
+    );
+    // Generating the fragment module.
+    assert_eq!(
+        forbidden_fields_error("UserLogin", |options| {
+            forbid(options);
+            options.set_fragment("AccountDetails".to_owned());
+        })
+        .unwrap(),
+        format!(
+            "Policy error in {}: the fragment `AccountDetails` selects the forbidden field `User.email` (as `Account.email`).",
+            query_path.display()
+        )
+    );
+}
+
+#[test]
+fn unknown_forbidden_fields_are_reported() {
+    assert_eq!(
+        forbidden_fields_error("UserLogin", |options| options
+            .set_forbidden_fields("User.password".to_owned()))
+        .unwrap(),
+        "The forbidden field `User.password` is not a field of an object or interface type of the schema."
+    );
+}
+
+#[test]
+fn fields_on_types_that_are_not_allowed_are_reported() {
+    let query_path = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("src/tests/forbidden_fields_query.graphql");
+    let allow = |options: &mut crate::GraphQLClientCodegenOptions| {
+        options.set_allowed_types("Query, User, Account".to_owned())
+    };
+
+    assert_eq!(forbidden_fields_error("ViewerDetails", allow), None);
+    assert_eq!(
+        forbidden_fields_error("RepositoryOwner", allow).unwrap(),
+        format!(
+            "Policy error in {}: the operation `RepositoryOwner` selects `Repository.name`, but `Repository` is not in the allowed types.",
+            query_path.display()
+        )
+    );
+}
+
+#[test]
+fn warnings_are_collected() {
+    use crate::deprecation::DeprecationStrategy;
+    use crate::warnings::{Location, Warning, WarningCode};
+    use crate::{generate_code, CodegenMode, GraphQLClientCodegenOptions};
+
+    let generate = |deprecation_strategy: DeprecationStrategy| {
+        let mut options = GraphQLClientCodegenOptions::new(CodegenMode::Cli);
+        options.set_deprecation_strategy(deprecation_strategy);
+
+        generate_code(
+            std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+                .join("src/tests/warnings_query.graphql"),
+            &std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+                .join("../graphql_client/tests/deprecation/schema.graphql"),
+            options,
+        )
+        .map_err(|err| err.to_string())
+        .unwrap()
+        .warnings
+    };
+    let unused_fragment = Warning {
+        code: WarningCode::UnusedFragment,
+        message: "The fragment `Unused` is not used by any operation.".to_owned(),
+        location: Some(Location {
+            line: 14,
+            column: 1,
+        }),
+    };
+
+    assert_eq!(
+        generate(DeprecationStrategy::Warn),
+        vec![
+            Warning {
+                code: WarningCode::DeprecatedField,
+                message: "The field `TestUser.deprecatedWithReason` is deprecated: Because"
+                    .to_owned(),
+                location: Some(Location { line: 4, column: 5 }),
+            },
+            Warning {
+                code: WarningCode::DeprecatedField,
+                message: "The field `TestUser.deprecatedNoReason` is deprecated.".to_owned(),
+                location: Some(Location {
+                    line: 11,
+                    column: 3
+                }),
+            },
+            unused_fragment.clone(),
+        ]
+    );
+    assert_eq!(generate(DeprecationStrategy::Allow), vec![unused_fragment]);
+    assert_eq!(
+        generate(DeprecationStrategy::Deny)[0].message,
+        "The field `TestUser.deprecatedWithReason` is deprecated, it is left out of the generated code because of the `deny` deprecation strategy."
+    );
+}
+
+#[test]
+fn operation_options_are_merged_over_the_global_options() {
+    use crate::{CodegenMode, GraphQLClientCodegenOptions, OperationOptions};
+
+    let mut options = GraphQLClientCodegenOptions::new(CodegenMode::Cli);
+    options.set_variables_derives("Debug".to_owned());
+    options.set_response_derives("Debug".to_owned());
+    options.set_operation_options(
+        "UserLogin".to_owned(),
+        OperationOptions {
+            response_derives: Some("Clone".to_owned()),
+            ..Default::default()
+        },
+    );
+
+    let user_login = options.for_operation("UserLogin");
+    assert_eq!(user_login.response_derives(), Some("Clone"));
+    assert_eq!(user_login.variables_derives(), Some("Debug"));
+    assert_eq!(
+        options.for_operation("UserEmail").response_derives(),
+        Some("Debug")
+    );
+}
+
+#[test]
+fn operations_of_the_same_document_can_have_different_options() {
+    use crate::{generate_code, CodegenMode, GraphQLClientCodegenOptions, OperationOptions};
+
+    let tests_dir = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("src/tests");
+    let mut options = GraphQLClientCodegenOptions::new(CodegenMode::Cli);
+    options.set_response_derives("Debug".to_owned());
+    options.set_operation_options(
+        "UserLogin".to_owned(),
+        OperationOptions {
+            response_derives: Some("Debug, PartialEq".to_owned()),
+            ..Default::default()
+        },
+    );
+    options.set_operation_options("RepoView".to_owned(), OperationOptions::default());
+
+    let generated = generate_code(
+        tests_dir.join("forbidden_fields_query.graphql"),
+        &tests_dir.join("forbidden_fields_schema.graphql"),
+        options,
+    )
+    .map_err(|err| err.to_string())
+    .unwrap();
+    let tokens = generated.tokens.to_string();
+
+    assert!(
+        tokens.contains("# [derive (Debug , Deserialize , PartialEq)] pub struct UserLoginUser")
+    );
+    assert!(tokens.contains("# [derive (Debug , Deserialize)] pub struct UserEmailUser"));
+    assert_eq!(
+        generated.warnings,
+        vec![crate::warnings::Warning {
+            code: crate::warnings::WarningCode::UnknownOperation,
+            message: "Options are set for the operation `RepoView`, but the query document has no such operation. The operations are: `UserLogin`, `UserEmail`, `AccountEmail`, `ViewerDetails`, `RepositoryOwner`.".to_owned(),
+            location: None,
+        }]
+    );
+}
+
+#[test]
+fn variables_implement_default_only_when_all_of_them_can_be_left_out() {
+    let generate = |query: &str| {
+        let tests_dir = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("../graphql_client/tests/scalar_variables");
+
+        crate::generate_module_token_stream(
+            tests_dir.join(query),
+            &tests_dir.join("scalar_variables_schema.graphql"),
+            crate::GraphQLClientCodegenOptions::new(crate::CodegenMode::Cli),
+        )
+        .map_err(|err| err.to_string())
+        .unwrap()
+        .to_string()
+    };
+    let default_impl = "impl :: std :: default :: Default for Variables";
+
+    assert!(generate("scalar_variables_query_defaults.graphql").contains(default_impl));
+    // `$msg: String!` has no default value.
+    assert!(!generate("scalar_variables_query.graphql").contains(default_impl));
+}
+
+#[test]
+fn post_process_hook_transforms_each_module() {
+    fn allow_dead_code(module: proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+        quote::quote!(#[allow(dead_code)] #module)
+    }
+
+    let tokens = generate_fixture(
+        "../graphql_client/tests/operation_selection",
+        "queries.graphql",
+        "schema.graphql",
+        |options| options.set_post_process(allow_dead_code),
+    )
+    .unwrap();
+
+    let allow_clippy = "# [allow (clippy :: all , clippy :: pedantic)]";
+    assert!(tokens.starts_with(&format!(
+        "# [allow (dead_code)] {} struct Heights ; mod heights {{",
+        allow_clippy
+    )));
+    assert!(tokens.contains(&format!(
+        "# [allow (dead_code)] {} struct Echo ; mod echo {{",
+        allow_clippy
+    )));
+}
+
+#[test]
+fn redacting_a_field_that_does_not_exist_is_an_error() {
+    let generate = |redact: &str| {
+        generate_fixture(
+            "../graphql_client/tests/redact",
+            "mutation.graphql",
+            "schema.graphql",
+            |options| {
+                options.set_variables_derives("Debug".to_owned());
+                options.set_redact(redact.to_owned());
+            },
+        )
+    };
+
+    assert!(generate("Variables: password").is_ok());
+    assert_eq!(
+        generate("Variables: passwd").unwrap_err(),
+        "The redacted field `passwd` is not a field of `Variables`."
+    );
+    // The error is located at the field of the struct.
+    assert_eq!(
+        generate("LoginLogin: secret").unwrap_err(),
+        format!(
+            "The redacted field `secret` is not a field of `LoginLogin`. ({}:2:3)",
+            fixture_dir("../graphql_client/tests/redact/mutation.graphql").display()
+        )
+    );
+}
+
+#[test]
+fn included_query_files_can_only_define_new_fragments() {
+    let tests_dir = fixture_dir("../graphql_client/tests/included_query_files");
+    let generate = |included: &[&str]| {
+        generate_fixture(&tests_dir, "query.graphql", "schema.graphql", |options| {
+            options.set_included_query_files(
+                included.iter().map(|file| tests_dir.join(file)).collect(),
+            )
+        })
+    };
+
+    assert!(generate(&["fragments.graphql"]).is_ok());
+    assert_eq!(
+        generate(&["query.graphql"]).unwrap_err(),
+        format!(
+            "The included query file {} defines an operation. Included query files can only define fragments.",
+            tests_dir.join("query.graphql").display()
+        )
+    );
+    assert_eq!(
+        generate(&["fragments.graphql", "fragments.graphql"]).unwrap_err(),
+        format!(
+            "The fragment `AuthorFields` is defined in both {} and {}.",
+            tests_dir.join("fragments.graphql").display(),
+            tests_dir.join("fragments.graphql").display()
+        )
+    );
+}
+
+#[test]
+fn skipping_a_field_that_does_not_exist_is_an_error() {
+    let generate = |skip_serde: &str| {
+        generate_fixture(
+            "../graphql_client/tests/skip_serde",
+            "query.graphql",
+            "schema.graphql",
+            |options| options.set_skip_serde(skip_serde.to_owned()),
+        )
+    };
+
+    assert!(generate("ItemsQueryItems: local_notes").is_ok());
+    assert_eq!(
+        generate("ItemsQueryItems: notes").unwrap_err(),
+        format!(
+            "The skipped field `notes` is not a field of `ItemsQueryItems`. ({}:2:3)",
+            fixture_dir("../graphql_client/tests/skip_serde/query.graphql").display()
+        )
+    );
+    assert_eq!(
+        generate("local_notes").unwrap_err(),
+        "Invalid skip_serde entry `local_notes`, expected `TypeName: field, field`."
+    );
+}
+
+#[test]
+fn type_options_for_types_that_are_not_generated_are_errors() {
+    let generate = |configure: &dyn Fn(&mut crate::GraphQLClientCodegenOptions)| {
+        let tests_dir =
+            std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("../graphql_client/tests/redact");
+        let mut options = crate::GraphQLClientCodegenOptions::new(crate::CodegenMode::Cli);
+        configure(&mut options);
+
+        crate::generate_code(
+            tests_dir.join("mutation.graphql"),
+            &tests_dir.join("schema.graphql"),
+            options,
+        )
+        .map_err(|err| err.to_string())
+    };
+
+    assert!(generate(&|options| options.set_redact("LoginLoginUser: name".to_owned())).is_ok());
+    assert_eq!(
+        generate(&|options| options.set_redact("Variable: password".to_owned()))
+            .err()
+            .unwrap(),
+        "The redact type `Variable` is not generated, or the option does not apply to it. Types it applies to: LoginLogin, LoginLoginUser, ResponseData, Variables"
+    );
+    assert!(generate(&|options| {
+        options.set_skip_serde("LoginLogin: token".to_owned());
+        options.set_derives_for("Episode: Hash; LoginLogin: Default".to_owned());
+    })
+    .err()
+    .unwrap()
+    .starts_with("The derives_for type `Episode` is not generated"));
+
+    // With the deferred check, the caller gets them instead.
+    let generated = generate(&|options| {
+        options.set_derives_for("Episode: Hash".to_owned());
+        options.set_defer_option_checks(true);
+    })
+    .unwrap();
+    assert_eq!(
+        generated.unapplied_type_options,
+        [crate::UnappliedTypeOption {
+            option: "derives_for",
+            type_name: "Episode".to_owned(),
+        }]
+    );
+}
+
+#[test]
+fn non_exhaustive_enums_are_marked() {
+    let generate = |exhaustive_enums: bool| {
+        generate_fixture(
+            "../graphql_client/tests/non_exhaustive_enums",
+            "query.graphql",
+            "schema.graphql",
+            |options| {
+                options.set_exhaustive_enums(exhaustive_enums);
+                options.set_enum_other_variant("Unknown".to_owned());
+            },
+        )
+        .unwrap()
+    };
+
+    assert!(generate(false).contains("# [non_exhaustive] pub enum Category"));
+    assert!(!generate(true).contains("non_exhaustive"));
+}
+
+#[test]
+fn enum_values_named_like_the_other_variant_are_rejected() {
+    let generate = |enum_other_variant: Option<&str>| {
+        generate_fixture(
+            "../graphql_client/tests/non_exhaustive_enums",
+            "query.graphql",
+            "schema.graphql",
+            |options| {
+                if let Some(enum_other_variant) = enum_other_variant {
+                    options.set_enum_other_variant(enum_other_variant.to_owned());
+                }
+            },
+        )
+    };
+
+    assert_eq!(
+        generate(None).unwrap_err(),
+        "The enum `Category` has the value `Other`, which is also the name of the variant for the values unknown to the schema. Set `enum_other_variant` to another name."
+    );
+    assert_eq!(
+        generate(Some("BOOK")).unwrap_err(),
+        "The enum `Category` has the value `BOOK`, which is also the name of the variant for the values unknown to the schema. Set `enum_other_variant` to another name."
+    );
+    assert_eq!(
+        generate(Some("not valid")).unwrap_err(),
+        "The enum variant name `not valid` is not a valid Rust identifier."
+    );
+    assert!(generate(Some("Unknown")).is_ok());
+}
+
+#[test]
+fn enum_tests_are_generated_for_a_schema_file() {
+    let generate = |enum_tests_schema: Option<&str>| {
+        generate_fixture(
+            "../graphql_client/tests/codegen_tests",
+            "query.graphql",
+            "schema.graphql",
+            |options| {
+                if let Some(enum_tests_schema) = enum_tests_schema {
+                    options.set_enum_tests_schema(enum_tests_schema.into());
+                }
+            },
+        )
+        .unwrap()
+    };
+
+    assert!(generate(Some("schema.graphql")).contains(
+        "# [cfg (test)] # [test] pub fn task_status_matches_the_schema () { :: graphql_client :: codegen_tests :: assert_enum_values (\"schema.graphql\" , \"TaskStatus\" , TaskStatus :: VARIANTS) ; }"
+    ));
+    assert!(!generate(None).contains("matches_the_schema"));
+}
+
+#[test]
+fn read_file_strips_byte_order_mark() {
+    let path = std::env::temp_dir().join("graphql_client_codegen_bom_query.graphql");
+    std::fs::write(&path, b"\xEF\xBB\xBFquery Q { name }").unwrap();
+
+    assert_eq!(crate::read_file(&path).unwrap(), "query Q { name }");
+}
+
+#[test]
+fn read_file_rejects_non_utf8_files() {
+    // "café" encoded as latin-1.
+    let path = std::env::temp_dir().join("graphql_client_codegen_latin1_schema.graphql");
+    std::fs::write(&path, b"# caf\xE9\ntype Query { name: String }").unwrap();
+
+    let error = crate::read_file(&path).unwrap_err().to_string();
+
+    assert!(error.contains(&path.display().to_string()));
+    assert!(error.contains("invalid byte at offset 5"));
+    assert!(error.contains("re-saving the file as UTF-8"));
+}
+
+#[test]
+fn unsupported_schema_extensions_are_reported() {
+    use crate::{generate_code, CodegenMode, GraphQLClientCodegenOptions};
+
+    let tests_dir = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("src/tests");
+    let schema_path = std::env::temp_dir().join("graphql_client_codegen_schema.yaml");
+    std::fs::write(&schema_path, "type Query { name: String }").unwrap();
+
+    let error = generate_code(
+        tests_dir.join("missing_sub_selection_query.graphql"),
+        &schema_path,
+        GraphQLClientCodegenOptions::new(CodegenMode::Cli),
+    )
+    .err()
+    .expect("Reading a schema with an unknown extension fails");
+
+    assert_eq!(
+        error
+            .downcast_ref::<crate::UnsupportedSchemaExtension>()
+            .expect("The error is an UnsupportedSchemaExtension")
+            .extension,
+        "yaml"
+    );
+    assert_eq!(
+        error.to_string(),
+        "Unsupported extension for the GraphQL schema: yaml (only .json and .graphql are supported)"
+    );
+}
+
+#[test]
+fn operations_without_root_type_in_the_schema_are_rejected() {
+    use crate::{codegen, schema::Schema, CodegenMode, GraphQLClientCodegenOptions};
+
+    let schema = graphql_parser::parse_schema(include_str!("forbidden_fields_schema.graphql"))
+        .expect("Parse forbidden fields schema");
+    let schema = Schema::from(&schema);
+    let options = GraphQLClientCodegenOptions::new(CodegenMode::Cli);
+    let query = graphql_parser::parse_query("mutation RenameUser { renameUser { id } }")
+        .expect("Parse query");
+    let operations = codegen::all_operations(&query).unwrap();
+
+    let error = codegen::response_for_query(&schema, &query, &operations[0], &options)
+        .unwrap_err()
+        .to_string();
+
+    assert_eq!(
+        error,
+        "The mutation `RenameUser` cannot be generated: the schema has no `Mutation` type."
+    );
+}
+
+#[test]
+fn untagged_variants_selecting_the_same_fields_are_rejected() {
+    let generate = |query: &str, untagged_unions: bool| {
+        let tests_dir = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("../graphql_client/tests/untagged_unions");
+        let mut options = crate::GraphQLClientCodegenOptions::new(crate::CodegenMode::Cli);
+        options.set_untagged_unions(untagged_unions);
+
+        crate::generate_module_token_stream(
+            tests_dir.join(query),
+            &tests_dir.join("schema.graphql"),
+            options,
+        )
+        .map(|tokens| tokens.to_string())
+        .map_err(|err| err.to_string())
+    };
+
+    assert_eq!(
+        generate("ambiguous_query.graphql", true).unwrap_err(),
+        format!(
+            "The `Book` and `Podcast` variants of `AmbiguousQuerySearch` select the same fields, so they cannot be told apart without `__typename`. ({}:6:9)",
+            std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+                .join("../graphql_client/tests/untagged_unions/ambiguous_query.graphql")
+                .display()
+        )
+    );
+
+    let generated = generate("query.graphql", true).unwrap();
+    assert!(generated.contains("# [serde (untagged)] pub enum SearchQuerySearch { Book (SearchQuerySearchOnBook) , Magazine (SearchQuerySearchOnMagazine) , Podcast (SearchQuerySearchOnPodcast) , Other { } }"));
+    assert!(generate("query.graphql", false).is_err());
+}
+
+#[test]
+fn query_files_are_read_again_when_they_change() {
+    let directory = std::env::temp_dir().join("graphql_client_codegen_cache_invalidation");
+    std::fs::create_dir_all(&directory).unwrap();
+    std::fs::write(
+        directory.join("schema.graphql"),
+        "type User { name: String! email: String! }\ntype Query { user: User! }\n",
+    )
+    .unwrap();
+
+    let generate = |query: &str| {
+        std::fs::write(directory.join("query.graphql"), query).unwrap();
+        generate_fixture(&directory, "query.graphql", "schema.graphql", |_| ()).unwrap()
+    };
+
+    assert!(!generate("query UserQuery { user { name } }").contains("pub email"));
+    assert!(generate("query UserQuery { user { name email } }").contains("pub email"));
+}
+
+#[test]
+fn each_connection_gets_its_own_cursor_newtype() {
+    let generated = generate_fixture(
+        "../graphql_client/tests/cursor_newtypes",
+        "query.graphql",
+        "schema.graphql",
+        |options| options.set_cursor_newtypes(true),
+    )
+    .unwrap();
+
+    assert!(generated.contains("pub struct RepoViewRepositoryIssuesCursor (pub String)"));
+    assert!(generated.contains("pub struct RepoViewRepositoryPullRequestsCursor (pub String)"));
+    assert!(generated.contains("pub cursor : RepoViewRepositoryIssuesCursor"));
+    assert!(generated.contains("pub issues_after : Option < RepoViewRepositoryIssuesCursor >"));
+    assert!(generated
+        .contains("pub pull_requests_after : Option < RepoViewRepositoryPullRequestsCursor >"));
+}
+
+#[test]
+fn connections_with_the_same_name_get_different_cursor_newtypes() {
+    let directory = std::env::temp_dir().join("graphql_client_codegen_aliased_connections");
+    std::fs::create_dir_all(&directory).unwrap();
+    std::fs::copy(
+        fixture_dir("../graphql_client/tests/cursor_newtypes").join("schema.graphql"),
+        directory.join("schema.graphql"),
+    )
+    .unwrap();
+    std::fs::write(
+        directory.join("query.graphql"),
+        "query TwoRepositories($mainAfter: String, $forkAfter: String) {\n  \
+         main: repository(name: \"graphql-client\") {\n    \
+         issues(first: 10, after: $mainAfter) { pageInfo { endCursor } }\n  \
+         }\n  \
+         fork: repository(name: \"graphql-client-fork\") {\n    \
+         issues(first: 10, after: $forkAfter) { pageInfo { endCursor } }\n  \
+         }\n}\n",
+    )
+    .unwrap();
+
+    let generated = generate_fixture(&directory, "query.graphql", "schema.graphql", |options| {
+        options.set_cursor_newtypes(true)
+    })
+    .unwrap();
+
+    assert!(generated.contains("pub main_after : Option < TwoRepositoriesMainIssuesCursor >"));
+    assert!(generated.contains("pub fork_after : Option < TwoRepositoriesForkIssuesCursor >"));
+}
+
+#[test]
+fn a_cursor_variable_shared_by_two_connections_is_rejected() {
+    let schema_path = ::std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("../graphql_client/tests/cursor_newtypes/schema.graphql");
+    let query_path = std::env::temp_dir().join("graphql_client_codegen_shared_cursor.graphql");
+    std::fs::write(
+        &query_path,
+        "query SharedCursor($after: String) {\n  \
+         repository(name: \"graphql-client\") {\n    \
+         issues(first: 10, after: $after) { pageInfo { endCursor } }\n    \
+         pullRequests(first: 10, after: $after) { pageInfo { endCursor } }\n  \
+         }\n}\n",
+    )
+    .unwrap();
+
+    let mut options = crate::GraphQLClientCodegenOptions::new(crate::CodegenMode::Cli);
+    options.set_cursor_newtypes(true);
+    let error = crate::generate_module_token_stream(query_path, &schema_path, options)
+        .map_err(|err| err.to_string())
+        .unwrap_err();
+
+    assert_eq!(
+        error,
+        "The variable `$after` is the cursor of two connections, with the `SharedCursorRepositoryIssuesCursor` and `SharedCursorRepositoryPullRequestsCursor` types. Use a variable for each connection."
+    );
+}
+
+#[test]
+fn queries_as_bytes_are_rejected_in_cli_mode() {
+    let error = generate_fixture(
+        "../graphql_client/tests/operation_selection",
+        "queries.graphql",
+        "schema.graphql",
+        |options| options.set_query_as_bytes(true),
+    )
+    .unwrap_err();
+
+    assert!(
+        error.contains("The `query_as_bytes` option is only supported by the derive"),
+        "{}",
+        error
+    );
+}
+
+#[test]
+fn literal_enum_arguments_are_validated() {
+    let generate =
+        |query: &str| generate_fixture("src/tests", query, "enum_arguments_schema.graphql", |_| ());
+
+    assert!(generate("enum_arguments_query.graphql").is_ok());
+
+    let error = generate("invalid_enum_arguments_query.graphql").unwrap_err();
+    assert!(error.contains(
+        "The argument `orderBy.field` of the field `Repository.issues` is `CREATD_AT`, which is not a value of the `IssueOrderField` enum. The values are: CREATED_AT, UPDATED_AT, COMMENTS."
+    ));
+    assert!(error.contains(
+        "The argument `by` of the field `Repository.pinnedIssues` is `UPDATED`, which is not a value of the `IssueOrderField` enum. The values are: CREATED_AT, UPDATED_AT, COMMENTS."
+    ));
+}
+
+#[test]
+fn date_time_scalars_are_mapped_to_the_chrono_and_time_types() {
+    use crate::date_times::DateTimeCrate;
+    use crate::{generate_code, CodegenMode, GraphQLClientCodegenOptions};
+
+    let fixtures =
+        std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("../graphql_client/tests/date_times");
+    let generate = |date_time_crate: DateTimeCrate| {
+        let mut options = GraphQLClientCodegenOptions::new(CodegenMode::Cli);
+        options.set_date_time_crate(date_time_crate);
+        generate_code(
+            fixtures.join("query.graphql"),
+            &fixtures.join("schema.graphql"),
+            options,
+        )
+        .map_err(|err| err.to_string())
+    };
+
+    let chrono = generate(DateTimeCrate::Chrono).unwrap();
+    let code = chrono.tokens.to_string();
+
+    assert!(code.contains("type DateTime = :: chrono :: DateTime < :: chrono :: Utc > ;"));
+    assert!(code.contains("type Date = :: chrono :: NaiveDate ;"));
+    assert!(code.contains("type Time = :: chrono :: NaiveTime ;"));
+    assert!(!code.contains("super :: DateTime"));
+    assert!(chrono.referenced_scalars.is_empty());
+
+    // Without a crate, the scalars are aliased from the parent module.
+    let off = generate(DateTimeCrate::Off).unwrap();
+    assert!(off
+        .tokens
+        .to_string()
+        .contains("type DateTime = super :: DateTime ;"));
+    assert_eq!(off.referenced_scalars, vec!["Date", "DateTime", "Time"]);
+
+    // The `time` types are (de)serialized as ISO 8601 strings by the helpers of graphql_client.
+    let time = generate(DateTimeCrate::Time).unwrap();
+    let code = time.tokens.to_string();
+
+    assert!(code.contains("type DateTime = :: time :: OffsetDateTime ;"));
+    assert!(code.contains("type Time = :: time :: Time ;"));
+    assert!(code.contains(
+        "# [serde (default , with = \"graphql_client::serde_helpers::iso8601\")] pub opens_at : Option < Time >"
+    ));
+    assert!(time.referenced_scalars.is_empty());
+}
+
+#[test]
+fn deprecated_interface_fields_are_reported_for_sdl_and_json_schemas() {
+    use crate::deprecation::DeprecationStrategy;
+    use crate::warnings::{Location, Warning, WarningCode};
+    use crate::{generate_code, CodegenMode, GraphQLClientCodegenOptions};
+
+    let tests_dir = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("src/tests");
+    let generate = |schema: &str, deprecation_strategy: DeprecationStrategy| {
+        let mut options = GraphQLClientCodegenOptions::new(CodegenMode::Cli);
+        options.set_deprecation_strategy(deprecation_strategy);
+
+        generate_code(
+            tests_dir.join("deprecated_interface_query.graphql"),
+            &tests_dir.join(schema),
+            options,
+        )
+        .map_err(|err| err.to_string())
+        .unwrap()
+    };
+
+    for schema in &[
+        "deprecated_interface_schema.graphql",
+        "deprecated_interface_schema.json",
+    ] {
+        assert_eq!(
+            generate(schema, DeprecationStrategy::Warn).warnings,
+            vec![Warning {
+                code: WarningCode::DeprecatedField,
+                message: "The field `Node.legacyId` is deprecated: Use `id`.".to_owned(),
+                location: Some(Location { line: 5, column: 5 }),
+            }],
+            "{}",
+            schema
+        );
+        assert!(!generate(schema, DeprecationStrategy::Deny)
+            .tokens
+            .to_string()
+            .contains("legacy_id"));
+    }
+}
+
+#[test]
+fn schema_info_counts_the_types_and_detects_the_features() {
+    use crate::schema::{FeatureSupport, SchemaFeature, SchemaInfo};
+
+    let tests_dir = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("src/tests");
+    let schema_info = |schema: &str| {
+        crate::schema_info(&tests_dir.join(schema))
+            .map_err(|err| err.to_string())
+            .unwrap()
+    };
+
+    let sdl = schema_info("schema_features_schema.graphql");
+
+    assert_eq!(
+        sdl,
+        SchemaInfo {
+            objects: 3,
+            interfaces: 1,
+            unions: 1,
+            enums: 1,
+            inputs: 1,
+            scalars: 1,
+            // `@oneOf` is a custom directive too, until it is supported.
+            directives: 2,
+            query_type: "Query".to_owned(),
+            mutation_type: None,
+            subscription_type: Some("Subscription".to_owned()),
+            features: vec![
+                SchemaFeature::Subscriptions,
+                SchemaFeature::CustomScalars,
+                SchemaFeature::Deprecations,
+                SchemaFeature::CustomDirectives,
+                SchemaFeature::TypeExtensions,
+                SchemaFeature::OneOfInputObjects,
+            ]
+            .into_iter()
+            .collect(),
+        }
+    );
+    assert_eq!(
+        SchemaFeature::CustomDirectives.support(),
+        FeatureSupport::Ignored
+    );
+    assert_eq!(
+        sdl.unsupported_features().collect::<Vec<_>>(),
+        vec![
+            SchemaFeature::TypeExtensions,
+            SchemaFeature::OneOfInputObjects
+        ]
+    );
+
+    let json = schema_info("schema_features_schema.json");
+
+    // The introspection types and the built-in directives are not counted.
+    assert_eq!((json.objects, json.interfaces, json.directives), (2, 2, 1));
+    assert_eq!(json.subscription_type, None);
+    assert_eq!(
+        json.features.into_iter().collect::<Vec<_>>(),
+        vec![
+            SchemaFeature::CustomDirectives,
+            SchemaFeature::InterfaceInheritance
+        ]
+    );
+}
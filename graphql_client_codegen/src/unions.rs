@@ -1,10 +1,16 @@
+use crate::constants::TYPENAME_FIELD;
 use crate::query::QueryContext;
-use crate::selection::Selection;
+use crate::selection::{Selection, SelectionItem};
 use failure::*;
+use heck::SnakeCase;
 use proc_macro2::{Ident, Span, TokenStream};
 use quote::quote;
 use std::cell::Cell;
-use std::collections::BTreeSet;
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// The variant of untagged enums for the members without selected fields.
+const UNSELECTED_MEMBERS_VARIANT: &str = "Other";
 
 /// A GraphQL union (simplified schema representation).
 ///
@@ -28,28 +34,37 @@ enum UnionError {
     MissingTypename { union_name: String },
 }
 
-type UnionVariantResult<'selection> =
-    Result<(Vec<TokenStream>, Vec<TokenStream>, Vec<&'selection str>), failure::Error>;
-
-/// Returns a triple.
+type UnionVariantResult<'selection> = Result<
+    (
+        Vec<TokenStream>,
+        Vec<TokenStream>,
+        Vec<&'selection str>,
+        Vec<Ident>,
+    ),
+    failure::Error,
+>;
+
+/// Returns a quadruple.
 ///
 /// - The first element is the union variants to be inserted directly into the `enum` declaration.
 /// - The second is the structs for each variant's sub-selection
-/// - The last one contains which fields have been selected on the union, so we can make the enum exhaustive by complementing with those missing.
+/// - The third one contains which fields have been selected on the union, so we can make the enum exhaustive by complementing with those missing.
+/// - The last one is the names of the variant structs.
 pub(crate) fn union_variants<'selection>(
     selection: &'selection Selection<'_>,
     context: &'selection QueryContext<'selection, 'selection>,
     prefix: &str,
     selection_on: &str,
 ) -> UnionVariantResult<'selection> {
-    let selection = selection.selected_variants_on_union(context, selection_on)?;
-    let mut used_variants: Vec<&str> = selection.keys().cloned().collect();
-    let mut children_definitions = Vec::with_capacity(selection.len());
-    let mut variants = Vec::with_capacity(selection.len());
+    let selected_variants = selection.selected_variants_on_union(context, selection_on)?;
+    let used_variants: Vec<&str> = selected_variants.keys().cloned().collect();
+    let mut children_definitions = Vec::with_capacity(selected_variants.len());
+    let mut variants = Vec::with_capacity(selected_variants.len());
+    let mut variant_types = Vec::with_capacity(selected_variants.len());
+    let mut errors = Vec::new();
 
-    for (on, fields) in selection.iter() {
-        let variant_name = Ident::new(&on, Span::call_site());
-        used_variants.push(on);
+    for (on, fields) in selected_variants.iter() {
+        let variant_name = Ident::new(on, Span::call_site());
 
         let new_prefix = format!("{}On{}", prefix, on);
 
@@ -59,36 +74,208 @@ pub(crate) fn union_variants<'selection>(
             .schema
             .objects
             .get(on)
-            .map(|_f| context.maybe_expand_field(&on, fields, &new_prefix));
+            .map(|_f| context.maybe_expand_field(on, fields, &new_prefix));
         let field_interface = context
             .schema
             .interfaces
             .get(on)
-            .map(|_f| context.maybe_expand_field(&on, fields, &new_prefix));
+            .map(|_f| context.maybe_expand_field(on, fields, &new_prefix));
         let field_union_type = context
             .schema
             .unions
             .get(on)
-            .map(|_f| context.maybe_expand_field(&on, fields, &new_prefix));
-
-        match field_object_type.or(field_interface).or(field_union_type) {
-            Some(Ok(Some(tokens))) => children_definitions.push(tokens),
-            Some(Err(err)) => return Err(err),
-            Some(Ok(None)) => (),
-            None => {
-                return Err(UnionError::UnknownType {
+            .map(|_f| context.maybe_expand_field(on, fields, &new_prefix));
+
+        let error = match field_object_type.or(field_interface).or(field_union_type) {
+            Some(Ok(Some(tokens))) => {
+                children_definitions.push(tokens);
+                None
+            }
+            Some(Err(err)) => Some(err),
+            Some(Ok(None)) => None,
+            None => Some(
+                UnionError::UnknownType {
                     ty: (*on).to_string(),
                 }
-                .into())
-            }
+                .into(),
+            ),
         };
 
+        if let Some(error) = error {
+            errors.push(at_variant(selection, context, on, error));
+        }
+
         variants.push(quote! {
             #variant_name(#variant_type)
+        });
+        variant_types.push(variant_type);
+    }
+
+    // The errors of all the variants are reported together.
+    if !errors.is_empty() {
+        return Err(crate::errors::combine(errors));
+    }
+
+    Ok((variants, children_definitions, used_variants, variant_types))
+}
+
+/// The error located where the selection refines the union or interface to the member, or at the
+/// start of the selection.
+fn at_variant(
+    selection: &Selection<'_>,
+    context: &QueryContext<'_, '_>,
+    on: &str,
+    error: failure::Error,
+) -> failure::Error {
+    match selection
+        .variant_position(context, on)
+        .or_else(|| selection.position())
+    {
+        Some(position) => crate::errors::at(position, error),
+        None => error,
+    }
+}
+
+/// The fields selected on a member of a union or interface, by response key, with whether they are
+/// non-null. The fields of the spread fragments are included, since they are flattened in the
+/// struct of the variant.
+fn selected_fields(
+    selection: &Selection<'_>,
+    on: &str,
+    context: &QueryContext<'_, '_>,
+    fields: &mut BTreeMap<String, bool>,
+) {
+    for item in selection {
+        match item {
+            SelectionItem::Field(field) if field.name == TYPENAME_FIELD => (),
+            SelectionItem::Field(field) => {
+                let non_null = !field.conditional
+                    && context
+                        .schema
+                        .object_or_interface_fields(on)
+                        .and_then(|fields| fields.iter().find(|f| f.name == field.name))
+                        .map(|schema_field| !schema_field.type_.is_optional())
+                        .unwrap_or(false);
+                fields.insert(field.alias.unwrap_or(field.name).to_owned(), non_null);
+            }
+            SelectionItem::FragmentSpread(spread) => {
+                if let Some(fragment) = context.fragments.get(spread.fragment_name) {
+                    selected_fields(&fragment.selection, on, context, fields);
+                }
+            }
+            SelectionItem::InlineFragment(inline_fragment) => {
+                selected_fields(&inline_fragment.fields, inline_fragment.on, context, fields)
+            }
+        }
+    }
+}
+
+/// The variants of an untagged union or interface enum, for responses without `__typename`.
+///
+/// Serde deserializes a response as the first variant it matches, so the variants are ordered from
+/// the most specific, the one with the most non-null fields, to the least specific. The members
+/// without selected fields can't be told apart: they are deserialized as a last `Other {}` variant.
+///
+/// Fails when two variants select the same fields, since nothing tells their responses apart.
+pub(crate) fn untagged_variants(
+    selection: &Selection<'_>,
+    context: &QueryContext<'_, '_>,
+    enum_name: &str,
+    selection_on: &str,
+    variants: Vec<TokenStream>,
+    used_variants: &[&str],
+    has_unselected_members: bool,
+) -> Result<Vec<TokenStream>, failure::Error> {
+    let selected_variants = selection.selected_variants_on_union(context, selection_on)?;
+    let mut variants: Vec<(&str, BTreeMap<String, bool>, TokenStream)> = used_variants
+        .iter()
+        .zip(variants)
+        .map(|(on, tokens)| {
+            let mut fields = BTreeMap::new();
+            if let Some(selection) = selected_variants.get(on) {
+                selected_fields(selection, on, context, &mut fields);
+            }
+            (*on, fields, tokens)
+        })
+        .collect();
+
+    if has_unselected_members {
+        if used_variants.contains(&UNSELECTED_MEMBERS_VARIANT) {
+            return Err(at_variant(
+                selection,
+                context,
+                UNSELECTED_MEMBERS_VARIANT,
+                format_err!(
+                    "The `{}` member of `{}` has the name of the variant for the members without selected fields, which `untagged_unions` generates.",
+                    UNSELECTED_MEMBERS_VARIANT,
+                    enum_name
+                ),
+            ));
+        }
+
+        let name = Ident::new(UNSELECTED_MEMBERS_VARIANT, Span::call_site());
+        variants.push((
+            UNSELECTED_MEMBERS_VARIANT,
+            BTreeMap::new(),
+            quote!(#name {}),
+        ));
+    }
+
+    let errors: Vec<failure::Error> = variants
+        .iter()
+        .enumerate()
+        .flat_map(|(index, (name, fields, _))| {
+            variants[index + 1..]
+                .iter()
+                .filter(move |(_, other_fields, _)| other_fields.keys().eq(fields.keys()))
+                .map(move |(other_name, _, _)| {
+                    let error = format_err!(
+                        "The `{}` and `{}` variants of `{}` select the same fields, so they cannot be told apart without `__typename`.",
+                        name,
+                        other_name,
+                        enum_name
+                    );
+                    at_variant(selection, context, other_name, error)
+                })
         })
+        .collect();
+
+    if !errors.is_empty() {
+        return Err(crate::errors::combine(errors));
     }
 
-    Ok((variants, children_definitions, used_variants))
+    variants.sort_by_key(|(name, fields, _)| {
+        let non_null = fields.values().filter(|non_null| **non_null).count();
+        (Reverse(non_null), Reverse(fields.len()), *name)
+    });
+
+    Ok(variants.into_iter().map(|(_, _, tokens)| tokens).collect())
+}
+
+/// The sealed `{Union}Member` trait, implemented by the structs of the variants of the union.
+fn member_trait(union_name: &Ident, variant_types: &[Ident]) -> TokenStream {
+    let trait_name = Ident::new(&format!("{}Member", union_name), Span::call_site());
+    let sealed_module = Ident::new(
+        &format!("{}_member_sealed", union_name.to_string().to_snake_case()),
+        Span::call_site(),
+    );
+    let doc = format!(
+        "Implemented by the types of the variants of [`{}`]. It can't be implemented outside of this module.",
+        union_name
+    );
+
+    quote! {
+        mod #sealed_module {
+            pub trait Sealed {}
+
+            #(impl Sealed for super::#variant_types {})*
+        }
+
+        #[doc = #doc]
+        pub trait #trait_name: #sealed_module::Sealed {}
+
+        #(impl #trait_name for #variant_types {})*
+    }
 }
 
 impl<'schema> GqlUnion<'schema> {
@@ -99,49 +286,96 @@ impl<'schema> GqlUnion<'schema> {
         selection: &Selection<'_>,
         prefix: &str,
     ) -> Result<TokenStream, failure::Error> {
-        let typename_field = selection.extract_typename(query_context);
-
-        if typename_field.is_none() {
-            return Err(UnionError::MissingTypename {
-                union_name: prefix.into(),
+        // Untagged enums are deserialized without `__typename`.
+        let typename_field = match selection.extract_typename(query_context) {
+            None if !query_context.untagged_unions => {
+                let error = UnionError::MissingTypename {
+                    union_name: prefix.into(),
+                }
+                .into();
+                Err(match selection.position() {
+                    Some(position) => crate::errors::at(position, error),
+                    None => error,
+                })
             }
-            .into());
-        }
+            _ => Ok(()),
+        };
 
         let struct_name = Ident::new(prefix, Span::call_site());
-        let derives = query_context.response_derives();
-
-        let (mut variants, children_definitions, used_variants) =
-            union_variants(selection, query_context, prefix, &self.name)?;
-
-        for used_variant in used_variants.iter() {
-            if !self.variants.contains(used_variant) {
-                return Err(UnionError::UnknownVariant {
+        let derives = query_context.response_derives(prefix);
+        let attributes = query_context.selection_attributes(prefix);
+
+        let (_, (mut variants, children_definitions, used_variants, variant_types)) =
+            crate::errors::join(
+                typename_field,
+                union_variants(selection, query_context, prefix, self.name),
+            )?;
+
+        let unknown_variants: Vec<failure::Error> = used_variants
+            .iter()
+            .filter(|used_variant| !self.variants.contains(*used_variant))
+            .map(|used_variant| {
+                let error = UnionError::UnknownVariant {
                     ty: self.name.into(),
                     var: (*used_variant).to_string(),
                 }
-                .into());
-            }
+                .into();
+                at_variant(selection, query_context, used_variant, error)
+            })
+            .collect();
+
+        if !unknown_variants.is_empty() {
+            return Err(crate::errors::combine(unknown_variants));
         }
 
-        variants.extend(
-            self.variants
-                .iter()
-                .filter(|v| used_variants.iter().find(|a| a == v).is_none())
-                .map(|v| {
-                    let v = Ident::new(v, Span::call_site());
-                    quote!(#v)
-                }),
-        );
+        let mut unselected_variants = self
+            .variants
+            .iter()
+            .filter(|v| used_variants.iter().find(|a| a == v).is_none())
+            .peekable();
+
+        let (variants, serde_attribute) = if query_context.untagged_unions {
+            let variants = untagged_variants(
+                selection,
+                query_context,
+                prefix,
+                self.name,
+                variants,
+                &used_variants,
+                unselected_variants.peek().is_some(),
+            )?;
+            (variants, quote!(#[serde(untagged)]))
+        } else {
+            variants.extend(unselected_variants.map(|v| {
+                let v = Ident::new(v, Span::call_site());
+                quote!(#v)
+            }));
+            (variants, quote!(#[serde(tag = "__typename")]))
+        };
+
+        let (serde_remote_attribute, serde_impls) =
+            crate::response_serde::union_enum_serde(query_context, &struct_name);
+
+        let member_trait = if query_context.union_member_traits {
+            Some(member_trait(&struct_name, &variant_types))
+        } else {
+            None
+        };
 
         Ok(quote! {
             #(#children_definitions)*
 
             #derives
-            #[serde(tag = "__typename")]
+            #attributes
+            #serde_attribute
+            #serde_remote_attribute
             pub enum #struct_name {
                 #(#variants),*
             }
+
+            #serde_impls
+
+            #member_trait
         })
     }
 }
@@ -154,24 +388,35 @@ mod tests {
     use crate::field_type::FieldType;
     use crate::objects::{GqlObject, GqlObjectField};
     use crate::selection::*;
+    use graphql_parser::Pos;
 
     #[test]
     fn union_response_for_selection_complains_if_typename_is_missing() {
         let fields = vec![
             SelectionItem::InlineFragment(SelectionInlineFragment {
                 on: "User",
+                position: Pos { line: 3, column: 9 },
                 fields: Selection::from_vec(vec![SelectionItem::Field(SelectionField {
                     alias: None,
                     name: "firstName",
                     fields: Selection::new_empty(),
+                    rust_with: None,
+                    conditional: false,
+                    arguments: &[],
+                    position: Pos::default(),
                 })]),
             }),
             SelectionItem::InlineFragment(SelectionInlineFragment {
                 on: "Organization",
+                position: Pos::default(),
                 fields: Selection::from_vec(vec![SelectionItem::Field(SelectionField {
                     alias: None,
                     name: "title",
                     fields: Selection::new_empty(),
+                    rust_with: None,
+                    conditional: false,
+                    arguments: &[],
+                    position: Pos::default(),
                 })]),
             }),
         ];
@@ -197,6 +442,7 @@ mod tests {
                         name: "firstName",
                         type_: FieldType::new("String").nonnull(),
                         deprecation: DeprecationStatus::Current,
+                        arguments: Vec::new(),
                     },
                     GqlObjectField {
                         description: None,
@@ -204,12 +450,14 @@ mod tests {
                         type_: FieldType::new("String").nonnull(),
 
                         deprecation: DeprecationStatus::Current,
+                        arguments: Vec::new(),
                     },
                     GqlObjectField {
                         description: None,
                         name: "createdAt",
                         type_: FieldType::new("Date").nonnull(),
                         deprecation: DeprecationStatus::Current,
+                        arguments: Vec::new(),
                     },
                 ],
                 is_required: false.into(),
@@ -227,12 +475,14 @@ mod tests {
                         name: "title",
                         type_: FieldType::new("String").nonnull(),
                         deprecation: DeprecationStatus::Current,
+                        arguments: Vec::new(),
                     },
                     GqlObjectField {
                         description: None,
                         name: "created_at",
                         type_: FieldType::new("Date").nonnull(),
                         deprecation: DeprecationStatus::Current,
+                        arguments: Vec::new(),
                     },
                 ],
                 is_required: false.into(),
@@ -240,13 +490,13 @@ mod tests {
         );
         let context = QueryContext::new_empty(&schema);
 
-        let result = union.response_for_selection(&context, &selection, &prefix);
+        let result = union.response_for_selection(&context, &selection, prefix);
 
         assert!(result.is_err());
 
         assert_eq!(
             format!("{}", result.unwrap_err()),
-            "Missing __typename in selection for Meow"
+            "Missing __typename in selection for Meow (line 3, column 9)"
         );
     }
 
@@ -257,21 +507,35 @@ mod tests {
                 alias: None,
                 name: "__typename",
                 fields: Selection::new_empty(),
+                rust_with: None,
+                conditional: false,
+                arguments: &[],
+                position: Pos::default(),
             }),
             SelectionItem::InlineFragment(SelectionInlineFragment {
                 on: "User",
+                position: Pos::default(),
                 fields: Selection::from_vec(vec![SelectionItem::Field(SelectionField {
                     alias: None,
                     name: "firstName",
                     fields: Selection::new_empty(),
+                    rust_with: None,
+                    conditional: false,
+                    arguments: &[],
+                    position: Pos::default(),
                 })]),
             }),
             SelectionItem::InlineFragment(SelectionInlineFragment {
                 on: "Organization",
+                position: Pos::default(),
                 fields: Selection::from_vec(vec![SelectionItem::Field(SelectionField {
                     alias: None,
                     name: "title",
                     fields: Selection::new_empty(),
+                    rust_with: None,
+                    conditional: false,
+                    arguments: &[],
+                    position: Pos::default(),
                 })]),
             }),
         ];
@@ -289,7 +553,7 @@ mod tests {
             is_required: false.into(),
         };
 
-        let result = union.response_for_selection(&context, &selection, &prefix);
+        let result = union.response_for_selection(&context, &selection, prefix);
 
         assert!(result.is_err());
 
@@ -305,24 +569,28 @@ mod tests {
                         name: "__typename",
                         type_: FieldType::new(string_type()).nonnull(),
                         deprecation: DeprecationStatus::Current,
+                        arguments: Vec::new(),
                     },
                     GqlObjectField {
                         description: None,
                         name: "firstName",
                         type_: FieldType::new(string_type()).nonnull(),
                         deprecation: DeprecationStatus::Current,
+                        arguments: Vec::new(),
                     },
                     GqlObjectField {
                         description: None,
                         name: "lastName",
                         type_: FieldType::new(string_type()).nonnull(),
                         deprecation: DeprecationStatus::Current,
+                        arguments: Vec::new(),
                     },
                     GqlObjectField {
                         description: None,
                         name: "createdAt",
                         type_: FieldType::new("Date").nonnull(),
                         deprecation: DeprecationStatus::Current,
+                        arguments: Vec::new(),
                     },
                 ],
                 is_required: false.into(),
@@ -340,18 +608,21 @@ mod tests {
                         name: "__typename",
                         type_: FieldType::new(string_type()).nonnull(),
                         deprecation: DeprecationStatus::Current,
+                        arguments: Vec::new(),
                     },
                     GqlObjectField {
                         description: None,
                         name: "title",
                         type_: FieldType::new("String").nonnull(),
                         deprecation: DeprecationStatus::Current,
+                        arguments: Vec::new(),
                     },
                     GqlObjectField {
                         description: None,
                         name: "createdAt",
                         type_: FieldType::new("Date").nonnull(),
                         deprecation: DeprecationStatus::Current,
+                        arguments: Vec::new(),
                     },
                 ],
                 is_required: false.into(),
@@ -360,7 +631,7 @@ mod tests {
 
         let context = QueryContext::new_empty(&schema);
 
-        let result = union.response_for_selection(&context, &selection, &prefix);
+        let result = union.response_for_selection(&context, &selection, prefix);
 
         println!("{:?}", result);
 
@@ -369,13 +640,13 @@ mod tests {
         assert_eq!(
             result.unwrap().to_string(),
             vec![
-                "# [ derive ( Deserialize ) ] ",
+                "# [derive (Deserialize)] ",
                 "pub struct MeowOnOrganization { pub title : String , } ",
-                "# [ derive ( Deserialize ) ] ",
-                "pub struct MeowOnUser { # [ serde ( rename = \"firstName\" ) ] pub first_name : String , } ",
-                "# [ derive ( Deserialize ) ] ",
-                "# [ serde ( tag = \"__typename\" ) ] ",
-                "pub enum Meow { Organization ( MeowOnOrganization ) , User ( MeowOnUser ) }",
+                "# [derive (Deserialize)] ",
+                "pub struct MeowOnUser { # [serde (rename = \"firstName\")] pub first_name : String , } ",
+                "# [derive (Deserialize)] ",
+                "# [serde (tag = \"__typename\")] ",
+                "pub enum Meow { Organization (MeowOnOrganization) , User (MeowOnUser) }",
             ].into_iter()
                 .collect::<String>(),
         );
@@ -388,13 +659,22 @@ mod tests {
                 alias: None,
                 name: "__typename",
                 fields: Selection::new_empty(),
+                rust_with: None,
+                conditional: false,
+                arguments: &[],
+                position: Pos::default(),
             }),
             SelectionItem::InlineFragment(SelectionInlineFragment {
                 on: "SomeNonUnionType",
+                position: Pos::default(),
                 fields: Selection::from_vec(vec![SelectionItem::Field(SelectionField {
                     alias: None,
                     name: "field",
                     fields: Selection::new_empty(),
+                    rust_with: None,
+                    conditional: false,
+                    arguments: &[],
+                    position: Pos::default(),
                 })]),
             }),
         ];
@@ -412,7 +692,7 @@ mod tests {
             is_required: false.into(),
         };
 
-        let result = union.response_for_selection(&context, &selection, &prefix);
+        let result = union.response_for_selection(&context, &selection, prefix);
 
         assert!(result.is_err());
 
@@ -428,6 +708,7 @@ mod tests {
                     name: "field",
                     type_: FieldType::new(string_type()),
                     deprecation: DeprecationStatus::Current,
+                    arguments: Vec::new(),
                 }],
                 is_required: false.into(),
             },
@@ -435,13 +716,20 @@ mod tests {
 
         let context = QueryContext::new_empty(&schema);
 
-        let result = union.response_for_selection(&context, &selection, &prefix);
+        let result = union.response_for_selection(&context, &selection, prefix);
 
         println!("{:?}", result);
 
         assert!(result.is_err());
 
-        match result.unwrap_err().downcast::<UnionError>() {
+        let error = result.unwrap_err();
+        // The tests selection items have no position.
+        match error
+            .downcast::<crate::errors::LocatedError>()
+            .map(|located| located.error)
+            .unwrap_or_else(|error| error)
+            .downcast::<UnionError>()
+        {
             Ok(UnionError::UnknownVariant { var, ty }) => {
                 assert_eq!(var, "SomeNonUnionType");
                 assert_eq!(ty, "MyUnion");
@@ -1,4 +1,5 @@
 use crate::field_type::FieldType;
+use crate::inputs::GqlInput;
 use crate::query::QueryContext;
 use proc_macro2::{Ident, Span, TokenStream};
 use quote::quote;
@@ -9,24 +10,37 @@ pub struct Variable<'query> {
     pub name: &'query str,
     pub ty: FieldType<'query>,
     pub default: Option<&'query graphql_parser::query::Value>,
+    /// Where the variable is defined in the query document.
+    pub position: graphql_parser::Pos,
 }
 
 impl<'query> Variable<'query> {
+    /// The name of the generated function returning the default value of the variable.
+    pub(crate) fn default_value_constructor_name(&self) -> Ident {
+        Ident::new(&format!("default_{}", self.name), Span::call_site())
+    }
+
     pub(crate) fn generate_default_value_constructor(
         &self,
         context: &QueryContext<'_, '_>,
     ) -> Option<TokenStream> {
-        context.schema.require(&self.ty.inner_name_str());
+        context.schema.require(self.ty.inner_name_str());
         match &self.default {
             Some(default) => {
-                let fn_name = Ident::new(&format!("default_{}", self.name), Span::call_site());
-                let ty = self.ty.to_rust(context, "");
+                let fn_name = self.default_value_constructor_name();
+                let borrowed_ty = if context.variables_lifetime {
+                    self.ty.to_borrowed_rust(context)
+                } else {
+                    None
+                };
                 let value = graphql_parser_value_to_literal(
                     default,
                     context,
                     &self.ty,
                     self.ty.is_optional(),
+                    borrowed_ty.is_some(),
                 );
+                let ty = borrowed_ty.unwrap_or_else(|| self.ty.to_rust(context, ""));
                 Some(quote! {
                     pub fn #fn_name() -> #ty {
                         #value
@@ -47,15 +61,20 @@ impl<'query> std::convert::From<&'query graphql_parser::query::VariableDefinitio
             name: &def.name,
             ty: FieldType::from(&def.var_type),
             default: def.default_value.as_ref(),
+            position: def.position,
         }
     }
 }
 
-fn graphql_parser_value_to_literal(
+/// The Rust expression for a GraphQL value of type `ty`, as in the variable default values. When
+/// `borrowed`, the strings and lists are the `&'a str` and `Cow<'a, [T]>` of a `Variables<'a>`
+/// struct, down to the input objects, which are owned.
+pub(crate) fn graphql_parser_value_to_literal(
     value: &graphql_parser::query::Value,
     context: &QueryContext<'_, '_>,
     ty: &FieldType<'_>,
     is_optional: bool,
+    borrowed: bool,
 ) -> TokenStream {
     use graphql_parser::query::Value;
 
@@ -67,28 +86,69 @@ fn graphql_parser_value_to_literal(
                 quote!(false)
             }
         }
+        Value::String(s) if borrowed => quote!(#s),
         Value::String(s) => quote!(#s.to_string()),
         Value::Variable(_) => panic!("variable in variable"),
-        Value::Null => panic!("null as default value"),
+        // `null` is the absence of a value, even in a non-null list or input field.
+        Value::Null => return quote!(None),
         Value::Float(f) => quote!(#f),
-        Value::Int(i) => {
-            let i = i.as_i64();
-            quote!(#i)
-        }
-        Value::Enum(en) => quote!(#en),
-        Value::List(inner) => {
-            let elements = inner
-                .iter()
-                .map(|val| graphql_parser_value_to_literal(val, context, ty, false));
-            quote! {
-                vec![
-                    #(#elements,)*
-                ]
+        // Int literals are valid Float and ID values too.
+        Value::Int(i) => match ty.inner_name_str() {
+            "Float" => {
+                let f = i.as_i64().map(|i| i as f64);
+                quote!(#f)
             }
+            "ID" => {
+                let s = i.as_i64().map(|i| i.to_string());
+                if borrowed {
+                    quote!(#s)
+                } else {
+                    quote!(#s.to_string())
+                }
+            }
+            _ => {
+                let i = i.as_i64();
+                quote!(#i)
+            }
+        },
+        Value::Enum(en) => render_enum_literal(en, ty, context),
+        // A single value is a valid default for a list: it is a list with one element.
+        Value::List(inner) if ty.is_list() => {
+            let element_type = ty.list_element();
+            let elements = inner.iter().map(|val| {
+                graphql_parser_value_to_literal(
+                    val,
+                    context,
+                    &element_type,
+                    element_type.is_optional(),
+                    borrowed,
+                )
+            });
+            borrowed_list(
+                quote! {
+                    vec![
+                        #(#elements,)*
+                    ]
+                },
+                borrowed,
+            )
         }
+        Value::List(_) => panic!("list default value for the non-list type {:?}", ty),
         Value::Object(obj) => render_object_literal(obj, ty, context),
     };
 
+    let inner = if ty.is_list() && !matches!(value, Value::List(_)) {
+        let element_type = ty.list_element();
+        let element = if element_type.is_optional() {
+            quote!(Some(#inner))
+        } else {
+            inner
+        };
+        borrowed_list(quote!(vec![#element]), borrowed)
+    } else {
+        inner
+    };
+
     if is_optional {
         quote!(Some(#inner))
     } else {
@@ -96,35 +156,82 @@ fn graphql_parser_value_to_literal(
     }
 }
 
+/// The `vec![...]` of a list, as a `Cow` when it is borrowed.
+fn borrowed_list(list: TokenStream, borrowed: bool) -> TokenStream {
+    if borrowed {
+        quote!(::std::borrow::Cow::Owned(#list))
+    } else {
+        list
+    }
+}
+
+fn render_enum_literal(
+    value: &str,
+    ty: &FieldType<'_>,
+    context: &QueryContext<'_, '_>,
+) -> TokenStream {
+    let enm = context
+        .schema
+        .enums
+        .get(ty.inner_name_str())
+        .expect("unknown enum type");
+    let enum_name = enm.name_ident(context.normalization);
+
+    if enm.variants.iter().any(|variant| variant.name == value) {
+        let variant = crate::normalization::enum_variant_name(
+            context.normalization,
+            context.rename_all,
+            value,
+        );
+        let variant = Ident::new(&variant, Span::call_site());
+        quote!(#enum_name::#variant)
+    } else {
+        let other = &context.enum_other_variant;
+        quote!(#enum_name::#other(#value.to_string()))
+    }
+}
+
 fn render_object_literal(
     object: &BTreeMap<String, graphql_parser::query::Value>,
     ty: &FieldType<'_>,
     context: &QueryContext<'_, '_>,
 ) -> TokenStream {
     let type_name = ty.inner_name_str();
-    let constructor = Ident::new(&type_name, Span::call_site());
     let schema_type = context
         .schema
         .inputs
         .get(type_name)
         .expect("unknown input type");
+    let constructor = schema_type.name_ident(context.normalization);
     let fields: Vec<TokenStream> = schema_type
         .fields
         .iter()
         .map(|(name, field)| {
-            let field_name = Ident::new(&name, Span::call_site());
+            let field_name = Ident::new(&context.normalization.field_name(name), Span::call_site());
             let provided_value = object.get(name.to_owned());
-            match provided_value {
-                Some(default_value) => {
-                    let value = graphql_parser_value_to_literal(
-                        default_value,
-                        context,
-                        &field.type_,
-                        field.type_.is_optional(),
-                    );
-                    quote!(#field_name: #value)
+            let value = match provided_value {
+                Some(default_value) => graphql_parser_value_to_literal(
+                    default_value,
+                    context,
+                    &field.type_,
+                    field.type_.is_optional(),
+                    false,
+                ),
+                // The fields that are left out get their default value from the schema, which is
+                // boxed like the field.
+                None if schema_type.default_values.contains_key(name) => {
+                    let default_value_constructor =
+                        schema_type.default_value_constructor_name(name, context.normalization);
+                    return quote!(#field_name: #constructor::#default_value_constructor());
                 }
-                None => quote!(#field_name: None),
+                None => quote!(None),
+            };
+
+            // Recursive input types are boxed, as in the generated struct.
+            if GqlInput::is_boxed(context, field) {
+                quote!(#field_name: Box::new(#value))
+            } else {
+                quote!(#field_name: #value)
             }
         })
         .collect();
@@ -0,0 +1,359 @@
+//! Problems found in a query document that do not prevent code generation.
+
+use crate::deprecation::{DeprecationStatus, DeprecationStrategy};
+use crate::operations::Operation;
+use crate::schema::Schema;
+use crate::GraphQLClientCodegenOptions;
+use graphql_parser::query;
+use std::collections::BTreeSet;
+use std::fmt;
+
+/// What a [`Warning`] is about.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum WarningCode {
+    /// A deprecated field is selected.
+    DeprecatedField,
+    /// A fragment is not spread by any operation of the query document.
+    UnusedFragment,
+    /// Options are set for an operation that is not in the query document.
+    UnknownOperation,
+}
+
+impl WarningCode {
+    /// The name of the warning, as printed by the CLI (`deprecated_field`).
+    pub fn as_str(self) -> &'static str {
+        match self {
+            WarningCode::DeprecatedField => "deprecated_field",
+            WarningCode::UnusedFragment => "unused_fragment",
+            WarningCode::UnknownOperation => "unknown_operation",
+        }
+    }
+}
+
+/// A position in the query document.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Location {
+    /// The line, starting at 1.
+    pub line: usize,
+    /// The column, starting at 1.
+    pub column: usize,
+}
+
+impl From<graphql_parser::Pos> for Location {
+    fn from(pos: graphql_parser::Pos) -> Self {
+        Location {
+            line: pos.line,
+            column: pos.column,
+        }
+    }
+}
+
+/// A problem found in the query document that does not prevent code generation.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Warning {
+    /// What the warning is about.
+    pub code: WarningCode,
+    /// The description of the problem.
+    pub message: String,
+    /// Where the problem is in the query document, if it is known.
+    pub location: Option<Location>,
+}
+
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[{}] {}", self.code.as_str(), self.message)?;
+
+        if let Some(location) = self.location {
+            write!(f, " (line {}, column {})", location.line, location.column)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// The warnings for the operations code is generated for, sorted by location.
+pub(crate) fn collect(
+    schema: &Schema<'_>,
+    query: &query::Document,
+    operations: &[Operation<'_>],
+    options: &GraphQLClientCodegenOptions,
+    included_fragments: &[String],
+) -> Vec<Warning> {
+    let mut warnings = BTreeSet::new();
+
+    for operation in operations {
+        let selection_set = match find_operation(query, &operation.name) {
+            Some(selection_set) => selection_set,
+            None => continue,
+        };
+
+        DeprecatedFields {
+            schema,
+            query,
+            deprecation_strategy: &options.deprecation_strategy(),
+            warnings: &mut warnings,
+        }
+        .visit(
+            operation.root_name(schema),
+            selection_set,
+            &mut BTreeSet::new(),
+        );
+    }
+
+    warnings.extend(unused_fragments(query, included_fragments));
+    // With several query files, the caller checks the operations against all of them.
+    if !options.defer_option_checks() {
+        warnings.extend(unknown_operations(&operation_names(query), options));
+    }
+
+    let mut warnings: Vec<Warning> = warnings.into_iter().collect();
+    warnings.sort_by_key(|warning| warning.location);
+    warnings
+}
+
+/// The selection set of the operation with this name.
+fn find_operation<'a>(query: &'a query::Document, name: &str) -> Option<&'a query::SelectionSet> {
+    query
+        .definitions
+        .iter()
+        .filter_map(|definition| match definition {
+            query::Definition::Operation(query::OperationDefinition::Query(query)) => {
+                Some((query.name.as_ref(), &query.selection_set))
+            }
+            query::Definition::Operation(query::OperationDefinition::Mutation(mutation)) => {
+                Some((mutation.name.as_ref(), &mutation.selection_set))
+            }
+            query::Definition::Operation(query::OperationDefinition::Subscription(
+                subscription,
+            )) => Some((subscription.name.as_ref(), &subscription.selection_set)),
+            _ => None,
+        })
+        .find(|(operation_name, _)| operation_name.map(String::as_str) == Some(name))
+        .map(|(_, selection_set)| selection_set)
+}
+
+fn find_fragment<'a>(
+    query: &'a query::Document,
+    name: &str,
+) -> Option<&'a query::FragmentDefinition> {
+    query
+        .definitions
+        .iter()
+        .find_map(|definition| match definition {
+            query::Definition::Fragment(fragment) if fragment.name == name => Some(fragment),
+            _ => None,
+        })
+}
+
+/// Collects the deprecated fields selected by an operation, including in the fragments it spreads.
+struct DeprecatedFields<'a, 'schema> {
+    schema: &'a Schema<'schema>,
+    query: &'a query::Document,
+    deprecation_strategy: &'a DeprecationStrategy,
+    warnings: &'a mut BTreeSet<Warning>,
+}
+
+impl<'a, 'schema> DeprecatedFields<'a, 'schema> {
+    fn visit(
+        &mut self,
+        type_name: &str,
+        selection_set: &'a query::SelectionSet,
+        visited_fragments: &mut BTreeSet<&'a str>,
+    ) {
+        for item in &selection_set.items {
+            match item {
+                query::Selection::Field(field) => {
+                    let schema_field = self
+                        .schema
+                        .object_or_interface_fields(type_name)
+                        .and_then(|fields| fields.iter().find(|f| f.name == field.name));
+                    // Unknown fields are reported by the code generation.
+                    let schema_field = match schema_field {
+                        Some(schema_field) => schema_field,
+                        None => continue,
+                    };
+
+                    if let DeprecationStatus::Deprecated(reason) = &schema_field.deprecation {
+                        self.report(type_name, &field.name, reason.as_deref(), field.position);
+                    }
+
+                    let field_type = schema_field.type_.inner_name_str();
+                    self.visit(field_type, &field.selection_set, visited_fragments);
+                }
+                query::Selection::FragmentSpread(spread) => {
+                    if !visited_fragments.insert(&spread.fragment_name) {
+                        continue;
+                    }
+
+                    if let Some(fragment) = find_fragment(self.query, &spread.fragment_name) {
+                        let query::TypeCondition::On(on) = &fragment.type_condition;
+                        self.visit(on, &fragment.selection_set, visited_fragments);
+                    }
+                }
+                query::Selection::InlineFragment(inline_fragment) => {
+                    let on = match &inline_fragment.type_condition {
+                        Some(query::TypeCondition::On(on)) => on.as_str(),
+                        None => type_name,
+                    };
+                    self.visit(on, &inline_fragment.selection_set, visited_fragments);
+                }
+            }
+        }
+    }
+
+    fn report(
+        &mut self,
+        type_name: &str,
+        field_name: &str,
+        reason: Option<&str>,
+        position: graphql_parser::Pos,
+    ) {
+        let message = match (self.deprecation_strategy, reason) {
+            (DeprecationStrategy::Allow, _) => return,
+            (DeprecationStrategy::Warn, Some(reason)) => format!(
+                "The field `{}.{}` is deprecated: {}",
+                type_name, field_name, reason
+            ),
+            (DeprecationStrategy::Warn, None) => {
+                format!("The field `{}.{}` is deprecated.", type_name, field_name)
+            }
+            (DeprecationStrategy::Deny, _) => format!(
+                "The field `{}.{}` is deprecated, it is left out of the generated code because of the `deny` deprecation strategy.",
+                type_name, field_name
+            ),
+        };
+
+        self.warnings.insert(Warning {
+            code: WarningCode::DeprecatedField,
+            message,
+            location: Some(position.into()),
+        });
+    }
+}
+
+/// Add the fragments spread in the selection set, directly or through other fragments of the query
+/// document, to `used`.
+pub(crate) fn spread_fragments<'a>(
+    query: &'a query::Document,
+    selection_set: &'a query::SelectionSet,
+    used: &mut BTreeSet<&'a str>,
+) {
+    for item in &selection_set.items {
+        match item {
+            query::Selection::Field(field) => spread_fragments(query, &field.selection_set, used),
+            query::Selection::FragmentSpread(spread) => {
+                if used.insert(&spread.fragment_name) {
+                    if let Some(fragment) = find_fragment(query, &spread.fragment_name) {
+                        spread_fragments(query, &fragment.selection_set, used);
+                    }
+                }
+            }
+            query::Selection::InlineFragment(inline_fragment) => {
+                spread_fragments(query, &inline_fragment.selection_set, used)
+            }
+        }
+    }
+}
+
+/// The selection set of an operation or a fragment.
+pub(crate) fn selection_set(definition: &query::Definition) -> &query::SelectionSet {
+    match definition {
+        query::Definition::Operation(query::OperationDefinition::Query(query)) => {
+            &query.selection_set
+        }
+        query::Definition::Operation(query::OperationDefinition::Mutation(mutation)) => {
+            &mutation.selection_set
+        }
+        query::Definition::Operation(query::OperationDefinition::Subscription(subscription)) => {
+            &subscription.selection_set
+        }
+        query::Definition::Operation(query::OperationDefinition::SelectionSet(selection_set)) => {
+            selection_set
+        }
+        query::Definition::Fragment(fragment) => &fragment.selection_set,
+    }
+}
+
+/// The fragments that are not spread by any operation of the query document, directly or through
+/// other fragments. The fragments of the included query files are left out, since they are shared
+/// by several query files.
+fn unused_fragments(query: &query::Document, included_fragments: &[String]) -> Vec<Warning> {
+    let mut used = BTreeSet::new();
+
+    for definition in &query.definitions {
+        if let query::Definition::Operation(_) = definition {
+            spread_fragments(query, selection_set(definition), &mut used);
+        }
+    }
+
+    query
+        .definitions
+        .iter()
+        .filter_map(|definition| match definition {
+            query::Definition::Fragment(fragment)
+                if !used.contains(fragment.name.as_str())
+                    && !included_fragments.contains(&fragment.name) =>
+            {
+                Some(Warning {
+                    code: WarningCode::UnusedFragment,
+                    message: format!(
+                        "The fragment `{}` is not used by any operation.",
+                        fragment.name
+                    ),
+                    location: Some(fragment.position.into()),
+                })
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// The names of the operations of the query document.
+pub(crate) fn operation_names(query: &query::Document) -> Vec<&str> {
+    query
+        .definitions
+        .iter()
+        .filter_map(|definition| match definition {
+            query::Definition::Operation(query::OperationDefinition::Query(query)) => {
+                query.name.as_deref()
+            }
+            query::Definition::Operation(query::OperationDefinition::Mutation(mutation)) => {
+                mutation.name.as_deref()
+            }
+            query::Definition::Operation(query::OperationDefinition::Subscription(
+                subscription,
+            )) => subscription.name.as_deref(),
+            _ => None,
+        })
+        .collect()
+}
+
+/// The operations options are set for that are not in `operation_names`, the operations of the
+/// query document, or of all the query files generated with the options.
+pub fn unknown_operations<S: AsRef<str>>(
+    operation_names: &[S],
+    options: &GraphQLClientCodegenOptions,
+) -> Vec<Warning> {
+    options
+        .operation_options()
+        .keys()
+        .filter(|name| {
+            !operation_names
+                .iter()
+                .any(|operation_name| operation_name.as_ref() == name.as_str())
+        })
+        .map(|name| Warning {
+            code: WarningCode::UnknownOperation,
+            message: format!(
+                "Options are set for the operation `{}`, but the query document has no such operation. The operations are: {}.",
+                name,
+                operation_names
+                    .iter()
+                    .map(|name| format!("`{}`", name.as_ref()))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+            location: None,
+        })
+        .collect()
+}
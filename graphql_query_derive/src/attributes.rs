@@ -1,10 +1,42 @@
-use anyhow::*;
+use anyhow::{format_err, Result};
+use graphql_client_codegen::date_times::DateTimeCrate;
 use graphql_client_codegen::deprecation::DeprecationStrategy;
-use graphql_client_codegen::normalization::Normalization;
+use graphql_client_codegen::normalization::{Normalization, RenameAll};
 
 const DEPRECATION_ERROR: &str = "deprecated must be one of 'allow', 'deny', or 'warn'";
-const NORMALIZATION_ERROR: &str = "normalization must be one of 'none' or 'rust'";
+const NORMALIZATION_ERROR: &str = "normalization must be one of 'none', 'rust' or 'preserve'";
+const RENAME_ALL_ERROR: &str = "rename_all must be one of 'camelCase', 'snake_case' or 'none'";
 const SERDE_CRATE_ERROR: &str = "serde_crate must be a valid path to serde";
+const ERROR_EXTENSIONS_ERROR: &str = "error_extensions must be a valid path to a type";
+const NULL_COERCION_ERROR: &str = "null_coercion must be one of 'true' or 'false'";
+const SKIP_SERIALIZING_NONE_ERROR: &str = "skip_serializing_none must be one of 'true' or 'false'";
+const VARIABLES_LIFETIME_ERROR: &str = "variables_lifetime must be one of 'true' or 'false'";
+const ALLOW_MULTIPLE_SUBSCRIPTION_FIELDS_ERROR: &str =
+    "allow_multiple_subscription_fields must be one of 'true' or 'false'";
+const FACADE_ERROR: &str = "facade must be one of 'true' or 'false'";
+const KEEP_IDEMPOTENT_DIRECTIVE_ERROR: &str =
+    "keep_idempotent_directive must be one of 'true' or 'false'";
+const PRIVATE_FIELDS_WITH_GETTERS_ERROR: &str =
+    "private_fields_with_getters must be one of 'true' or 'false'";
+const DEDUPE_TYPES_ERROR: &str = "dedupe_types must be one of 'true' or 'false'";
+const EMIT_SELECTED_FIELDS_ERROR: &str = "emit_selected_fields must be one of 'true' or 'false'";
+const UNION_MEMBER_TRAITS_ERROR: &str = "union_member_traits must be one of 'true' or 'false'";
+const CURSOR_NEWTYPES_ERROR: &str = "cursor_newtypes must be one of 'true' or 'false'";
+const DATE_TIME_CRATE_ERROR: &str = "date_time_crate must be one of 'chrono', 'time' or 'off'";
+const UNTAGGED_UNIONS_ERROR: &str = "untagged_unions must be one of 'true' or 'false'";
+const GRAPHQL_ERROR_NAMES_ERROR: &str = "graphql_error_names must be one of 'true' or 'false'";
+const ENUM_DESCRIPTIONS_ERROR: &str = "enum_descriptions must be one of 'true' or 'false'";
+const EXHAUSTIVE_ENUMS_ERROR: &str = "exhaustive_enums must be one of 'true' or 'false'";
+const QUERY_AS_BYTES_ERROR: &str = "query_as_bytes must be one of 'true' or 'false'";
+const QUERY_WITH_INCLUDED_FILES_ERROR: &str =
+    "query_with_included_files must be one of 'true' or 'false'";
+const COMPACT_DEBUG_ERROR: &str =
+    "compact_debug must be one of 'true', 'false', or a maximum number of list items";
+const EXPAND_RECURSIVE_FRAGMENTS_ERROR: &str =
+    "expand_recursive_fragments must be a number of levels";
+
+/// The maximum number of list items shown by compact `Debug` impls when none is specified.
+const DEFAULT_COMPACT_DEBUG_MAX_ITEMS: usize = 10;
 
 /// The `graphql` attribute as a `syn::Path`.
 fn path_to_match() -> syn::Path {
@@ -13,6 +45,16 @@ fn path_to_match() -> syn::Path {
 
 /// Extract an configuration parameter specified in the `graphql` attribute.
 pub fn extract_attr(ast: &syn::DeriveInput, attr: &str) -> Result<String> {
+    extract_attr_lit(ast, attr).map(|lit| lit.value())
+}
+
+/// The span of a configuration parameter specified in the `graphql` attribute, to point
+/// compile errors at it.
+pub fn attr_span(ast: &syn::DeriveInput, attr: &str) -> Option<proc_macro2::Span> {
+    extract_attr_lit(ast, attr).ok().map(|lit| lit.span())
+}
+
+fn extract_attr_lit(ast: &syn::DeriveInput, attr: &str) -> Result<syn::LitStr> {
     let attributes = &ast.attrs;
     let graphql_path = path_to_match();
     let attribute = attributes
@@ -26,7 +68,7 @@ pub fn extract_attr(ast: &syn::DeriveInput, attr: &str) -> Result<String> {
                 if let Some(ident) = path.get_ident() {
                     if ident == attr {
                         if let syn::Lit::Str(lit) = lit {
-                            return Ok(lit.value());
+                            return Ok(lit.clone());
                         }
                     }
                 }
@@ -39,7 +81,7 @@ pub fn extract_attr(ast: &syn::DeriveInput, attr: &str) -> Result<String> {
 
 /// Get the deprecation from a struct attribute in the derive case.
 pub fn extract_deprecation_strategy(ast: &syn::DeriveInput) -> Result<DeprecationStrategy> {
-    extract_attr(&ast, "deprecated")?
+    extract_attr(ast, "deprecated")?
         .to_lowercase()
         .as_str()
         .parse()
@@ -48,24 +90,236 @@ pub fn extract_deprecation_strategy(ast: &syn::DeriveInput) -> Result<Deprecatio
 
 /// Get the deprecation from a struct attribute in the derive case.
 pub fn extract_normalization(ast: &syn::DeriveInput) -> Result<Normalization> {
-    extract_attr(&ast, "normalization")?
+    extract_attr(ast, "normalization")?
         .to_lowercase()
         .as_str()
         .parse()
         .map_err(|_| format_err!("{}", NORMALIZATION_ERROR))
 }
 
+/// Get the casing of the response fields and enum variants from a struct attribute in the derive case.
+pub fn extract_rename_all(ast: &syn::DeriveInput) -> Result<RenameAll> {
+    extract_attr(ast, "rename_all")?
+        .parse()
+        .map_err(|_| format_err!("{}", RENAME_ALL_ERROR))
+}
+
 /// Get the serde crate from a struct attribute in the derive case.
 pub fn extract_serde_crate(ast: &syn::DeriveInput) -> Result<syn::Path> {
-    let serde_crate_attr = extract_attr(&ast, "serde_crate")?;
-    syn::parse_str::<syn::Path>(&*serde_crate_attr)
+    let serde_crate_attr = extract_attr(ast, "serde_crate")?;
+    syn::parse_str::<syn::Path>(&serde_crate_attr)
         .map_err(|_| format_err!("{}", SERDE_CRATE_ERROR))
 }
 
+/// Get the type of the error extensions from a struct attribute in the derive case.
+pub fn extract_error_extensions(ast: &syn::DeriveInput) -> Result<syn::Path> {
+    let error_extensions_attr = extract_attr(ast, "error_extensions")?;
+    syn::parse_str::<syn::Path>(&error_extensions_attr)
+        .map_err(|_| format_err!("{}", ERROR_EXTENSIONS_ERROR))
+}
+
+/// Get the null coercion setting from a struct attribute in the derive case.
+pub fn extract_null_coercion(ast: &syn::DeriveInput) -> Result<bool> {
+    extract_attr(ast, "null_coercion")?
+        .to_lowercase()
+        .as_str()
+        .parse()
+        .map_err(|_| format_err!("{}", NULL_COERCION_ERROR))
+}
+
+/// Get the setting for leaving `None` variables out of the serialized variables from a struct attribute in the derive case.
+pub fn extract_skip_serializing_none(ast: &syn::DeriveInput) -> Result<bool> {
+    extract_attr(ast, "skip_serializing_none")?
+        .to_lowercase()
+        .as_str()
+        .parse()
+        .map_err(|_| format_err!("{}", SKIP_SERIALIZING_NONE_ERROR))
+}
+
+/// Get the setting for borrowing the strings and lists of the variables from a struct attribute in the derive case.
+pub fn extract_variables_lifetime(ast: &syn::DeriveInput) -> Result<bool> {
+    extract_attr(ast, "variables_lifetime")?
+        .to_lowercase()
+        .as_str()
+        .parse()
+        .map_err(|_| format_err!("{}", VARIABLES_LIFETIME_ERROR))
+}
+
+/// Get the setting for generating the subscriptions with several root fields from a struct attribute in the derive case.
+pub fn extract_allow_multiple_subscription_fields(ast: &syn::DeriveInput) -> Result<bool> {
+    extract_attr(ast, "allow_multiple_subscription_fields")?
+        .to_lowercase()
+        .as_str()
+        .parse()
+        .map_err(|_| format_err!("{}", ALLOW_MULTIPLE_SUBSCRIPTION_FIELDS_ERROR))
+}
+
+/// Get the facade setting from a struct attribute in the derive case.
+pub fn extract_facade(ast: &syn::DeriveInput) -> Result<bool> {
+    extract_attr(ast, "facade")?
+        .to_lowercase()
+        .as_str()
+        .parse()
+        .map_err(|_| format_err!("{}", FACADE_ERROR))
+}
+
+/// Get the setting for keeping the `@idempotent` directive in the query from a struct attribute in the derive case.
+pub fn extract_keep_idempotent_directive(ast: &syn::DeriveInput) -> Result<bool> {
+    extract_attr(ast, "keep_idempotent_directive")?
+        .to_lowercase()
+        .as_str()
+        .parse()
+        .map_err(|_| format_err!("{}", KEEP_IDEMPOTENT_DIRECTIVE_ERROR))
+}
+
+/// Get the setting for private response fields with getters from a struct attribute in the derive case.
+pub fn extract_private_fields_with_getters(ast: &syn::DeriveInput) -> Result<bool> {
+    extract_attr(ast, "private_fields_with_getters")?
+        .to_lowercase()
+        .as_str()
+        .parse()
+        .map_err(|_| format_err!("{}", PRIVATE_FIELDS_WITH_GETTERS_ERROR))
+}
+
+/// Get the setting for merging identical selection types from a struct attribute in the derive case.
+pub fn extract_dedupe_types(ast: &syn::DeriveInput) -> Result<bool> {
+    extract_attr(ast, "dedupe_types")?
+        .to_lowercase()
+        .as_str()
+        .parse()
+        .map_err(|_| format_err!("{}", DEDUPE_TYPES_ERROR))
+}
+
+/// Get the setting for the `SELECTED_FIELDS` constant from a struct attribute in the derive case.
+pub fn extract_emit_selected_fields(ast: &syn::DeriveInput) -> Result<bool> {
+    extract_attr(ast, "emit_selected_fields")?
+        .to_lowercase()
+        .as_str()
+        .parse()
+        .map_err(|_| format_err!("{}", EMIT_SELECTED_FIELDS_ERROR))
+}
+
+/// Get the setting for the union member traits from a struct attribute in the derive case.
+pub fn extract_union_member_traits(ast: &syn::DeriveInput) -> Result<bool> {
+    extract_attr(ast, "union_member_traits")?
+        .to_lowercase()
+        .as_str()
+        .parse()
+        .map_err(|_| format_err!("{}", UNION_MEMBER_TRAITS_ERROR))
+}
+
+/// Get the setting for the newtypes of connection cursors from a struct attribute in the derive case.
+pub fn extract_cursor_newtypes(ast: &syn::DeriveInput) -> Result<bool> {
+    extract_attr(ast, "cursor_newtypes")?
+        .to_lowercase()
+        .as_str()
+        .parse()
+        .map_err(|_| format_err!("{}", CURSOR_NEWTYPES_ERROR))
+}
+
+/// Get the crate of the date and time scalars from a struct attribute in the derive case.
+pub fn extract_date_time_crate(ast: &syn::DeriveInput) -> Result<DateTimeCrate> {
+    extract_attr(ast, "date_time_crate")?
+        .to_lowercase()
+        .as_str()
+        .parse()
+        .map_err(|_| format_err!("{}", DATE_TIME_CRATE_ERROR))
+}
+
+/// Get the setting for untagged union and interface enums from a struct attribute in the derive case.
+pub fn extract_untagged_unions(ast: &syn::DeriveInput) -> Result<bool> {
+    extract_attr(ast, "untagged_unions")?
+        .to_lowercase()
+        .as_str()
+        .parse()
+        .map_err(|_| format_err!("{}", UNTAGGED_UNIONS_ERROR))
+}
+
+/// Get the setting for the GraphQL names in the errors about missing fields from a struct attribute in the derive case.
+pub fn extract_graphql_error_names(ast: &syn::DeriveInput) -> Result<bool> {
+    extract_attr(ast, "graphql_error_names")?
+        .to_lowercase()
+        .as_str()
+        .parse()
+        .map_err(|_| format_err!("{}", GRAPHQL_ERROR_NAMES_ERROR))
+}
+
+/// Get the setting for the `description` method of enums from a struct attribute in the derive case.
+pub fn extract_enum_descriptions(ast: &syn::DeriveInput) -> Result<bool> {
+    extract_attr(ast, "enum_descriptions")?
+        .to_lowercase()
+        .as_str()
+        .parse()
+        .map_err(|_| format_err!("{}", ENUM_DESCRIPTIONS_ERROR))
+}
+
+/// Get the setting for `#[non_exhaustive]` on enums from a struct attribute in the derive case.
+pub fn extract_exhaustive_enums(ast: &syn::DeriveInput) -> Result<bool> {
+    extract_attr(ast, "exhaustive_enums")?
+        .to_lowercase()
+        .as_str()
+        .parse()
+        .map_err(|_| format_err!("{}", EXHAUSTIVE_ENUMS_ERROR))
+}
+
+/// Get the setting for embedding the query with `include_bytes!` from a struct attribute in the derive case.
+pub fn extract_query_as_bytes(ast: &syn::DeriveInput) -> Result<bool> {
+    extract_attr(ast, "query_as_bytes")?
+        .to_lowercase()
+        .as_str()
+        .parse()
+        .map_err(|_| format_err!("{}", QUERY_AS_BYTES_ERROR))
+}
+
+/// Get the setting for sending the included query files along with the query from a struct attribute in the derive case.
+pub fn extract_query_with_included_files(ast: &syn::DeriveInput) -> Result<bool> {
+    extract_attr(ast, "query_with_included_files")?
+        .to_lowercase()
+        .as_str()
+        .parse()
+        .map_err(|_| format_err!("{}", QUERY_WITH_INCLUDED_FILES_ERROR))
+}
+
+/// Get the compact debug setting from a struct attribute in the derive case: `true` for the
+/// default number of list items, or the number of items.
+pub fn extract_compact_debug(ast: &syn::DeriveInput) -> Result<Option<usize>> {
+    match extract_attr(ast, "compact_debug")?.to_lowercase().as_str() {
+        "true" => Ok(Some(DEFAULT_COMPACT_DEBUG_MAX_ITEMS)),
+        "false" => Ok(None),
+        max_items => max_items
+            .parse()
+            .map(Some)
+            .map_err(|_| format_err!("{}", COMPACT_DEBUG_ERROR)),
+    }
+}
+
+/// Get the number of levels self-recursive fragments are expanded to from a struct attribute in the derive case.
+pub fn extract_expand_recursive_fragments(ast: &syn::DeriveInput) -> Result<usize> {
+    extract_attr(ast, "expand_recursive_fragments")?
+        .parse()
+        .map_err(|_| format_err!("{}", EXPAND_RECURSIVE_FRAGMENTS_ERROR))
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
 
+    #[test]
+    fn test_attr_span() {
+        let input = "
+        #[derive(GraphQLQuery)]
+        #[graphql(
+            schema_path = \"x\",
+            query_path = \"x\",
+        )]
+        struct MyQuery;
+        ";
+        let parsed = syn::parse_str(input).unwrap();
+
+        assert!(attr_span(&parsed, "schema_path").is_some());
+        assert!(attr_span(&parsed, "deprecated").is_none());
+    }
+
     #[test]
     fn test_deprecation_strategy() {
         let input = "
@@ -119,4 +373,106 @@ mod test {
             Err(e) => assert_eq!(&format!("{}", e), DEPRECATION_ERROR),
         };
     }
+
+    #[test]
+    fn test_invalid_skip_serializing_none() {
+        let input = "
+        #[derive(GraphQLQuery)]
+        #[graphql(
+            schema_path = \"x\",
+            query_path = \"x\",
+            skip_serializing_none = \"sometimes\",
+        )]
+        struct MyQuery;
+        ";
+        let parsed = syn::parse_str(input).unwrap();
+        match extract_skip_serializing_none(&parsed) {
+            Ok(_) => panic!("parsed unexpectedly"),
+            Err(e) => assert_eq!(&format!("{}", e), SKIP_SERIALIZING_NONE_ERROR),
+        };
+    }
+
+    #[test]
+    fn test_invalid_variables_lifetime() {
+        let input = "
+        #[derive(GraphQLQuery)]
+        #[graphql(
+            schema_path = \"x\",
+            query_path = \"x\",
+            variables_lifetime = \"sometimes\",
+        )]
+        struct MyQuery;
+        ";
+        let parsed = syn::parse_str(input).unwrap();
+        match extract_variables_lifetime(&parsed) {
+            Ok(_) => panic!("parsed unexpectedly"),
+            Err(e) => assert_eq!(&format!("{}", e), VARIABLES_LIFETIME_ERROR),
+        };
+    }
+
+    #[test]
+    fn test_invalid_allow_multiple_subscription_fields() {
+        let input = "
+        #[derive(GraphQLQuery)]
+        #[graphql(
+            schema_path = \"x\",
+            query_path = \"x\",
+            allow_multiple_subscription_fields = \"sometimes\",
+        )]
+        struct MyQuery;
+        ";
+        let parsed = syn::parse_str(input).unwrap();
+        match extract_allow_multiple_subscription_fields(&parsed) {
+            Ok(_) => panic!("parsed unexpectedly"),
+            Err(e) => assert_eq!(&format!("{}", e), ALLOW_MULTIPLE_SUBSCRIPTION_FIELDS_ERROR),
+        };
+    }
+
+    #[test]
+    fn test_invalid_null_coercion() {
+        let input = "
+        #[derive(GraphQLQuery)]
+        #[graphql(
+            schema_path = \"x\",
+            query_path = \"x\",
+            null_coercion = \"sometimes\",
+        )]
+        struct MyQuery;
+        ";
+        let parsed = syn::parse_str(input).unwrap();
+        match extract_null_coercion(&parsed) {
+            Ok(_) => panic!("parsed unexpectedly"),
+            Err(e) => assert_eq!(&format!("{}", e), NULL_COERCION_ERROR),
+        };
+    }
+
+    #[test]
+    fn test_compact_debug() {
+        let input = |value: &str| {
+            let input = format!(
+                "
+                #[derive(GraphQLQuery)]
+                #[graphql(
+                    schema_path = \"x\",
+                    query_path = \"x\",
+                    compact_debug = \"{}\",
+                )]
+                struct MyQuery;
+                ",
+                value
+            );
+            syn::parse_str(&input).unwrap()
+        };
+
+        assert_eq!(
+            extract_compact_debug(&input("true")).unwrap(),
+            Some(DEFAULT_COMPACT_DEBUG_MAX_ITEMS)
+        );
+        assert_eq!(extract_compact_debug(&input("false")).unwrap(), None);
+        assert_eq!(extract_compact_debug(&input("3")).unwrap(), Some(3));
+        match extract_compact_debug(&input("some")) {
+            Ok(_) => panic!("parsed unexpectedly"),
+            Err(e) => assert_eq!(&format!("{}", e), COMPACT_DEBUG_ERROR),
+        };
+    }
 }
@@ -2,14 +2,17 @@ extern crate proc_macro;
 
 /// Derive-related code. This will be moved into graphql_query_derive.
 mod attributes;
+mod module_names;
 
 use anyhow::Context;
+use graphql_client_codegen::warnings::{Warning, WarningCode};
 use graphql_client_codegen::{
-    generate_module_token_stream, CodegenMode, GraphQLClientCodegenOptions,
+    generate_code, CodegenMode, GraphQLClientCodegenOptions, UnsupportedSchemaExtension,
 };
 use std::path::{Path, PathBuf};
 
 use proc_macro2::TokenStream;
+use quote::quote;
 
 #[proc_macro_derive(GraphQLQuery, attributes(graphql))]
 pub fn derive_graphql_query(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
@@ -23,31 +26,117 @@ fn graphql_query_derive_inner(
     input: proc_macro::TokenStream,
 ) -> Result<proc_macro::TokenStream, anyhow::Error> {
     let input = TokenStream::from(input);
-    let ast = syn::parse2(input).context("Derive input parsing.")?;
-    let (query_path, schema_path) = build_query_and_schema_path(&ast)?;
-    let options = build_graphql_client_derive_options(&ast, query_path.to_path_buf())?;
-    Ok(
-        generate_module_token_stream(query_path, &schema_path, options)
-            .map(Into::into)
-            .map_err(|fail| fail.compat())
-            .context("Code generation failed.")?,
-    )
+    let ast: syn::DeriveInput = syn::parse2(input).context("Derive input parsing.")?;
+    let (query_path, included_query_paths, schema_path) = build_query_and_schema_path(&ast)?;
+    let mut options = build_graphql_client_derive_options(&ast, query_path.to_path_buf())?;
+    options.set_included_query_files(included_query_paths);
+
+    // With the `codegen-tests` feature, each enum gets a test comparing it with the schema file.
+    if cfg!(feature = "codegen-tests") {
+        options.set_enum_tests_schema(schema_path.clone());
+    }
+
+    if let Some(module_name) = options.module_name() {
+        if syn::parse_str::<syn::Ident>(module_name).is_err() {
+            let span =
+                attributes::attr_span(&ast, "module_name").unwrap_or_else(|| ast.ident.span());
+            let message = format!(
+                "The module name `{}` is not a valid Rust identifier.",
+                module_name
+            );
+            return Ok(syn::Error::new(span, message).to_compile_error().into());
+        }
+    }
+
+    // Point at the struct instead of the duplicate module rustc would report in the generated code.
+    let scope = format!(
+        "{}::{}",
+        std::env::var("CARGO_CRATE_NAME").unwrap_or_default(),
+        proc_macro::Span::call_site().file()
+    );
+    if let Err(message) = module_names::claim(&scope, &ast, &options) {
+        return Ok(syn::Error::new(ast.ident.span(), message)
+            .to_compile_error()
+            .into());
+    }
+
+    let generated = match generate_code(query_path.clone(), &schema_path, options) {
+        Ok(generated) => generated,
+        // Point at the `schema_path` attribute instead of panicking in the proc macro.
+        Err(fail) if fail.downcast_ref::<UnsupportedSchemaExtension>().is_some() => {
+            let span =
+                attributes::attr_span(&ast, "schema_path").unwrap_or_else(|| ast.ident.span());
+            return Ok(syn::Error::new(span, fail).to_compile_error().into());
+        }
+        // The compiler error points at the struct, so the message names the query file.
+        Err(fail) => {
+            return Err(fail.compat())
+                .with_context(|| format!("Code generation failed for {}.", query_path.display()))
+        }
+    };
+    let warnings = warnings_as_deprecations(&query_path, &generated.warnings, ast.ident.span());
+    let tokens = generated.tokens;
+
+    Ok(quote!(#tokens #warnings).into())
 }
 
+/// Proc macros can't emit warnings on stable Rust, so each warning is reported as the use of a
+/// deprecated item, with the warning as deprecation note. The use has the span of the struct:
+/// rustc does not report deprecations in code that comes from macros.
+///
+/// The selected deprecated fields are left out: the generated fields are already marked as
+/// deprecated.
+fn warnings_as_deprecations(
+    query_path: &Path,
+    warnings: &[Warning],
+    span: proc_macro2::Span,
+) -> TokenStream {
+    let notes = warnings
+        .iter()
+        .filter(|warning| warning.code != WarningCode::DeprecatedField)
+        .map(|warning| format!("graphql_client: {}: {}", query_path.display(), warning));
+
+    let warning_use = quote::quote_spanned!(span=> GraphQLClientWarning);
+
+    quote! {
+        #(
+            const _: () = {
+                #[deprecated(note = #notes)]
+                struct GraphQLClientWarning;
+
+                #[allow(dead_code)]
+                fn warn() {
+                    let _ = #warning_use;
+                }
+            };
+        )*
+    }
+}
+
+/// The query path, the included query paths and the schema path. `query_path` can be a
+/// comma-separated list of paths: the last one is the query file, the ones before it are included
+/// query files defining fragments it spreads.
 fn build_query_and_schema_path(
     input: &syn::DeriveInput,
-) -> Result<(PathBuf, PathBuf), anyhow::Error> {
+) -> Result<(PathBuf, Vec<PathBuf>, PathBuf), anyhow::Error> {
     let cargo_manifest_dir = ::std::env::var("CARGO_MANIFEST_DIR")
         .context("Checking that the CARGO_MANIFEST_DIR env variable is defined.")?;
 
-    let query_path =
+    let query_paths =
         attributes::extract_attr(input, "query_path").context("Extracting query path.")?;
-    let query_path = format!("{}/{}", cargo_manifest_dir, query_path);
-    let query_path = Path::new(&query_path).to_path_buf();
+    let mut query_paths: Vec<PathBuf> = query_paths
+        .split(',')
+        .map(str::trim)
+        .filter(|path| !path.is_empty())
+        .map(|path| Path::new(&format!("{}/{}", cargo_manifest_dir, path)).to_path_buf())
+        .collect();
+    let query_path = query_paths
+        .pop()
+        .ok_or_else(|| anyhow::format_err!("The query path is empty."))?;
     let schema_path =
         attributes::extract_attr(input, "schema_path").context("Extracting schema path.")?;
     let schema_path = Path::new(&cargo_manifest_dir).join(schema_path);
-    Ok((query_path, schema_path))
+    Ok((query_path, query_paths, schema_path))
 }
 
 fn build_graphql_client_derive_options(
@@ -56,7 +145,13 @@ fn build_graphql_client_derive_options(
 ) -> Result<GraphQLClientCodegenOptions, anyhow::Error> {
     let variables_derives = attributes::extract_attr(input, "variables_derives").ok();
     let response_derives = attributes::extract_attr(input, "response_derives").ok();
+    let derives_for = attributes::extract_attr(input, "derives_for").ok();
+    let redact = attributes::extract_attr(input, "redact").ok();
+    let skip_serde = attributes::extract_attr(input, "skip_serde").ok();
     let serde_crate = attributes::extract_serde_crate(input).ok();
+    let response_struct_name = attributes::extract_attr(input, "response_struct_name").ok();
+    let fragment = attributes::extract_attr(input, "fragment").ok();
+    let module_name = attributes::extract_attr(input, "module_name").ok();
 
     let mut options = GraphQLClientCodegenOptions::new(CodegenMode::Derive);
     options.set_query_file(query_path);
@@ -69,10 +164,34 @@ fn build_graphql_client_derive_options(
         options.set_response_derives(response_derives);
     };
 
+    if let Some(derives_for) = derives_for {
+        options.set_derives_for(derives_for);
+    };
+
+    if let Some(redact) = redact {
+        options.set_redact(redact);
+    };
+
+    if let Some(skip_serde) = skip_serde {
+        options.set_skip_serde(skip_serde);
+    };
+
     if let Some(serde_crate) = serde_crate {
         options.set_serde_crate(serde_crate);
     }
 
+    if let Some(response_struct_name) = response_struct_name {
+        options.set_response_struct_name(response_struct_name);
+    }
+
+    if let Some(fragment) = fragment {
+        options.set_fragment(fragment);
+    }
+
+    if let Some(module_name) = module_name {
+        options.set_module_name(module_name);
+    }
+
     // The user can determine what to do about deprecations.
     if let Ok(deprecation_strategy) = attributes::extract_deprecation_strategy(input) {
         options.set_deprecation_strategy(deprecation_strategy);
@@ -83,6 +202,142 @@ fn build_graphql_client_derive_options(
         options.set_normalization(normalization);
     };
 
+    // The user can choose the casing of the response fields and enum variants.
+    if let Ok(rename_all) = attributes::extract_rename_all(input) {
+        options.set_rename_all(rename_all);
+    };
+
+    // The user can opt into deserializing null lists and strings as their default value.
+    if let Ok(null_coercion) = attributes::extract_null_coercion(input) {
+        options.set_null_coercion(null_coercion);
+    };
+
+    // The user can leave the variables that are `None` out instead of sending `null`.
+    if let Ok(skip_serializing_none) = attributes::extract_skip_serializing_none(input) {
+        options.set_skip_serializing_none(skip_serializing_none);
+    };
+
+    // The user can borrow large strings and lists in the variables instead of cloning them.
+    if let Ok(variables_lifetime) = attributes::extract_variables_lifetime(input) {
+        options.set_variables_lifetime(variables_lifetime);
+    };
+
+    // The user can generate subscriptions with several root fields for the servers that accept them.
+    if let Ok(allow_multiple_subscription_fields) =
+        attributes::extract_allow_multiple_subscription_fields(input)
+    {
+        options.set_allow_multiple_subscription_fields(allow_multiple_subscription_fields);
+    };
+
+    // The user can ask for a stable facade module over the generated types.
+    if let Ok(facade) = attributes::extract_facade(input) {
+        options.set_facade(facade);
+    };
+
+    // The user can ask for truncated Debug output for big responses.
+    if let Ok(Some(max_items)) = attributes::extract_compact_debug(input) {
+        options.set_compact_debug(max_items);
+    };
+
+    // The user can send the `@idempotent` directive to servers that understand it.
+    if let Ok(keep_idempotent_directive) = attributes::extract_keep_idempotent_directive(input) {
+        options.set_keep_idempotent_directive(keep_idempotent_directive);
+    };
+
+    // The user can hide the fields of response structs behind getters.
+    if let Ok(private_fields_with_getters) = attributes::extract_private_fields_with_getters(input)
+    {
+        options.set_private_fields_with_getters(private_fields_with_getters);
+    };
+
+    // The user can merge the selection types with the same shape.
+    if let Ok(dedupe_types) = attributes::extract_dedupe_types(input) {
+        options.set_dedupe_types(dedupe_types);
+    };
+
+    // The user can list the selected fields for usage analytics.
+    if let Ok(emit_selected_fields) = attributes::extract_emit_selected_fields(input) {
+        options.set_emit_selected_fields(emit_selected_fields);
+    };
+
+    // The user can write generic code over the variants of unions.
+    if let Ok(union_member_traits) = attributes::extract_union_member_traits(input) {
+        options.set_union_member_traits(union_member_traits);
+    };
+
+    // The user can give the cursors of each connection their own type.
+    if let Ok(cursor_newtypes) = attributes::extract_cursor_newtypes(input) {
+        options.set_cursor_newtypes(cursor_newtypes);
+    };
+
+    // The user can map the date and time scalars to the types of chrono or time.
+    if let Ok(date_time_crate) = attributes::extract_date_time_crate(input) {
+        options.set_date_time_crate(date_time_crate);
+    };
+
+    if let Ok(date_time_scalars) = attributes::extract_attr(input, "date_time_scalars") {
+        options.set_date_time_scalars(date_time_scalars);
+    };
+
+    // The user can deserialize unions and interfaces from responses without `__typename`.
+    if let Ok(untagged_unions) = attributes::extract_untagged_unions(input) {
+        options.set_untagged_unions(untagged_unions);
+    };
+
+    // The user can name the GraphQL fields and types in the errors about missing fields.
+    if let Ok(graphql_error_names) = attributes::extract_graphql_error_names(input) {
+        options.set_graphql_error_names(graphql_error_names);
+    };
+
+    // The user can send the fragments of the included query files along with the query.
+    if let Ok(query_with_included_files) = attributes::extract_query_with_included_files(input) {
+        options.set_query_with_included_files(query_with_included_files);
+    };
+
+    // The user can display the schema descriptions of enum values.
+    if let Ok(enum_descriptions) = attributes::extract_enum_descriptions(input) {
+        options.set_enum_descriptions(enum_descriptions);
+    };
+
+    // The user can make the enums non-exhaustive, so that the values added to the schema are not breaking changes.
+    if let Ok(exhaustive_enums) = attributes::extract_exhaustive_enums(input) {
+        options.set_exhaustive_enums(exhaustive_enums);
+    };
+
+    // The user can rename the variant of enums for unknown values, when an enum has an `Other` value.
+    if let Ok(enum_other_variant) = attributes::extract_attr(input, "enum_other_variant") {
+        options.set_enum_other_variant(enum_other_variant);
+    };
+
+    // The user can document the generated module with a license or "do not edit" header.
+    if let Ok(file_header) = attributes::extract_attr(input, "file_header") {
+        options.set_file_header(file_header);
+    };
+
+    // The user can deserialize the extensions of the errors to their own type.
+    if let Ok(error_extensions) = attributes::extract_error_extensions(input) {
+        options.set_error_extensions(error_extensions);
+    };
+
+    // The user can keep very big queries out of string literals.
+    if let Ok(query_as_bytes) = attributes::extract_query_as_bytes(input) {
+        options.set_query_as_bytes(query_as_bytes);
+    };
+
+    // The user can expand recursive fragments to a fixed depth instead of boxing them.
+    if let Ok(levels) = attributes::extract_expand_recursive_fragments(input) {
+        options.set_expand_recursive_fragments(levels);
+    };
+
+    // The user can forbid selecting some fields, or fields on some types.
+    if let Ok(forbidden_fields) = attributes::extract_attr(input, "forbidden_fields") {
+        options.set_forbidden_fields(forbidden_fields);
+    };
+
+    if let Ok(allowed_types) = attributes::extract_attr(input, "allowed_types") {
+        options.set_allowed_types(allowed_types);
+    };
+
     options.set_struct_ident(input.ident.clone());
     options.set_module_visibility(input.vis.clone());
     options.set_operation_name(input.ident.to_string());
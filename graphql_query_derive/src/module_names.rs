@@ -0,0 +1,146 @@
+//! Detection of derives generating modules with the same name.
+//!
+//! The derives can't see each other's output, so the modules they generate are recorded for the
+//! whole compilation. This is best effort: proc macros don't know the Rust module they are
+//! expanded in, so the modules are compared within the same source file.
+
+use graphql_client_codegen::GraphQLClientCodegenOptions;
+use heck::SnakeCase;
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+
+/// The derive that first generated a module.
+#[derive(Debug)]
+struct Claim {
+    struct_name: String,
+    /// The operation or fragment the module is generated for, e.g. "the `FooQuery` operation".
+    definition: String,
+}
+
+/// The generated modules, by scope (crate and source file) and module name.
+static GENERATED_MODULES: Mutex<BTreeMap<(String, String), Claim>> = Mutex::new(BTreeMap::new());
+
+/// Record the module generated by the derive on `ast` in `scope`. Fails with a message for the
+/// user if a derive on another struct already generated a module with the same name.
+///
+/// The modules named with `module_name` are not recorded: the user picked the name, and the
+/// derives in different Rust modules of a file can pick the same one.
+pub(crate) fn claim(
+    scope: &str,
+    ast: &syn::DeriveInput,
+    options: &GraphQLClientCodegenOptions,
+) -> Result<(), String> {
+    if options.module_name().is_some() {
+        return Ok(());
+    }
+
+    let struct_name = ast.ident.to_string();
+    let (module_name, definition) = match options.fragment() {
+        Some(fragment) => (
+            fragment.to_snake_case(),
+            format!("the `{}` fragment", fragment),
+        ),
+        None => (
+            struct_name.to_snake_case(),
+            format!("the `{}` operation", struct_name),
+        ),
+    };
+
+    let mut generated_modules = GENERATED_MODULES
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+    let claim = generated_modules
+        .entry((scope.to_owned(), module_name.clone()))
+        .or_insert_with(|| Claim {
+            struct_name: struct_name.clone(),
+            definition,
+        });
+
+    // The same derive can be expanded several times, e.g. by IDEs.
+    if claim.struct_name == struct_name {
+        return Ok(());
+    }
+
+    Err(format!(
+        "The `{}` module generated for `{}` is already generated for {} in this file. Set `module_name` on one of the derives to generate it in another module.",
+        module_name, struct_name, claim.definition
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn claim_derive(scope: &str, input: &str) -> Result<(), String> {
+        let ast: syn::DeriveInput = syn::parse_str(input).unwrap();
+        let options =
+            crate::build_graphql_client_derive_options(&ast, "query.graphql".into()).unwrap();
+        claim(scope, &ast, &options)
+    }
+
+    #[test]
+    fn two_derives_claiming_the_same_module_are_rejected() {
+        let scope = "my_crate::src/queries.rs";
+        let foo_query = "
+        #[graphql(schema_path = \"x\", query_path = \"x\")]
+        struct FooQuery;
+        ";
+
+        assert!(claim_derive(scope, foo_query).is_ok());
+        assert!(claim_derive(scope, foo_query).is_ok());
+        assert_eq!(
+            claim_derive(
+                scope,
+                "#[graphql(schema_path = \"x\", query_path = \"x\")] struct Foo_Query;"
+            )
+            .unwrap_err(),
+            "The `foo_query` module generated for `Foo_Query` is already generated for the `FooQuery` operation in this file. Set `module_name` on one of the derives to generate it in another module."
+        );
+    }
+
+    #[test]
+    fn fragment_modules_are_claimed_by_fragment_name() {
+        let scope = "my_crate::src/fragments.rs";
+
+        assert!(claim_derive(
+            scope,
+            "#[graphql(schema_path = \"x\", query_path = \"x\", fragment = \"UserFields\")] struct User;"
+        )
+        .is_ok());
+        assert_eq!(
+            claim_derive(
+                scope,
+                "#[graphql(schema_path = \"x\", query_path = \"x\")] struct UserFields;"
+            )
+            .unwrap_err(),
+            "The `user_fields` module generated for `UserFields` is already generated for the `UserFields` fragment in this file. Set `module_name` on one of the derives to generate it in another module."
+        );
+    }
+
+    #[test]
+    fn modules_are_scoped_by_file() {
+        let bar_query = "#[graphql(schema_path = \"x\", query_path = \"x\")] struct BarQuery;";
+
+        assert!(claim_derive("my_crate::src/a.rs", bar_query).is_ok());
+        assert!(claim_derive(
+            "my_crate::src/b.rs",
+            "#[graphql(schema_path = \"x\", query_path = \"x\")] struct Bar_Query;"
+        )
+        .is_ok());
+    }
+
+    #[test]
+    fn module_names_set_by_the_user_are_not_claimed() {
+        let scope = "my_crate::src/renamed.rs";
+        let input = |struct_name: &str| {
+            format!(
+                "#[graphql(schema_path = \"x\", query_path = \"x\", module_name = \"shared\")] struct {};",
+                struct_name
+            )
+        };
+
+        assert!(claim_derive(scope, &input("First")).is_ok());
+        assert!(claim_derive(scope, &input("Second")).is_ok());
+    }
+}